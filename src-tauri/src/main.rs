@@ -3,139 +3,1811 @@
 mod models;
 mod services;
 
-use tauri::{Manager, menu::{Menu, MenuItem}, tray::{TrayIconBuilder, TrayIconEvent}};
-use tauri_plugin_global_shortcut::GlobalShortcutExt;
+use tauri::{Emitter, LogicalSize, Manager, PhysicalPosition, PhysicalSize, WebviewUrl, WebviewWindowBuilder, window::{Effect, EffectState, EffectsBuilder}, menu::{Menu, MenuItem, PredefinedMenuItem}, tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent}};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+use tracing::{error, instrument, warn};
+use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::{Duration, Instant};
 
 // Import our models
-use models::{Note, Category, NoteLink, GraphPosition, GraphViewport};
+use models::{Note, Category, NoteLink, GraphPosition, GraphViewport, Board, AppSettings, FeedSubscription, Webhook, AiAction, AiLogEntry, Draft, Citation, Highlight, PinnedPaste, SourcedAnswer, ContradictionProposal, WindowPlacement, WindowSettings, CaptureRule, TimelineLinks, TitleStrategy, EmptyCategoryCleanup, TrayIconStyle, AutoLinkSettings, AuditEntry, IdleHideSettings, Profile, SyncSettings, MountedVault, CrossVaultLink, Visibility, RetentionRule};
+use services::archive_service::VaultImportSummary;
+use services::board_service::{BoardColumnInput, BoardView};
+use services::graph_service::{CategoryLinkMatrix, GraphFilter, GraphSnapshot, GraphSubset, NoteNeighborhood, NotePath, RelatedNote};
+use services::timeline_service::TimelineEntry;
+use services::tray_service::{TrayBadge, TrayIconState};
+use services::dedup_service::SavedNote;
+use services::category_service::CategoryTreeImport;
+use services::calendar_service::CalendarMonth;
+use services::plugin_service::PluginInfo;
+use services::ai_action_service::{AiActionInput, AiActionResult};
+use services::ai_log_service::AiLogFilter;
+use services::ai_queue_service::OfflineStatus;
+use services::diagnostics_service::DiagnosticsReport;
+use services::stats_service::{HeatmapDay, VaultSummary, WritingStreak};
+use services::import_service::{ImportReport, MarkdownImportOptions};
+use services::note_service::{NoteQuery, NoteUpdateError};
+use services::lock_service::{EditSession, NoteEditLocks};
+use services::paste_service::PinnedShortcuts;
+use services::block_service::NoteBlock;
+use services::outline_service::OutlineHeading;
+use services::code_search_service::{CodeSearchHit, CodeSearchQuery};
+use services::highlight_service::HighlightImportReport;
+use services::app_lock_service::AppLockStatus;
+use services::job_service::ImportJob;
+use services::rename_service::TermRename;
+use services::sync_service::{NoteSyncStatus, SyncOverview};
+use services::vault_service::SharedNote;
+use services::retention_service::RetentionReport;
+use services::maintenance_service::MaintenanceReport;
+use services::metrics_service::PerformanceMetrics;
+use services::quick_switch_service::SwitchResult;
+use services::reminder_service::{ParsedDate, UpcomingReminder};
+use services::text_expansion_service::Expansion;
+use services::quick_lookup_service::QuickLookup;
+use services::quick_ask_service::QuickAsk;
+use services::ai_service::ModelAnswer;
+use services::screenshot_service::ScreenRegion;
+use services::focus_service::FocusSession;
 
 // Tauri Commands - Simplified wrappers around services
 #[tauri::command]
-async fn ask_ai(question: String, response_type: Option<String>) -> Result<String, String> {
-    services::ai_service::ask_ai(question, response_type).await
+#[instrument(target = "timing", skip_all)]
+async fn ask_ai(question: String, response_type: Option<String>, request_id: Option<String>) -> Result<String, String> {
+    services::ai_service::ask_ai_cancellable(question, response_type, request_id).await
 }
 
 #[tauri::command]
-async fn save_note(content: String, category_path: Option<Vec<String>>, custom_title: Option<String>) -> Result<Note, String> {
+#[instrument(target = "timing", skip_all)]
+async fn save_note(content: String, category_path: Option<Vec<String>>, custom_title: Option<String>) -> Result<SavedNote, String> {
     services::save_note_simplified(content, category_path, custom_title).await
 }
 
 #[tauri::command]
-async fn update_note(id: String, content: String) -> Result<Note, String> {
-    services::update_note(id, content).await
+#[instrument(target = "timing", skip_all)]
+async fn update_note(id: String, content: String, expected_revision: Option<u64>, editor_id: Option<String>, locks: tauri::State<'_, NoteEditLocks>) -> Result<Note, NoteUpdateError> {
+    // An explicit revision (CLI/API callers) wins over the one tracked for the window's edit session
+    let base_revision = expected_revision.or_else(|| editor_id.as_ref().and_then(|editor| locks.base_revision(&id, editor)));
+    let note = services::update_note(id, content, base_revision).await?;
+    if let Some(editor) = &editor_id {
+        locks.advance(&note.id, editor, note.revision);
+    }
+    Ok(note)
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn update_note_with_title(id: String, content: String, title: Option<String>, expected_revision: Option<u64>, editor_id: Option<String>, locks: tauri::State<'_, NoteEditLocks>) -> Result<Note, NoteUpdateError> {
+    // An explicit revision (CLI/API callers) wins over the one tracked for the window's edit session
+    let base_revision = expected_revision.or_else(|| editor_id.as_ref().and_then(|editor| locks.base_revision(&id, editor)));
+    let note = services::update_note_with_title(id, content, title, base_revision).await?;
+    if let Some(editor) = &editor_id {
+        locks.advance(&note.id, editor, note.revision);
+    }
+    Ok(note)
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn get_notes() -> Result<Vec<Note>, String> {
+    services::get_notes().await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn delete_note(id: String) -> Result<(), String> {
+    services::delete_note(id).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn get_categories() -> Result<Vec<Category>, String> {
+    let database = services::load_categories()?;
+    Ok(database.categories)
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn create_category(name: String, parent_path: Option<Vec<String>>) -> Result<Category, String> {
+    services::create_category_safe(name, parent_path)
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn rename_category(category_id: String, new_name: String) -> Result<(), String> {
+    services::category_service::rename_category(category_id, new_name)
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn delete_category(category_id: String) -> Result<(), String> {
+    services::safe_delete_category(&category_id)
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn get_notes_by_category(category_path: Vec<String>) -> Result<Vec<Note>, String> {
+    services::get_notes_by_category(category_path).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn get_category_by_id_cmd(category_id: String) -> Result<Option<Category>, String> {
+    services::get_category_by_id(&category_id)
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn get_category_hierarchy_cmd() -> Result<Vec<Category>, String> {
+    services::get_category_hierarchy()
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn validate_category_path_cmd(path: Vec<String>) -> Result<bool, String> {
+    services::validate_category_path(&path)
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn find_categories_fuzzy(search_name: String) -> Result<Vec<Category>, String> {
+    services::find_category_by_name_fuzzy(&search_name)
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn rebuild_hierarchy_cmd() -> Result<(), String> {
+    services::rebuild_hierarchy()
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn save_note_position(note_id: String, x: f64, y: f64) -> Result<(), String> {
+    services::save_note_position(note_id, x, y).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn get_all_note_positions() -> Result<Vec<(String, GraphPosition)>, String> {
+    services::get_all_note_positions().await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn create_note_link(source_id: String, target_id: String, link_type: String, label: Option<String>) -> Result<NoteLink, String> {
+    services::create_note_link(source_id, target_id, link_type, label).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn create_note_link_with_options(source_id: String, target_id: String, link_type: String, label: Option<String>, color: Option<String>, directional: Option<bool>) -> Result<NoteLink, String> {
+    services::create_note_link_with_options(source_id, target_id, link_type, label, color, directional).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn delete_note_link(link_id: String) -> Result<(), String> {
+    services::delete_note_link(link_id).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn get_all_note_links() -> Result<Vec<NoteLink>, String> {
+    services::get_all_note_links().await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn get_note_links(note_id: String) -> Result<Vec<NoteLink>, String> {
+    services::get_note_links(note_id).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn save_graph_viewport(x: f64, y: f64, zoom: f64) -> Result<(), String> {
+    services::save_graph_viewport(x, y, zoom).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn get_graph_viewport() -> Result<GraphViewport, String> {
+    services::get_graph_viewport().await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn export_vault_archive(path: String) -> Result<String, String> {
+    services::export_vault_archive(path).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn import_vault_archive(path: String, merge_strategy: String, pins: tauri::State<'_, PinnedShortcuts>) -> Result<VaultImportSummary, String> {
+    let summary = services::import_vault_archive(path, merge_strategy).await?;
+    pins.reload();
+    Ok(summary)
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn export_notes_csv(dest: String, columns: Option<Vec<String>>) -> Result<usize, String> {
+    services::export_notes_csv(dest, columns).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn export_notes_jsonl(dest: String) -> Result<usize, String> {
+    services::export_notes_jsonl(dest).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn import_apple_notes(path: String) -> Result<ImportReport, String> {
+    services::import_apple_notes(path).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn import_joplin(path: String) -> Result<ImportReport, String> {
+    services::import_joplin(path).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn import_markdown_folder(path: String, options: Option<MarkdownImportOptions>) -> Result<ImportReport, String> {
+    services::import_markdown_folder(path, options).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn import_bookmarks_html(path: String, fetch_content: Option<bool>) -> Result<ImportReport, String> {
+    services::import_bookmarks_html(path, fetch_content).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn set_note_metadata(note_id: String, key: String, value: Option<serde_json::Value>) -> Result<Note, String> {
+    services::set_note_metadata(note_id, key, value).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn get_note_metadata(note_id: String) -> Result<HashMap<String, serde_json::Value>, String> {
+    services::get_note_metadata(note_id).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn query_notes(query: NoteQuery) -> Result<Vec<Note>, String> {
+    services::query_notes(query).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn set_note_status(note_id: String, status: Option<String>) -> Result<Note, String> {
+    services::set_note_status(note_id, status).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn set_note_rating(note_id: String, rating: Option<u8>) -> Result<Note, String> {
+    services::set_note_rating(note_id, rating).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn create_board(name: String, columns: Vec<BoardColumnInput>, category_path: Option<Vec<String>>) -> Result<Board, String> {
+    services::create_board(name, columns, category_path).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn list_boards() -> Result<Vec<Board>, String> {
+    services::list_boards().await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn delete_board(board_id: String) -> Result<(), String> {
+    services::delete_board(board_id).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn get_board(board_id: String) -> Result<BoardView, String> {
+    services::get_board(board_id).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn move_note_to_column(board_id: String, note_id: String, column_id: String) -> Result<Note, String> {
+    services::move_note_to_column(board_id, note_id, column_id).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn set_note_due_date(note_id: String, due_date: Option<chrono::DateTime<chrono::Utc>>) -> Result<Note, String> {
+    services::set_note_due_date(note_id, due_date).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn get_notes_calendar(year: i32, month: u32) -> Result<CalendarMonth, String> {
+    services::get_notes_calendar(year, month).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn get_activity_heatmap(days: u32) -> Result<Vec<HeatmapDay>, String> {
+    services::get_activity_heatmap(days).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn get_writing_streak() -> Result<WritingStreak, String> {
+    services::get_writing_streak().await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn share_note_as_html(note_id: String, copy_to_clipboard: Option<bool>) -> Result<String, String> {
+    services::share_note_as_html(note_id, copy_to_clipboard).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn get_settings() -> Result<AppSettings, String> {
+    services::get_settings().await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn update_settings(settings: AppSettings) -> Result<AppSettings, String> {
+    services::update_settings(settings).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn check_email_now() -> Result<ImportReport, String> {
+    services::check_email_now().await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn subscribe_feed(url: String, category_path: Option<Vec<String>>) -> Result<FeedSubscription, String> {
+    services::subscribe_feed(url, category_path).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn list_feeds() -> Result<Vec<FeedSubscription>, String> {
+    services::list_feeds().await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn unsubscribe_feed(feed_id: String) -> Result<(), String> {
+    services::unsubscribe_feed(feed_id).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn refresh_feeds() -> Result<ImportReport, String> {
+    services::refresh_feeds().await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn import_calendar_events(source: String) -> Result<ImportReport, String> {
+    services::import_calendar_events(source).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn add_note_tag(note_id: String, tag: String) -> Result<Note, String> {
+    services::add_note_tag(note_id, tag).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn remove_note_tag(note_id: String, tag: String) -> Result<Note, String> {
+    services::remove_note_tag(note_id, tag).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn register_webhook(url: String, events: Vec<String>, secret: Option<String>) -> Result<Webhook, String> {
+    services::register_webhook(url, events, secret).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn list_webhooks() -> Result<Vec<Webhook>, String> {
+    services::list_webhooks().await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn delete_webhook(webhook_id: String) -> Result<(), String> {
+    services::delete_webhook(webhook_id).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn set_webhook_enabled(webhook_id: String, enabled: bool) -> Result<Webhook, String> {
+    services::set_webhook_enabled(webhook_id, enabled).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn test_webhook(webhook_id: String) -> Result<(), String> {
+    services::test_webhook(webhook_id).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn list_plugins() -> Result<Vec<PluginInfo>, String> {
+    services::list_plugins().await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn run_plugin_command(plugin: String, command: String, args: Option<serde_json::Value>) -> Result<serde_json::Value, String> {
+    services::run_plugin_command(plugin, command, args).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn list_ai_actions() -> Result<Vec<AiAction>, String> {
+    services::list_ai_actions().await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn save_ai_action(action: AiAction) -> Result<AiAction, String> {
+    services::save_ai_action(action).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn delete_ai_action(action_id: String) -> Result<(), String> {
+    services::delete_ai_action(action_id).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn run_ai_action(action_id: String, target: AiActionInput) -> Result<AiActionResult, String> {
+    services::run_ai_action(action_id, target).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn get_ai_log(filter: Option<AiLogFilter>) -> Result<Vec<AiLogEntry>, String> {
+    services::get_ai_log(filter).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn clear_ai_log() -> Result<(), String> {
+    services::clear_ai_log().await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn get_offline_status() -> Result<OfflineStatus, String> {
+    services::get_offline_status().await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn set_offline_mode(enabled: bool) -> Result<OfflineStatus, String> {
+    services::set_offline_mode(enabled).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn run_diagnostics() -> Result<DiagnosticsReport, String> {
+    services::run_diagnostics().await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn set_log_level(level: String) -> Result<String, String> {
+    services::set_log_level(level).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn get_recent_logs(lines: Option<usize>) -> Result<Vec<String>, String> {
+    services::get_recent_logs(lines).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn save_draft(draft_id: String, content: String, note_id: Option<String>) -> Result<Option<Draft>, String> {
+    services::save_draft(draft_id, content, note_id).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn get_drafts() -> Result<Vec<Draft>, String> {
+    services::get_drafts().await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn discard_draft(draft_id: String) -> Result<(), String> {
+    services::discard_draft(draft_id).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn begin_note_edit(note_id: String, editor_id: String, locks: tauri::State<'_, NoteEditLocks>) -> Result<EditSession, String> {
+    locks.begin(&note_id, &editor_id)
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn end_note_edit(note_id: String, editor_id: String, locks: tauri::State<'_, NoteEditLocks>) -> Result<(), String> {
+    locks.end(&note_id, &editor_id);
+    Ok(())
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn save_qa_note(question: String, answer: String, model: Option<String>, category_path: Option<Vec<String>>) -> Result<Note, String> {
+    services::save_qa_note(question, answer, model, category_path).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn append_followup(note_id: String, question: String, answer: String, model: Option<String>) -> Result<Note, String> {
+    services::append_followup(note_id, question, answer, model).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn atomize_note(note_id: String) -> Result<Vec<Note>, String> {
+    services::atomize_note(note_id).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn resolve_embeds(note_id: String) -> Result<String, String> {
+    services::resolve_embeds(note_id).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn get_note_blocks(note_id: String) -> Result<Vec<NoteBlock>, String> {
+    services::get_note_blocks(note_id).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn get_block(note_id: String, block_id: String) -> Result<NoteBlock, String> {
+    services::get_block(note_id, block_id).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn assign_block_id(note_id: String, block_index: usize) -> Result<NoteBlock, String> {
+    services::assign_block_id(note_id, block_index).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn create_block_link(source_id: String, target_id: String, block_id: String, label: Option<String>) -> Result<NoteLink, String> {
+    services::create_block_link(source_id, target_id, block_id, label).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn get_note_outline(note_id: String) -> Result<Vec<OutlineHeading>, String> {
+    services::get_note_outline(note_id).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn render_markdown(note_id: String) -> Result<String, String> {
+    services::render_markdown(note_id).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn save_snippet(code: String, language: Option<String>, title: Option<String>, category_path: Option<Vec<String>>) -> Result<Note, String> {
+    services::save_snippet(code, language, title, category_path).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn get_snippets(language: Option<String>) -> Result<Vec<Note>, String> {
+    services::get_snippets(language).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn copy_snippet_to_clipboard(note_id: String) -> Result<(), String> {
+    services::copy_snippet_to_clipboard(note_id).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn search_code_blocks(query: CodeSearchQuery) -> Result<Vec<CodeSearchHit>, String> {
+    services::search_code_blocks(query).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn import_bibtex(path: String) -> Result<ImportReport, String> {
+    services::import_bibtex(path).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn set_note_citation(note_id: String, citation: Citation) -> Result<Note, String> {
+    services::set_note_citation(note_id, citation).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn format_citation(note_id: String, style: String) -> Result<String, String> {
+    services::format_citation(note_id, style).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn lookup_reference(identifier: String) -> Result<Note, String> {
+    services::lookup_reference(identifier).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn get_highlights(note_id: String) -> Result<Vec<Highlight>, String> {
+    services::get_highlights(note_id).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn add_highlight(note_id: String, text: String, location: Option<String>, comment: Option<String>) -> Result<Highlight, String> {
+    services::add_highlight(note_id, text, location, comment).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn update_highlight(note_id: String, highlight_id: String, text: Option<String>, location: Option<String>, comment: Option<String>) -> Result<Highlight, String> {
+    services::update_highlight(note_id, highlight_id, text, location, comment).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn delete_highlight(note_id: String, highlight_id: String) -> Result<(), String> {
+    services::delete_highlight(note_id, highlight_id).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn import_kindle_clippings(path: String) -> Result<HighlightImportReport, String> {
+    services::import_kindle_clippings(path).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn import_readwise_csv(path: String) -> Result<HighlightImportReport, String> {
+    services::import_readwise_csv(path).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn export_stats_csv(dest: String, period: String) -> Result<usize, String> {
+    services::export_stats_csv(dest, period).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn copy_note_to_clipboard(note_id: String, format: String) -> Result<(), String> {
+    services::copy_note_to_clipboard(note_id, format).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn import_attachments_folder(path: String, category_path: Option<Vec<String>>) -> Result<ImportJob, String> {
+    services::import_attachments_folder(path, category_path).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn get_import_job(job_id: String) -> Result<ImportJob, String> {
+    services::get_import_job(job_id).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn list_import_jobs() -> Result<Vec<ImportJob>, String> {
+    services::list_import_jobs().await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn parse_natural_date(text: String) -> Result<ParsedDate, String> {
+    services::parse_natural_date(text).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn set_note_reminder(note_id: String, text: String) -> Result<Note, String> {
+    services::set_note_reminder(note_id, text).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn set_note_recurrence(note_id: String, rule: Option<String>) -> Result<Note, String> {
+    services::set_note_recurrence(note_id, rule).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn complete_reminder(note_id: String) -> Result<Note, String> {
+    services::complete_reminder(note_id).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn get_upcoming_reminders(days: Option<i64>) -> Result<Vec<UpcomingReminder>, String> {
+    services::get_upcoming_reminders(days).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn pin_note_for_paste(app: tauri::AppHandle, note_id: String, shortcut: Option<String>, pins: tauri::State<'_, PinnedShortcuts>) -> Result<PinnedPaste, String> {
+    let (pin, replaced) = services::pin_note_for_paste(note_id.clone(), shortcut).await?;
+    if let Some(replaced) = replaced {
+        let _ = app.global_shortcut().unregister(replaced.as_str());
+    }
+    if let Err(e) = app.global_shortcut().register(pin.shortcut.as_str()) {
+        let _ = services::unpin_note_for_paste(note_id).await;
+        pins.reload();
+        refresh_tray_menu(&app);
+        return Err(format!("Failed to register shortcut {}: {}", pin.shortcut, e));
+    }
+    pins.reload();
+    refresh_tray_menu(&app);
+    Ok(pin)
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn unpin_note_for_paste(app: tauri::AppHandle, note_id: String, pins: tauri::State<'_, PinnedShortcuts>) -> Result<(), String> {
+    let pin = services::unpin_note_for_paste(note_id).await?;
+    let _ = app.global_shortcut().unregister(pin.shortcut.as_str());
+    pins.reload();
+    refresh_tray_menu(&app);
+    Ok(())
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn get_pinned_pastes() -> Result<Vec<PinnedPaste>, String> {
+    services::get_pinned_pastes().await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn paste_pinned_note(note_id: String) -> Result<(), String> {
+    services::paste_pinned_note(note_id).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn list_expansions() -> Result<Vec<Expansion>, String> {
+    services::list_expansions().await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn set_snippet_trigger(note_id: String, trigger: Option<String>) -> Result<Note, String> {
+    services::set_snippet_trigger(note_id, trigger).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn set_text_expansion_enabled(enabled: bool) -> Result<bool, String> {
+    services::set_text_expansion_enabled(enabled).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn quick_lookup(selection: String) -> Result<QuickLookup, String> {
+    services::quick_lookup(selection).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn get_quick_lookup() -> Result<Option<QuickLookup>, String> {
+    services::get_quick_lookup().await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn capture_screenshot_to_note(app: tauri::AppHandle, mode: String, region: Option<ScreenRegion>, ocr: Option<bool>, category_path: Option<Vec<String>>) -> Result<Note, String> {
+    // Keep the always-on-top main window out of the picture
+    let main_window = app.get_webview_window("main").filter(|w| w.is_visible().unwrap_or(false));
+    if let Some(window) = &main_window {
+        let _ = window.hide();
+        tokio::time::sleep(Duration::from_millis(300)).await;
+    }
+    let result = services::capture_screenshot_to_note(mode, region, ocr, category_path).await;
+    if let Some(window) = &main_window {
+        let _ = window.show();
+    }
+    result
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn reset_window_position(app: tauri::AppHandle) -> Result<(), String> {
+    reset_main_window(&app)
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn get_window_appearance() -> Result<WindowSettings, String> {
+    services::get_window_appearance().await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn set_always_on_top(app: tauri::AppHandle, enabled: bool) -> Result<WindowSettings, String> {
+    let appearance = services::set_always_on_top(enabled).await?;
+    apply_window_appearance(&app, &appearance);
+    Ok(appearance)
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn set_window_opacity(app: tauri::AppHandle, value: f64) -> Result<WindowSettings, String> {
+    let appearance = services::set_window_opacity(value).await?;
+    apply_window_appearance(&app, &appearance);
+    Ok(appearance)
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn set_window_vibrancy(app: tauri::AppHandle, enabled: bool) -> Result<WindowSettings, String> {
+    let appearance = services::set_window_vibrancy(enabled).await?;
+    apply_window_appearance(&app, &appearance);
+    Ok(appearance)
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn start_focus_session(minutes: u32, label: Option<String>, log_note: Option<bool>) -> Result<FocusSession, String> {
+    services::start_focus_session(minutes, label, log_note).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn get_focus_session() -> Result<Option<FocusSession>, String> {
+    services::get_focus_session().await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn stop_focus_session() -> Result<Option<Note>, String> {
+    services::stop_focus_session().await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn get_locale() -> Result<String, String> {
+    services::get_locale().await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn set_locale(lang: String) -> Result<String, String> {
+    services::set_locale(lang).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn translate_note(note_id: String, target_lang: String) -> Result<Note, String> {
+    services::translate_note(note_id, target_lang).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn list_capture_rules() -> Result<Vec<CaptureRule>, String> {
+    services::list_capture_rules().await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn save_capture_rule(rule: CaptureRule) -> Result<CaptureRule, String> {
+    services::save_capture_rule(rule).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn delete_capture_rule(rule_id: String) -> Result<(), String> {
+    services::delete_capture_rule(rule_id).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn set_default_category(category_path: Option<Vec<String>>) -> Result<Vec<String>, String> {
+    services::set_default_category(category_path).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn merge_notes(source_id: String, target_id: String) -> Result<Note, String> {
+    services::merge_notes(source_id, target_id).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn flush() -> Result<(), String> {
+    services::storage_service::flush_pending_writes()
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn save_note_positions(positions: Vec<(String, f64, f64, Option<i32>)>) -> Result<usize, String> {
+    services::save_note_positions(positions).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn bring_note_to_front(note_id: String) -> Result<Vec<(String, GraphPosition)>, String> {
+    services::bring_note_to_front(note_id).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn send_note_to_back(note_id: String) -> Result<Vec<(String, GraphPosition)>, String> {
+    services::send_note_to_back(note_id).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn set_note_size(note_id: String, width: Option<f64>, height: Option<f64>) -> Result<GraphPosition, String> {
+    services::set_note_size(note_id, width, height).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn set_note_collapsed(note_id: String, collapsed: bool) -> Result<GraphPosition, String> {
+    services::set_note_collapsed(note_id, collapsed).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn get_graph_subset(filter: GraphFilter) -> Result<GraphSubset, String> {
+    services::get_graph_subset(filter).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn get_note_neighborhood(note_id: String, depth: usize) -> Result<NoteNeighborhood, String> {
+    services::get_note_neighborhood(note_id, depth).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn find_path_between_notes(from: String, to: String) -> Result<Option<NotePath>, String> {
+    services::find_path_between_notes(from, to).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn set_link_weight(link_id: String, weight: Option<f32>) -> Result<NoteLink, String> {
+    services::set_link_weight(link_id, weight).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn recompute_link_weights() -> Result<usize, String> {
+    services::recompute_link_weights().await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn get_related_notes(note_id: String, limit: Option<usize>) -> Result<Vec<RelatedNote>, String> {
+    services::get_related_notes(note_id, limit).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn set_timeline_links(mode: TimelineLinks) -> Result<TimelineLinks, String> {
+    services::set_timeline_links(mode).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn get_note_timeline(category_path: Vec<String>) -> Result<Vec<TimelineEntry>, String> {
+    services::get_note_timeline(category_path).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn get_category_link_matrix(depth: Option<usize>) -> Result<CategoryLinkMatrix, String> {
+    services::get_category_link_matrix(depth).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn set_title_strategy(strategy: TitleStrategy, category_path: Option<Vec<String>>) -> Result<AppSettings, String> {
+    services::set_title_strategy(strategy, category_path).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn clear_category_title_strategy(category_path: Vec<String>) -> Result<AppSettings, String> {
+    services::clear_category_title_strategy(category_path).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn regenerate_note_title(note_id: String) -> Result<Note, String> {
+    services::regenerate_note_title(note_id).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn append_to_note(note_id: String, text: String, with_timestamp: bool) -> Result<Note, String> {
+    services::append_to_note(note_id, text, with_timestamp).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn prepend_to_note(note_id: String, text: String, with_timestamp: bool) -> Result<Note, String> {
+    services::prepend_to_note(note_id, text, with_timestamp).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn create_note_with_source(content: String, source_app: Option<String>, source_url: Option<String>, category_path: Option<Vec<String>>) -> Result<SavedNote, String> {
+    services::create_note_with_source(content, source_app, source_url, category_path).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn enqueue_for_reading(note_id: String) -> Result<Note, String> {
+    services::enqueue_for_reading(note_id).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn remove_from_reading_queue(note_id: String) -> Result<Note, String> {
+    services::remove_from_reading_queue(note_id).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn get_reading_queue() -> Result<Vec<Note>, String> {
+    services::get_reading_queue().await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn set_reading_progress(note_id: String, pct: u8) -> Result<Note, String> {
+    services::set_reading_progress(note_id, pct).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn mark_read(note_id: String) -> Result<Note, String> {
+    services::mark_read(note_id).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn export_category_tree() -> Result<String, String> {
+    services::export_category_tree()
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn import_category_tree(json: String, merge: bool) -> Result<CategoryTreeImport, String> {
+    services::import_category_tree(&json, merge)
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn list_taxonomy_templates() -> Result<Vec<String>, String> {
+    Ok(services::list_taxonomy_templates())
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn apply_taxonomy_template(name: String) -> Result<CategoryTreeImport, String> {
+    services::apply_taxonomy_template(&name)
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn recount_categories() -> Result<Vec<Category>, String> {
+    services::recount_categories().await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn find_empty_categories() -> Result<Vec<Category>, String> {
+    services::find_empty_categories().await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn set_empty_category_cleanup(mode: EmptyCategoryCleanup) -> Result<AppSettings, String> {
+    services::set_empty_category_cleanup(mode).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn export_notes_folder(dest: String, format: String, category_path: Option<Vec<String>>) -> Result<usize, String> {
+    services::export_notes_folder(dest, format, category_path).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn get_vault_summary() -> Result<VaultSummary, String> {
+    services::get_vault_summary().await
+}
+
+/// The tray menu's vault summary line, kept so it can be updated without a rebuild
+static TRAY_SUMMARY_ITEM: Mutex<Option<MenuItem<tauri::Wry>>> = Mutex::new(None);
+
+fn tray_tooltip(summary: Option<&VaultSummary>) -> String {
+    match summary {
+        Some(summary) => format!("NowledgeBase - {}", summary.describe()),
+        None => "NowledgeBase".to_string(),
+    }
 }
 
 #[tauri::command]
-async fn update_note_with_title(id: String, content: String, title: Option<String>) -> Result<Note, String> {
-    services::update_note_with_title(id, content, title).await
+#[instrument(target = "timing", skip_all)]
+async fn get_tray_icon_state() -> Result<TrayIconState, String> {
+    services::get_tray_icon_state().await
 }
 
 #[tauri::command]
-async fn get_notes() -> Result<Vec<Note>, String> {
-    services::get_notes().await
+#[instrument(target = "timing", skip_all)]
+async fn set_tray_icon_style(style: TrayIconStyle) -> Result<TrayIconState, String> {
+    services::set_tray_icon_style(style).await
 }
 
 #[tauri::command]
-async fn delete_note(id: String) -> Result<(), String> {
-    services::delete_note(id).await
+#[instrument(target = "timing", skip_all)]
+async fn quick_ask(question: String, response_type: Option<String>) -> Result<QuickAsk, String> {
+    services::quick_ask(question, response_type).await
 }
 
 #[tauri::command]
-async fn get_categories() -> Result<Vec<Category>, String> {
-    let database = services::load_categories()?;
-    Ok(database.categories)
+#[instrument(target = "timing", skip_all)]
+async fn get_quick_ask() -> Result<Option<QuickAsk>, String> {
+    services::get_quick_ask().await
 }
 
 #[tauri::command]
-async fn create_category(name: String, parent_path: Option<Vec<String>>) -> Result<Category, String> {
-    services::create_category_safe(name, parent_path)
+#[instrument(target = "timing", skip_all)]
+async fn list_response_types() -> Result<Vec<String>, String> {
+    services::ai_service::list_response_types().await
 }
 
 #[tauri::command]
-async fn rename_category(category_id: String, new_name: String) -> Result<(), String> {
-    services::category_service::rename_category(category_id, new_name)
+#[instrument(target = "timing", skip_all)]
+async fn cancel_ai_request(request_id: String) -> Result<bool, String> {
+    services::ai_service::cancel_ai_request(request_id).await
 }
 
 #[tauri::command]
-async fn delete_category(category_id: String) -> Result<(), String> {
-    services::safe_delete_category(&category_id)
+#[instrument(target = "timing", skip_all)]
+async fn ask_ai_compare(question: String, models: Vec<String>, response_type: Option<String>, request_id: Option<String>) -> Result<Vec<ModelAnswer>, String> {
+    services::ai_service::ask_ai_compare(question, models, response_type, request_id).await
 }
 
 #[tauri::command]
-async fn get_notes_by_category(category_path: Vec<String>) -> Result<Vec<Note>, String> {
-    services::get_notes_by_category(category_path).await
+#[instrument(target = "timing", skip_all)]
+async fn summarize_note(note_id: String) -> Result<String, String> {
+    services::summarize_note(note_id).await
 }
 
 #[tauri::command]
-async fn get_category_by_id_cmd(category_id: String) -> Result<Option<Category>, String> {
-    services::get_category_by_id(&category_id)
+#[instrument(target = "timing", skip_all)]
+async fn ask_ai_about_note(note_id: String, question: String) -> Result<String, String> {
+    services::ask_ai_about_note(note_id, question).await
 }
 
 #[tauri::command]
-async fn get_category_hierarchy_cmd() -> Result<Vec<Category>, String> {
-    services::get_category_hierarchy()
+#[instrument(target = "timing", skip_all)]
+async fn ask_vault(question: String) -> Result<SourcedAnswer, String> {
+    services::ask_vault(question).await
 }
 
 #[tauri::command]
-async fn validate_category_path_cmd(path: Vec<String>) -> Result<bool, String> {
-    services::validate_category_path(&path)
+#[instrument(target = "timing", skip_all)]
+async fn get_answer_history(limit: Option<usize>) -> Result<Vec<SourcedAnswer>, String> {
+    services::get_answer_history(limit).await
 }
 
 #[tauri::command]
-async fn find_categories_fuzzy(search_name: String) -> Result<Vec<Category>, String> {
-    services::find_category_by_name_fuzzy(&search_name)
+#[instrument(target = "timing", skip_all)]
+async fn answer_to_note(answer_id: String, category_path: Option<Vec<String>>) -> Result<Note, String> {
+    services::answer_to_note(answer_id, category_path).await
 }
 
 #[tauri::command]
-async fn rebuild_hierarchy_cmd() -> Result<(), String> {
-    services::rebuild_hierarchy()
+#[instrument(target = "timing", skip_all)]
+async fn detect_contradictions(scope: Vec<String>) -> Result<ImportJob, String> {
+    services::detect_contradictions(scope).await
 }
 
 #[tauri::command]
-async fn save_note_position(note_id: String, x: f64, y: f64) -> Result<(), String> {
-    services::save_note_position(note_id, x, y).await
+#[instrument(target = "timing", skip_all)]
+async fn list_contradiction_proposals() -> Result<Vec<ContradictionProposal>, String> {
+    services::list_contradiction_proposals().await
 }
 
 #[tauri::command]
-async fn get_all_note_positions() -> Result<Vec<(String, GraphPosition)>, String> {
-    services::get_all_note_positions().await
+#[instrument(target = "timing", skip_all)]
+async fn accept_contradiction(proposal_id: String) -> Result<NoteLink, String> {
+    services::accept_contradiction(proposal_id).await
 }
 
 #[tauri::command]
-async fn create_note_link(source_id: String, target_id: String, link_type: String, label: Option<String>) -> Result<NoteLink, String> {
-    services::create_note_link(source_id, target_id, link_type, label).await
+#[instrument(target = "timing", skip_all)]
+async fn dismiss_contradiction(proposal_id: String) -> Result<(), String> {
+    services::dismiss_contradiction(proposal_id).await
 }
 
 #[tauri::command]
-async fn create_note_link_with_options(source_id: String, target_id: String, link_type: String, label: Option<String>, color: Option<String>, directional: Option<bool>) -> Result<NoteLink, String> {
-    services::create_note_link_with_options(source_id, target_id, link_type, label, color, directional).await
+#[instrument(target = "timing", skip_all)]
+async fn set_auto_link_settings(settings: AutoLinkSettings) -> Result<AutoLinkSettings, String> {
+    services::set_auto_link_settings(settings).await
 }
 
 #[tauri::command]
-async fn delete_note_link(link_id: String) -> Result<(), String> {
-    services::delete_note_link(link_id).await
+#[instrument(target = "timing", skip_all)]
+async fn get_graph_snapshot(at: chrono::DateTime<chrono::Utc>) -> Result<GraphSnapshot, String> {
+    services::get_graph_snapshot(at).await
 }
 
 #[tauri::command]
-async fn get_all_note_links() -> Result<Vec<NoteLink>, String> {
-    services::get_all_note_links().await
+#[instrument(target = "timing", skip_all)]
+async fn get_audit_log(limit: Option<usize>) -> Result<Vec<AuditEntry>, String> {
+    services::get_audit_log(limit).await
 }
 
 #[tauri::command]
-async fn get_note_links(note_id: String) -> Result<Vec<NoteLink>, String> {
-    services::get_note_links(note_id).await
+#[instrument(target = "timing", skip_all)]
+async fn rename_term(old: String, new: String, scope: Option<Vec<String>>, dry_run: bool) -> Result<TermRename, String> {
+    services::rename_term(old, new, scope, dry_run).await
 }
 
 #[tauri::command]
-async fn save_graph_viewport(x: f64, y: f64, zoom: f64) -> Result<(), String> {
-    services::save_graph_viewport(x, y, zoom).await
+#[instrument(target = "timing", skip_all)]
+async fn get_app_lock_status() -> Result<AppLockStatus, String> {
+    services::get_app_lock_status().await
 }
 
 #[tauri::command]
-async fn get_graph_viewport() -> Result<GraphViewport, String> {
-    services::get_graph_viewport().await
+#[instrument(target = "timing", skip_all)]
+async fn lock_app() -> Result<AppLockStatus, String> {
+    services::lock_app().await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn unlock_app(pin: String) -> Result<AppLockStatus, String> {
+    services::unlock_app(pin).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn set_app_lock(pin: Option<String>, current_pin: Option<String>, idle_minutes: u32, lock_on_show: bool) -> Result<AppLockStatus, String> {
+    services::set_app_lock(pin, current_pin, idle_minutes, lock_on_show).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn record_user_activity() -> Result<(), String> {
+    services::record_user_activity().await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn get_idle_hide_settings() -> Result<IdleHideSettings, String> {
+    services::get_idle_hide_settings().await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn set_idle_hide_settings(settings: IdleHideSettings) -> Result<IdleHideSettings, String> {
+    services::set_idle_hide_settings(settings).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn list_profiles() -> Result<Vec<Profile>, String> {
+    services::list_profiles().await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn get_active_profile() -> Result<Option<Profile>, String> {
+    services::get_active_profile().await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn create_profile(name: String) -> Result<Profile, String> {
+    services::create_profile(name).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn switch_profile(profile_id: String) -> Result<Profile, String> {
+    services::switch_profile(profile_id).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn get_notes_by_author(author: Option<String>) -> Result<Vec<Note>, String> {
+    services::get_notes_by_author(author).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn get_sync_status() -> Result<SyncOverview, String> {
+    services::get_sync_status().await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn get_note_sync_status(note_id: String) -> Result<NoteSyncStatus, String> {
+    services::get_note_sync_status(note_id).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn set_sync_settings(enabled: bool, relay_url: Option<String>, vault_id: Option<String>, interval_minutes: u32) -> Result<SyncSettings, String> {
+    services::set_sync_settings(enabled, relay_url, vault_id, interval_minutes).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn sync_now() -> Result<SyncOverview, String> {
+    services::sync_now().await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn list_mounted_vaults() -> Result<Vec<MountedVault>, String> {
+    services::list_mounted_vaults().await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn mount_shared_vault(path: String, read_only: bool, name: Option<String>) -> Result<MountedVault, String> {
+    services::mount_shared_vault(path, read_only, name).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn unmount_shared_vault(vault_id: String) -> Result<(), String> {
+    services::unmount_shared_vault(vault_id).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn get_shared_vault_notes(vault_id: String) -> Result<Vec<Note>, String> {
+    services::get_shared_vault_notes(vault_id).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn search_shared_vaults(query: NoteQuery) -> Result<Vec<SharedNote>, String> {
+    services::search_shared_vaults(query).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn update_shared_note(vault_id: String, note_id: String, content: String) -> Result<Note, String> {
+    services::update_shared_note(vault_id, note_id, content).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn create_cross_vault_link(source_id: String, vault_id: String, target_id: String, label: Option<String>) -> Result<CrossVaultLink, String> {
+    services::create_cross_vault_link(source_id, vault_id, target_id, label).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn delete_cross_vault_link(link_id: String) -> Result<(), String> {
+    services::delete_cross_vault_link(link_id).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn get_cross_vault_links(source_id: Option<String>) -> Result<Vec<CrossVaultLink>, String> {
+    services::get_cross_vault_links(source_id).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn set_note_visibility(note_id: String, visibility: Option<Visibility>) -> Result<Note, String> {
+    services::set_note_visibility(note_id, visibility).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn set_category_visibility(category_path: Vec<String>, visibility: Option<Visibility>) -> Result<Category, String> {
+    services::set_category_visibility(category_path, visibility).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn get_effective_visibility(note_id: String) -> Result<Visibility, String> {
+    services::get_effective_visibility(note_id).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn set_note_ai_excluded(note_id: String, excluded: bool) -> Result<Note, String> {
+    services::set_note_ai_excluded(note_id, excluded).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn set_category_ai_excluded(category_path: Vec<String>, excluded: bool) -> Result<Category, String> {
+    services::set_category_ai_excluded(category_path, excluded).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn list_retention_rules() -> Result<Vec<RetentionRule>, String> {
+    services::list_retention_rules().await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn save_retention_rule(rule: RetentionRule) -> Result<RetentionRule, String> {
+    services::save_retention_rule(rule).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn delete_retention_rule(rule_id: String) -> Result<(), String> {
+    services::delete_retention_rule(rule_id).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn run_retention(dry_run: Option<bool>) -> Result<RetentionReport, String> {
+    services::run_retention(dry_run).await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn run_maintenance(pins: tauri::State<'_, PinnedShortcuts>) -> Result<MaintenanceReport, String> {
+    let report = services::run_maintenance().await?;
+    pins.reload();
+    Ok(report)
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn get_performance_metrics() -> Result<PerformanceMetrics, String> {
+    services::get_performance_metrics().await
+}
+
+#[tauri::command]
+#[instrument(target = "timing", skip_all)]
+async fn quick_switch(query: String, limit: Option<usize>) -> Result<Vec<SwitchResult>, String> {
+    services::quick_switch(query, limit).await
+}
+
+/// Redraw the tray icon from the app icon for `state` (style and badge dot)
+fn apply_tray_icon(app: &tauri::AppHandle, state: &TrayIconState) {
+    let (Some(tray), Some(icon)) = (app.tray_by_id("tray"), app.default_window_icon()) else { return };
+    let rgba = services::tray_service::render_tray_icon(icon.rgba(), icon.width(), icon.height(), state);
+    let _ = tray.set_icon(Some(tauri::image::Image::new_owned(rgba, icon.width(), icon.height())));
+    let _ = tray.set_icon_as_template(state.style == TrayIconStyle::Template);
+}
+
+/// Tray menu: a vault summary line, window controls and a "Paste" entry for every pinned
+/// note among `notes` (none while the app is locked). Built at launch without notes so the tray doesn't wait on loading
+/// them; `preload_stores` rebuilds it once they're in.
+fn build_tray_menu<M: Manager<tauri::Wry>>(app: &M, notes: &[Note]) -> tauri::Result<Menu<tauri::Wry>> {
+    let summary = services::stats_service::last_vault_summary().map(|s| s.describe()).unwrap_or_default();
+    let summary_i = MenuItem::with_id(app, "summary", summary, false, None::<&str>)?;
+    let show_i = MenuItem::with_id(app, "show", "Show AI Helper", true, None::<&str>)?;
+    let hide_i = MenuItem::with_id(app, "hide", "Hide", true, None::<&str>)?;
+    let quit_i = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
+    let menu = Menu::with_items(app, &[&summary_i, &PredefinedMenuItem::separator(app)?, &show_i, &hide_i])?;
+    *TRAY_SUMMARY_ITEM.lock().unwrap_or_else(|e| e.into_inner()) = Some(summary_i);
+
+    // Pinned note titles are note data too - left out while the app is locked
+    let pins = services::storage_service::load_pinned_pastes().map(|db| db.pins).unwrap_or_default();
+    let pins = if services::app_lock_service::is_locked() { Vec::new() } else { pins };
+    let pinned: Vec<(&PinnedPaste, &Note)> = pins.iter()
+        .filter_map(|pin| notes.iter().find(|n| n.id == pin.note_id).map(|note| (pin, note)))
+        .collect();
+    if !pinned.is_empty() {
+        menu.append(&PredefinedMenuItem::separator(app)?)?;
+        for (pin, note) in pinned {
+            let item = MenuItem::with_id(app, format!("paste:{}", note.id), format!("Paste \"{}\"", note.title), true, Some(pin.shortcut.as_str()))?;
+            menu.append(&item)?;
+        }
+    }
+
+    menu.append(&PredefinedMenuItem::separator(app)?)?;
+    menu.append(&MenuItem::with_id(app, "reset-position", "Reset Window Position", true, None::<&str>)?)?;
+    menu.append(&quit_i)?;
+    Ok(menu)
+}
+
+fn refresh_tray_menu(app: &tauri::AppHandle) {
+    let Some(tray) = app.tray_by_id("tray") else { return };
+    let notes = services::note_service::load_notes().map(|db| db.notes).unwrap_or_default();
+    match build_tray_menu(app, &notes) {
+        Ok(menu) => {
+            let _ = tray.set_menu(Some(menu));
+        }
+        Err(e) => warn!("Failed to rebuild tray menu: {}", e),
+    }
+}
+
+fn paste_pinned_note_in_background(note_id: String) {
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = services::paste_pinned_note(note_id).await {
+            error!("Failed to paste pinned note: {}", e);
+        }
+    });
+}
+
+/// Reads the selection in any app and explains it in the quick-lookup popup
+const QUICK_LOOKUP_SHORTCUT: &str = "CmdOrCtrl+Alt+L";
+
+fn show_quick_lookup_window(app: &tauri::AppHandle) {
+    if let Some(window) = app.get_webview_window("quick-lookup") {
+        let _ = window.show();
+        let _ = window.set_focus();
+        return;
+    }
+    // The frontend renders the popup instead of the main UI for this window label
+    let built = WebviewWindowBuilder::new(app, "quick-lookup", WebviewUrl::App("index.html".into()))
+        .title("Quick lookup")
+        .inner_size(420.0, 320.0)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .build();
+    match built {
+        Ok(window) => restore_window_placement(&window),
+        Err(e) => error!("Failed to open quick lookup window: {}", e),
+    }
+}
+
+/// Grab the selection first (the popup would steal focus), then open the popup and ask
+fn start_quick_lookup(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let selection = match services::quick_lookup_service::read_selection().await {
+            Ok(selection) => selection,
+            Err(e) => {
+                warn!("Quick lookup skipped: {}", e);
+                return;
+            }
+        };
+        show_quick_lookup_window(&app);
+        if let Err(e) = services::quick_lookup(selection).await {
+            warn!("Quick lookup failed: {}", e);
+        }
+    });
+}
+
+/// Compact ask box opened by clicking the tray icon
+const QUICK_ASK_WINDOW: &str = "quick-ask";
+/// Gap between the tray icon and the popover
+const QUICK_ASK_MARGIN: f64 = 8.0;
+/// Clicking the tray icon while the popover is open blurs it first - a click this soon
+/// after the blur closed it is that same click, not a request to reopen
+const QUICK_ASK_REOPEN_GUARD_MS: u64 = 300;
+static QUICK_ASK_HIDDEN_AT: Mutex<Option<Instant>> = Mutex::new(None);
+
+/// Put the popover next to the tray icon at `anchor`: below it when the icon is in a top
+/// menu bar, above it for a bottom taskbar, and always fully on that screen
+fn position_quick_ask(window: &tauri::WebviewWindow, anchor: PhysicalPosition<f64>) {
+    let Ok(size) = window.outer_size() else { return };
+    let monitors = window.available_monitors().unwrap_or_default();
+    let Some(monitor) = monitors.iter().find(|m| {
+        let (mx, my) = (m.position().x as f64, m.position().y as f64);
+        anchor.x >= mx && anchor.x < mx + m.size().width as f64 && anchor.y >= my && anchor.y < my + m.size().height as f64
+    }) else { return };
+
+    let (mx, my) = (monitor.position().x as f64, monitor.position().y as f64);
+    let (mw, mh) = (monitor.size().width as f64, monitor.size().height as f64);
+    let (width, height) = (size.width as f64, size.height as f64);
+    let margin = QUICK_ASK_MARGIN * monitor.scale_factor();
+    let x = (anchor.x - width / 2.0).clamp(mx, (mx + mw - width).max(mx));
+    let y = if anchor.y < my + mh / 2.0 {
+        anchor.y + margin
+    } else {
+        anchor.y - height - margin
+    };
+    let _ = window.set_position(PhysicalPosition::new(x as i32, y.clamp(my, (my + mh - height).max(my)) as i32));
+}
+
+/// Tray click: open the quick-ask popover by the icon, or close it if it's open
+fn toggle_quick_ask_window(app: &tauri::AppHandle, anchor: PhysicalPosition<f64>) {
+    let just_hidden = QUICK_ASK_HIDDEN_AT.lock().unwrap_or_else(|e| e.into_inner())
+        .is_some_and(|at| at.elapsed() < Duration::from_millis(QUICK_ASK_REOPEN_GUARD_MS));
+    if let Some(window) = app.get_webview_window(QUICK_ASK_WINDOW) {
+        if window.is_visible().unwrap_or(false) {
+            let _ = window.hide();
+        } else if !just_hidden {
+            position_quick_ask(&window, anchor);
+            let _ = window.show();
+            let _ = window.set_focus();
+        }
+        return;
+    }
+    // The frontend renders the popover instead of the main UI for this window label
+    let built = WebviewWindowBuilder::new(app, QUICK_ASK_WINDOW, WebviewUrl::App("index.html".into()))
+        .title("Quick ask")
+        .inner_size(360.0, 280.0)
+        .decorations(false)
+        .resizable(false)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .visible(false)
+        .build();
+    match built {
+        Ok(window) => {
+            position_quick_ask(&window, anchor);
+            let _ = window.show();
+            let _ = window.set_focus();
+        }
+        Err(e) => error!("Failed to open quick ask window: {}", e),
+    }
+}
+
+/// Windows whose position and size are remembered per monitor layout
+const REMEMBERED_WINDOWS: [&str; 2] = ["main", "quick-lookup"];
+/// Wait for a drag or resize to settle before writing the placement
+const PLACEMENT_SAVE_DELAY_MS: u64 = 500;
+static PLACEMENT_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Key for the connected set of monitors, e.g. "DP-1@0,0 2560x1440|eDP-1@2560,0 1920x1080"
+fn monitor_layout(window: &tauri::WebviewWindow) -> Option<String> {
+    let mut monitors: Vec<String> = window.available_monitors().ok()?.iter()
+        .map(|m| format!("{}@{},{} {}x{}", m.name().map(String::as_str).unwrap_or("monitor"),
+            m.position().x, m.position().y, m.size().width, m.size().height))
+        .collect();
+    if monitors.is_empty() {
+        return None;
+    }
+    monitors.sort();
+    Some(monitors.join("|"))
+}
+
+/// Put a window back where it was last left on this monitor layout. Placements that
+/// wouldn't leave enough of the window on any screen to grab are ignored.
+fn restore_window_placement(window: &tauri::WebviewWindow) {
+    let Some(layout) = monitor_layout(window) else { return };
+    let Some(placement) = services::window_service::window_placement(&layout, window.label()) else { return };
+
+    let visible = window.available_monitors().unwrap_or_default().iter().any(|m| {
+        let (mx, my) = (m.position().x, m.position().y);
+        let (mw, mh) = (m.size().width as i32, m.size().height as i32);
+        placement.x + 100 <= mx + mw && placement.x + placement.width as i32 >= mx + 100
+            && placement.y >= my && placement.y + 40 <= my + mh
+    });
+    if !visible {
+        return;
+    }
+
+    let _ = window.set_size(PhysicalSize::new(placement.width, placement.height));
+    let _ = window.set_position(PhysicalPosition::new(placement.x, placement.y));
+    if placement.maximized {
+        let _ = window.maximize();
+    }
+}
+
+/// Save a window's placement once it stops moving. A maximized window keeps its
+/// earlier normal bounds so un-maximizing after a restart still works.
+fn remember_window_placement(window: tauri::WebviewWindow) {
+    let generation = PLACEMENT_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(PLACEMENT_SAVE_DELAY_MS)).await;
+        if PLACEMENT_GENERATION.load(Ordering::SeqCst) != generation
+            || !window.is_visible().unwrap_or(false)
+            || window.is_minimized().unwrap_or(true)
+        {
+            return;
+        }
+        let Some(layout) = monitor_layout(&window) else { return };
+        let (Ok(position), Ok(size)) = (window.outer_position(), window.inner_size()) else { return };
+
+        let maximized = window.is_maximized().unwrap_or(false);
+        let placement = match services::window_service::window_placement(&layout, window.label()) {
+            Some(previous) if maximized => WindowPlacement { maximized: true, ..previous },
+            _ => WindowPlacement { x: position.x, y: position.y, width: size.width, height: size.height, maximized },
+        };
+        if let Err(e) = services::window_service::save_window_placement(&layout, window.label(), placement) {
+            warn!("Failed to save window position: {}", e);
+        }
+    });
+}
+
+/// Forget the saved placements for the current monitors and bring the main window back
+/// to the middle of the screen at its default size
+fn reset_main_window(app: &tauri::AppHandle) -> Result<(), String> {
+    let window = app.get_webview_window("main").ok_or("Main window not found")?;
+    if let Some(layout) = monitor_layout(&window) {
+        services::window_service::forget_window_placements(&layout)?;
+    }
+    let _ = window.unmaximize();
+    window.set_size(LogicalSize::new(1000.0, 700.0))
+        .and_then(|_| window.center())
+        .and_then(|_| window.show())
+        .and_then(|_| window.set_focus())
+        .map_err(|e| format!("Failed to reset window position: {}", e))?;
+    if let Some(popup) = app.get_webview_window("quick-lookup") {
+        let _ = popup.center();
+    }
+    Ok(())
+}
+
+/// Apply the appearance settings to the main window. Opacity is applied by the page
+/// itself, so it's sent along in `window:appearance`.
+fn apply_window_appearance(app: &tauri::AppHandle, appearance: &WindowSettings) {
+    let Some(window) = app.get_webview_window("main") else { return };
+    if let Err(e) = window.set_always_on_top(appearance.always_on_top) {
+        warn!("Failed to set always-on-top: {}", e);
+    }
+    let effects = appearance.vibrancy.then(|| {
+        let effect = if cfg!(target_os = "macos") { Effect::HudWindow } else { Effect::Acrylic };
+        EffectsBuilder::new().effect(effect).state(EffectState::FollowsWindowActiveState).build()
+    });
+    if let Err(e) = window.set_effects(effects) {
+        warn!("Failed to set window vibrancy: {}", e);
+    }
+    let _ = app.emit("window:appearance", appearance);
+}
+
+/// Refuse every command except the unlock ones while the app is locked, so no note data
+/// leaves the backend. Commands that get through count as activity for the idle lock.
+fn lock_gate<R: tauri::Runtime>(handler: impl Fn(tauri::ipc::Invoke<R>) -> bool + Send + Sync + 'static) -> impl Fn(tauri::ipc::Invoke<R>) -> bool + Send + Sync + 'static {
+    move |invoke| {
+        if !services::app_lock_service::UNLOCKED_COMMANDS.contains(&invoke.message.command()) {
+            if services::app_lock_service::is_locked() {
+                invoke.resolver.reject(services::app_lock_service::LOCKED_MESSAGE);
+                return true;
+            }
+            services::app_lock_service::record_activity();
+        }
+        handler(invoke)
+    }
+}
+
+/// Hide the main window (and lock, if asked) once the user has been idle for the
+/// configured time. The frontend pings `record_user_activity` while it's being used.
+async fn run_idle_hide_loop(app: tauri::AppHandle) {
+    loop {
+        tokio::time::sleep(Duration::from_secs(services::app_lock_service::IDLE_CHECK_SECS)).await;
+        let Some(window) = app.get_webview_window("main") else { continue };
+        if !window.is_visible().unwrap_or(false) || !services::app_lock_service::idle_hide_due() {
+            continue;
+        }
+        let _ = window.hide();
+        if services::settings_service::current_settings().idle_hide.lock {
+            services::app_lock_service::lock_now();
+        }
+    }
 }
 
 fn toggle_window(app: &tauri::AppHandle) {
@@ -145,8 +1817,10 @@ fn toggle_window(app: &tauri::AppHandle) {
                 if visible {
                     let _ = window.hide();
                 } else {
+                    services::app_lock_service::lock_on_show();
                     let _ = window.show();
                     let _ = window.set_focus();
+                    services::tray_service::set_tray_badge(TrayBadge::JobFinished, false);
                 }
             }
             Err(_) => {
@@ -162,12 +1836,30 @@ fn main() {
     // Load environment variables from .env file
     dotenv::dotenv().ok();
     
+    // File logging (logs/ in the app data dir) - the app still runs if it can't be set up
+    if let Err(e) = services::logging_service::init_logging() {
+        eprintln!("Failed to set up logging: {}", e);
+    }
+    
     tauri::Builder::default()
         .plugin(
             tauri_plugin_global_shortcut::Builder::new()
                 .with_handler({
                     let last_trigger = Arc::new(Mutex::new(Instant::now()));
-                    move |app, _shortcut, _event| {
+                    move |app, shortcut, event| {
+                        if QUICK_LOOKUP_SHORTCUT.parse::<Shortcut>().is_ok_and(|s| s == *shortcut) {
+                            if event.state == ShortcutState::Pressed {
+                                start_quick_lookup(app.clone());
+                            }
+                            return;
+                        }
+                        if let Some(note_id) = app.state::<PinnedShortcuts>().note_for(shortcut) {
+                            if event.state == ShortcutState::Pressed {
+                                paste_pinned_note_in_background(note_id);
+                            }
+                            return;
+                        }
+
                         let mut last = last_trigger.lock().unwrap();
                         let now = Instant::now();
                         if now.duration_since(*last) > Duration::from_millis(100) {
@@ -178,7 +1870,9 @@ fn main() {
                 })
                 .build()
         )
-        .invoke_handler(tauri::generate_handler![
+        .manage(NoteEditLocks::default())
+        .manage(PinnedShortcuts::default())
+        .invoke_handler(lock_gate(tauri::generate_handler![
             ask_ai, 
             save_note, 
             update_note,
@@ -203,27 +1897,349 @@ fn main() {
             get_all_note_links,
             get_note_links,
             save_graph_viewport,
-            get_graph_viewport
-        ])
+            get_graph_viewport,
+            export_vault_archive,
+            import_vault_archive,
+            export_notes_csv,
+            export_notes_jsonl,
+            import_apple_notes,
+            import_joplin,
+            import_markdown_folder,
+            import_bookmarks_html,
+            set_note_metadata,
+            get_note_metadata,
+            query_notes,
+            set_note_status,
+            set_note_rating,
+            create_board,
+            list_boards,
+            delete_board,
+            get_board,
+            move_note_to_column,
+            set_note_due_date,
+            get_notes_calendar,
+            get_activity_heatmap,
+            get_writing_streak,
+            share_note_as_html,
+            get_settings,
+            update_settings,
+            check_email_now,
+            subscribe_feed,
+            list_feeds,
+            unsubscribe_feed,
+            refresh_feeds,
+            import_calendar_events,
+            add_note_tag,
+            remove_note_tag,
+            register_webhook,
+            list_webhooks,
+            delete_webhook,
+            set_webhook_enabled,
+            test_webhook,
+            list_plugins,
+            run_plugin_command,
+            list_ai_actions,
+            save_ai_action,
+            delete_ai_action,
+            run_ai_action,
+            get_ai_log,
+            clear_ai_log,
+            get_offline_status,
+            set_offline_mode,
+            run_diagnostics,
+            set_log_level,
+            get_recent_logs,
+            save_draft,
+            get_drafts,
+            discard_draft,
+            begin_note_edit,
+            end_note_edit,
+            save_qa_note,
+            append_followup,
+            atomize_note,
+            resolve_embeds,
+            get_note_blocks,
+            get_block,
+            assign_block_id,
+            create_block_link,
+            get_note_outline,
+            render_markdown,
+            save_snippet,
+            get_snippets,
+            copy_snippet_to_clipboard,
+            search_code_blocks,
+            import_bibtex,
+            set_note_citation,
+            format_citation,
+            lookup_reference,
+            get_highlights,
+            add_highlight,
+            update_highlight,
+            delete_highlight,
+            import_kindle_clippings,
+            import_readwise_csv,
+            export_stats_csv,
+            copy_note_to_clipboard,
+            import_attachments_folder,
+            get_import_job,
+            list_import_jobs,
+            parse_natural_date,
+            set_note_reminder,
+            set_note_recurrence,
+            complete_reminder,
+            get_upcoming_reminders,
+            pin_note_for_paste,
+            unpin_note_for_paste,
+            get_pinned_pastes,
+            paste_pinned_note,
+            list_expansions,
+            set_snippet_trigger,
+            set_text_expansion_enabled,
+            quick_lookup,
+            get_quick_lookup,
+            capture_screenshot_to_note,
+            reset_window_position,
+            get_window_appearance,
+            set_always_on_top,
+            set_window_opacity,
+            set_window_vibrancy,
+            start_focus_session,
+            get_focus_session,
+            stop_focus_session,
+            get_locale,
+            set_locale,
+            translate_note,
+            list_capture_rules,
+            save_capture_rule,
+            delete_capture_rule,
+            set_default_category,
+            merge_notes,
+            flush,
+            save_note_positions,
+            bring_note_to_front,
+            send_note_to_back,
+            set_note_size,
+            set_note_collapsed,
+            get_graph_subset,
+            get_note_neighborhood,
+            find_path_between_notes,
+            set_link_weight,
+            recompute_link_weights,
+            get_related_notes,
+            set_timeline_links,
+            get_note_timeline,
+            get_category_link_matrix,
+            set_title_strategy,
+            clear_category_title_strategy,
+            regenerate_note_title,
+            append_to_note,
+            prepend_to_note,
+            create_note_with_source,
+            enqueue_for_reading,
+            remove_from_reading_queue,
+            get_reading_queue,
+            set_reading_progress,
+            mark_read,
+            export_category_tree,
+            import_category_tree,
+            list_taxonomy_templates,
+            apply_taxonomy_template,
+            recount_categories,
+            find_empty_categories,
+            set_empty_category_cleanup,
+            export_notes_folder,
+            get_vault_summary,
+            get_tray_icon_state,
+            set_tray_icon_style,
+            quick_ask,
+            get_quick_ask,
+            list_response_types,
+            cancel_ai_request,
+            ask_ai_compare,
+            summarize_note,
+            ask_ai_about_note,
+            ask_vault,
+            get_answer_history,
+            answer_to_note,
+            detect_contradictions,
+            list_contradiction_proposals,
+            accept_contradiction,
+            dismiss_contradiction,
+            set_auto_link_settings,
+            get_graph_snapshot,
+            get_audit_log,
+            rename_term,
+            get_app_lock_status,
+            lock_app,
+            unlock_app,
+            set_app_lock,
+            record_user_activity,
+            get_idle_hide_settings,
+            set_idle_hide_settings,
+            list_profiles,
+            get_active_profile,
+            create_profile,
+            switch_profile,
+            get_notes_by_author,
+            get_sync_status,
+            get_note_sync_status,
+            set_sync_settings,
+            sync_now,
+            list_mounted_vaults,
+            mount_shared_vault,
+            unmount_shared_vault,
+            get_shared_vault_notes,
+            search_shared_vaults,
+            update_shared_note,
+            create_cross_vault_link,
+            delete_cross_vault_link,
+            get_cross_vault_links,
+            set_note_visibility,
+            set_category_visibility,
+            get_effective_visibility,
+            set_note_ai_excluded,
+            set_category_ai_excluded,
+            list_retention_rules,
+            save_retention_rule,
+            delete_retention_rule,
+            run_retention,
+            run_maintenance,
+            get_performance_metrics,
+            quick_switch
+        ]))
         .setup(|app| {
-            let quit_i = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
-            let show_i = MenuItem::with_id(app, "show", "Show AI Helper", true, None::<&str>)?;
-            let hide_i = MenuItem::with_id(app, "hide", "Hide", true, None::<&str>)?;
-            let menu = Menu::with_items(app, &[&show_i, &hide_i, &quit_i])?;
+            // Before anything else, so the windows' first commands already find the app locked
+            services::app_lock_service::lock_at_launch();
 
+            if let Some(window) = app.get_webview_window("main") {
+                restore_window_placement(&window);
+            }
+            apply_window_appearance(app.handle(), &services::settings_service::current_settings().window);
+
+            let menu = build_tray_menu(app, &[])?;
+
+            // Left click opens the quick-ask popover; the menu is on right click
             let _tray = TrayIconBuilder::with_id("tray")
                 .menu(&menu)
+                .show_menu_on_left_click(false)
                 .icon(app.default_window_icon().unwrap().clone())
                 .build(app)?;
             
-            // Register global shortcut: Cmd+Option+N (Mac) / Ctrl+Alt+N (Windows/Linux)
+            // Register global shortcuts: Cmd+Option+N (Mac) / Ctrl+Alt+N (Windows/Linux) toggles the
+            // window, Cmd+Option+L / Ctrl+Alt+L looks up the selected text
             app.global_shortcut().register("CmdOrCtrl+Alt+N")?;
+            app.global_shortcut().register(QUICK_LOOKUP_SHORTCUT)?;
+
+            // Shortcuts of notes pinned for paste - a taken shortcut only disables that pin
+            for pin in services::storage_service::load_pinned_pastes().map(|db| db.pins).unwrap_or_default() {
+                if let Err(e) = app.global_shortcut().register(pin.shortcut.as_str()) {
+                    warn!("Failed to register paste shortcut {}: {}", pin.shortcut, e);
+                }
+            }
+            app.state::<PinnedShortcuts>().reload();
+
+            // Let the frontend know when a deferred AI title replaces the placeholder
+            let handle = app.handle().clone();
+            services::ai_queue_service::set_title_updated_listener(move |note| {
+                let _ = handle.emit("note:title-updated", note);
+            });
+
+            // Quick lookup popup follows the pending/answered lookup
+            let handle = app.handle().clone();
+            services::quick_lookup_service::set_quick_lookup_listener(move |lookup| {
+                let _ = handle.emit("quick-lookup:updated", lookup);
+            });
+
+            // Model comparison answers arrive one by one
+            let handle = app.handle().clone();
+            services::ai_service::set_compare_listener(move |answer| {
+                let _ = handle.emit("ai-compare:answer", answer);
+            });
+
+            // Tray popover follows its pending/answered question
+            let handle = app.handle().clone();
+            services::quick_ask_service::set_quick_ask_listener(move |ask| {
+                let _ = handle.emit("quick-ask:updated", ask);
+            });
+
+            // Per-file progress of background imports
+            let handle = app.handle().clone();
+            services::job_service::set_job_listener(move |job| {
+                let _ = handle.emit("import:progress", job);
+            });
+
+            // Focus sessions show their remaining time in the tray tooltip
+            let handle = app.handle().clone();
+            services::focus_service::set_focus_listener(move |session| {
+                if let Some(tray) = handle.tray_by_id("tray") {
+                    let tooltip = match session {
+                        Some(s) => format!("NowledgeBase - focusing, {} min left", (s.remaining_seconds + 59) / 60),
+                        None => tray_tooltip(services::stats_service::vault_summary().ok().as_ref()),
+                    };
+                    let _ = tray.set_tooltip(Some(tooltip));
+                }
+                let _ = handle.emit("focus:updated", session);
+            });
+
+            // Vault summary in the tray tooltip (unless a focus session owns it) and menu
+            let handle = app.handle().clone();
+            services::stats_service::set_summary_listener(move |summary| {
+                if let Some(item) = TRAY_SUMMARY_ITEM.lock().unwrap_or_else(|e| e.into_inner()).as_ref() {
+                    let _ = item.set_text(summary.describe());
+                }
+                if let Some(tray) = handle.tray_by_id("tray").filter(|_| !services::focus_service::is_focusing()) {
+                    let _ = tray.set_tooltip(Some(tray_tooltip(Some(summary))));
+                }
+            });
+
+            // App lock: the frontend swaps in its lock screen when this fires
+            let handle = app.handle().clone();
+            services::app_lock_service::set_lock_listener(move |status| {
+                let _ = handle.emit("app-lock:changed", status);
+                refresh_tray_menu(&handle);
+            });
+
+            // Per-note sync badges refresh when this fires
+            let handle = app.handle().clone();
+            services::sync_service::set_sync_listener(move |overview| {
+                let _ = handle.emit("sync:updated", overview);
+            });
+
+            // Tray icon style and badge dot, drawn once now and again on every change
+            let handle = app.handle().clone();
+            apply_tray_icon(&handle, &services::tray_service::tray_icon_state());
+            services::tray_service::set_tray_listener(move |state| apply_tray_icon(&handle, state));
+
+            // Background jobs (each one idles until configured in settings)
+            tauri::async_runtime::spawn(services::email_service::run_email_capture_loop());
+            tauri::async_runtime::spawn(services::feed_service::run_feed_refresh_loop());
+            tauri::async_runtime::spawn(services::webhook_service::run_reminder_watch_loop());
+            tauri::async_runtime::spawn(services::ai_queue_service::run_ai_queue_loop());
+            tauri::async_runtime::spawn(services::text_expansion_service::run_text_expansion_loop());
+            tauri::async_runtime::spawn(services::focus_service::run_focus_loop());
+            tauri::async_runtime::spawn(services::storage_service::run_write_flush_loop());
+            tauri::async_runtime::spawn(services::stats_service::run_vault_summary_loop());
+            tauri::async_runtime::spawn(services::app_lock_service::run_idle_lock_loop());
+            tauri::async_runtime::spawn(run_idle_hide_loop(app.handle().clone()));
+            tauri::async_runtime::spawn(services::sync_service::run_sync_loop());
+            tauri::async_runtime::spawn(services::retention_service::run_retention_loop());
+            tauri::async_runtime::spawn(services::maintenance_service::run_maintenance_loop());
+
+            // Nothing above reads the notes; they're loaded here while the window comes up
+            let handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                services::storage_service::preload_stores();
+                refresh_tray_menu(&handle);
+                if let Err(e) = services::quick_switch_service::warm_switcher_index() {
+                    warn!("Failed to build the quick switcher index: {}", e);
+                }
+            });
             
             Ok(())
         })
         .on_tray_icon_event(|app, event| match event {
-            TrayIconEvent::Click { .. } => {
-                toggle_window(app);
+            TrayIconEvent::Click { position, button: MouseButton::Left, button_state: MouseButtonState::Up, .. } => {
+                toggle_quick_ask_window(app, position);
             }
             TrayIconEvent::Enter { .. } => {}
             TrayIconEvent::Leave { .. } => {}
@@ -231,12 +2247,17 @@ fn main() {
         })
         .on_menu_event(|app, event| match event.id().as_ref() {
             "quit" => {
+                if let Err(e) = services::storage_service::flush_pending_writes() {
+                    error!("Failed to write notes before quitting: {}", e);
+                }
                 std::process::exit(0);
             }
             "show" => {
                 if let Some(window) = app.get_webview_window("main") {
+                    services::app_lock_service::lock_on_show();
                     let _ = window.show();
                     let _ = window.set_focus();
+                    services::tray_service::set_tray_badge(TrayBadge::JobFinished, false);
                 }
             }
             "hide" => {
@@ -244,6 +2265,14 @@ fn main() {
                     let _ = window.hide();
                 }
             }
+            "reset-position" => {
+                if let Err(e) = reset_main_window(app) {
+                    error!("{}", e);
+                }
+            }
+            id if id.starts_with("paste:") => {
+                paste_pinned_note_in_background(id["paste:".len()..].to_string());
+            }
             _ => {}
         })
         .on_window_event(|window, event| match event {
@@ -251,8 +2280,30 @@ fn main() {
                 window.hide().unwrap();
                 api.prevent_close();
             }
+            // Bringing the window back counts as activity, so the idle timers start over
+            tauri::WindowEvent::Focused(true) if window.label() == "main" => {
+                services::app_lock_service::record_activity();
+            }
+            // The popover goes away like a menu when something else is clicked
+            tauri::WindowEvent::Focused(false) if window.label() == QUICK_ASK_WINDOW => {
+                let _ = window.hide();
+                *QUICK_ASK_HIDDEN_AT.lock().unwrap_or_else(|e| e.into_inner()) = Some(Instant::now());
+            }
+            tauri::WindowEvent::Moved(_) | tauri::WindowEvent::Resized(_) if REMEMBERED_WINDOWS.contains(&window.label()) => {
+                if let Some(window) = window.get_webview_window(window.label()) {
+                    remember_window_placement(window);
+                }
+            }
             _ => {}
         })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|_app, event| {
+            // Deferred note writes must not be lost when the app goes away
+            if let tauri::RunEvent::Exit = event {
+                if let Err(e) = services::storage_service::flush_pending_writes() {
+                    error!("Failed to write notes on exit: {}", e);
+                }
+            }
+        });
 }
\ No newline at end of file
@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+use chrono::NaiveDate;
+
+/// Writing activity aggregated per local calendar day
+#[derive(Serialize, Deserialize, Clone)]
+pub struct DailyActivity {
+    pub date: NaiveDate,
+    pub notes_created: u32,
+    pub words_written: u64, // Sum of positive word deltas from creates and edits
+    pub edits: u32,
+    #[serde(default)]
+    pub ai_calls: u32, // Successful AI requests
+    #[serde(default)]
+    pub reviews: u32,  // Status/rating changes from the review workflow
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct ActivityDatabase {
+    pub days: Vec<DailyActivity>,
+}
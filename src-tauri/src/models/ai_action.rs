@@ -0,0 +1,27 @@
+use serde::{Deserialize, Serialize};
+
+/// A user-defined AI action, e.g. "Extract decisions" or "Translate to German"
+#[derive(Serialize, Deserialize, Clone)]
+pub struct AiAction {
+    pub id: String,
+    pub name: String,
+    pub prompt_template: String, // Supports {{text}}, {{title}} and {{category}} placeholders
+    pub target: AiActionTarget,
+    pub output: AiActionOutput,
+}
+
+/// What text the action runs over
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub enum AiActionTarget {
+    Selection,
+    Note,
+    Category,
+}
+
+/// What happens with the model's answer
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub enum AiActionOutput {
+    Replace,
+    Append,
+    NewNote,
+}
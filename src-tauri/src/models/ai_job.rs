@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+
+/// AI work postponed while offline, replayed when connectivity returns
+#[derive(Serialize, Deserialize, Clone)]
+pub struct AiJob {
+    pub id: String,
+    pub kind: AiJobKind,
+    pub note_id: String,
+    pub created_at: DateTime<Utc>,
+    #[serde(default)]
+    pub attempts: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub enum AiJobKind {
+    Title,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct AiJobsDatabase {
+    pub jobs: Vec<AiJob>,
+}
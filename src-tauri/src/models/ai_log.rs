@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+
+/// Token counts as reported by the provider
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct TokenUsage {
+    pub prompt_tokens: u32,
+    pub completion_tokens: u32,
+    pub total_tokens: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct AiLogEntry {
+    pub id: String,
+    pub timestamp: DateTime<Utc>,
+    pub feature: String, // e.g. "title", "chat:brief", "action:<name>"
+    pub model: String,
+    pub prompt: String,   // Replaced with a placeholder when redaction is on
+    pub response: String,
+    pub latency_ms: u64,
+    pub usage: Option<TokenUsage>,
+    pub error: Option<String>,
+    pub redacted: bool,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct AiLogDatabase {
+    pub entries: Vec<AiLogEntry>,
+}
@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+
+/// An answer drawn from the vault, with the notes it cites
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SourcedAnswer {
+    pub id: String,
+    pub question: String,
+    pub answer: String,              // Cites sources as [1], [2]... in `cited_note_ids` order
+    pub cited_note_ids: Vec<String>,
+    pub model: String,
+    pub asked_at: DateTime<Utc>,
+    #[serde(default)]
+    pub note_id: Option<String>, // The note it was turned into, if any
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct AnswerHistoryDatabase {
+    pub answers: Vec<SourcedAnswer>, // Oldest first
+}
@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Attachment {
+    pub id: String,
+    pub file_name: String,    // Original file name as imported
+    pub stored_path: String,  // Path relative to the app data dir (e.g., "attachments/<id>/diagram.png")
+    pub mime_type: String,
+    pub size_bytes: u64,
+    pub created_at: DateTime<Utc>,
+}
@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use crate::models::{Category, NoteLink};
+
+/// Something removed from the vault, kept so the graph can be shown as it was before
+#[derive(Serialize, Deserialize, Clone)]
+pub struct AuditEntry {
+    pub at: DateTime<Utc>,
+    pub event: AuditEvent,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(tag = "type")]
+pub enum AuditEvent {
+    // Every event is a deletion - the JSON names say so, and existing logs keep loading
+    #[serde(rename = "NoteDeleted")]
+    Note {
+        note_id: String,
+        title: String,
+        category_path: Vec<String>,
+        created_at: DateTime<Utc>,
+    },
+    #[serde(rename = "LinkDeleted")]
+    Link { link: NoteLink },
+    #[serde(rename = "CategoryDeleted")]
+    Category { category: Category },
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct AuditLogDatabase {
+    pub entries: Vec<AuditEntry>, // Oldest first
+}
@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use crate::models::NoteStatus;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Board {
+    pub id: String,
+    pub name: String,
+    pub columns: Vec<BoardColumn>,
+    pub category_path: Option<Vec<String>>, // Limit the board to one category subtree
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct BoardColumn {
+    pub id: String,
+    pub name: String,
+    pub rule: ColumnRule,
+}
+
+/// Which notes belong in a column
+#[derive(Serialize, Deserialize, Clone)]
+pub enum ColumnRule {
+    Status(NoteStatus),
+    Tag(String),
+}
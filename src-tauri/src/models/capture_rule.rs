@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+
+/// Auto-filing rule: notes saved without a category go to `category_path` when `condition`
+/// matches. Rules are tried in order and the first match wins.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct CaptureRule {
+    pub id: String,
+    pub name: String,
+    pub condition: CaptureCondition,
+    pub category_path: Vec<String>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(tag = "type", content = "value")]
+pub enum CaptureCondition {
+    Regex(String),     // Matched against the content (and title)
+    UrlDomain(String), // Content links to this domain or a subdomain of it
+    Tag(String),       // Note has the tag, or #tag appears in the content
+}
@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
+use crate::models::Visibility;
 
 fn default_created_at() -> DateTime<Utc> {
     Utc::now()
@@ -15,9 +16,21 @@ pub struct Category {
     pub path: Vec<String>,    // Path array for compatibility (["Technical", "Python", "Flask"])
     #[serde(default)]
     pub level: u32,          // Hierarchy depth (0 = root, 1 = child, etc.)
-    pub note_count: u32,     // Cached count of notes in this category
+    pub note_count: u32,     // Same as `total_note_count`, kept for older readers
+    #[serde(default)]
+    pub direct_note_count: u32, // Notes filed in exactly this category
+    #[serde(default)]
+    pub total_note_count: u32,  // Notes in this category and all its subcategories
     #[serde(default = "default_created_at")]
     pub created_at: DateTime<Utc>, // When category was created
     pub color: Option<String>, // Optional color for UI
+    #[serde(default)]
+    pub auto_created: bool,    // Made by filing a note under a new path, not by the user
+    #[serde(default)]
+    pub flagged_empty: bool,   // Empty auto-created leaf, set under `EmptyCategoryCleanup::Flag`
+    #[serde(default)]
+    pub visibility: Option<Visibility>, // Applies to notes and subcategories that don't set their own
+    #[serde(default)]
+    pub ai_excluded: bool, // Keeps this category's notes, subcategories included, away from AI
 }
 
@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+
+/// Bibliographic data for a `Reference` note. Field names follow BibTeX.
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct Citation {
+    pub entry_type: String,  // article, book, inproceedings, misc...
+    pub citation_key: String,
+    pub authors: Vec<String>, // As written, "Last, First" or "First Last"
+    pub title: String,
+    pub year: Option<i32>,
+    pub journal: Option<String>, // Journal or book title / proceedings
+    pub publisher: Option<String>,
+    pub volume: Option<String>,
+    pub issue: Option<String>,
+    pub pages: Option<String>,
+    pub doi: Option<String>,
+    pub url: Option<String>,
+    pub isbn: Option<String>,
+}
@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+
+/// Two notes the AI thinks make conflicting claims, waiting for the user to accept it as
+/// a `Contradicts` link or dismiss it
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ContradictionProposal {
+    pub id: String,
+    pub source_id: String,
+    pub target_id: String,
+    pub explanation: String, // What conflicts - becomes the link label
+    pub proposed_at: DateTime<Utc>,
+    #[serde(default)]
+    pub dismissed: bool, // Kept so the pair isn't proposed again
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct ContradictionsDatabase {
+    pub proposals: Vec<ContradictionProposal>,
+}
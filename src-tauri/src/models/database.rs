@@ -1,7 +1,8 @@
 use serde::{Deserialize, Serialize};
-use crate::models::{Note, Category, NoteLink};
+use std::collections::HashMap;
+use crate::models::{Note, Category, NoteLink, Board, FeedSubscription, Webhook};
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct NotesDatabase {
     pub notes: Vec<Note>,
 }
@@ -14,4 +15,21 @@ pub struct CategoriesDatabase {
 #[derive(Serialize, Deserialize)]
 pub struct LinksDatabase {
     pub links: Vec<NoteLink>,
-}
\ No newline at end of file
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct BoardsDatabase {
+    pub boards: Vec<Board>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct FeedsDatabase {
+    pub feeds: Vec<FeedSubscription>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct WebhooksDatabase {
+    pub webhooks: Vec<Webhook>,
+    #[serde(default)]
+    pub reminders_sent: HashMap<String, String>, // Note id -> due date last announced (RFC 3339)
+}
@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+
+/// Unsaved editor text, autosaved so it survives crashes and accidental closes
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Draft {
+    pub id: String,              // Chosen by the editor so repeated autosaves hit the same draft
+    pub content: String,
+    pub note_id: Option<String>, // Set when the draft is an edit of an existing note
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct DraftsDatabase {
+    pub drafts: Vec<Draft>,
+}
@@ -0,0 +1,14 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct FeedSubscription {
+    pub id: String,
+    pub url: String,
+    pub title: String,
+    pub category_path: Vec<String>, // Where new items are filed (defaults to ["Feeds", <title>])
+    #[serde(default)]
+    pub seen_guids: Vec<String>,    // Item ids already captured, for deduplication
+    pub last_fetched: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
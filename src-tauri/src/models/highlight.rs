@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+
+/// A passage marked in a book or article, with the reader's own comment
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Highlight {
+    pub id: String,
+    pub text: String,
+    pub location: Option<String>, // "page 12", "loc. 1234-1236", ...
+    pub comment: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
@@ -11,15 +11,22 @@ pub struct NoteLink {
     pub color: Option<LinkColor>,
     pub directional: Option<bool>,
     pub created_at: DateTime<Utc>,
+    #[serde(default)]
+    pub target_block: Option<String>, // `^block-id` inside the target note, None = whole note
+    #[serde(default)]
+    pub weight: Option<f32>, // Strength 0-1, None = never computed
+    #[serde(default)]
+    pub weight_is_manual: bool, // Set by hand - recompute_link_weights leaves it alone
 }
 
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
 pub enum LinkType {
     Related,
     Reference,
     FollowUp,
     Contradicts,
     Supports,
+    Embed, // Source transcludes the target with ![[...]]
     Custom(String),
 }
 
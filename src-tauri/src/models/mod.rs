@@ -3,10 +3,54 @@ pub mod category;
 pub mod link;
 pub mod database;
 pub mod ui_state;
+pub mod attachment;
+pub mod board;
+pub mod activity;
+pub mod settings;
+pub mod feed;
+pub mod webhook;
+pub mod ai_action;
+pub mod ai_log;
+pub mod ai_job;
+pub mod draft;
+pub mod citation;
+pub mod highlight;
+pub mod reminder;
+pub mod pin;
+pub mod answer;
+pub mod contradiction;
+pub mod audit;
+pub mod capture_rule;
+pub mod profile;
+pub mod sync;
+pub mod vault;
+pub mod retention;
 
 // Re-export all public structs for easy importing
-pub use note::{Note, GraphPosition};
+pub use note::{Note, NoteKind, NoteStatus, Visibility, GraphPosition, Provenance, ReadingState, QaThread, QaExchange};
 pub use category::Category;
 pub use link::{NoteLink, LinkType, LinkColor};
-pub use database::{NotesDatabase, CategoriesDatabase, LinksDatabase};
-pub use ui_state::{GraphViewport, UIState, UIStateDatabase};
\ No newline at end of file
+pub use database::{NotesDatabase, CategoriesDatabase, LinksDatabase, BoardsDatabase, FeedsDatabase, WebhooksDatabase};
+pub use ui_state::{GraphViewport, UIState, UIStateDatabase, WindowPlacement};
+pub use attachment::Attachment;
+pub use board::{Board, BoardColumn, ColumnRule};
+pub use activity::{ActivityDatabase, DailyActivity};
+pub use settings::{AppSettings, AutoLinkSettings, ChunkStrategy, DuplicateDetection, EmailCaptureSettings, EmptyCategoryCleanup, IdleHideSettings, ResponseTypeSettings, SettingsDatabase, SyncSettings, TitlePolicy, TitleStrategy, CategoryTitleStrategy, TimelineLinks, TrayIconStyle, WindowSettings};
+pub use feed::FeedSubscription;
+pub use webhook::{Webhook, WebhookEvent};
+pub use ai_action::{AiAction, AiActionOutput, AiActionTarget};
+pub use ai_log::{AiLogDatabase, AiLogEntry, TokenUsage};
+pub use ai_job::{AiJob, AiJobKind, AiJobsDatabase};
+pub use draft::{Draft, DraftsDatabase};
+pub use citation::Citation;
+pub use highlight::Highlight;
+pub use reminder::{Frequency, Recurrence};
+pub use pin::{PinnedPaste, PinnedPastesDatabase};
+pub use answer::{AnswerHistoryDatabase, SourcedAnswer};
+pub use contradiction::{ContradictionProposal, ContradictionsDatabase};
+pub use audit::{AuditEntry, AuditEvent, AuditLogDatabase};
+pub use capture_rule::{CaptureCondition, CaptureRule};
+pub use profile::{Profile, ProfilesDatabase};
+pub use sync::{NoteSyncState, SyncStateDatabase, SyncStatus};
+pub use vault::{CrossVaultLink, MountedVault, MountedVaultsDatabase};
+pub use retention::{RetentionAction, RetentionRule};
@@ -1,7 +1,10 @@
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use uuid::Uuid;
+use crate::models::{Attachment, Citation, Highlight, Recurrence};
 
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone, Default)]
 pub struct Note {
     pub id: String,
     pub title: String,             // Auto-generated title or first line if short
@@ -13,11 +16,189 @@ pub struct Note {
     
     // Graph positioning
     pub position: Option<GraphPosition>,
+
+    // Files stored alongside the note, referenced in content as `attachment:<id>`
+    #[serde(default)]
+    pub attachments: Vec<Attachment>,
+
+    // User-defined fields (source, author, ...) - free-form so no schema change is needed
+    #[serde(default)]
+    pub metadata: HashMap<String, serde_json::Value>,
+
+    // Review workflow
+    #[serde(default)]
+    pub status: Option<NoteStatus>,
+    #[serde(default)]
+    pub rating: Option<u8>, // 1-5 stars
+
+    #[serde(default)]
+    pub due_date: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub recurrence: Option<Recurrence>, // Repeats the due date; `due_date` is the first occurrence
+
+    // Bumped on every edit so concurrent editors can detect stale writes
+    #[serde(default)]
+    pub revision: u64,
+
+    // Chat captures keep their structured thread here; `content` holds the rendered text
+    #[serde(default)]
+    pub qa: Option<QaThread>,
+
+    #[serde(default)]
+    pub kind: NoteKind,
+    #[serde(default)]
+    pub language: Option<String>, // Snippets: programming language of `content`
+    #[serde(default)]
+    pub title_is_custom: bool, // Chosen by the user - content edits don't regenerate it
+    #[serde(default)]
+    pub citation: Option<Citation>, // References: what the note is about
+    #[serde(default)]
+    pub highlights: Vec<Highlight>, // References: passages captured while reading
+    #[serde(default)]
+    pub provenance: Option<Provenance>, // Where captured text came from
+    #[serde(default)]
+    pub reading: Option<ReadingState>, // Set while the note is in the read-later queue
+    #[serde(default)]
+    pub author: Option<String>, // Id of the profile that was active when the note was created
+    #[serde(default)]
+    pub visibility: Option<Visibility>, // None = inherited from the category (see visibility_service)
+    #[serde(default)]
+    pub ai_excluded: bool, // Never sent to the AI provider (see ai_privacy_service)
 }
 
+/// Who a note may reach through export, webhooks, sync and publishing
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub enum Visibility {
+    Private, // Never leaves this machine
+    #[default]
+    Shared,  // Exports and services the user set up, but not published
+    Public,  // Anywhere, including published HTML
+}
+
+/// A note's place in the read-later queue
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ReadingState {
+    pub queued_at: DateTime<Utc>,
+    pub progress: u8, // Percent read, 0-100
+    pub read_at: Option<DateTime<Utc>>,
+}
+
+/// Where a note's text was captured from, shown and exported as a citation line
 #[derive(Serialize, Deserialize, Clone)]
+pub struct Provenance {
+    pub app: Option<String>, // e.g. "Safari"
+    pub url: Option<String>,
+    pub captured_at: DateTime<Utc>,
+}
+
+/// What a note holds - regular prose or something with its own structure
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq)]
+pub enum NoteKind {
+    #[default]
+    Standard,
+    Snippet,   // `content` is raw code in `language`
+    Reference, // A paper/book/page described by `citation`; `content` holds the reader's notes
+}
+
+/// Workflow stage - digital-garden growth stages or simple task states
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub enum NoteStatus {
+    Seedling,
+    Growing,
+    Evergreen,
+    Todo,
+    Doing,
+    Done,
+    Custom(String),
+}
+
+impl NoteStatus {
+    pub fn parse(value: &str) -> Self {
+        match value.trim().to_lowercase().as_str() {
+            "seedling" => NoteStatus::Seedling,
+            "growing" => NoteStatus::Growing,
+            "evergreen" => NoteStatus::Evergreen,
+            "todo" => NoteStatus::Todo,
+            "doing" => NoteStatus::Doing,
+            "done" => NoteStatus::Done,
+            _ => NoteStatus::Custom(value.trim().to_string()),
+        }
+    }
+}
+
+/// A captured AI conversation: the original question plus any follow-ups
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct QaThread {
+    pub exchanges: Vec<QaExchange>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct QaExchange {
+    pub question: String,
+    pub answer: String,
+    pub model: Option<String>,
+    pub asked_at: DateTime<Utc>,
+}
+
+/// Between exchanges in the rendered content
+const QA_SEPARATOR: &str = "\n\n---\n\n";
+
+impl QaThread {
+    /// Plain-text form stored in `content`, in the same "Q: … A: …" shape chat captures always used
+    pub fn render(&self) -> String {
+        self.exchanges.iter()
+            .map(|e| format!("Q: {}\n\nA: {}", e.question.trim(), e.answer.trim()))
+            .collect::<Vec<_>>()
+            .join(QA_SEPARATOR)
+    }
+
+    /// Read a thread back out of rendered/legacy content. Returns `None` unless every
+    /// section is a "Q: … A: …" pair. `previous` supplies model/time for unchanged questions.
+    pub fn parse(content: &str, previous: Option<&QaThread>, asked_at: DateTime<Utc>) -> Option<Self> {
+        let mut exchanges = Vec::new();
+        for (index, section) in content.trim().split(QA_SEPARATOR).enumerate() {
+            let rest = section.trim().strip_prefix("Q:")?;
+            let split = rest.find("\n\nA:")?;
+            let question = rest[..split].trim().to_string();
+            let answer = rest[split + 4..].trim().to_string();
+
+            let earlier = previous
+                .and_then(|thread| thread.exchanges.get(index))
+                .filter(|e| e.question.trim() == question);
+            exchanges.push(QaExchange {
+                question,
+                answer,
+                model: earlier.and_then(|e| e.model.clone()),
+                asked_at: earlier.map(|e| e.asked_at).unwrap_or(asked_at),
+            });
+        }
+        Some(QaThread { exchanges })
+    }
+}
+
+impl Note {
+    /// Create a fresh note with a new id and the current timestamp
+    pub fn new(title: String, content: String, category_path: Vec<String>) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            title,
+            content,
+            category_path,
+            timestamp: Utc::now(),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
 pub struct GraphPosition {
     pub x: f64,
     pub y: f64,
     pub z_index: Option<i32>,
+    #[serde(default)]
+    pub width: Option<f64>, // None = the card's natural size
+    #[serde(default)]
+    pub height: Option<f64>,
+    #[serde(default)]
+    pub collapsed: bool, // Card shows only its title
 }
\ No newline at end of file
@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+
+/// A note whose content a global shortcut pastes into whatever app is in front
+#[derive(Serialize, Deserialize, Clone)]
+pub struct PinnedPaste {
+    pub note_id: String,
+    pub shortcut: String, // Accelerator, e.g. "CmdOrCtrl+Alt+1"
+    pub pinned_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct PinnedPastesDatabase {
+    pub pins: Vec<PinnedPaste>,
+}
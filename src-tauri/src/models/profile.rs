@@ -0,0 +1,16 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+
+/// A person using this machine's vault - notes they create carry the profile's id
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Profile {
+    pub id: String,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct ProfilesDatabase {
+    pub profiles: Vec<Profile>,
+    pub active_profile_id: Option<String>, // None = notes are saved without an author
+}
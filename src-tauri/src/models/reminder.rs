@@ -0,0 +1,216 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc, Weekday};
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+/// Recurrence of a note's reminder - the subset of RFC 5545 RRULE the app understands
+/// (FREQ, INTERVAL, BYDAY for weekly rules, COUNT, UNTIL). The note's `due_date` is the
+/// first occurrence; later ones keep its local time of day.
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub struct Recurrence {
+    pub frequency: Frequency,
+    #[serde(default = "default_interval")]
+    pub interval: u32, // Every N days/weeks/...
+    #[serde(default)]
+    pub weekdays: Vec<Weekday>, // Weekly rules only; empty = the due date's weekday
+    #[serde(default)]
+    pub count: Option<u32>, // Total occurrences, including the first
+    #[serde(default)]
+    pub until: Option<DateTime<Utc>>,
+}
+
+fn default_interval() -> u32 {
+    1
+}
+
+const WEEKDAY_CODES: [(&str, Weekday); 7] = [
+    ("MO", Weekday::Mon), ("TU", Weekday::Tue), ("WE", Weekday::Wed), ("TH", Weekday::Thu),
+    ("FR", Weekday::Fri), ("SA", Weekday::Sat), ("SU", Weekday::Sun),
+];
+
+impl Recurrence {
+    pub fn new(frequency: Frequency, interval: u32) -> Self {
+        Recurrence { frequency, interval: interval.max(1), weekdays: Vec::new(), count: None, until: None }
+    }
+
+    /// Read "daily", "weekly", "monthly", "yearly", "every 2 weeks", "every monday and
+    /// thursday", or an RRULE such as "FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,TH"
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let value = value.trim();
+        let upper = value.to_uppercase();
+        if let Some(rule) = upper.strip_prefix("RRULE:").or(upper.contains("FREQ=").then_some(upper.as_str())) {
+            return Self::parse_rrule(rule);
+        }
+
+        let lower = value.to_lowercase().replace(',', " ");
+        let words: Vec<&str> = lower.split_whitespace()
+            .filter(|w| !matches!(*w, "every" | "each" | "and" | "on" | "other"))
+            .collect();
+        let every_other = lower.split_whitespace().any(|w| w == "other");
+
+        let weekdays: Vec<Weekday> = words.iter().filter_map(|w| w.parse::<Weekday>().ok()).collect();
+        if !weekdays.is_empty() && weekdays.len() == words.len() {
+            let mut rule = Self::new(Frequency::Weekly, if every_other { 2 } else { 1 });
+            rule.weekdays = weekdays;
+            return Ok(rule);
+        }
+
+        let (interval, unit) = match words.as_slice() {
+            [unit] => (if every_other { 2 } else { 1 }, *unit),
+            [amount, unit] => (amount.parse::<u32>().map_err(|_| unknown_rule(value))?, *unit),
+            _ => return Err(unknown_rule(value)),
+        };
+        let frequency = match unit.trim_end_matches('s') {
+            "daily" | "day" => Frequency::Daily,
+            "weekly" | "week" => Frequency::Weekly,
+            "fortnightly" | "fortnight" => return Ok(Self::new(Frequency::Weekly, interval * 2)),
+            "monthly" | "month" => Frequency::Monthly,
+            "quarterly" | "quarter" => return Ok(Self::new(Frequency::Monthly, interval * 3)),
+            "yearly" | "annually" | "year" => Frequency::Yearly,
+            _ => return Err(unknown_rule(value)),
+        };
+        Ok(Self::new(frequency, interval))
+    }
+
+    fn parse_rrule(rule: &str) -> Result<Self, String> {
+        let mut frequency = None;
+        let mut recurrence = Self::new(Frequency::Daily, 1);
+
+        for part in rule.split(';').map(str::trim).filter(|p| !p.is_empty()) {
+            let (key, value) = part.split_once('=').ok_or_else(|| format!("Invalid RRULE part '{}'", part))?;
+            match key {
+                "FREQ" => frequency = Some(match value {
+                    "DAILY" => Frequency::Daily,
+                    "WEEKLY" => Frequency::Weekly,
+                    "MONTHLY" => Frequency::Monthly,
+                    "YEARLY" => Frequency::Yearly,
+                    _ => return Err(format!("Unsupported RRULE frequency '{}'", value)),
+                }),
+                "INTERVAL" => recurrence.interval = value.parse::<u32>()
+                    .map_err(|_| format!("Invalid RRULE interval '{}'", value))?
+                    .max(1),
+                "BYDAY" => recurrence.weekdays = value.split(',')
+                    .map(|code| WEEKDAY_CODES.iter().find(|(c, _)| *c == code).map(|(_, day)| *day)
+                        .ok_or_else(|| format!("Unsupported RRULE weekday '{}'", code)))
+                    .collect::<Result<_, _>>()?,
+                "COUNT" => recurrence.count = Some(value.parse().map_err(|_| format!("Invalid RRULE count '{}'", value))?),
+                "UNTIL" => recurrence.until = Some(parse_rrule_date(value)?),
+                "WKST" => {}
+                _ => return Err(format!("Unsupported RRULE part '{}'", key)),
+            }
+        }
+
+        recurrence.frequency = frequency.ok_or("RRULE is missing FREQ")?;
+        if !recurrence.weekdays.is_empty() && recurrence.frequency != Frequency::Weekly {
+            return Err("BYDAY is only supported for weekly rules".to_string());
+        }
+        Ok(recurrence)
+    }
+
+    /// The rule in RRULE form, e.g. "FREQ=WEEKLY;INTERVAL=2;BYDAY=MO"
+    pub fn to_rrule(&self) -> String {
+        let frequency = match self.frequency {
+            Frequency::Daily => "DAILY",
+            Frequency::Weekly => "WEEKLY",
+            Frequency::Monthly => "MONTHLY",
+            Frequency::Yearly => "YEARLY",
+        };
+        let mut rule = format!("FREQ={}", frequency);
+        if self.interval > 1 {
+            rule.push_str(&format!(";INTERVAL={}", self.interval));
+        }
+        if !self.weekdays.is_empty() {
+            let days: Vec<&str> = self.weekdays.iter()
+                .filter_map(|day| WEEKDAY_CODES.iter().find(|(_, d)| d == day).map(|(c, _)| *c))
+                .collect();
+            rule.push_str(&format!(";BYDAY={}", days.join(",")));
+        }
+        if let Some(count) = self.count {
+            rule.push_str(&format!(";COUNT={}", count));
+        }
+        if let Some(until) = self.until {
+            rule.push_str(&format!(";UNTIL={}", until.format("%Y%m%dT%H%M%SZ")));
+        }
+        rule
+    }
+
+    /// "every 2 weeks on Mon, Thu"
+    pub fn describe(&self) -> String {
+        let unit = match self.frequency {
+            Frequency::Daily => "day",
+            Frequency::Weekly => "week",
+            Frequency::Monthly => "month",
+            Frequency::Yearly => "year",
+        };
+        let mut text = if self.interval == 1 { format!("every {}", unit) } else { format!("every {} {}s", self.interval, unit) };
+        if !self.weekdays.is_empty() {
+            let days: Vec<String> = self.weekdays.iter().map(|d| d.to_string()).collect();
+            text.push_str(&format!(" on {}", days.join(", ")));
+        }
+        text
+    }
+}
+
+fn unknown_rule(value: &str) -> String {
+    format!("Couldn't understand recurrence '{}'. Try \"weekly\", \"every 2 months\" or an RRULE like FREQ=WEEKLY;BYDAY=MO", value)
+}
+
+/// UNTIL as 20261231, 20261231T090000 or 20261231T090000Z (all read as UTC)
+fn parse_rrule_date(value: &str) -> Result<DateTime<Utc>, String> {
+    let value = value.trim_end_matches('Z');
+    NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S")
+        .or_else(|_| NaiveDate::parse_from_str(value, "%Y%m%d").map(|d| d.and_hms_opt(23, 59, 59).unwrap_or_default()))
+        .map(|d| Utc.from_utc_datetime(&d))
+        .map_err(|_| format!("Invalid RRULE UNTIL '{}'", value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_rrules_and_writes_them_back() {
+        for rule in ["FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,TH", "FREQ=DAILY;COUNT=5", "FREQ=MONTHLY;UNTIL=20261231T235959Z", "FREQ=YEARLY"] {
+            assert_eq!(Recurrence::parse(rule).unwrap().to_rrule(), rule);
+        }
+
+        let rule = Recurrence::parse("RRULE:FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,TH;WKST=MO").unwrap();
+        assert!(rule.frequency == Frequency::Weekly);
+        assert_eq!(rule.interval, 2);
+        assert_eq!(rule.weekdays, vec![Weekday::Mon, Weekday::Thu]);
+    }
+
+    #[test]
+    fn reads_a_date_only_until_as_the_end_of_that_day() {
+        let rule = Recurrence::parse("FREQ=DAILY;UNTIL=20261231").unwrap();
+        assert_eq!(rule.until, Some(Utc.with_ymd_and_hms(2026, 12, 31, 23, 59, 59).unwrap()));
+    }
+
+    #[test]
+    fn rejects_rrules_outside_the_subset() {
+        for rule in ["FREQ=HOURLY", "INTERVAL=2", "FREQ=DAILY;BYDAY=MO", "FREQ=WEEKLY;BYDAY=1MO", "FREQ=DAILY;BYMONTH=3", "FREQ=DAILY;COUNT=x"] {
+            assert!(Recurrence::parse(rule).is_err(), "{} should be rejected", rule);
+        }
+    }
+
+    #[test]
+    fn parses_plain_english_rules() {
+        let parsed = |value: &str| {
+            let rule = Recurrence::parse(value).unwrap();
+            (rule.frequency, rule.interval, rule.weekdays)
+        };
+        assert!(parsed("daily") == (Frequency::Daily, 1, Vec::new()));
+        assert!(parsed("every 2 weeks") == (Frequency::Weekly, 2, Vec::new()));
+        assert!(parsed("every other month") == (Frequency::Monthly, 2, Vec::new()));
+        assert!(parsed("fortnightly") == (Frequency::Weekly, 2, Vec::new()));
+        assert!(parsed("quarterly") == (Frequency::Monthly, 3, Vec::new()));
+        assert!(parsed("every monday and thursday") == (Frequency::Weekly, 1, vec![Weekday::Mon, Weekday::Thu]));
+        assert!(Recurrence::parse("every now and then").is_err());
+    }
+}
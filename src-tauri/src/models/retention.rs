@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+
+/// Housekeeping for one category (and its subcategories): what happens to notes once they
+/// are `older_than_days` old. When rules overlap, the one for the deepest category wins,
+/// so a subcategory can override - or with `Keep`, opt out of - its parent's rule.
+/// Notes are aged from when they were created.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct RetentionRule {
+    pub id: String,
+    pub category_path: Vec<String>, // Empty = every note
+    pub older_than_days: u32,
+    pub action: RetentionAction,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum RetentionAction {
+    Archive, // Move under the "Archive" category, keeping the rest of the path
+    Delete,  // Delete for good (recorded in the audit log)
+    Keep,    // Never touch these notes, whatever a parent category's rule says
+}
@@ -0,0 +1,332 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use crate::models::{AiAction, CaptureRule, RetentionRule};
+
+/// User-configurable application settings. Every field has a default so older
+/// settings files keep loading as new options are added.
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct AppSettings {
+    pub email_capture: EmailCaptureSettings,
+    pub feeds: FeedSettings,
+    pub ai_actions: Vec<AiAction>,
+    pub ai_models: AiModelSettings,
+    pub ai_log: AiLogSettings,
+    pub offline_mode: bool, // Never call the AI provider; AI work is queued instead
+    pub last_vault_export: Option<DateTime<Utc>>, // Set by export_vault_archive, used to judge backup freshness
+    pub log_level: Option<String>, // error | warn | info | debug | trace (defaults to info)
+    pub text_expansion: bool, // Expand snippet triggers typed in any app (global keyboard hook)
+    pub window: WindowSettings,
+    pub locale: Option<String>, // Language of backend text (see locale_service); None = system language
+    pub ai_language: Option<String>, // Language AI answers are written in, e.g. "German"; None = the question's language
+    pub default_category: Option<Vec<String>>, // Where notes saved without a category go; None = "General"
+    pub capture_rules: Vec<CaptureRule>,
+    pub title_policy: TitlePolicy, // What happens when a title is already used in the category
+    pub timeline_links: TimelineLinks, // Link each new note into a chain (see timeline_service)
+    pub title_strategy: TitleStrategy, // How notes saved without a title get one
+    pub category_title_strategies: Vec<CategoryTitleStrategy>, // Overrides; the deepest matching category wins
+    pub duplicates: DuplicateSettings, // Whether capturing known content returns the existing note
+    pub empty_categories: EmptyCategoryCleanup, // What happens to auto-created categories left without notes
+    pub tray_icon: TrayIconStyle,
+    pub chunking: ChunkingSettings, // Notes longer than one AI request can take
+    pub auto_links: AutoLinkSettings, // Related links created when a note is saved
+    pub app_lock: AppLockSettings,
+    pub idle_hide: IdleHideSettings, // Hide the window after a while without user activity
+    pub sync: SyncSettings,
+    pub retention_rules: Vec<RetentionRule>,
+    pub last_retention_run: Option<DateTime<Utc>>, // Set by the retention job so it runs once a day
+    pub last_maintenance_run: Option<DateTime<Utc>>, // Set by run_maintenance_loop so it runs once a week
+}
+
+/// Note content shared with other devices through a relay (see sync_service)
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct SyncSettings {
+    pub enabled: bool,
+    pub relay_url: Option<String>,
+    pub vault_id: Option<String>,  // Shared by every device syncing this vault
+    pub device_id: Option<String>, // This machine, generated on first sync
+    pub interval_minutes: u32,
+}
+
+impl Default for SyncSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            relay_url: None,
+            vault_id: None,
+            device_id: None,
+            interval_minutes: 5,
+        }
+    }
+}
+
+/// Hides the main window once the user has been away for a while
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct IdleHideSettings {
+    pub minutes: u32, // Hide after this long without activity, 0 = never
+    pub lock: bool,   // Also lock the app (needs a PIN) when the window is hidden this way
+}
+
+/// PIN lock in front of all note data
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct AppLockSettings {
+    pub enabled: bool,
+    pub pin_hash: Option<String>, // "salt$hash", never the PIN itself
+    pub idle_minutes: u32,        // Lock after this long without a command, 0 = never
+    pub lock_on_show: bool,       // Lock whenever the window is brought back from the tray
+}
+
+impl Default for AppLockSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            pin_hash: None,
+            idle_minutes: 10,
+            lock_on_show: true,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct AutoLinkSettings {
+    pub enabled: bool,
+    pub max_links_per_note: usize, // Auto links a note can have - the best matches win
+    pub min_similarity: f32,       // 0-1 share of title or content words two notes must have in common
+    pub rare_tag_max_notes: usize, // A tag on at most this many notes links them all
+}
+
+impl Default for AutoLinkSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_links_per_note: 3,
+            min_similarity: 0.35,
+            rare_tag_max_notes: 5,
+        }
+    }
+}
+
+/// What happens to text too long to send to the AI in one request
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub enum ChunkStrategy {
+    #[default]
+    MapReduce, // Summarize it piece by piece, then work from the summaries
+    Truncate,  // Send only the beginning
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct ChunkingSettings {
+    pub strategy: ChunkStrategy,
+    pub max_input_chars: usize, // Longest text sent as is
+    pub chunk_chars: usize,     // Size of each piece summarized under `MapReduce`
+}
+
+impl Default for ChunkingSettings {
+    fn default() -> Self {
+        Self {
+            strategy: ChunkStrategy::MapReduce,
+            max_input_chars: 24_000,
+            chunk_chars: 8_000,
+        }
+    }
+}
+
+/// How the tray icon is drawn
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub enum TrayIconStyle {
+    #[default]
+    Color,    // The app icon as is
+    Template, // Monochrome, tinted by macOS to match the menu bar
+    Dark,     // White glyph, for dark menu bars and taskbars
+    Light,    // Black glyph, for light ones
+}
+
+/// Handling of empty leaf categories that were created automatically when a note was
+/// filed under a new path. Categories the user made are never touched.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub enum EmptyCategoryCleanup {
+    #[default]
+    Keep,
+    Flag,  // Mark them `flagged_empty` so the sidebar can offer to remove them
+    Prune, // Remove them as soon as their last note goes
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub enum DuplicateDetection {
+    Off,
+    #[default]
+    Exact,   // Same text, ignoring case and whitespace
+    Similar, // Also text sharing at least `threshold` of its phrases
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct DuplicateSettings {
+    pub detection: DuplicateDetection,
+    pub threshold: f32, // 0-1, only used by `Similar`
+}
+
+impl Default for DuplicateSettings {
+    fn default() -> Self {
+        Self {
+            detection: DuplicateDetection::Exact,
+            threshold: 0.9,
+        }
+    }
+}
+
+/// Where the title of a note saved without one comes from
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub enum TitleStrategy {
+    #[default]
+    Ai,        // AI title for substantial content, the content itself when it's short
+    FirstLine, // The first line, shortened
+    Date,      // When the note was created
+    Ask,       // Never invent one - the user has to give a title
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct CategoryTitleStrategy {
+    pub category_path: Vec<String>, // Applies to the category and its subcategories
+    pub strategy: TitleStrategy,
+}
+
+/// Automatic `FollowUp` links made when a note is created
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub enum TimelineLinks {
+    #[default]
+    Off,
+    Category,  // From the previous note in the same category
+    DailyNote, // From the daily note of the day it was created, if there is one
+}
+
+/// Uniqueness of note titles within a category, so wikilinks and aliases resolve to one note
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub enum TitlePolicy {
+    #[default]
+    Allow,  // Duplicates are fine
+    Suffix, // "Title (2)", "Title (3)", ...
+    Reject, // Refuse the save
+    Merge,  // Refuse the save and offer merging into the existing note
+}
+
+/// How the main window sits on the desktop, applied at startup
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct WindowSettings {
+    pub always_on_top: bool,
+    pub opacity: f64,   // 0.3 - 1.0, applied by the page since windows have no native opacity
+    pub vibrancy: bool, // macOS vibrancy / Windows acrylic behind the translucent page
+}
+
+impl Default for WindowSettings {
+    fn default() -> Self {
+        Self {
+            always_on_top: true,
+            opacity: 1.0,
+            vibrancy: false,
+        }
+    }
+}
+
+/// Opt-in log of every AI request. With `redact_content` only metadata is kept,
+/// never the note text that was sent.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct AiLogSettings {
+    pub enabled: bool,
+    pub redact_content: bool,
+    pub max_entries: usize, // Oldest entries are dropped beyond this
+}
+
+impl Default for AiLogSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            redact_content: false,
+            max_entries: 1000,
+        }
+    }
+}
+
+/// Per-feature model overrides. Unset roles fall back to the `AI_*_MODEL` env vars, then `AI_MODEL`.
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct AiModelSettings {
+    pub default_model: Option<String>,
+    pub title: Option<String>,
+    pub tagging: Option<String>,
+    pub chat: Option<String>,
+    pub detailed_chat: Option<String>,
+    pub embedding: Option<String>,
+    pub response_types: Vec<ResponseTypeSettings>, // Per chat response type, built-in or new
+}
+
+/// Model, temperature and length for one chat response type. Types other than the
+/// built-in yes_no / brief / bullet / detailed need `instructions` to be useful.
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct ResponseTypeSettings {
+    pub name: String,
+    pub model: Option<String>,
+    pub temperature: Option<f32>,
+    pub max_tokens: Option<u32>,
+    pub instructions: Option<String>, // Replaces the built-in instructions for this type
+}
+
+/// IMAP inbox polling. The password is read from `IMAP_PASSWORD` in `.env`, never stored here.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct EmailCaptureSettings {
+    pub enabled: bool,
+    pub imap_host: String,
+    pub imap_port: u16,
+    pub username: String,
+    pub mailbox: String,                  // Folder/label to watch
+    pub recipient_filter: Option<String>, // Only capture mail sent to this (plus-)address
+    pub poll_interval_minutes: u32,
+    pub category_path: Vec<String>,
+}
+
+impl Default for EmailCaptureSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            imap_host: String::new(),
+            imap_port: 993,
+            username: String::new(),
+            mailbox: "INBOX".to_string(),
+            recipient_filter: None,
+            poll_interval_minutes: 5,
+            category_path: vec!["Inbox".to_string()],
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct FeedSettings {
+    pub enabled: bool, // Background refresh of subscribed feeds
+    pub poll_interval_minutes: u32,
+    pub max_content_chars: usize, // Item summaries longer than this are cut
+}
+
+impl Default for FeedSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            poll_interval_minutes: 60,
+            max_content_chars: 2000,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct SettingsDatabase {
+    pub settings: AppSettings,
+}
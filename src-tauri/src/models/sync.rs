@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+
+/// Where a note stands with the relay, as shown next to it in the UI
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum SyncStatus {
+    Synced,  // The relay has exactly this content
+    Pending, // Edited (or never sent) since the last sync
+    Error,   // The last attempt to sync this note failed
+    Private, // Kept off the relay by its visibility
+}
+
+/// What the last sync left behind for one note
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct NoteSyncState {
+    pub synced_hash: Option<String>, // Content hash when the note was last in step with the relay
+    pub last_synced_at: Option<DateTime<Utc>>,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct SyncStateDatabase {
+    pub cursor: Option<String>, // Relay position - only changes after it are sent back
+    pub last_sync_at: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+    pub notes: HashMap<String, NoteSyncState>,
+}
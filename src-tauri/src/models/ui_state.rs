@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 #[derive(Serialize, Deserialize, Clone)]
 pub struct GraphViewport {
@@ -7,12 +8,27 @@ pub struct GraphViewport {
     pub zoom: f64,
 }
 
+/// Where a window was last left, in physical pixels
+#[derive(Serialize, Deserialize, Clone)]
+pub struct WindowPlacement {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    #[serde(default)]
+    pub maximized: bool,
+}
+
 #[derive(Serialize, Deserialize, Clone)]
 pub struct UIState {
     pub graph_viewport: GraphViewport,
+    /// Monitor layout key -> window label -> placement, so docking and undocking a
+    /// laptop puts each window back where it was on that set of screens
+    #[serde(default)]
+    pub window_placements: HashMap<String, HashMap<String, WindowPlacement>>,
 }
 
 #[derive(Serialize, Deserialize)]
 pub struct UIStateDatabase {
     pub ui_state: UIState,
-}
\ No newline at end of file
+}
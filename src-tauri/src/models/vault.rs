@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+
+/// Another vault's data folder (e.g. a team vault on a shared drive) shown alongside this one
+#[derive(Serialize, Deserialize, Clone)]
+pub struct MountedVault {
+    pub id: String,
+    pub name: String,
+    pub path: String,   // Folder holding that vault's notes.json / notes/ segments
+    pub read_only: bool,
+    pub mounted_at: DateTime<Utc>,
+}
+
+/// Link from a note in this vault to a note in a mounted vault. Kept here only - the
+/// mounted vault never learns about it.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct CrossVaultLink {
+    pub id: String,
+    pub source_id: String,
+    pub vault_id: String,
+    pub target_id: String,
+    pub label: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct MountedVaultsDatabase {
+    pub vaults: Vec<MountedVault>,
+    pub links: Vec<CrossVaultLink>,
+}
@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Webhook {
+    pub id: String,
+    pub url: String,
+    pub events: Vec<WebhookEvent>,
+    pub secret: Option<String>, // Sent back as X-Webhook-Secret so receivers can verify the caller
+    pub enabled: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub enum WebhookEvent {
+    NoteCreated,
+    NoteUpdated,
+    TagAdded,
+    ReminderDue,
+}
+
+impl WebhookEvent {
+    /// Name used in payloads and accepted by `parse`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookEvent::NoteCreated => "note.created",
+            WebhookEvent::NoteUpdated => "note.updated",
+            WebhookEvent::TagAdded => "tag.added",
+            WebhookEvent::ReminderDue => "reminder.due",
+        }
+    }
+
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "note.created" | "NoteCreated" => Ok(WebhookEvent::NoteCreated),
+            "note.updated" | "NoteUpdated" => Ok(WebhookEvent::NoteUpdated),
+            "tag.added" | "TagAdded" => Ok(WebhookEvent::TagAdded),
+            "reminder.due" | "ReminderDue" => Ok(WebhookEvent::ReminderDue),
+            _ => Err(format!("Unknown webhook event '{}'. Expected note.created, note.updated, tag.added or reminder.due", value)),
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use crate::models::{AiAction, AiActionOutput, AiActionTarget, Note};
+use crate::services::ai_config::{AiConfig, ModelRole};
+use crate::services::ai_service::{fit_to_context, run_prompt};
+use crate::services::note_service::{load_notes, save_note_simplified, update_note_with_title};
+use crate::services::storage_service::{load_settings, save_settings};
+use crate::services::locale_service::tr;
+use crate::services::ai_privacy_service::{ai_allowed_notes, ensure_ai_allowed};
+
+/// What an action should run on, as sent by the frontend
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct AiActionInput {
+    pub note_id: Option<String>,
+    pub selection: Option<String>,
+    pub category_path: Option<Vec<String>>,
+}
+
+#[derive(Serialize)]
+pub struct AiActionResult {
+    pub output: String,
+    pub note: Option<Note>, // Note that was changed or created, if any
+}
+
+pub async fn list_ai_actions() -> Result<Vec<AiAction>, String> {
+    Ok(load_settings()?.settings.ai_actions)
+}
+
+/// Create an action, or update it in place when `id` matches an existing one
+pub async fn save_ai_action(mut action: AiAction) -> Result<AiAction, String> {
+    action.name = action.name.trim().to_string();
+    if action.name.is_empty() {
+        return Err("Action name cannot be empty".to_string());
+    }
+    if action.prompt_template.trim().is_empty() {
+        return Err("Action prompt cannot be empty".to_string());
+    }
+    if action.target == AiActionTarget::Category && action.output != AiActionOutput::NewNote {
+        return Err("Category actions can only write their result to a new note".to_string());
+    }
+
+    let mut database = load_settings()?;
+    if action.id.trim().is_empty() {
+        action.id = Uuid::new_v4().to_string();
+    }
+    match database.settings.ai_actions.iter_mut().find(|a| a.id == action.id) {
+        Some(existing) => *existing = action.clone(),
+        None => database.settings.ai_actions.push(action.clone()),
+    }
+    save_settings(&database)?;
+    Ok(action)
+}
+
+pub async fn delete_ai_action(action_id: String) -> Result<(), String> {
+    let mut database = load_settings()?;
+    let initial_len = database.settings.ai_actions.len();
+    database.settings.ai_actions.retain(|a| a.id != action_id);
+    if database.settings.ai_actions.len() == initial_len {
+        return Err(format!("AI action with id {} not found", action_id));
+    }
+    save_settings(&database)
+}
+
+fn fill_template(template: &str, text: &str, title: &str, category: &str) -> String {
+    let filled = template
+        .replace("{{title}}", title)
+        .replace("{{category}}", category);
+    if filled.contains("{{text}}") {
+        filled.replace("{{text}}", text)
+    } else {
+        // Templates without a placeholder get the text appended
+        format!("{}\n\n{}", filled.trim_end(), text)
+    }
+}
+
+/// Run a user-defined action over a selection, note or category and apply its output
+pub async fn run_ai_action(action_id: String, target: AiActionInput) -> Result<AiActionResult, String> {
+    let action = load_settings()?.settings.ai_actions.into_iter()
+        .find(|a| a.id == action_id)
+        .ok_or_else(|| format!("AI action with id {} not found", action_id))?;
+
+    let database = load_notes()?;
+    let note = match &target.note_id {
+        Some(id) => Some(database.notes.iter()
+            .find(|n| &n.id == id)
+            .cloned()
+            .ok_or_else(|| tr("error.note_not_found", &[&id]))?),
+        None => None,
+    };
+    // A selection comes from the note too, so it's held to the same rule
+    if let Some(note) = &note {
+        ensure_ai_allowed(note)?;
+    }
+
+    let (text, title, category_path) = match action.target {
+        AiActionTarget::Selection => {
+            let selection = target.selection.clone()
+                .filter(|s| !s.trim().is_empty())
+                .ok_or("This action needs selected text")?;
+            let title = note.as_ref().map(|n| n.title.clone()).unwrap_or_default();
+            let path = note.as_ref().map(|n| n.category_path.clone()).unwrap_or_default();
+            (selection, title, path)
+        }
+        AiActionTarget::Note => {
+            let note = note.as_ref().ok_or("This action needs a note")?;
+            (note.content.clone(), note.title.clone(), note.category_path.clone())
+        }
+        AiActionTarget::Category => {
+            let path = target.category_path.clone()
+                .filter(|p| !p.is_empty())
+                .ok_or("This action needs a category")?;
+            let mut text = String::new();
+            let allowed = ai_allowed_notes(database.notes.clone())?;
+            for n in allowed.iter().filter(|n| n.category_path.starts_with(&path)) {
+                text.push_str(&format!("## {}\n{}\n\n", n.title, n.content));
+            }
+            let title = path.last().cloned().unwrap_or_default();
+            (text, title, path)
+        }
+    };
+
+    // Long input is condensed to fit the model - except when the output replaces it,
+    // which would silently throw away everything that was condensed
+    let input = fit_to_context(&format!("action:{}", action.name), &text, &action.name).await?;
+    if input != text && action.output == AiActionOutput::Replace {
+        return Err(format!("This text is too long for \"{}\" to rewrite in one go - try it on a selection", action.name));
+    }
+    let prompt = fill_template(&action.prompt_template, &input, &title, &category_path.join(" > "));
+    let config = AiConfig::load();
+    let output = run_prompt(&format!("action:{}", action.name), &prompt, &config.model_for(ModelRole::DetailedChat), config.detailed_tokens, 0.3).await?;
+
+    let changed = match (&action.output, &note) {
+        (AiActionOutput::NewNote, _) => {
+            let new_title = if title.is_empty() { action.name.clone() } else { format!("{}: {}", action.name, title) };
+            let path = if category_path.is_empty() { None } else { Some(category_path.clone()) };
+            Some(save_note_simplified(output.clone(), path, Some(new_title)).await?.note)
+        }
+        (AiActionOutput::Replace, Some(note)) => {
+            let content = match action.target {
+                AiActionTarget::Selection if note.content.contains(&text) => note.content.replacen(&text, &output, 1),
+                AiActionTarget::Selection => return Err("The selected text is no longer in the note".to_string()),
+                _ => output.clone(),
+            };
+            Some(update_note_with_title(note.id.clone(), content, Some(note.title.clone()), Some(note.revision)).await
+                .map_err(|e| e.to_string())?)
+        }
+        (AiActionOutput::Append, Some(note)) => {
+            let content = format!("{}\n\n{}", note.content.trim_end(), output);
+            Some(update_note_with_title(note.id.clone(), content, Some(note.title.clone()), Some(note.revision)).await
+                .map_err(|e| e.to_string())?)
+        }
+        // A bare selection with no note behind it - the caller places the result
+        _ => None,
+    };
+
+    Ok(AiActionResult { output, note: changed })
+}
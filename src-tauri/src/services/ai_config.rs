@@ -1,4 +1,7 @@
 use std::env;
+use tracing::warn;
+use crate::models::ResponseTypeSettings;
+use crate::services::settings_service::current_settings;
 
 /// AI Configuration constants and validation
 /// 
@@ -14,16 +17,58 @@ pub const DEFAULT_DETAILED_TOKENS: u32 = 1500;
 pub const DEFAULT_YES_NO_TOKENS: u32 = 100;
 pub const DEFAULT_BULLET_TOKENS: u32 = 400;
 
+/// Temperature of chat answers unless the response type sets its own
+pub const DEFAULT_CHAT_TEMPERATURE: f32 = 0.3;
+
+/// Response types with built-in instructions
+pub const BUILT_IN_RESPONSE_TYPES: [&str; 4] = ["yes_no", "brief", "bullet", "detailed"];
+
 /// Maximum reasonable token limit (to prevent excessive API costs)
 pub const MAX_REASONABLE_TOKENS: u32 = 4000;
 
+/// Model used when neither settings nor env pick one
+pub const DEFAULT_MODEL: &str = "deepseek/deepseek-r1";
+
+/// Default model for embeddings - chat models can't produce them
+pub const DEFAULT_EMBEDDING_MODEL: &str = "openai/text-embedding-3-small";
+
+/// Which job a model is requested for. Each role can be routed to its own model.
+#[derive(Clone, Copy, PartialEq)]
+pub enum ModelRole {
+    Title,        // Short, cheap completions - note titles
+    Tagging,      // Tag and category suggestions
+    Chat,         // Brief / bullet / yes-no answers
+    DetailedChat, // Long-form answers
+    Embedding,    // Vectors for semantic search
+}
+
+impl ModelRole {
+    /// Env var that overrides the model for this role
+    fn env_var(&self) -> &'static str {
+        match self {
+            ModelRole::Title => "AI_TITLE_MODEL",
+            ModelRole::Tagging => "AI_TAGGING_MODEL",
+            ModelRole::Chat => "AI_CHAT_MODEL",
+            ModelRole::DetailedChat => "AI_DETAILED_MODEL",
+            ModelRole::Embedding => "AI_EMBEDDING_MODEL",
+        }
+    }
+}
+
 /// AI Configuration structure
 pub struct AiConfig {
-    pub model: String,
+    pub model: String, // Default for every chat role without its own model
+    pub title_model: Option<String>,
+    pub tagging_model: Option<String>,
+    pub chat_model: Option<String>,
+    pub detailed_model: Option<String>,
+    pub embedding_model: Option<String>,
     pub brief_tokens: u32,
     pub detailed_tokens: u32,
     pub yes_no_tokens: u32,
     pub bullet_tokens: u32,
+    pub answer_language: Option<String>, // Every chat answer is written in this language
+    pub response_types: Vec<ResponseTypeSettings>, // Overrides from settings, by response type name
 }
 
 impl AiConfig {
@@ -31,11 +76,18 @@ impl AiConfig {
     pub fn from_env() -> Self {
         let config = Self {
             model: env::var("AI_MODEL")
-                .unwrap_or_else(|_| "deepseek/deepseek-r1".to_string()),
+                .unwrap_or_else(|_| DEFAULT_MODEL.to_string()),
+            title_model: env_model(ModelRole::Title),
+            tagging_model: env_model(ModelRole::Tagging),
+            chat_model: env_model(ModelRole::Chat),
+            detailed_model: env_model(ModelRole::DetailedChat),
+            embedding_model: env_model(ModelRole::Embedding),
             brief_tokens: parse_env_token_limit("MAX_TOKENS", DEFAULT_BRIEF_TOKENS),
             detailed_tokens: parse_env_token_limit("MAX_DETAILED_TOKENS", DEFAULT_DETAILED_TOKENS),
             yes_no_tokens: parse_env_token_limit("MAX_YES_NO_TOKENS", DEFAULT_YES_NO_TOKENS),
             bullet_tokens: parse_env_token_limit("MAX_BULLET_TOKENS", DEFAULT_BULLET_TOKENS),
+            answer_language: env::var("AI_LANGUAGE").ok().filter(|l| !l.trim().is_empty()),
+            response_types: Vec::new(),
         };
         
         // Validate configuration
@@ -43,8 +95,68 @@ impl AiConfig {
         config
     }
     
+    /// Env configuration with the per-feature model choices from settings applied on top
+    pub fn load() -> Self {
+        let mut config = Self::from_env();
+        let settings = current_settings();
+        if let Some(language) = settings.ai_language.filter(|l| !l.trim().is_empty()) {
+            config.answer_language = Some(language);
+        }
+        let models = settings.ai_models;
+        let pick = |setting: Option<String>, current: Option<String>| {
+            setting.filter(|m| !m.trim().is_empty()).or(current)
+        };
+        config.title_model = pick(models.title, config.title_model);
+        config.tagging_model = pick(models.tagging, config.tagging_model);
+        config.chat_model = pick(models.chat, config.chat_model);
+        config.detailed_model = pick(models.detailed_chat, config.detailed_model);
+        config.embedding_model = pick(models.embedding, config.embedding_model);
+        if let Some(default_model) = models.default_model.filter(|m| !m.trim().is_empty()) {
+            config.model = default_model;
+        }
+        config.response_types = models.response_types;
+        config.validate_response_types();
+        config
+    }
+
+    /// Settings for a response type, if it has any
+    fn response_type(&self, response_type: &str) -> Option<&ResponseTypeSettings> {
+        self.response_types.iter().find(|t| t.name == response_type)
+    }
+    
+    /// Model to call for a feature, falling back to the default model
+    pub fn model_for(&self, role: ModelRole) -> String {
+        let specific = match role {
+            ModelRole::Title => &self.title_model,
+            ModelRole::Tagging => &self.tagging_model,
+            ModelRole::Chat => &self.chat_model,
+            ModelRole::DetailedChat => &self.detailed_model,
+            ModelRole::Embedding => &self.embedding_model,
+        };
+        match (specific, role) {
+            (Some(model), _) => model.clone(),
+            (None, ModelRole::Embedding) => DEFAULT_EMBEDDING_MODEL.to_string(),
+            (None, _) => self.model.clone(),
+        }
+    }
+    
+    /// Model for a chat response type: its own if set, otherwise the long-form model for
+    /// "detailed" and the chat model for everything else
+    pub fn chat_model_for(&self, response_type: &str) -> String {
+        if let Some(model) = self.response_type(response_type).and_then(|t| t.model.as_ref()).filter(|m| !m.trim().is_empty()) {
+            model.clone()
+        } else if response_type == "detailed" {
+            self.model_for(ModelRole::DetailedChat)
+        } else {
+            self.model_for(ModelRole::Chat)
+        }
+    }
+    
     /// Get token limit for specific response type
     pub fn get_token_limit(&self, response_type: &str) -> u32 {
+        if let Some(tokens) = self.response_type(response_type).and_then(|t| t.max_tokens).filter(|t| *t > 0) {
+            return tokens;
+        }
         match response_type {
             "detailed" => self.detailed_tokens,
             "yes_no" => self.yes_no_tokens,
@@ -53,26 +165,75 @@ impl AiConfig {
         }
     }
     
+    pub fn temperature_for(&self, response_type: &str) -> f32 {
+        self.response_type(response_type)
+            .and_then(|t| t.temperature)
+            .map(|t| t.clamp(0.0, 2.0))
+            .unwrap_or(DEFAULT_CHAT_TEMPERATURE)
+    }
+
+    /// Instructions replacing the built-in ones for a response type, if configured
+    pub fn instructions_for(&self, response_type: &str) -> Option<&str> {
+        self.response_type(response_type)
+            .and_then(|t| t.instructions.as_deref())
+            .filter(|i| !i.trim().is_empty())
+    }
+
+    /// Response types offered to the user: the built-in ones, then the ones added in settings
+    pub fn response_type_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = BUILT_IN_RESPONSE_TYPES.iter().map(|t| t.to_string()).collect();
+        for response_type in &self.response_types {
+            if !response_type.name.trim().is_empty() && !names.contains(&response_type.name) {
+                names.push(response_type.name.clone());
+            }
+        }
+        names
+    }
+
+    /// Warn about response type settings that will truncate answers or cost a lot
+    fn validate_response_types(&self) {
+        for response_type in &self.response_types {
+            match response_type.max_tokens {
+                Some(tokens) if tokens > 0 && tokens < MIN_SAFE_TOKENS && response_type.name != "yes_no" => {
+                    warn!("max_tokens for '{}' ({}) is below safe minimum ({}). This may cause truncated responses!",
+                             response_type.name, tokens, MIN_SAFE_TOKENS);
+                }
+                Some(tokens) if tokens > MAX_REASONABLE_TOKENS => {
+                    warn!("max_tokens for '{}' ({}) is very high. This may cause excessive API costs!",
+                             response_type.name, tokens);
+                }
+                _ => {}
+            }
+            if !BUILT_IN_RESPONSE_TYPES.contains(&response_type.name.as_str()) && response_type.instructions.is_none() {
+                warn!("Response type '{}' has no instructions - it will be answered like 'brief'", response_type.name);
+            }
+        }
+    }
+
     /// Validate configuration and log warnings for unsafe values
     fn validate(&self) {
         if self.brief_tokens < MIN_SAFE_TOKENS {
-            eprintln!("⚠️  WARNING: brief_tokens ({}) is below safe minimum ({}). This may cause truncated responses!", 
+            warn!("brief_tokens ({}) is below safe minimum ({}). This may cause truncated responses!", 
                      self.brief_tokens, MIN_SAFE_TOKENS);
         }
         
         if self.detailed_tokens < MIN_SAFE_TOKENS {
-            eprintln!("⚠️  WARNING: detailed_tokens ({}) is below safe minimum ({}). This may cause truncated responses!", 
+            warn!("detailed_tokens ({}) is below safe minimum ({}). This may cause truncated responses!", 
                      self.detailed_tokens, MIN_SAFE_TOKENS);
         }
         
         // Check for unreasonably high values
         if self.detailed_tokens > MAX_REASONABLE_TOKENS {
-            eprintln!("⚠️  WARNING: detailed_tokens ({}) is very high. This may cause excessive API costs!", 
+            warn!("detailed_tokens ({}) is very high. This may cause excessive API costs!", 
                      self.detailed_tokens);
         }
     }
 }
 
+fn env_model(role: ModelRole) -> Option<String> {
+    env::var(role.env_var()).ok().filter(|m| !m.trim().is_empty())
+}
+
 /// Parse token limit from environment with validation
 fn parse_env_token_limit(env_var: &str, default: u32) -> u32 {
     env::var(env_var)
@@ -80,11 +241,11 @@ fn parse_env_token_limit(env_var: &str, default: u32) -> u32 {
         .and_then(|s| s.parse().ok())
         .map(|tokens| {
             if tokens < MIN_SAFE_TOKENS {
-                eprintln!("⚠️  WARNING: {} ({}) is below safe minimum ({}). Using minimum safe value.", 
+                warn!("{} ({}) is below safe minimum ({}). Using minimum safe value.", 
                          env_var, tokens, MIN_SAFE_TOKENS);
                 MIN_SAFE_TOKENS
             } else if tokens > MAX_REASONABLE_TOKENS {
-                eprintln!("⚠️  WARNING: {} ({}) is very high. Consider if this is necessary.", 
+                warn!("{} ({}) is very high. Consider if this is necessary.", 
                          env_var, tokens);
                 tokens
             } else {
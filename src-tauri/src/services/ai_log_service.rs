@@ -0,0 +1,85 @@
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use uuid::Uuid;
+use tracing::error;
+use crate::models::{AiLogDatabase, AiLogEntry, TokenUsage};
+use crate::services::settings_service::current_settings;
+use crate::services::storage_service::{load_ai_log, save_ai_log};
+
+/// Filters for `get_ai_log` - every provided field must match
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct AiLogFilter {
+    pub feature: Option<String>, // Prefix match, so "chat" covers every chat response type
+    pub model: Option<String>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+    pub text: Option<String>,    // Case-insensitive match on prompt or response
+    pub errors_only: bool,
+    pub limit: Option<usize>,
+}
+
+fn redacted(text: &str) -> String {
+    format!("[redacted {} chars]", text.chars().count())
+}
+
+/// Append one request to the log if logging is enabled. Never fails the AI call itself.
+pub fn record_ai_interaction(feature: &str, model: &str, prompt: &str, result: &Result<(String, Option<TokenUsage>), String>, latency_ms: u64) {
+    let settings = current_settings().ai_log;
+    if !settings.enabled {
+        return;
+    }
+
+    let (response, usage, error) = match result {
+        Ok((text, usage)) => (text.clone(), usage.clone(), None),
+        Err(e) => (String::new(), None, Some(e.clone())),
+    };
+
+    let entry = AiLogEntry {
+        id: Uuid::new_v4().to_string(),
+        timestamp: Utc::now(),
+        feature: feature.to_string(),
+        model: model.to_string(),
+        prompt: if settings.redact_content { redacted(prompt) } else { prompt.to_string() },
+        response: if settings.redact_content { redacted(&response) } else { response },
+        latency_ms,
+        usage,
+        error,
+        redacted: settings.redact_content,
+    };
+
+    let result = load_ai_log().and_then(|mut database| {
+        database.entries.push(entry);
+        let overflow = database.entries.len().saturating_sub(settings.max_entries.max(1));
+        database.entries.drain(..overflow);
+        save_ai_log(&database)
+    });
+    if let Err(e) = result {
+        error!("Failed to write AI log: {}", e);
+    }
+}
+
+/// Logged AI interactions, newest first
+pub async fn get_ai_log(filter: Option<AiLogFilter>) -> Result<Vec<AiLogEntry>, String> {
+    let filter = filter.unwrap_or_default();
+    let text = filter.text.as_ref().map(|t| t.to_lowercase());
+
+    let mut entries: Vec<AiLogEntry> = load_ai_log()?.entries.into_iter()
+        .filter(|e| filter.feature.as_ref().map(|f| e.feature.starts_with(f.as_str())).unwrap_or(true))
+        .filter(|e| filter.model.as_ref().map(|m| &e.model == m).unwrap_or(true))
+        .filter(|e| filter.since.map(|since| e.timestamp >= since).unwrap_or(true))
+        .filter(|e| filter.until.map(|until| e.timestamp <= until).unwrap_or(true))
+        .filter(|e| !filter.errors_only || e.error.is_some())
+        .filter(|e| text.as_ref().map(|t| e.prompt.to_lowercase().contains(t) || e.response.to_lowercase().contains(t)).unwrap_or(true))
+        .collect();
+
+    entries.reverse();
+    if let Some(limit) = filter.limit {
+        entries.truncate(limit);
+    }
+    Ok(entries)
+}
+
+pub async fn clear_ai_log() -> Result<(), String> {
+    save_ai_log(&AiLogDatabase::default())
+}
@@ -0,0 +1,139 @@
+use serde::Deserialize;
+use crate::models::Note;
+use crate::services::ai_config::{AiConfig, ModelRole};
+use crate::services::ai_service::{fit_to_context, parse_json_reply, run_prompt};
+use crate::services::link_service::create_note_link_with_options;
+use crate::services::note_service::{load_notes, save_note_simplified};
+use crate::services::locale_service::tr;
+use crate::services::ai_privacy_service::ensure_ai_allowed;
+
+#[derive(Deserialize)]
+struct NoteDraft {
+    title: String,
+    content: String,
+}
+
+/// Break a long note into atomic concept notes. They're created in the note's category,
+/// each linked back to the original with a `Reference` link, and returned.
+pub async fn atomize_note(note_id: String) -> Result<Vec<Note>, String> {
+    let original = load_notes()?.notes.into_iter()
+        .find(|n| n.id == note_id)
+        .ok_or_else(|| tr("error.note_not_found", &[&note_id]))?;
+    ensure_ai_allowed(&original)?;
+
+    let prompt = format!(
+        "Split the following note into atomic notes, Zettelkasten style: each one covers exactly one idea or concept, \
+        is understandable on its own, and keeps the original wording where possible. Do not add new information. \
+        Respond with ONLY a JSON array of objects with \"title\" (max 50 characters) and \"content\" fields.\n\n\
+        Title: {}\n\n{}",
+        original.title, original.content
+    );
+    let config = AiConfig::load();
+    let reply = run_prompt("atomize", &prompt, &config.model_for(ModelRole::DetailedChat), config.detailed_tokens, 0.2).await?;
+
+    let drafts: Vec<NoteDraft> = parse_json_reply(&reply)?;
+    let drafts: Vec<NoteDraft> = drafts.into_iter()
+        .filter(|d| !d.content.trim().is_empty())
+        .collect();
+    if drafts.len() < 2 {
+        return Err("The note is already atomic - the AI found nothing to split".to_string());
+    }
+
+    let mut created = Vec::new();
+    for draft in drafts {
+        let note = save_note_simplified(draft.content.trim().to_string(), Some(original.category_path.clone()), Some(draft.title)).await?.note;
+        create_note_link_with_options(
+            note.id.clone(),
+            original.id.clone(),
+            "Reference".to_string(),
+            Some("Atomized from".to_string()),
+            None,
+            Some(true),
+        ).await?;
+        created.push(note);
+    }
+
+    Ok(created)
+}
+
+/// Translate a note into `target_lang` (e.g. "German"). The translation is created as
+/// a new note in the same category, linked back to the original, and returned.
+pub async fn translate_note(note_id: String, target_lang: String) -> Result<Note, String> {
+    let target_lang = target_lang.trim().to_string();
+    if target_lang.is_empty() {
+        return Err("Choose a language to translate into".to_string());
+    }
+    let original = load_notes()?.notes.into_iter()
+        .find(|n| n.id == note_id)
+        .ok_or_else(|| tr("error.note_not_found", &[&note_id]))?;
+    ensure_ai_allowed(&original)?;
+
+    let prompt = format!(
+        "Translate the following note into {}. Keep the Markdown formatting, links, code and names unchanged, \
+        and translate everything else faithfully without adding or leaving out anything. \
+        Respond with ONLY a JSON object with \"title\" and \"content\" fields.\n\n\
+        Title: {}\n\n{}",
+        target_lang, original.title, original.content
+    );
+    let config = AiConfig::load();
+    // A translation is about as long as the original; 3 characters per token leaves headroom
+    let token_limit = ((original.title.len() + original.content.len()) as u32 / 3 + 200).max(config.detailed_tokens);
+    let reply = run_prompt("translate", &prompt, &config.model_for(ModelRole::DetailedChat), token_limit, 0.2).await?;
+
+    let draft: NoteDraft = parse_json_reply(&reply)?;
+    if draft.content.trim().is_empty() {
+        return Err("The AI returned an empty translation".to_string());
+    }
+    let note = save_note_simplified(draft.content.trim().to_string(), Some(original.category_path.clone()), Some(draft.title)).await?.note;
+    create_note_link_with_options(
+        note.id.clone(),
+        original.id.clone(),
+        "Reference".to_string(),
+        Some(format!("Translation ({})", target_lang)),
+        None,
+        Some(true),
+    ).await?;
+
+    Ok(note)
+}
+
+/// The note, if AI features may read it
+fn find_note(note_id: &str) -> Result<Note, String> {
+    let note = load_notes()?.notes.into_iter()
+        .find(|n| n.id == note_id)
+        .ok_or_else(|| tr("error.note_not_found", &[&note_id]))?;
+    ensure_ai_allowed(&note)?;
+    Ok(note)
+}
+
+/// A short summary of a note. Notes too long for one request are summarized in parts first.
+pub async fn summarize_note(note_id: String) -> Result<String, String> {
+    let note = find_note(&note_id)?;
+    let content = fit_to_context("summarize", &note.content, "the main points of the note").await?;
+
+    let prompt = format!(
+        "Summarize the following note in one short paragraph, keeping its key facts and conclusions. \
+        Respond with ONLY the summary.\n\nTitle: {}\n\n{}",
+        note.title, content
+    );
+    let config = AiConfig::load();
+    run_prompt("summarize", &prompt, &config.model_for(ModelRole::Chat), config.detailed_tokens, 0.2).await
+}
+
+/// Answer a question about one note, using only what the note says
+pub async fn ask_ai_about_note(note_id: String, question: String) -> Result<String, String> {
+    let question = question.trim().to_string();
+    if question.is_empty() {
+        return Err("Ask a question about the note".to_string());
+    }
+    let note = find_note(&note_id)?;
+    let content = fit_to_context("ask_note", &note.content, &question).await?;
+
+    let prompt = format!(
+        "Answer the question using only the note below. If the note doesn't say, answer that it doesn't.\n\n\
+        Title: {}\n\n{}\n\nQuestion: {}",
+        note.title, content, question
+    );
+    let config = AiConfig::load();
+    run_prompt("ask_note", &prompt, &config.model_for(ModelRole::DetailedChat), config.detailed_tokens, 0.3).await
+}
@@ -0,0 +1,59 @@
+use crate::models::{Category, Note};
+use crate::services::category_service::load_categories;
+use crate::services::note_service::load_notes;
+use crate::services::storage_service::{save_categories, save_notes};
+use crate::services::locale_service::tr;
+
+/// Excluded if the note is, or any category on its path is - a subcategory can't opt
+/// back in to AI once a parent has opted out
+pub fn is_ai_excluded_in(note: &Note, categories: &[Category]) -> bool {
+    note.ai_excluded || (1..=note.category_path.len()).any(|depth| categories.iter()
+        .any(|c| c.ai_excluded && c.path == note.category_path[..depth]))
+}
+
+/// Whether a note must stay away from the AI provider. If the categories can't be read
+/// the note is treated as excluded.
+pub fn is_ai_excluded(note: &Note) -> bool {
+    note.ai_excluded || load_categories()
+        .map(|db| is_ai_excluded_in(note, &db.categories))
+        .unwrap_or(true)
+}
+
+/// The notes AI features may read, in their original order
+pub fn ai_allowed_notes(notes: Vec<Note>) -> Result<Vec<Note>, String> {
+    let categories = load_categories()?.categories;
+    Ok(notes.into_iter()
+        .filter(|note| !is_ai_excluded_in(note, &categories))
+        .collect())
+}
+
+pub fn ensure_ai_allowed(note: &Note) -> Result<(), String> {
+    if is_ai_excluded(note) {
+        return Err(format!("'{}' is excluded from AI features", note.title));
+    }
+    Ok(())
+}
+
+pub async fn set_note_ai_excluded(note_id: String, excluded: bool) -> Result<Note, String> {
+    let mut database = load_notes()?;
+    let note = database.notes.iter_mut()
+        .find(|n| n.id == note_id)
+        .ok_or_else(|| tr("error.note_not_found", &[&note_id]))?;
+    note.ai_excluded = excluded;
+    note.revision += 1;
+    let updated = note.clone();
+    save_notes(&database)?;
+    Ok(updated)
+}
+
+/// Exclude a category, with all its notes and subcategories, from AI features
+pub async fn set_category_ai_excluded(category_path: Vec<String>, excluded: bool) -> Result<Category, String> {
+    let mut database = load_categories()?;
+    let category = database.categories.iter_mut()
+        .find(|c| c.path == category_path)
+        .ok_or_else(|| format!("Category {} not found", category_path.join(" → ")))?;
+    category.ai_excluded = excluded;
+    let updated = category.clone();
+    save_categories(&database)?;
+    Ok(updated)
+}
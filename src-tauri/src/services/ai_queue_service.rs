@@ -0,0 +1,214 @@
+use chrono::Utc;
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+use uuid::Uuid;
+use tracing::{error, warn};
+use crate::models::{AiJob, AiJobKind, Note};
+use crate::services::ai_service::{generate_note_title, generate_simple_title};
+use crate::services::note_service::{apply_title_policy, load_notes};
+use crate::services::settings_service::current_settings;
+use crate::services::storage_service::{load_ai_jobs, save_ai_jobs, save_notes, load_settings, save_settings};
+
+/// Set when an AI request fails to reach the provider, cleared once one gets through
+static NETWORK_DOWN: AtomicBool = AtomicBool::new(false);
+
+/// Guards against two tasks draining the queue at once
+static QUEUE_RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// Called with the note whenever a queued AI title replaces its placeholder.
+/// main.rs installs one that emits `note:title-updated` to the frontend.
+type TitleListener = Box<dyn Fn(&Note) + Send + Sync>;
+static TITLE_LISTENER: OnceLock<TitleListener> = OnceLock::new();
+
+/// Give up on a job after this many failed runs that weren't connectivity problems
+const MAX_JOB_ATTEMPTS: u32 = 5;
+
+#[derive(Serialize)]
+pub struct OfflineStatus {
+    pub offline: bool,
+    pub manual: bool,        // Offline mode switched on in settings
+    pub auto_detected: bool, // Provider unreachable on the last attempt
+    pub pending_jobs: usize,
+}
+
+/// True when AI calls should be skipped - either by choice or because the network is down
+pub fn is_offline() -> bool {
+    current_settings().offline_mode || NETWORK_DOWN.load(Ordering::Relaxed)
+}
+
+/// Record whether the last AI request reached the provider
+pub fn report_network_result(reachable: bool) {
+    let was_down = NETWORK_DOWN.swap(!reachable, Ordering::Relaxed);
+    if !reachable && !was_down {
+        warn!("AI provider unreachable - switching to offline mode until it responds again");
+    }
+}
+
+/// Queue AI work for a note. Only one job of each kind is kept per note.
+pub fn enqueue_ai_job(kind: AiJobKind, note_id: &str) {
+    let result = load_ai_jobs().and_then(|mut database| {
+        if database.jobs.iter().any(|j| j.kind == kind && j.note_id == note_id) {
+            return Ok(());
+        }
+        database.jobs.push(AiJob {
+            id: Uuid::new_v4().to_string(),
+            kind,
+            note_id: note_id.to_string(),
+            created_at: Utc::now(),
+            attempts: 0,
+        });
+        save_ai_jobs(&database)
+    });
+    if let Err(e) = result {
+        error!("Failed to queue AI job: {}", e);
+    }
+}
+
+/// Replace a note's placeholder title with an AI one. A title the user changed in the
+/// meantime is left alone.
+async fn run_title_job(note_id: &str) -> Result<(), String> {
+    let Some(note) = load_notes()?.notes.into_iter().find(|n| n.id == note_id) else {
+        return Ok(()); // Note was deleted - nothing to do
+    };
+    let placeholder = generate_simple_title(&note.content);
+    if note.title_is_custom || note.title != placeholder {
+        return Ok(());
+    }
+
+    let title = generate_note_title(&note).await?;
+
+    // Reload in case the note changed while the request was in flight
+    let mut database = load_notes()?;
+    let title = apply_title_policy(&database.notes, note_id, &note.category_path, title, false)
+        .map_err(|collision| collision.to_string())?;
+    if let Some(current) = database.notes.iter_mut().find(|n| n.id == note_id) {
+        if !current.title_is_custom && current.title == placeholder && current.title != title {
+            current.title = title;
+            current.revision += 1;
+            let updated = current.clone();
+            save_notes(&database)?;
+            if let Some(listener) = TITLE_LISTENER.get() {
+                listener(&updated);
+            }
+        }
+    }
+    Ok(())
+}
+
+pub fn set_title_updated_listener(listener: impl Fn(&Note) + Send + Sync + 'static) {
+    let _ = TITLE_LISTENER.set(Box::new(listener));
+}
+
+/// Drain the queue in the background, unless another task already is
+pub fn spawn_queue_processing() {
+    tokio::spawn(async {
+        if let Err(e) = process_ai_queue().await {
+            error!("Failed to process AI queue: {}", e);
+        }
+    });
+}
+
+/// Work through the queue until it's empty or the provider becomes unreachable
+pub async fn process_ai_queue() -> Result<usize, String> {
+    if QUEUE_RUNNING.swap(true, Ordering::Acquire) {
+        return Ok(0);
+    }
+    let result = drain_queue().await;
+    QUEUE_RUNNING.store(false, Ordering::Release);
+    result
+}
+
+async fn drain_queue() -> Result<usize, String> {
+    let mut completed = 0;
+    loop {
+        if is_offline() {
+            break;
+        }
+        let Some(job) = load_ai_jobs()?.jobs.first().cloned() else { break };
+
+        let result = match job.kind {
+            AiJobKind::Title => run_title_job(&job.note_id).await,
+        };
+
+        let mut database = load_ai_jobs()?;
+        match result {
+            Ok(()) => {
+                database.jobs.retain(|j| j.id != job.id);
+                completed += 1;
+            }
+            Err(_) if is_offline() => break, // Connectivity dropped - keep the job for later
+            Err(e) => {
+                error!("AI job for note {} failed: {}", job.note_id, e);
+                if let Some(stored) = database.jobs.iter_mut().find(|j| j.id == job.id) {
+                    stored.attempts += 1;
+                }
+                // Move it to the back so one bad job doesn't block the rest
+                database.jobs.retain(|j| j.id != job.id || j.attempts < MAX_JOB_ATTEMPTS);
+                if let Some(index) = database.jobs.iter().position(|j| j.id == job.id) {
+                    let moved = database.jobs.remove(index);
+                    database.jobs.push(moved);
+                }
+            }
+        }
+        save_ai_jobs(&database)?;
+    }
+    Ok(completed)
+}
+
+/// Cheap reachability check against the provider, without spending tokens
+pub async fn provider_reachable() -> bool {
+    reqwest::Client::new()
+        .get("https://openrouter.ai/api/v1/models")
+        .timeout(std::time::Duration::from_secs(5))
+        .send()
+        .await
+        .is_ok()
+}
+
+/// Background job started at launch: notices when the network is back and drains the queue
+pub async fn run_ai_queue_loop() {
+    loop {
+        tokio::time::sleep(std::time::Duration::from_secs(30)).await;
+
+        if current_settings().offline_mode {
+            continue;
+        }
+        if NETWORK_DOWN.load(Ordering::Relaxed) {
+            if !provider_reachable().await {
+                continue;
+            }
+            report_network_result(true);
+        }
+
+        let has_jobs = load_ai_jobs().map(|db| !db.jobs.is_empty()).unwrap_or(false);
+        if has_jobs {
+            spawn_queue_processing();
+        }
+    }
+}
+
+pub async fn get_offline_status() -> Result<OfflineStatus, String> {
+    let manual = current_settings().offline_mode;
+    let auto_detected = NETWORK_DOWN.load(Ordering::Relaxed);
+    Ok(OfflineStatus {
+        offline: manual || auto_detected,
+        manual,
+        auto_detected,
+        pending_jobs: load_ai_jobs()?.jobs.len(),
+    })
+}
+
+/// Switch offline mode on or off. Turning it off also clears the auto-detected state
+/// and replays queued work in the background.
+pub async fn set_offline_mode(enabled: bool) -> Result<OfflineStatus, String> {
+    let mut database = load_settings()?;
+    database.settings.offline_mode = enabled;
+    save_settings(&database)?;
+
+    if !enabled {
+        report_network_result(true);
+        spawn_queue_processing();
+    }
+    get_offline_status().await
+}
@@ -1,6 +1,32 @@
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use std::env;
-use super::ai_config::AiConfig;
+use std::future::Future;
+use std::sync::{Mutex, OnceLock};
+use std::time::Instant;
+use tokio::sync::oneshot;
+use tracing::instrument;
+use uuid::Uuid;
+use super::ai_config::{AiConfig, ModelRole};
+use super::ai_log_service::record_ai_interaction;
+use super::ai_queue_service::{is_offline, report_network_result};
+use super::settings_service::current_settings;
+use super::stats_service::record_ai_call;
+use super::ai_privacy_service::is_ai_excluded;
+use crate::models::{ChunkStrategy, Note, NoteKind, TokenUsage};
+
+/// How many models `ask_ai_compare` asks at once
+const MIN_COMPARE_MODELS: usize = 2;
+const MAX_COMPARE_MODELS: usize = 3;
+
+/// Smallest text limit honoured from settings - below this chunking just multiplies requests
+const MIN_CONTEXT_CHARS: usize = 2_000;
+/// Rounds of summarizing summaries before the rest is cut
+const MAX_REDUCE_ROUNDS: usize = 3;
+/// Length of each chunk's summary
+const CHUNK_SUMMARY_TOKENS: u32 = 400;
+
+/// Requests started with a request id, and how to stop each one
+static IN_FLIGHT: Mutex<Vec<(String, oneshot::Sender<()>)>> = Mutex::new(Vec::new());
 
 #[derive(Serialize)]
 struct OpenRouterRequest {
@@ -19,6 +45,8 @@ struct Message {
 #[derive(Deserialize)]
 struct OpenRouterResponse {
     choices: Vec<Choice>,
+    #[serde(default)]
+    usage: Option<TokenUsage>,
 }
 
 #[derive(Deserialize)]
@@ -26,28 +54,23 @@ struct Choice {
     message: Message,
 }
 
-pub fn create_concise_prompt(question: &str, response_type: &str) -> String {
-    match response_type {
-        "yes_no" => format!(
-            "You are a concise assistant. Answer with ONLY 'Yes' or 'No' followed by a single brief sentence if needed. If it you cannot answer the question with a yes or no(like a what or how question), then don't answer yes or no in front, act like the brief mode. Question: {}", 
-            question
-        ),
-        "brief" => format!(
-            "You are a concise assistant. Provide the most direct, brief answer possible. No explanations, examples, or elaboration unless absolutely necessary. Maximum 2 sentences. Question: {}", 
-            question
-        ),
-        "bullet" => format!(
-            "You are a concise assistant. Answer with only the key points in bullet format. Maximum 3 bullet points. Question: {}", 
-            question
-        ),
-        "detailed" => format!(
-            "You are a knowledgeable assistant. Provide as comprehensive and detailed answer as you can that fully explains the topic. Include relevant context, examples, and thorough explanations. Be informative and complete. Question: {}", 
-            question
-        ),
-        _ => format!(
-            "You are a concise assistant. Be direct and brief. No unnecessary explanations. Question: {}", 
-            question
-        ),
+/// Prompt for a chat question. With `language` set the answer is requested in that
+/// language whatever the question was asked in.
+pub fn create_concise_prompt(question: &str, response_type: &str, language: Option<&str>) -> String {
+    let instructions = match response_type {
+        "yes_no" => "You are a concise assistant. Answer with ONLY 'Yes' or 'No' followed by a single brief sentence if needed. If it you cannot answer the question with a yes or no(like a what or how question), then don't answer yes or no in front, act like the brief mode.",
+        "brief" => "You are a concise assistant. Provide the most direct, brief answer possible. No explanations, examples, or elaboration unless absolutely necessary. Maximum 2 sentences.",
+        "bullet" => "You are a concise assistant. Answer with only the key points in bullet format. Maximum 3 bullet points.",
+        "detailed" => "You are a knowledgeable assistant. Provide as comprehensive and detailed answer as you can that fully explains the topic. Include relevant context, examples, and thorough explanations. Be informative and complete.",
+        _ => "You are a concise assistant. Be direct and brief. No unnecessary explanations.",
+    };
+    compose_prompt(instructions, question, language)
+}
+
+fn compose_prompt(instructions: &str, question: &str, language: Option<&str>) -> String {
+    match language {
+        Some(language) => format!("{} Always write your answer in {}. Question: {}", instructions, language, question),
+        None => format!("{} Question: {}", instructions, question),
     }
 }
 
@@ -87,26 +110,10 @@ pub fn generate_simple_title(content: &str) -> String {
     }
 }
 
-pub async fn generate_ai_title(content: &str) -> Result<String, String> {
-    let client = reqwest::Client::new();
-    let mut headers = reqwest::header::HeaderMap::new();
-    
-    let api_key = env::var("OPENROUTER_API_KEY")
-        .map_err(|_| "OPENROUTER_API_KEY environment variable not set. Please check your .env file.".to_string())?;
-    
-    let auth_header = format!("Bearer {}", api_key);
-    headers.insert(
-        reqwest::header::AUTHORIZATION,
-        reqwest::header::HeaderValue::from_str(&auth_header)
-            .map_err(|e| format!("Invalid API key format: {}", e))?
-    );
-    headers.insert(
-        reqwest::header::CONTENT_TYPE,
-        reqwest::header::HeaderValue::from_static("application/json")
-    );
-
+pub async fn generate_ai_title(full_content: &str) -> Result<String, String> {
     // Check if this is a Q&A format (chat-to-notes)
-    let is_qa_format = content.starts_with("Q:") && content.contains("\n\nA:");
+    let is_qa_format = full_content.starts_with("Q:") && full_content.contains("\n\nA:");
+    let content = &fit_to_context("title", full_content, "what the text is mainly about").await?;
     
     let title_prompt = if is_qa_format {
         format!(
@@ -120,42 +127,136 @@ pub async fn generate_ai_title(content: &str) -> Result<String, String> {
         )
     };
 
-    let title_request = OpenRouterRequest {
-        model: env::var("AI_MODEL")
-            .unwrap_or_else(|_| "deepseek/deepseek-r1".to_string()),
-        messages: vec![Message {
-            role: "user".to_string(),
-            content: title_prompt,
-        }],
-        max_tokens: 50, // More tokens for better title analysis
-        temperature: 0.1,
-    };
+    let model = AiConfig::load().model_for(ModelRole::Title);
+    let title = run_prompt("title", &title_prompt, &model, 50, 0.1).await // More tokens for better title analysis
+        .map_err(|e| format!("Title request failed: {}", e))?;
+    
+    // Ensure title isn't too long
+    if !title.is_empty() && title.len() <= 60 {
+        return Ok(title);
+    }
+    
+    // Fallback to simple title generation
+    Ok(generate_simple_title(full_content))
+}
 
-    let title_response = client
-        .post("https://openrouter.ai/api/v1/chat/completions")
-        .headers(headers)
-        .json(&title_request)
-        .send()
-        .await
+/// Title for a code snippet, based on what the code does rather than how it reads
+pub async fn generate_snippet_title(code: &str, language: Option<&str>) -> Result<String, String> {
+    let language = language.unwrap_or("unknown language");
+    let title_prompt = format!(
+        "This is a saved code snippet ({}). Generate a short, descriptive title (max 50 characters) that says what the code does, e.g. \"Find files larger than 100MB\" or \"Debounce hook for React\". Respond with ONLY the title:\n\n{}",
+        language, code
+    );
+
+    let model = AiConfig::load().model_for(ModelRole::Title);
+    let title = run_prompt("title", &title_prompt, &model, 50, 0.1).await
         .map_err(|e| format!("Title request failed: {}", e))?;
 
-    if title_response.status().is_success() {
-        let api_response: OpenRouterResponse = title_response
-            .json()
-            .await
-            .map_err(|e| format!("Failed to parse title response: {}", e))?;
-        
-        if let Some(choice) = api_response.choices.first() {
-            let title = choice.message.content.trim().to_string();
-            // Ensure title isn't too long
-            if title.len() <= 60 {
-                return Ok(title);
+    if !title.is_empty() && title.len() <= 60 {
+        return Ok(title);
+    }
+    Ok(generate_simple_title(code))
+}
+
+/// AI title for a note, picking the prompt that fits its kind. Notes excluded from AI
+/// get the first-line title instead, without a request.
+pub async fn generate_note_title(note: &Note) -> Result<String, String> {
+    if is_ai_excluded(note) {
+        return Ok(generate_simple_title(&note.content));
+    }
+    match note.kind {
+        NoteKind::Snippet => generate_snippet_title(&note.content, note.language.as_deref()).await,
+        NoteKind::Standard | NoteKind::Reference => generate_ai_title(&note.content).await,
+    }
+}
+
+/// Split `text` into pieces of at most `chunk_chars` characters, breaking between
+/// paragraphs where possible and otherwise at the last space before the limit
+pub fn split_into_chunks(text: &str, chunk_chars: usize) -> Vec<String> {
+    let chunk_chars = chunk_chars.max(1);
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    for paragraph in text.split("\n\n").filter(|p| !p.trim().is_empty()) {
+        if !current.is_empty() && current.chars().count() + paragraph.chars().count() + 2 > chunk_chars {
+            chunks.push(std::mem::take(&mut current));
+        }
+        let mut rest = paragraph;
+        while rest.chars().count() > chunk_chars {
+            let limit = rest.char_indices().nth(chunk_chars).map(|(i, _)| i).unwrap_or(rest.len());
+            let cut = rest[..limit].rfind(char::is_whitespace).filter(|i| *i > 0).unwrap_or(limit);
+            if !current.is_empty() {
+                chunks.push(std::mem::take(&mut current));
             }
+            chunks.push(rest[..cut].to_string());
+            rest = rest[cut..].trim_start();
+        }
+        if !current.is_empty() {
+            current.push_str("\n\n");
         }
+        current.push_str(rest);
     }
-    
-    // Fallback to simple title generation
-    Ok(generate_simple_title(content))
+    if !current.trim().is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+/// `text` made short enough for one request, following the chunking settings. Under
+/// `MapReduce` every chunk is summarized keeping `focus` in mind (what the result will be
+/// used for) and the joined summaries stand in for the text. Short text is returned as is.
+pub async fn fit_to_context(feature: &str, text: &str, focus: &str) -> Result<String, String> {
+    let settings = current_settings().chunking;
+    let max_chars = settings.max_input_chars.max(MIN_CONTEXT_CHARS);
+    if text.chars().count() <= max_chars {
+        return Ok(text.to_string());
+    }
+    if settings.strategy == ChunkStrategy::Truncate {
+        let kept: String = text.chars().take(max_chars).collect();
+        return Ok(format!("{}\n\n[... the rest was cut to fit]", kept));
+    }
+
+    let model = AiConfig::load().model_for(ModelRole::Chat);
+    let mut current = text.to_string();
+    for _ in 0..MAX_REDUCE_ROUNDS {
+        if current.chars().count() <= max_chars {
+            break;
+        }
+        let chunks = split_into_chunks(&current, settings.chunk_chars.clamp(MIN_CONTEXT_CHARS / 2, max_chars));
+        let total = chunks.len();
+
+        // Map: summarize every chunk at once
+        let mut requests = tokio::task::JoinSet::new();
+        for (index, chunk) in chunks.into_iter().enumerate() {
+            let prompt = format!(
+                "This is part {} of {} of a longer text. Summarize it in a few sentences, keeping every fact, name and figure relevant to: {}. Respond with ONLY the summary.\n\n{}",
+                index + 1, total, focus, chunk
+            );
+            let (feature, model) = (format!("{}:chunk", feature), model.clone());
+            requests.spawn(async move {
+                (index, run_prompt(&feature, &prompt, &model, CHUNK_SUMMARY_TOKENS, 0.2).await)
+            });
+        }
+        let mut summaries: Vec<(usize, String)> = Vec::with_capacity(total);
+        while let Some(finished) = requests.join_next().await {
+            let (index, summary) = finished.map_err(|e| format!("Summary task failed: {}", e))?;
+            summaries.push((index, summary?));
+        }
+        summaries.sort_by_key(|(index, _)| *index);
+
+        // Reduce: the summaries, in order, are the new text
+        current = summaries.into_iter().map(|(_, summary)| summary).collect::<Vec<_>>().join("\n\n");
+    }
+    Ok(current.chars().take(max_chars).collect())
+}
+
+/// Parse a JSON value out of a model reply, tolerating reasoning blocks and ```json fences
+pub fn parse_json_reply<T: DeserializeOwned>(reply: &str) -> Result<T, String> {
+    // Reasoning models may think out loud before answering
+    let reply = reply.rsplit("</think>").next().unwrap_or(reply);
+    let start = reply.find(['[', '{']).ok_or("AI reply did not contain JSON")?;
+    let end = reply.rfind([']', '}']).filter(|&end| end > start).ok_or("AI reply did not contain JSON")?;
+    serde_json::from_str(&reply[start..=end])
+        .map_err(|e| format!("Failed to parse AI reply as JSON: {}", e))
 }
 
 /// Main AI chat function
@@ -167,14 +268,149 @@ pub async fn generate_ai_title(content: &str) -> Result<String, String> {
 /// Now uses configurable limits with validation to prevent regression.
 pub async fn ask_ai(question: String, response_type: Option<String>) -> Result<String, String> {
     let response_type = response_type.unwrap_or_else(|| "brief".to_string());
-    let prompt = create_concise_prompt(&question, &response_type);
-    
     // Load AI configuration with safe defaults
-    let config = AiConfig::from_env();
+    let config = AiConfig::load();
+    let prompt = chat_prompt(&config, &question, &response_type);
     
-    // Get token limit for response type
-    let token_limit = if response_type == "detailed" { 1500 } else { 500 };
+    // Token limit, model and temperature all follow the response type's settings
+    let token_limit = config.get_token_limit(&response_type);
+    let temperature = config.temperature_for(&response_type);
     
+    run_prompt(&format!("chat:{}", response_type), &prompt, &config.chat_model_for(&response_type), token_limit, temperature).await
+}
+
+fn chat_prompt(config: &AiConfig, question: &str, response_type: &str) -> String {
+    let language = config.answer_language.as_deref();
+    match config.instructions_for(response_type) {
+        Some(instructions) => compose_prompt(instructions, question, language),
+        None => create_concise_prompt(question, response_type, language),
+    }
+}
+
+/// One model's answer in a comparison
+#[derive(Serialize, Clone)]
+pub struct ModelAnswer {
+    pub compare_id: String,
+    pub model: String,
+    pub answer: Option<String>,
+    pub error: Option<String>, // This model failed; the others still answer
+    pub elapsed_ms: u64,
+}
+
+/// Called with each answer of `ask_ai_compare` as soon as it arrives. main.rs installs
+/// one that emits `ai-compare:answer`.
+type CompareListener = Box<dyn Fn(&ModelAnswer) + Send + Sync>;
+static COMPARE_LISTENER: OnceLock<CompareListener> = OnceLock::new();
+
+pub fn set_compare_listener(listener: impl Fn(&ModelAnswer) + Send + Sync + 'static) {
+    let _ = COMPARE_LISTENER.set(Box::new(listener));
+}
+
+/// Ask the same question of 2-3 models at once, e.g. a cheap and a premium one. Each
+/// answer is published as it arrives; the result lists them in the order of `models`.
+/// `request_id` labels the published answers and makes the whole comparison cancellable.
+pub async fn ask_ai_compare(question: String, models: Vec<String>, response_type: Option<String>, request_id: Option<String>) -> Result<Vec<ModelAnswer>, String> {
+    let mut unique: Vec<String> = Vec::new();
+    for model in models.into_iter().map(|m| m.trim().to_string()).filter(|m| !m.is_empty()) {
+        if !unique.contains(&model) {
+            unique.push(model);
+        }
+    }
+    if !(MIN_COMPARE_MODELS..=MAX_COMPARE_MODELS).contains(&unique.len()) {
+        return Err(format!("Pick {} to {} different models to compare", MIN_COMPARE_MODELS, MAX_COMPARE_MODELS));
+    }
+
+    let response_type = response_type.unwrap_or_else(|| "brief".to_string());
+    let config = AiConfig::load();
+    let prompt = chat_prompt(&config, &question, &response_type);
+    let token_limit = config.get_token_limit(&response_type);
+    let temperature = config.temperature_for(&response_type);
+    let compare_id = request_id.clone().unwrap_or_else(|| Uuid::new_v4().to_string());
+    let feature = format!("compare:{}", response_type);
+
+    // Dropping the set (on cancel) aborts every model's request
+    let compare = async {
+        let mut requests = tokio::task::JoinSet::new();
+        for (index, model) in unique.iter().cloned().enumerate() {
+            let (prompt, feature, compare_id) = (prompt.clone(), feature.clone(), compare_id.clone());
+            requests.spawn(async move {
+                let started = Instant::now();
+                let result = run_prompt(&feature, &prompt, &model, token_limit, temperature).await;
+                let (answer, error) = match result {
+                    Ok(answer) => (Some(answer), None),
+                    Err(e) => (None, Some(e)),
+                };
+                let answer = ModelAnswer { compare_id, model, answer, error, elapsed_ms: started.elapsed().as_millis() as u64 };
+                if let Some(listener) = COMPARE_LISTENER.get() {
+                    listener(&answer);
+                }
+                (index, answer)
+            });
+        }
+
+        let mut answers: Vec<(usize, ModelAnswer)> = Vec::new();
+        while let Some(finished) = requests.join_next().await {
+            answers.push(finished.map_err(|e| format!("Comparison task failed: {}", e))?);
+        }
+        answers.sort_by_key(|(index, _)| *index);
+        Ok(answers.into_iter().map(|(_, answer)| answer).collect())
+    };
+    cancellable(request_id, compare).await
+}
+
+/// `ask_ai` that `cancel_ai_request(request_id)` can stop. The caller picks the id
+/// before asking, so it can cancel while the answer is still on its way.
+pub async fn ask_ai_cancellable(question: String, response_type: Option<String>, request_id: Option<String>) -> Result<String, String> {
+    cancellable(request_id, ask_ai(question, response_type)).await
+}
+
+/// Run an AI request until it finishes or is cancelled. Cancelling drops the request's
+/// future, which aborts the HTTP call instead of waiting out its timeout.
+pub async fn cancellable<T>(request_id: Option<String>, request: impl Future<Output = Result<T, String>>) -> Result<T, String> {
+    let Some(request_id) = request_id else { return request.await };
+    let (cancel, cancelled) = oneshot::channel();
+    IN_FLIGHT.lock().unwrap_or_else(|e| e.into_inner()).push((request_id.clone(), cancel));
+
+    let result = tokio::select! {
+        result = request => result,
+        _ = cancelled => Err("AI request cancelled".to_string()),
+    };
+    IN_FLIGHT.lock().unwrap_or_else(|e| e.into_inner()).retain(|(id, _)| id != &request_id);
+    result
+}
+
+/// Stop an in-flight request. Returns false if it had already finished.
+pub async fn cancel_ai_request(request_id: String) -> Result<bool, String> {
+    let cancel = {
+        let mut in_flight = IN_FLIGHT.lock().unwrap_or_else(|e| e.into_inner());
+        in_flight.iter().position(|(id, _)| *id == request_id).map(|index| in_flight.remove(index).1)
+    };
+    Ok(cancel.is_some_and(|cancel| cancel.send(()).is_ok()))
+}
+
+/// Response types `ask_ai` understands, including ones added in settings
+pub async fn list_response_types() -> Result<Vec<String>, String> {
+    Ok(AiConfig::load().response_type_names())
+}
+
+/// Send a single-message prompt to `model` and return the trimmed reply. `feature` names
+/// the caller in the AI interaction log.
+pub async fn run_prompt(feature: &str, prompt: &str, model: &str, max_tokens: u32, temperature: f32) -> Result<String, String> {
+    let started = Instant::now();
+    let result = send_prompt(prompt, model, max_tokens, temperature).await;
+    record_ai_interaction(feature, model, prompt, &result, started.elapsed().as_millis() as u64);
+    if result.is_ok() {
+        record_ai_call();
+    }
+    result.map(|(text, _)| text)
+}
+
+#[instrument(target = "timing", name = "ai.send_prompt", skip_all)]
+async fn send_prompt(prompt: &str, model: &str, max_tokens: u32, temperature: f32) -> Result<(String, Option<TokenUsage>), String> {
+    if is_offline() {
+        return Err("AI is unavailable in offline mode".to_string());
+    }
+
     let client = reqwest::Client::new();
     let mut headers = reqwest::header::HeaderMap::new();
     
@@ -193,13 +429,13 @@ pub async fn ask_ai(question: String, response_type: Option<String>) -> Result<S
     );
 
     let request_body = OpenRouterRequest {
-        model: env::var("AI_MODEL").unwrap_or_else(|_| "deepseek/deepseek-r1".to_string()),
+        model: model.to_string(),
         messages: vec![Message {
             role: "user".to_string(),
-            content: prompt,
+            content: prompt.to_string(),
         }],
-        max_tokens: token_limit,
-        temperature: 0.3,
+        max_tokens,
+        temperature,
     };
 
     let response = client
@@ -209,7 +445,13 @@ pub async fn ask_ai(question: String, response_type: Option<String>) -> Result<S
         .timeout(std::time::Duration::from_secs(30))
         .send()
         .await
-        .map_err(|e| format!("Network request failed: {}", e))?;
+        .map_err(|e| {
+            if e.is_connect() || e.is_timeout() {
+                report_network_result(false);
+            }
+            format!("Network request failed: {}", e)
+        })?;
+    report_network_result(true);
 
     if !response.status().is_success() {
         let status = response.status();
@@ -228,7 +470,7 @@ pub async fn ask_ai(question: String, response_type: Option<String>) -> Result<S
 
     if let Some(choice) = api_response.choices.first() {
         let result = choice.message.content.trim().to_string();
-        Ok(result)
+        Ok((result, api_response.usage))
     } else {
         Err("No response received from API".to_string())
     }
@@ -0,0 +1,186 @@
+use chrono::Utc;
+use std::collections::HashMap;
+use uuid::Uuid;
+use crate::models::{Note, SourcedAnswer};
+use crate::services::ai_config::{AiConfig, ModelRole};
+use crate::services::ai_service::run_prompt;
+use crate::services::link_service::{create_note_link_with_options, text_terms};
+use crate::services::note_service::load_notes;
+use crate::services::ai_privacy_service::ai_allowed_notes;
+use crate::services::qa_service::save_qa_note;
+use crate::services::storage_service::{load_answer_history, save_answer_history};
+use crate::services::locale_service::tr;
+
+/// Notes handed to the AI as sources for one answer
+const MAX_SOURCES: usize = 6;
+/// Characters of each source included - enough for the gist of a long note
+const MAX_SOURCE_CHARS: usize = 3_000;
+/// Answers kept in the history - the oldest are dropped first
+const MAX_HISTORY: usize = 500;
+/// Answers returned by `get_answer_history` when the caller doesn't say
+const DEFAULT_HISTORY_LIMIT: usize = 50;
+
+/// The notes sharing the most words with the question, best match first. Words in the
+/// title count double.
+fn relevant_notes<'a>(notes: &'a [Note], question: &str) -> Vec<&'a Note> {
+    let wanted = text_terms(question);
+    if wanted.is_empty() {
+        return Vec::new();
+    }
+
+    let mut scored: Vec<(&Note, usize)> = notes.iter()
+        .filter_map(|note| {
+            let terms = text_terms(&format!("{} {}", note.title, note.content));
+            let title = note.title.to_lowercase();
+            let score: usize = wanted.iter()
+                .filter(|w| terms.contains(*w))
+                .map(|w| if title.contains(w.as_str()) { 2 } else { 1 })
+                .sum();
+            (score > 0).then_some((note, score))
+        })
+        .collect();
+    scored.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| b.0.timestamp.cmp(&a.0.timestamp)));
+    scored.into_iter().take(MAX_SOURCES).map(|(note, _)| note).collect()
+}
+
+/// Rewrite the `[n]` / `[n, m]` markers in `answer` so the cited sources are numbered
+/// 1, 2, ... in order of first citation. Returns the answer and the cited source indices.
+/// Numbers that don't name a source are dropped.
+fn renumber_citations(answer: &str, source_count: usize) -> (String, Vec<usize>) {
+    let mut cited: Vec<usize> = Vec::new();
+    let mut result = String::with_capacity(answer.len());
+    let mut rest = answer;
+    while let Some(start) = rest.find('[') {
+        result.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let marker = after.find(']')
+            .map(|end| &after[..end])
+            .filter(|inner| !inner.is_empty() && inner.chars().all(|c| c.is_ascii_digit() || c == ',' || c == ' '));
+        let Some(inner) = marker else {
+            result.push('[');
+            rest = after;
+            continue;
+        };
+
+        let mut numbers = Vec::new();
+        for index in inner.split(',').filter_map(|n| n.trim().parse::<usize>().ok()).filter(|n| (1..=source_count).contains(n)) {
+            let position = match cited.iter().position(|c| *c == index - 1) {
+                Some(position) => position,
+                None => {
+                    cited.push(index - 1);
+                    cited.len() - 1
+                }
+            };
+            numbers.push((position + 1).to_string());
+        }
+        if !numbers.is_empty() {
+            result.push_str(&format!("[{}]", numbers.join(", ")));
+        }
+        rest = &after[inner.len() + 1..];
+    }
+    result.push_str(rest);
+    (result, cited)
+}
+
+/// Answer a question from the vault's notes, citing the ones it used. The answer is
+/// kept in the history so it can be found again or turned into a note.
+pub async fn ask_vault(question: String) -> Result<SourcedAnswer, String> {
+    let question = question.trim().to_string();
+    if question.is_empty() {
+        return Err(tr("error.question_empty", &[]));
+    }
+    let notes = ai_allowed_notes(load_notes()?.notes)?;
+    let sources = relevant_notes(&notes, &question);
+    if sources.is_empty() {
+        return Err("No notes mention anything from this question".to_string());
+    }
+
+    let context: String = sources.iter()
+        .enumerate()
+        .map(|(index, note)| {
+            let content: String = note.content.chars().take(MAX_SOURCE_CHARS).collect();
+            format!("[{}] {}\n{}\n\n", index + 1, note.title, content)
+        })
+        .collect();
+    let prompt = format!(
+        "Answer the question using only the numbered notes below. Cite the notes you use with their number \
+        in square brackets, e.g. [1] or [2, 3], right after the statement they support. If the notes don't \
+        answer the question, say so.\n\n{}Question: {}",
+        context, question
+    );
+    let config = AiConfig::load();
+    let model = config.model_for(ModelRole::DetailedChat);
+    let reply = run_prompt("ask_vault", &prompt, &model, config.detailed_tokens, 0.3).await?;
+
+    let (answer, cited) = renumber_citations(reply.trim(), sources.len());
+    let sourced = SourcedAnswer {
+        id: Uuid::new_v4().to_string(),
+        question,
+        answer,
+        cited_note_ids: cited.into_iter().map(|index| sources[index].id.clone()).collect(),
+        model,
+        asked_at: Utc::now(),
+        note_id: None,
+    };
+
+    let mut history = load_answer_history()?;
+    history.answers.push(sourced.clone());
+    let excess = history.answers.len().saturating_sub(MAX_HISTORY);
+    history.answers.drain(..excess);
+    save_answer_history(&history)?;
+    Ok(sourced)
+}
+
+/// Answers asked so far, newest first
+pub async fn get_answer_history(limit: Option<usize>) -> Result<Vec<SourcedAnswer>, String> {
+    Ok(load_answer_history()?.answers.into_iter()
+        .rev()
+        .take(limit.unwrap_or(DEFAULT_HISTORY_LIMIT))
+        .collect())
+}
+
+/// Save an answer as a Q&A note that lists its sources and links to each of them, so the
+/// graph shows where the answer came from. Converting an answer twice returns the same note.
+pub async fn answer_to_note(answer_id: String, category_path: Option<Vec<String>>) -> Result<Note, String> {
+    let history = load_answer_history()?;
+    let answer = history.answers.iter()
+        .find(|a| a.id == answer_id)
+        .ok_or_else(|| format!("Answer {} not found", answer_id))?
+        .clone();
+
+    let notes = load_notes()?.notes;
+    if let Some(existing) = answer.note_id.as_ref().and_then(|id| notes.iter().find(|n| &n.id == id)) {
+        return Ok(existing.clone());
+    }
+
+    let by_id: HashMap<&str, &Note> = notes.iter().map(|n| (n.id.as_str(), n)).collect();
+    let mut text = answer.answer.clone();
+    if !answer.cited_note_ids.is_empty() {
+        text.push_str("\n\nSources:");
+        for (index, id) in answer.cited_note_ids.iter().enumerate() {
+            // Deleted sources keep their number so the markers in the answer still line up
+            let title = by_id.get(id.as_str()).map(|n| format!("[[{}]]", n.title)).unwrap_or_else(|| "(deleted note)".to_string());
+            text.push_str(&format!("\n{}. {}", index + 1, title));
+        }
+    }
+    let note = save_qa_note(answer.question.clone(), text, Some(answer.model.clone()), category_path).await?;
+
+    for id in answer.cited_note_ids.iter().filter(|id| by_id.contains_key(id.as_str())) {
+        create_note_link_with_options(
+            note.id.clone(),
+            id.clone(),
+            "Reference".to_string(),
+            Some("Cites".to_string()),
+            None,
+            Some(true),
+        ).await?;
+    }
+
+    // Re-read in case the history changed while the note was being created
+    let mut history = load_answer_history()?;
+    if let Some(entry) = history.answers.iter_mut().find(|a| a.id == answer_id) {
+        entry.note_id = Some(note.id.clone());
+        save_answer_history(&history)?;
+    }
+    Ok(note)
+}
@@ -0,0 +1,257 @@
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+use crate::models::IdleHideSettings;
+use crate::services::settings_service::current_settings;
+use crate::services::storage_service::{load_settings, save_settings};
+
+/// Commands the frontend may call while locked - everything else is refused by the gate
+/// in main.rs
+pub const UNLOCKED_COMMANDS: &[&str] = &[
+    "get_app_lock_status",
+    "unlock_app",
+    "lock_app",
+    "get_window_appearance",
+    "record_user_activity",
+];
+
+/// Why anything touching note data is refused while locked
+pub const LOCKED_MESSAGE: &str = "The app is locked - enter your PIN to continue";
+
+/// Rounds of hashing per PIN check - slows down guessing a stolen settings file
+const PIN_HASH_ROUNDS: u32 = 100_000;
+/// Wrong PINs allowed before unlocking pauses
+const MAX_FAILED_ATTEMPTS: u32 = 5;
+const LOCKOUT_SECS: u64 = 30;
+/// How often the idle timer checks
+pub const IDLE_CHECK_SECS: u64 = 15;
+
+static LOCKED: AtomicBool = AtomicBool::new(false);
+static LAST_ACTIVITY: Mutex<Option<Instant>> = Mutex::new(None);
+/// Wrong PINs since the last unlock, and when the last one was entered
+static FAILED_ATTEMPTS: Mutex<(u32, Option<Instant>)> = Mutex::new((0, None));
+
+/// Called with the status whenever the app locks or unlocks. main.rs installs one that
+/// emits `app-lock:changed`.
+type LockListener = Box<dyn Fn(&AppLockStatus) + Send + Sync>;
+static LOCK_LISTENER: OnceLock<LockListener> = OnceLock::new();
+
+#[derive(Serialize, Clone)]
+pub struct AppLockStatus {
+    pub enabled: bool,
+    pub locked: bool,
+    pub idle_minutes: u32,
+    pub lock_on_show: bool,
+}
+
+pub fn set_lock_listener(listener: impl Fn(&AppLockStatus) + Send + Sync + 'static) {
+    let _ = LOCK_LISTENER.set(Box::new(listener));
+}
+
+fn hash_pin(salt: &str, pin: &str) -> String {
+    let mut digest = Sha256::digest(format!("{}${}", salt, pin).as_bytes());
+    for _ in 1..PIN_HASH_ROUNDS {
+        digest = Sha256::digest(digest);
+    }
+    digest.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// `hash_pin` on a blocking thread - all those rounds would otherwise hold up a runtime worker
+async fn hash_pin_blocking(salt: String, pin: String) -> Result<String, String> {
+    tokio::task::spawn_blocking(move || hash_pin(&salt, &pin))
+        .await
+        .map_err(|e| format!("PIN check failed: {}", e))
+}
+
+/// Compares every byte whatever differs, so timing doesn't tell how much of a hash matched
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+async fn pin_matches(stored: &str, pin: &str) -> Result<bool, String> {
+    let Some((salt, hash)) = stored.split_once('$') else { return Ok(false) };
+    let candidate = hash_pin_blocking(salt.to_string(), pin.to_string()).await?;
+    Ok(constant_time_eq(candidate.as_bytes(), hash.as_bytes()))
+}
+
+fn lock_status() -> AppLockStatus {
+    let settings = current_settings().app_lock;
+    AppLockStatus {
+        enabled: settings.enabled,
+        locked: LOCKED.load(Ordering::SeqCst),
+        idle_minutes: settings.idle_minutes,
+        lock_on_show: settings.lock_on_show,
+    }
+}
+
+fn set_locked(locked: bool) {
+    if LOCKED.swap(locked, Ordering::SeqCst) == locked {
+        return;
+    }
+    if !locked {
+        *FAILED_ATTEMPTS.lock().unwrap_or_else(|e| e.into_inner()) = (0, None);
+        record_activity();
+    }
+    if let Some(listener) = LOCK_LISTENER.get() {
+        listener(&lock_status());
+    }
+}
+
+pub fn is_locked() -> bool {
+    LOCKED.load(Ordering::SeqCst)
+}
+
+/// Note that the user did something, which restarts the idle timer
+pub fn record_activity() {
+    *LAST_ACTIVITY.lock().unwrap_or_else(|e| e.into_inner()) = Some(Instant::now());
+}
+
+/// How long since the user last did something
+pub fn idle_for() -> Duration {
+    LAST_ACTIVITY.lock().unwrap_or_else(|e| e.into_inner())
+        .map(|at| at.elapsed())
+        .unwrap_or_default()
+}
+
+pub async fn record_user_activity() -> Result<(), String> {
+    record_activity();
+    Ok(())
+}
+
+pub async fn get_idle_hide_settings() -> Result<IdleHideSettings, String> {
+    Ok(current_settings().idle_hide)
+}
+
+pub async fn set_idle_hide_settings(settings: IdleHideSettings) -> Result<IdleHideSettings, String> {
+    let mut database = load_settings()?;
+    database.settings.idle_hide = settings.clone();
+    save_settings(&database)?;
+    record_activity();
+    Ok(settings)
+}
+
+/// Whether the user has been away long enough for the idle timer to hide the window
+pub fn idle_hide_due() -> bool {
+    let minutes = current_settings().idle_hide.minutes;
+    minutes > 0 && idle_for() >= Duration::from_secs(minutes as u64 * 60)
+}
+
+/// Lock now, if a PIN is set up. Returns whether the app is locked.
+pub fn lock_now() -> bool {
+    let settings = current_settings().app_lock;
+    if settings.enabled && settings.pin_hash.is_some() {
+        set_locked(true);
+    }
+    is_locked()
+}
+
+/// Called when the window comes back from the tray
+pub fn lock_on_show() {
+    if current_settings().app_lock.lock_on_show {
+        lock_now();
+    }
+}
+
+pub async fn get_app_lock_status() -> Result<AppLockStatus, String> {
+    Ok(lock_status())
+}
+
+pub async fn lock_app() -> Result<AppLockStatus, String> {
+    if !lock_now() {
+        return Err("Set a PIN before locking the app".to_string());
+    }
+    Ok(lock_status())
+}
+
+/// Set up, change or (with `pin: None`) remove the PIN, and the lock timers. Once a PIN
+/// exists, `current_pin` must match it.
+pub async fn set_app_lock(pin: Option<String>, current_pin: Option<String>, idle_minutes: u32, lock_on_show: bool) -> Result<AppLockStatus, String> {
+    let mut database = load_settings()?;
+    if let Some(stored) = database.settings.app_lock.pin_hash.clone() {
+        let matches = match current_pin.as_deref() {
+            Some(current) => pin_matches(&stored, current).await?,
+            None => false,
+        };
+        if !matches {
+            return Err("The current PIN is wrong".to_string());
+        }
+    }
+
+    let lock = &mut database.settings.app_lock;
+    match pin.map(|p| p.trim().to_string()) {
+        Some(pin) => {
+            if pin.len() < 4 || !pin.chars().all(|c| c.is_ascii_digit()) {
+                return Err("The PIN must be at least 4 digits".to_string());
+            }
+            let salt = Uuid::new_v4().to_string();
+            let hash = hash_pin_blocking(salt.clone(), pin).await?;
+            lock.pin_hash = Some(format!("{}${}", salt, hash));
+            lock.enabled = true;
+        }
+        None => {
+            lock.pin_hash = None;
+            lock.enabled = false;
+        }
+    }
+    lock.idle_minutes = idle_minutes;
+    lock.lock_on_show = lock_on_show;
+    let enabled = lock.enabled;
+    save_settings(&database)?;
+
+    if !enabled {
+        set_locked(false);
+    }
+    record_activity();
+    Ok(lock_status())
+}
+
+pub async fn unlock_app(pin: String) -> Result<AppLockStatus, String> {
+    if !is_locked() {
+        return Ok(lock_status());
+    }
+    {
+        let attempts = FAILED_ATTEMPTS.lock().unwrap_or_else(|e| e.into_inner());
+        if let (count, Some(last)) = *attempts {
+            let waited = last.elapsed().as_secs();
+            if count >= MAX_FAILED_ATTEMPTS && waited < LOCKOUT_SECS {
+                return Err(format!("Too many wrong PINs - try again in {} seconds", LOCKOUT_SECS - waited));
+            }
+        }
+    }
+
+    let matches = match current_settings().app_lock.pin_hash {
+        Some(stored) => pin_matches(&stored, pin.trim()).await?,
+        None => false,
+    };
+    if !matches {
+        let mut attempts = FAILED_ATTEMPTS.lock().unwrap_or_else(|e| e.into_inner());
+        // A new round of attempts starts once the pause is over
+        let count = if attempts.0 >= MAX_FAILED_ATTEMPTS { 1 } else { attempts.0 + 1 };
+        *attempts = (count, Some(Instant::now()));
+        return Err("Wrong PIN".to_string());
+    }
+    set_locked(false);
+    Ok(lock_status())
+}
+
+/// Lock before the app serves anything, if a PIN is set. Called from setup in main.rs,
+/// ahead of the first command the windows can send.
+pub fn lock_at_launch() {
+    record_activity();
+    lock_now();
+}
+
+/// Background job started at launch: locks the app whenever it sits idle for the
+/// configured time
+pub async fn run_idle_lock_loop() {
+    loop {
+        tokio::time::sleep(Duration::from_secs(IDLE_CHECK_SECS)).await;
+        let settings = current_settings().app_lock;
+        if settings.idle_minutes > 0 && !is_locked() && idle_for() >= Duration::from_secs(settings.idle_minutes as u64 * 60) {
+            lock_now();
+        }
+    }
+}
@@ -0,0 +1,342 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use crate::models::{NotesDatabase, CategoriesDatabase, LinksDatabase};
+use crate::services::locale_service::forget_cached_locale;
+use crate::services::storage_service::{flush_pending_writes, get_app_data_dir, invalidate_store_caches, load_links, save_links, save_notes, save_categories, load_settings, save_settings};
+use crate::services::note_service::load_notes;
+use crate::services::category_service::{load_categories, update_category_note_counts};
+
+/// Version of the `.nbvault` layout. Bump when the archive structure changes.
+pub const VAULT_ARCHIVE_VERSION: u32 = 1;
+
+const MANIFEST_NAME: &str = "manifest.json";
+const NOTES_ENTRY: &str = "notes.json";
+const CATEGORIES_ENTRY: &str = "categories.json";
+const LINKS_ENTRY: &str = "note_links.json";
+
+/// Local to this install rather than part of the vault: never exported, and left alone
+/// when an archive replaces the vault
+const TRANSIENT_DIRS: [&str; 2] = ["logs/", "sync/"];
+const TRANSIENT_FILES: [&str; 3] = ["sync_state.json", "drafts.json", "ai_jobs.json"];
+
+fn is_transient(name: &str) -> bool {
+    TRANSIENT_DIRS.iter().any(|dir| name.starts_with(dir))
+        || TRANSIENT_FILES.contains(&name)
+        || name.ends_with(".bak")
+        || name.rsplit('/').next().is_some_and(|file| file.starts_with('.'))
+}
+
+/// Archive entries as (relative file name, raw content)
+type ArchiveEntries = Vec<(String, Vec<u8>)>;
+
+#[derive(Serialize, Deserialize)]
+pub struct VaultManifest {
+    pub format_version: u32,
+    pub app_version: String,
+    pub exported_at: DateTime<Utc>,
+    pub files: Vec<String>,
+}
+
+#[derive(Serialize, Clone, Default)]
+pub struct VaultImportSummary {
+    pub notes_added: usize,
+    pub categories_added: usize,
+    pub links_added: usize,
+    pub files_restored: usize,
+}
+
+pub enum MergeStrategy {
+    /// Keep local data and add anything from the archive that isn't already present
+    Merge,
+    /// Wipe the local vault and restore the archive as-is
+    Replace,
+}
+
+impl MergeStrategy {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "merge" => Ok(MergeStrategy::Merge),
+            "replace" => Ok(MergeStrategy::Replace),
+            _ => Err(format!("Unknown merge strategy '{}', expected 'merge' or 'replace'", value)),
+        }
+    }
+}
+
+/// Collect every file under the app data dir as (archive name, absolute path)
+fn collect_vault_files(root: &Path) -> Result<Vec<(String, PathBuf)>, String> {
+    let mut files = Vec::new();
+    let mut pending = vec![root.to_path_buf()];
+
+    while let Some(dir) = pending.pop() {
+        let entries = fs::read_dir(&dir)
+            .map_err(|e| format!("Failed to read directory {}: {}", dir.display(), e))?;
+
+        for entry in entries {
+            let entry = entry.map_err(|e| format!("Failed to read directory entry: {}", e))?;
+            let path = entry.path();
+            if path.is_dir() {
+                pending.push(path);
+            } else if let Ok(relative) = path.strip_prefix(root) {
+                // Zip entries always use forward slashes regardless of platform
+                let name = relative.components()
+                    .map(|c| c.as_os_str().to_string_lossy().to_string())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                files.push((name, path));
+            }
+        }
+    }
+
+    files.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(files)
+}
+
+/// Bundle all stores, attachments and settings into a single `.nbvault` zip
+pub async fn export_vault_archive(path: String) -> Result<String, String> {
+    let data_dir = get_app_data_dir()?;
+    // Notes go in as a single notes.json whatever segments they're stored in, so
+    // archives keep one layout
+    let files: Vec<(String, PathBuf)> = collect_vault_files(&data_dir)?.into_iter()
+        .filter(|(name, _)| !name.starts_with("notes/") && name != NOTES_ENTRY && !is_transient(name))
+        .collect();
+    let notes_content = serde_json::to_string_pretty(&load_notes()?)
+        .map_err(|e| format!("Failed to serialize notes: {}", e))?;
+
+    let mut dest = PathBuf::from(&path);
+    if dest.extension().is_none() {
+        dest.set_extension("nbvault");
+    }
+
+    let file = fs::File::create(&dest)
+        .map_err(|e| format!("Failed to create archive file: {}", e))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::FileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+
+    let manifest = VaultManifest {
+        format_version: VAULT_ARCHIVE_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        exported_at: Utc::now(),
+        files: std::iter::once(NOTES_ENTRY.to_string()).chain(files.iter().map(|(name, _)| name.clone())).collect(),
+    };
+    let manifest_content = serde_json::to_string_pretty(&manifest)
+        .map_err(|e| format!("Failed to serialize archive manifest: {}", e))?;
+
+    zip.start_file(MANIFEST_NAME, options)
+        .map_err(|e| format!("Failed to write archive manifest: {}", e))?;
+    zip.write_all(manifest_content.as_bytes())
+        .map_err(|e| format!("Failed to write archive manifest: {}", e))?;
+
+    zip.start_file(NOTES_ENTRY, options)
+        .map_err(|e| format!("Failed to add {} to archive: {}", NOTES_ENTRY, e))?;
+    zip.write_all(notes_content.as_bytes())
+        .map_err(|e| format!("Failed to add {} to archive: {}", NOTES_ENTRY, e))?;
+
+    for (name, file_path) in &files {
+        let content = fs::read(file_path)
+            .map_err(|e| format!("Failed to read {}: {}", name, e))?;
+        zip.start_file(name.as_str(), options)
+            .map_err(|e| format!("Failed to add {} to archive: {}", name, e))?;
+        zip.write_all(&content)
+            .map_err(|e| format!("Failed to add {} to archive: {}", name, e))?;
+    }
+
+    zip.finish()
+        .map_err(|e| format!("Failed to finalize archive: {}", e))?;
+
+    let mut settings_db = load_settings()?;
+    settings_db.settings.last_vault_export = Some(Utc::now());
+    save_settings(&settings_db)?;
+
+    Ok(dest.to_string_lossy().to_string())
+}
+
+/// Read every entry of the archive into memory, keyed by its (sanitized) name
+fn read_archive_entries(path: &str) -> Result<(VaultManifest, ArchiveEntries), String> {
+    let file = fs::File::open(path)
+        .map_err(|e| format!("Failed to open archive: {}", e))?;
+    let mut zip = zip::ZipArchive::new(file)
+        .map_err(|e| format!("Failed to read archive: {}", e))?;
+
+    let mut manifest: Option<VaultManifest> = None;
+    let mut entries = Vec::new();
+
+    for i in 0..zip.len() {
+        let mut entry = zip.by_index(i)
+            .map_err(|e| format!("Failed to read archive entry: {}", e))?;
+        if entry.is_dir() {
+            continue;
+        }
+
+        // Reject entries that would escape the data directory
+        let name = match entry.enclosed_name() {
+            Some(name) => name.to_string_lossy().replace('\\', "/"),
+            None => return Err(format!("Archive contains an unsafe path: {}", entry.name())),
+        };
+
+        let mut content = Vec::new();
+        entry.read_to_end(&mut content)
+            .map_err(|e| format!("Failed to read {} from archive: {}", name, e))?;
+
+        if name == MANIFEST_NAME {
+            manifest = Some(serde_json::from_slice(&content)
+                .map_err(|e| format!("Failed to parse archive manifest: {}", e))?);
+        } else {
+            entries.push((name, content));
+        }
+    }
+
+    let manifest = manifest.ok_or("Archive is missing its manifest - not a .nbvault file?")?;
+    if manifest.format_version > VAULT_ARCHIVE_VERSION {
+        return Err(format!(
+            "Archive format version {} is newer than supported version {}. Please update the app.",
+            manifest.format_version, VAULT_ARCHIVE_VERSION
+        ));
+    }
+
+    Ok((manifest, entries))
+}
+
+fn write_data_file(data_dir: &Path, name: &str, content: &[u8]) -> Result<(), String> {
+    let target = data_dir.join(name);
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create directory for {}: {}", name, e))?;
+    }
+    fs::write(&target, content)
+        .map_err(|e| format!("Failed to restore {}: {}", name, e))
+}
+
+fn parse_entry<T: for<'de> Deserialize<'de>>(entries: &[(String, Vec<u8>)], name: &str) -> Result<Option<T>, String> {
+    match entries.iter().find(|(entry_name, _)| entry_name == name) {
+        Some((_, content)) => serde_json::from_slice(content)
+            .map(Some)
+            .map_err(|e| format!("Failed to parse {} in archive: {}", name, e)),
+        None => Ok(None),
+    }
+}
+
+/// Restore a `.nbvault` archive, either replacing the local vault or merging into it
+pub async fn import_vault_archive(path: String, merge_strategy: String) -> Result<VaultImportSummary, String> {
+    let strategy = MergeStrategy::parse(&merge_strategy)?;
+    let (_manifest, entries) = read_archive_entries(&path)?;
+    let data_dir = get_app_data_dir()?;
+    match strategy {
+        MergeStrategy::Replace => {
+            // Deferred writes and cached notes belong to the vault being replaced - written
+            // later they'd overwrite the restored one
+            invalidate_store_caches();
+            let summary = replace_vault(&data_dir, &entries);
+            invalidate_store_caches();
+            forget_cached_locale();
+            summary
+        }
+        MergeStrategy::Merge => {
+            // A deferred write landing after the import would undo it
+            flush_pending_writes()?;
+            merge_vault(&data_dir, &entries)
+        }
+    }
+}
+
+fn replace_vault(data_dir: &Path, entries: &[(String, Vec<u8>)]) -> Result<VaultImportSummary, String> {
+    // Validate the stores before touching anything on disk
+    let notes: Option<NotesDatabase> = parse_entry(entries, NOTES_ENTRY)?;
+    let categories: Option<CategoriesDatabase> = parse_entry(entries, CATEGORIES_ENTRY)?;
+    let links: Option<LinksDatabase> = parse_entry(entries, LINKS_ENTRY)?;
+
+    // Only what an archive holds is replaced; logs, sync state and the like stay
+    for (name, _) in collect_vault_files(data_dir)?.into_iter().filter(|(name, _)| !is_transient(name)) {
+        fs::remove_file(data_dir.join(&name))
+            .map_err(|e| format!("Failed to remove {}: {}", name, e))?;
+    }
+
+    for (name, content) in entries.iter().filter(|(name, _)| !is_transient(name)) {
+        write_data_file(data_dir, name, content)?;
+    }
+
+    Ok(VaultImportSummary {
+        notes_added: notes.map(|db| db.notes.len()).unwrap_or(0),
+        categories_added: categories.map(|db| db.categories.len()).unwrap_or(0),
+        links_added: links.map(|db| db.links.len()).unwrap_or(0),
+        files_restored: entries.len(),
+    })
+}
+
+fn merge_vault(data_dir: &Path, entries: &[(String, Vec<u8>)]) -> Result<VaultImportSummary, String> {
+    let mut summary = VaultImportSummary::default();
+
+    let mut notes_db = load_notes()?;
+    let mut categories_db = load_categories()?;
+    let mut links_db = load_links()?;
+
+    // Notes are matched by id - local copies win on conflict
+    if let Some(archive_notes) = parse_entry::<NotesDatabase>(entries, NOTES_ENTRY)? {
+        for note in archive_notes.notes {
+            if !notes_db.notes.iter().any(|n| n.id == note.id) {
+                notes_db.notes.push(note);
+                summary.notes_added += 1;
+            }
+        }
+    }
+
+    // Categories are matched by path since ids differ between vaults
+    if let Some(archive_categories) = parse_entry::<CategoriesDatabase>(entries, CATEGORIES_ENTRY)? {
+        for category in archive_categories.categories {
+            if !categories_db.categories.iter().any(|c| c.path == category.path) {
+                categories_db.categories.push(category);
+                summary.categories_added += 1;
+            }
+        }
+    }
+
+    // Re-point parent ids at whichever category now owns the parent path
+    let path_to_id: Vec<(Vec<String>, String)> = categories_db.categories.iter()
+        .map(|c| (c.path.clone(), c.id.clone()))
+        .collect();
+    for category in &mut categories_db.categories {
+        category.parent_id = if category.path.len() > 1 {
+            let parent_path = &category.path[..category.path.len() - 1];
+            path_to_id.iter()
+                .find(|(path, _)| path.as_slice() == parent_path)
+                .map(|(_, id)| id.clone())
+        } else {
+            None
+        };
+    }
+
+    // Links are only kept when both endpoints exist after the merge
+    if let Some(archive_links) = parse_entry::<LinksDatabase>(entries, LINKS_ENTRY)? {
+        for link in archive_links.links {
+            let already_present = links_db.links.iter().any(|l| l.id == link.id);
+            let endpoints_exist = notes_db.notes.iter().any(|n| n.id == link.source_id)
+                && notes_db.notes.iter().any(|n| n.id == link.target_id);
+            if !already_present && endpoints_exist {
+                links_db.links.push(link);
+                summary.links_added += 1;
+            }
+        }
+    }
+
+    save_notes(&notes_db)?;
+    save_categories(&categories_db)?;
+    save_links(&links_db)?;
+
+    // Everything else (attachments, settings, UI state) is restored only where missing locally
+    for (name, content) in entries {
+        if name == NOTES_ENTRY || name == CATEGORIES_ENTRY || name == LINKS_ENTRY {
+            continue;
+        }
+        if !data_dir.join(name).exists() {
+            write_data_file(data_dir, name, content)?;
+            summary.files_restored += 1;
+        }
+    }
+
+    update_category_note_counts()?;
+
+    Ok(summary)
+}
@@ -0,0 +1,115 @@
+use chrono::Utc;
+use uuid::Uuid;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use crate::models::Attachment;
+use crate::services::storage_service::{get_app_data_dir, get_attachments_dir};
+
+/// Guess a MIME type from the file extension - good enough for rendering and export
+pub fn mime_type_for(file_name: &str) -> String {
+    let extension = Path::new(file_name)
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    match extension.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "svg" => "image/svg+xml",
+        "heic" => "image/heic",
+        "tif" | "tiff" => "image/tiff",
+        "pdf" => "application/pdf",
+        "txt" => "text/plain",
+        "md" | "markdown" => "text/markdown",
+        "html" | "htm" => "text/html",
+        "json" => "application/json",
+        "mp3" => "audio/mpeg",
+        "m4a" => "audio/mp4",
+        "mp4" => "video/mp4",
+        "mov" => "video/quicktime",
+        "zip" => "application/zip",
+        _ => "application/octet-stream",
+    }
+    .to_string()
+}
+
+/// Strip anything that could escape the attachment folder or upset the filesystem
+pub fn sanitize_file_name(file_name: &str) -> String {
+    let base = Path::new(file_name)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let cleaned: String = base.chars()
+        .map(|c| if c.is_alphanumeric() || matches!(c, '.' | '-' | '_' | ' ') { c } else { '_' })
+        .collect();
+
+    let cleaned = cleaned.trim().trim_start_matches('.').to_string();
+    if cleaned.is_empty() { "attachment".to_string() } else { cleaned }
+}
+
+/// Write raw bytes into the attachment store and return the record to put on a note
+pub fn store_attachment_bytes(file_name: &str, bytes: &[u8]) -> Result<Attachment, String> {
+    let id = Uuid::new_v4().to_string();
+    let safe_name = sanitize_file_name(file_name);
+
+    let mut dir = get_attachments_dir()?;
+    dir.push(&id);
+    fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create attachment directory: {}", e))?;
+
+    let file_path = dir.join(&safe_name);
+    fs::write(&file_path, bytes)
+        .map_err(|e| format!("Failed to write attachment {}: {}", safe_name, e))?;
+
+    Ok(Attachment {
+        id: id.clone(),
+        mime_type: mime_type_for(&safe_name),
+        stored_path: format!("attachments/{}/{}", id, safe_name),
+        file_name: safe_name,
+        size_bytes: bytes.len() as u64,
+        created_at: Utc::now(),
+    })
+}
+
+/// Copy an existing file from disk into the attachment store
+pub fn store_attachment_file(source: &Path) -> Result<Attachment, String> {
+    let bytes = fs::read(source)
+        .map_err(|e| format!("Failed to read attachment {}: {}", source.display(), e))?;
+    let file_name = source.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "attachment".to_string());
+    store_attachment_bytes(&file_name, &bytes)
+}
+
+/// Absolute location of an attachment's file on disk. `stored_path` can come from an
+/// imported archive or another device, so anything that isn't a plain path below
+/// `attachments/` is refused rather than followed out of the data directory.
+pub fn attachment_file_path(attachment: &Attachment) -> Result<PathBuf, String> {
+    let segments: Vec<&str> = attachment.stored_path.split('/').collect();
+    let plain = |segment: &&str| {
+        let mut components = Path::new(segment).components();
+        matches!((components.next(), components.next()), (Some(Component::Normal(_)), None))
+            && !segment.contains(['\\', ':'])
+    };
+    if segments.len() < 2 || segments[0] != "attachments" || !segments.iter().all(plain) {
+        return Err(format!("Invalid attachment path {}", attachment.stored_path));
+    }
+
+    let mut path = get_app_data_dir()?;
+    for segment in segments {
+        path.push(segment);
+    }
+    Ok(path)
+}
+
+/// Markdown reference used inside note content for an attachment
+pub fn attachment_markdown(attachment: &Attachment) -> String {
+    if attachment.mime_type.starts_with("image/") {
+        format!("![{}](attachment:{})", attachment.file_name, attachment.id)
+    } else {
+        format!("[{}](attachment:{})", attachment.file_name, attachment.id)
+    }
+}
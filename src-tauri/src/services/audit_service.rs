@@ -0,0 +1,52 @@
+use chrono::Utc;
+use tracing::warn;
+use crate::models::{AuditEntry, AuditEvent, Category, Note, NoteLink};
+use crate::services::storage_service::{load_audit_log, save_audit_log};
+
+/// Entries kept - the oldest are dropped first
+const MAX_AUDIT_ENTRIES: usize = 20_000;
+/// Entries returned by `get_audit_log` when the caller doesn't say
+const DEFAULT_AUDIT_LIMIT: usize = 100;
+
+pub fn note_deleted(note: &Note) -> AuditEvent {
+    AuditEvent::Note {
+        note_id: note.id.clone(),
+        title: note.title.clone(),
+        category_path: note.category_path.clone(),
+        created_at: note.timestamp,
+    }
+}
+
+pub fn link_deleted(link: &NoteLink) -> AuditEvent {
+    AuditEvent::Link { link: link.clone() }
+}
+
+pub fn category_deleted(category: &Category) -> AuditEvent {
+    AuditEvent::Category { category: category.clone() }
+}
+
+/// Append deletions to the audit log. Failures are logged - a deletion must never fail
+/// because its history couldn't be written.
+pub fn record_deletions(events: Vec<AuditEvent>) {
+    if events.is_empty() {
+        return;
+    }
+    let result = load_audit_log().and_then(|mut database| {
+        let at = Utc::now();
+        database.entries.extend(events.into_iter().map(|event| AuditEntry { at, event }));
+        let excess = database.entries.len().saturating_sub(MAX_AUDIT_ENTRIES);
+        database.entries.drain(..excess);
+        save_audit_log(&database)
+    });
+    if let Err(e) = result {
+        warn!("Failed to record deletions in the audit log: {}", e);
+    }
+}
+
+/// Recorded deletions, newest first
+pub async fn get_audit_log(limit: Option<usize>) -> Result<Vec<AuditEntry>, String> {
+    Ok(load_audit_log()?.entries.into_iter()
+        .rev()
+        .take(limit.unwrap_or(DEFAULT_AUDIT_LIMIT))
+        .collect())
+}
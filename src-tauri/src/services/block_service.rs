@@ -0,0 +1,156 @@
+use chrono::Utc;
+use serde::Serialize;
+use uuid::Uuid;
+use crate::models::{LinkType, NoteLink};
+use crate::services::note_service::load_notes;
+use crate::services::storage_service::{load_links, save_links, save_notes};
+use crate::services::locale_service::tr;
+
+/// A paragraph-level chunk of a note. Blocks are separated by blank lines; a block gets a
+/// stable id by ending its last line with ` ^block-id`.
+#[derive(Serialize, Clone)]
+pub struct NoteBlock {
+    pub index: usize,
+    pub id: Option<String>,
+    pub content: String, // Without the `^block-id` marker
+    pub start: usize,    // Byte offsets of the block in the note content
+    pub end: usize,
+}
+
+fn is_block_id(value: &str) -> bool {
+    !value.is_empty() && value.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Split a trailing ` ^block-id` marker off a block
+fn split_block_marker(block: &str) -> (&str, Option<&str>) {
+    let trimmed = block.trim_end();
+    if let Some(position) = trimmed.rfind('^') {
+        let id = &trimmed[position + 1..];
+        let before = &trimmed[..position];
+        if is_block_id(id) && (before.is_empty() || before.ends_with(char::is_whitespace)) {
+            return (before.trim_end(), Some(id));
+        }
+    }
+    (trimmed, None)
+}
+
+/// Blocks of a note's content in order. Blank lines inside fenced code don't split a block.
+pub fn parse_blocks(content: &str) -> Vec<NoteBlock> {
+    let mut blocks = Vec::new();
+    let mut block_start: Option<usize> = None;
+    let mut block_end = 0;
+    let mut in_fence = false;
+    let mut offset = 0;
+
+    let flush = |start: Option<usize>, end: usize, blocks: &mut Vec<NoteBlock>| {
+        if let Some(start) = start {
+            let (text, id) = split_block_marker(&content[start..end]);
+            blocks.push(NoteBlock {
+                index: blocks.len(),
+                id: id.map(|id| id.to_string()),
+                content: text.to_string(),
+                start,
+                end,
+            });
+        }
+    };
+
+    for line in content.split_inclusive('\n') {
+        let line_start = offset;
+        offset += line.len();
+
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+        }
+        if line.trim().is_empty() && !in_fence {
+            flush(block_start.take(), block_end, &mut blocks);
+            continue;
+        }
+        if block_start.is_none() {
+            block_start = Some(line_start);
+        }
+        block_end = line_start + line.trim_end_matches(['\n', '\r']).len();
+    }
+    flush(block_start, block_end, &mut blocks);
+
+    blocks
+}
+
+/// Every block of a note, with ids where they've been assigned
+pub async fn get_note_blocks(note_id: String) -> Result<Vec<NoteBlock>, String> {
+    let database = load_notes()?;
+    let note = database.notes.iter()
+        .find(|n| n.id == note_id)
+        .ok_or_else(|| tr("error.note_not_found", &[&note_id]))?;
+    Ok(parse_blocks(&note.content))
+}
+
+pub async fn get_block(note_id: String, block_id: String) -> Result<NoteBlock, String> {
+    get_note_blocks(note_id.clone()).await?
+        .into_iter()
+        .find(|b| b.id.as_deref() == Some(block_id.as_str()))
+        .ok_or_else(|| format!("Block ^{} not found in note {}", block_id, note_id))
+}
+
+/// Give the block at `block_index` a stable id by appending a `^block-id` marker. Blocks
+/// that already have one keep it, so this is safe to call before every block link.
+pub async fn assign_block_id(note_id: String, block_index: usize) -> Result<NoteBlock, String> {
+    let mut database = load_notes()?;
+    let note = database.notes.iter_mut()
+        .find(|n| n.id == note_id)
+        .ok_or_else(|| tr("error.note_not_found", &[&note_id]))?;
+
+    let block = parse_blocks(&note.content).into_iter()
+        .nth(block_index)
+        .ok_or_else(|| format!("Note {} has no block {}", note_id, block_index))?;
+    if block.id.is_some() {
+        return Ok(block);
+    }
+
+    let block_id = Uuid::new_v4().simple().to_string()[..6].to_string();
+    let marker = format!(" ^{}", block_id);
+    note.content.insert_str(block.end, &marker);
+    note.revision += 1;
+    save_notes(&database)?;
+
+    Ok(NoteBlock {
+        id: Some(block_id),
+        end: block.end + marker.len(),
+        ..block
+    })
+}
+
+/// Link a note to one block of another note rather than the whole note
+pub async fn create_block_link(source_id: String, target_id: String, block_id: String, label: Option<String>) -> Result<NoteLink, String> {
+    let notes_db = load_notes()?;
+    if !notes_db.notes.iter().any(|n| n.id == source_id) {
+        return Err(format!("Source note with id {} not found", source_id));
+    }
+    get_block(target_id.clone(), block_id.clone()).await?;
+
+    let mut links_db = load_links()?;
+    let exists = links_db.links.iter().any(|l| {
+        l.source_id == source_id && l.target_id == target_id && l.target_block.as_deref() == Some(block_id.as_str())
+    });
+    if exists {
+        return Err("A link to this block already exists".to_string());
+    }
+
+    let link = NoteLink {
+        id: Uuid::new_v4().to_string(),
+        source_id,
+        target_id,
+        link_type: LinkType::Reference,
+        label,
+        color: None,
+        directional: Some(true),
+        created_at: Utc::now(),
+        target_block: Some(block_id),
+        weight: None,
+        weight_is_manual: false,
+    };
+    links_db.links.push(link.clone());
+    save_links(&links_db)?;
+
+    Ok(link)
+}
@@ -0,0 +1,150 @@
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use crate::models::{Board, BoardColumn, ColumnRule, Note, NoteStatus, WebhookEvent};
+use crate::services::note_service::load_notes;
+use crate::services::storage_service::{load_boards, save_boards, save_notes};
+use crate::services::webhook_service::{emit_webhook_event, tag_added_payload};
+use crate::services::locale_service::tr;
+
+/// Column definition as sent by the frontend - exactly one of `status` / `tag` must be set
+#[derive(Deserialize)]
+pub struct BoardColumnInput {
+    pub name: String,
+    pub status: Option<String>,
+    pub tag: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct BoardColumnView {
+    pub column: BoardColumn,
+    pub notes: Vec<Note>,
+}
+
+#[derive(Serialize)]
+pub struct BoardView {
+    pub board: Board,
+    pub columns: Vec<BoardColumnView>,
+}
+
+fn note_in_column(note: &Note, rule: &ColumnRule) -> bool {
+    match rule {
+        ColumnRule::Status(status) => note.status.as_ref() == Some(status),
+        ColumnRule::Tag(tag) => note.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)),
+    }
+}
+
+pub async fn create_board(name: String, columns: Vec<BoardColumnInput>, category_path: Option<Vec<String>>) -> Result<Board, String> {
+    if name.trim().is_empty() {
+        return Err("Board name cannot be empty".to_string());
+    }
+    if columns.is_empty() {
+        return Err("A board needs at least one column".to_string());
+    }
+
+    let columns = columns.into_iter()
+        .map(|input| {
+            let rule = match (input.status, input.tag) {
+                (Some(status), None) => ColumnRule::Status(NoteStatus::parse(&status)),
+                (None, Some(tag)) => ColumnRule::Tag(tag.trim().to_string()),
+                _ => return Err(format!("Column '{}' must map to exactly one status or tag", input.name)),
+            };
+            Ok(BoardColumn {
+                id: Uuid::new_v4().to_string(),
+                name: input.name,
+                rule,
+            })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    let board = Board {
+        id: Uuid::new_v4().to_string(),
+        name: name.trim().to_string(),
+        columns,
+        category_path: category_path.filter(|p| !p.is_empty()),
+        created_at: Utc::now(),
+    };
+
+    let mut boards_db = load_boards()?;
+    boards_db.boards.push(board.clone());
+    save_boards(&boards_db)?;
+
+    Ok(board)
+}
+
+pub async fn list_boards() -> Result<Vec<Board>, String> {
+    Ok(load_boards()?.boards)
+}
+
+pub async fn delete_board(board_id: String) -> Result<(), String> {
+    let mut boards_db = load_boards()?;
+    let initial_len = boards_db.boards.len();
+    boards_db.boards.retain(|b| b.id != board_id);
+
+    if boards_db.boards.len() == initial_len {
+        return Err(format!("Board with id {} not found", board_id));
+    }
+
+    save_boards(&boards_db)
+}
+
+/// Return the board with its notes grouped into columns (a note lands in the first matching column)
+pub async fn get_board(board_id: String) -> Result<BoardView, String> {
+    let board = load_boards()?.boards.into_iter()
+        .find(|b| b.id == board_id)
+        .ok_or_else(|| format!("Board with id {} not found", board_id))?;
+
+    let notes: Vec<Note> = load_notes()?.notes.into_iter()
+        .filter(|note| board.category_path.as_ref().map(|p| note.category_path.starts_with(p)).unwrap_or(true))
+        .collect();
+
+    let mut columns: Vec<BoardColumnView> = board.columns.iter()
+        .map(|column| BoardColumnView { column: column.clone(), notes: Vec::new() })
+        .collect();
+
+    for note in notes {
+        if let Some(view) = columns.iter_mut().find(|view| note_in_column(&note, &view.column.rule)) {
+            view.notes.push(note);
+        }
+    }
+
+    Ok(BoardView { board, columns })
+}
+
+/// Move a note into a column by rewriting the status/tag the column is keyed on
+pub async fn move_note_to_column(board_id: String, note_id: String, column_id: String) -> Result<Note, String> {
+    let board = load_boards()?.boards.into_iter()
+        .find(|b| b.id == board_id)
+        .ok_or_else(|| format!("Board with id {} not found", board_id))?;
+    let column = board.columns.iter()
+        .find(|c| c.id == column_id)
+        .ok_or_else(|| format!("Column with id {} not found on board", column_id))?;
+
+    let mut database = load_notes()?;
+    let note = database.notes.iter_mut()
+        .find(|n| n.id == note_id)
+        .ok_or_else(|| tr("error.note_not_found", &[&note_id]))?;
+
+    match &column.rule {
+        ColumnRule::Status(status) => note.status = Some(status.clone()),
+        ColumnRule::Tag(tag) => {
+            // Leaving the other tag columns of this board, so drop their tags
+            let board_tags: Vec<&String> = board.columns.iter()
+                .filter_map(|c| match &c.rule {
+                    ColumnRule::Tag(t) => Some(t),
+                    _ => None,
+                })
+                .collect();
+            note.tags.retain(|t| !board_tags.iter().any(|bt| bt.eq_ignore_ascii_case(t)));
+            note.tags.push(tag.clone());
+        }
+    }
+
+    note.revision += 1;
+    let updated = note.clone();
+    save_notes(&database)?;
+    if let ColumnRule::Tag(tag) = &column.rule {
+        emit_webhook_event(WebhookEvent::TagAdded, tag_added_payload(&updated, tag));
+    }
+    Ok(updated)
+}
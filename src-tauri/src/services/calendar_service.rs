@@ -0,0 +1,363 @@
+use chrono::{DateTime, Datelike, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+use crate::models::{LinkType, Note, NoteLink};
+use crate::services::category_service::{ensure_category_path, update_category_note_counts};
+use crate::services::import_service::ImportReport;
+use crate::services::note_service::{daily_note_date, load_notes, DAILY_NOTES_CATEGORY};
+use crate::services::storage_service::{load_links, save_links, save_notes};
+use crate::services::locale_service::{format_date, tr};
+
+const MEETINGS_CATEGORY: &str = "Meetings";
+
+/// Separates the generated event details from the user's own meeting notes
+const DETAILS_SEPARATOR: &str = "\n\n---\n\n";
+
+#[derive(Serialize, Clone)]
+pub struct CalendarDay {
+    pub date: NaiveDate,
+    pub created: Vec<String>,        // Ids of notes created that day
+    pub due: Vec<String>,            // Ids of notes due that day
+    pub daily_note_id: Option<String>,
+    pub count: usize,                // Distinct notes touching the day
+}
+
+#[derive(Serialize)]
+pub struct CalendarMonth {
+    pub year: i32,
+    pub month: u32,
+    pub days: Vec<CalendarDay>,
+}
+
+/// Per-day note activity for one month, bucketed in the user's local time zone
+pub async fn get_notes_calendar(year: i32, month: u32) -> Result<CalendarMonth, String> {
+    let first_day = NaiveDate::from_ymd_opt(year, month, 1)
+        .ok_or_else(|| format!("Invalid month {}-{}", year, month))?;
+
+    let mut days: Vec<CalendarDay> = first_day.iter_days()
+        .take_while(|d| d.month() == month)
+        .map(|date| CalendarDay { date, created: Vec::new(), due: Vec::new(), daily_note_id: None, count: 0 })
+        .collect();
+
+    let database = load_notes()?;
+    for note in &database.notes {
+        let mut touch = |date: NaiveDate, apply: &mut dyn FnMut(&mut CalendarDay)| {
+            if date.year() == year && date.month() == month {
+                apply(&mut days[date.day0() as usize]);
+            }
+        };
+
+        touch(note.timestamp.with_timezone(&Local).date_naive(), &mut |day| day.created.push(note.id.clone()));
+        if let Some(due) = note.due_date {
+            touch(due.with_timezone(&Local).date_naive(), &mut |day| day.due.push(note.id.clone()));
+        }
+        if let Some(date) = daily_note_date(note) {
+            touch(date, &mut |day| day.daily_note_id = Some(note.id.clone()));
+        }
+    }
+
+    for day in &mut days {
+        let mut ids: Vec<&String> = day.created.iter().chain(day.due.iter()).chain(day.daily_note_id.iter()).collect();
+        ids.sort();
+        ids.dedup();
+        day.count = ids.len();
+    }
+
+    Ok(CalendarMonth { year, month, days })
+}
+
+/// One VEVENT from an .ics file
+struct CalendarEvent {
+    uid: String,
+    summary: String,
+    start: DateTime<Utc>,
+    end: Option<DateTime<Utc>>,
+    all_day: bool,
+    location: Option<String>,
+    description: Option<String>,
+    attendees: Vec<String>,
+}
+
+/// Undo RFC 5545 line folding (continuation lines start with a space or tab)
+fn unfold_lines(ics: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for raw in ics.lines() {
+        let line = raw.trim_end_matches('\r');
+        if let Some(continuation) = line.strip_prefix(' ').or_else(|| line.strip_prefix('\t')) {
+            if let Some(last) = lines.last_mut() {
+                last.push_str(continuation);
+                continue;
+            }
+        }
+        lines.push(line.to_string());
+    }
+    lines
+}
+
+fn unescape_text(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') | Some('N') => result.push('\n'),
+            Some(other) => result.push(other),
+            None => {}
+        }
+    }
+    result
+}
+
+/// Property parameters as (upper-cased name, value)
+type PropertyParams = Vec<(String, String)>;
+
+/// Split `NAME;PARAM=x;PARAM2=y:value` into (name, params, value)
+fn split_property(line: &str) -> Option<(String, PropertyParams, String)> {
+    let colon = line.find(':')?;
+    let (head, value) = (&line[..colon], &line[colon + 1..]);
+    let mut parts = head.split(';');
+    let name = parts.next()?.to_ascii_uppercase();
+    let params = parts
+        .filter_map(|p| p.split_once('='))
+        .map(|(k, v)| (k.to_ascii_uppercase(), v.trim_matches('"').to_string()))
+        .collect();
+    Some((name, params, value.to_string()))
+}
+
+/// Parse DTSTART/DTEND values. Returns the instant and whether it was a whole-day value.
+/// Floating and TZID times are read in the local time zone.
+fn parse_ics_datetime(value: &str, params: &[(String, String)]) -> Option<(DateTime<Utc>, bool)> {
+    let is_date = params.iter().any(|(k, v)| k == "VALUE" && v == "DATE") || value.len() == 8;
+    if is_date {
+        let date = NaiveDate::parse_from_str(value, "%Y%m%d").ok()?;
+        let local = Local.from_local_datetime(&date.and_hms_opt(0, 0, 0)?).earliest()?;
+        return Some((local.with_timezone(&Utc), true));
+    }
+
+    if let Some(utc_value) = value.strip_suffix('Z') {
+        let naive = NaiveDateTime::parse_from_str(utc_value, "%Y%m%dT%H%M%S").ok()?;
+        return Some((Utc.from_utc_datetime(&naive), false));
+    }
+
+    let naive = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?;
+    let local = Local.from_local_datetime(&naive).earliest()?;
+    Some((local.with_timezone(&Utc), false))
+}
+
+/// Attendee display name - the CN parameter if present, otherwise the address
+fn attendee_name(params: &[(String, String)], value: &str) -> String {
+    params.iter()
+        .find(|(k, _)| k == "CN")
+        .map(|(_, v)| v.clone())
+        .unwrap_or_else(|| value.trim_start_matches("mailto:").trim_start_matches("MAILTO:").to_string())
+}
+
+/// Extract every VEVENT. Recurring events are imported as their first occurrence.
+fn parse_ics_events(ics: &str) -> Vec<CalendarEvent> {
+    let mut events = Vec::new();
+    let mut current: Option<Vec<String>> = None;
+
+    for line in unfold_lines(ics) {
+        match line.as_str() {
+            "BEGIN:VEVENT" => current = Some(Vec::new()),
+            "END:VEVENT" => {
+                if let Some(lines) = current.take() {
+                    if let Some(event) = event_from_lines(&lines) {
+                        events.push(event);
+                    }
+                }
+            }
+            _ => {
+                if let Some(lines) = current.as_mut() {
+                    lines.push(line);
+                }
+            }
+        }
+    }
+
+    events
+}
+
+fn event_from_lines(lines: &[String]) -> Option<CalendarEvent> {
+    let mut uid = None;
+    let mut summary = None;
+    let mut start = None;
+    let mut end = None;
+    let mut location = None;
+    let mut description = None;
+    let mut attendees = Vec::new();
+
+    for line in lines {
+        let Some((name, params, value)) = split_property(line) else { continue };
+        match name.as_str() {
+            "UID" => uid = Some(value),
+            "SUMMARY" => summary = Some(unescape_text(&value)),
+            "DTSTART" => start = parse_ics_datetime(&value, &params),
+            "DTEND" => end = parse_ics_datetime(&value, &params).map(|(dt, _)| dt),
+            "LOCATION" => location = Some(unescape_text(&value)).filter(|l| !l.trim().is_empty()),
+            "DESCRIPTION" => description = Some(unescape_text(&value)).filter(|d| !d.trim().is_empty()),
+            "ATTENDEE" => attendees.push(attendee_name(&params, &value)),
+            _ => {}
+        }
+    }
+
+    let (start, all_day) = start?;
+    Some(CalendarEvent {
+        uid: uid?,
+        summary: summary.filter(|s| !s.trim().is_empty()).unwrap_or_else(|| "Untitled meeting".to_string()),
+        start,
+        end,
+        all_day,
+        location,
+        description,
+        attendees,
+    })
+}
+
+fn event_details(event: &CalendarEvent) -> String {
+    let start = event.start.with_timezone(&Local);
+    let when = if event.all_day {
+        tr("calendar.all_day", &[&format_date(&start, "date.day")])
+    } else {
+        match event.end {
+            Some(end) => format!("{} – {}", format_date(&start, "date.short"), end.with_timezone(&Local).format("%H:%M")),
+            None => format_date(&start, "date.short"),
+        }
+    };
+
+    let mut details = format!("**When:** {}", when);
+    if let Some(location) = &event.location {
+        details.push_str(&format!("\n**Location:** {}", location));
+    }
+    if !event.attendees.is_empty() {
+        details.push_str("\n**Attendees:**");
+        for attendee in &event.attendees {
+            details.push_str(&format!("\n- {}", attendee));
+        }
+    }
+    if let Some(description) = &event.description {
+        details.push_str(&format!("\n\n{}", description.trim()));
+    }
+    details
+}
+
+fn apply_event(note: &mut Note, event: &CalendarEvent) {
+    // Keep whatever the user wrote below the generated block
+    let user_notes = note.content.split_once(DETAILS_SEPARATOR).map(|(_, rest)| rest.to_string());
+    note.title = event.summary.clone();
+    note.content = match user_notes {
+        Some(rest) => format!("{}{}{}", event_details(event), DETAILS_SEPARATOR, rest),
+        None => format!("{}{}", event_details(event), DETAILS_SEPARATOR),
+    };
+    note.metadata.insert("source".to_string(), "calendar".into());
+    note.metadata.insert("ical_uid".to_string(), event.uid.clone().into());
+    note.metadata.insert("event_start".to_string(), event.start.to_rfc3339().into());
+    match event.end {
+        Some(end) => note.metadata.insert("event_end".to_string(), end.to_rfc3339().into()),
+        None => note.metadata.remove("event_end"),
+    };
+    note.metadata.insert("attendees".to_string(), event.attendees.clone().into());
+}
+
+async fn read_calendar_source(source: &str) -> Result<String, String> {
+    let source = source.trim();
+    let url = source.strip_prefix("webcal://").map(|rest| format!("https://{}", rest))
+        .or_else(|| (source.starts_with("http://") || source.starts_with("https://")).then(|| source.to_string()));
+
+    match url {
+        Some(url) => {
+            let response = reqwest::Client::new()
+                .get(&url)
+                .timeout(std::time::Duration::from_secs(20))
+                .send()
+                .await
+                .map_err(|e| format!("Failed to fetch calendar {}: {}", url, e))?;
+            if !response.status().is_success() {
+                return Err(format!("Fetching calendar {} failed with status {}", url, response.status()));
+            }
+            response.text().await
+                .map_err(|e| format!("Failed to read calendar body: {}", e))
+        }
+        None => std::fs::read_to_string(source)
+            .map_err(|e| format!("Failed to read calendar file: {}", e)),
+    }
+}
+
+/// Import events from an .ics file or calendar URL into the Meetings category.
+/// Events already imported (matched by UID) are updated in place, and every meeting
+/// is linked to the daily note of the day it happens, creating that note if needed.
+pub async fn import_calendar_events(source: String) -> Result<ImportReport, String> {
+    let ics = read_calendar_source(&source).await?;
+    let events = parse_ics_events(&ics);
+    if events.is_empty() {
+        return Err("No events found in calendar".to_string());
+    }
+
+    let mut report = ImportReport::default();
+    let mut database = load_notes()?;
+    let mut links_db = load_links()?;
+
+    for event in &events {
+        let existing = database.notes.iter().position(|n| {
+            n.metadata.get("ical_uid").and_then(|v| v.as_str()) == Some(event.uid.as_str())
+        });
+        let meeting_id = match existing {
+            Some(index) => {
+                apply_event(&mut database.notes[index], event);
+                database.notes[index].revision += 1;
+                database.notes[index].id.clone()
+            }
+            None => {
+                let mut note = Note::new(String::new(), String::new(), vec![MEETINGS_CATEGORY.to_string()]);
+                note.timestamp = event.start;
+                apply_event(&mut note, event);
+                let id = note.id.clone();
+                database.notes.push(note);
+                report.notes_imported += 1;
+                id
+            }
+        };
+
+        let day = event.start.with_timezone(&Local).date_naive();
+        let daily_id = match database.notes.iter().find(|n| daily_note_date(n) == Some(day)) {
+            Some(daily) => daily.id.clone(),
+            None => {
+                let daily = Note::new(day.format("%Y-%m-%d").to_string(), String::new(), vec![DAILY_NOTES_CATEGORY.to_string()]);
+                let id = daily.id.clone();
+                database.notes.push(daily);
+                id
+            }
+        };
+
+        let already_linked = links_db.links.iter().any(|l| {
+            (l.source_id == meeting_id && l.target_id == daily_id) || (l.source_id == daily_id && l.target_id == meeting_id)
+        });
+        if !already_linked {
+            links_db.links.push(NoteLink {
+                id: Uuid::new_v4().to_string(),
+                source_id: daily_id,
+                target_id: meeting_id,
+                link_type: LinkType::Related,
+                label: Some("Meeting".to_string()),
+                color: None,
+                directional: Some(true),
+                created_at: Utc::now(),
+                target_block: None,
+                weight: None,
+                weight_is_manual: false,
+            });
+            report.links_created += 1;
+        }
+    }
+
+    ensure_category_path(&[MEETINGS_CATEGORY.to_string()])?;
+    ensure_category_path(&[DAILY_NOTES_CATEGORY.to_string()])?;
+    save_notes(&database)?;
+    save_links(&links_db)?;
+    update_category_note_counts()?;
+
+    Ok(report)
+}
@@ -0,0 +1,120 @@
+use regex::Regex;
+use uuid::Uuid;
+use crate::models::{CaptureCondition, CaptureRule};
+use crate::services::locale_service::tr;
+use crate::services::settings_service::current_settings;
+use crate::services::storage_service::{load_settings, save_settings};
+
+pub async fn list_capture_rules() -> Result<Vec<CaptureRule>, String> {
+    Ok(load_settings()?.settings.capture_rules)
+}
+
+/// Create a rule, or update it in place when `id` matches an existing one
+pub async fn save_capture_rule(mut rule: CaptureRule) -> Result<CaptureRule, String> {
+    rule.name = rule.name.trim().to_string();
+    if rule.name.is_empty() {
+        return Err("Rule name cannot be empty".to_string());
+    }
+    rule.category_path = clean_path(rule.category_path);
+    if rule.category_path.is_empty() {
+        return Err("Choose a category for the rule to file notes into".to_string());
+    }
+    rule.condition = match rule.condition {
+        CaptureCondition::Regex(pattern) => {
+            Regex::new(&pattern).map_err(|e| format!("Invalid pattern: {}", e))?;
+            CaptureCondition::Regex(pattern)
+        }
+        CaptureCondition::UrlDomain(domain) => {
+            let domain = normalize_domain(&domain);
+            if domain.is_empty() {
+                return Err("Domain cannot be empty".to_string());
+            }
+            CaptureCondition::UrlDomain(domain)
+        }
+        CaptureCondition::Tag(tag) => {
+            let tag = tag.trim().trim_start_matches('#').to_string();
+            if tag.is_empty() {
+                return Err("Tag cannot be empty".to_string());
+            }
+            CaptureCondition::Tag(tag)
+        }
+    };
+
+    let mut database = load_settings()?;
+    if rule.id.trim().is_empty() {
+        rule.id = Uuid::new_v4().to_string();
+    }
+    match database.settings.capture_rules.iter_mut().find(|r| r.id == rule.id) {
+        Some(existing) => *existing = rule.clone(),
+        None => database.settings.capture_rules.push(rule.clone()),
+    }
+    save_settings(&database)?;
+    Ok(rule)
+}
+
+pub async fn delete_capture_rule(rule_id: String) -> Result<(), String> {
+    let mut database = load_settings()?;
+    let initial_len = database.settings.capture_rules.len();
+    database.settings.capture_rules.retain(|r| r.id != rule_id);
+    if database.settings.capture_rules.len() == initial_len {
+        return Err(format!("Capture rule with id {} not found", rule_id));
+    }
+    save_settings(&database)
+}
+
+/// Set where notes saved without a category go, or `None` to go back to "General"
+pub async fn set_default_category(category_path: Option<Vec<String>>) -> Result<Vec<String>, String> {
+    let mut database = load_settings()?;
+    database.settings.default_category = category_path.map(clean_path).filter(|p| !p.is_empty());
+    save_settings(&database)?;
+    Ok(default_category_of(database.settings.default_category))
+}
+
+fn clean_path(path: Vec<String>) -> Vec<String> {
+    path.into_iter()
+        .map(|part| part.trim().to_string())
+        .filter(|part| !part.is_empty())
+        .collect()
+}
+
+/// "https://www.Example.com/path" -> "example.com"
+fn normalize_domain(domain: &str) -> String {
+    let domain = domain.trim().to_lowercase();
+    let domain = domain.split("://").last().unwrap_or("");
+    let domain = domain.split(['/', ':', '?', '#']).next().unwrap_or("");
+    domain.trim_start_matches("www.").to_string()
+}
+
+fn default_category_of(setting: Option<Vec<String>>) -> Vec<String> {
+    setting.unwrap_or_else(|| vec![tr("category.default", &[])])
+}
+
+fn matches(condition: &CaptureCondition, title: &str, content: &str, tags: &[String]) -> bool {
+    match condition {
+        CaptureCondition::Regex(pattern) => Regex::new(pattern)
+            .is_ok_and(|re| re.is_match(content) || re.is_match(title)),
+        CaptureCondition::UrlDomain(domain) => {
+            let Ok(urls) = Regex::new(r"(?i)https?://([^/\s:?#)\]]+)") else { return false };
+            let linked = urls.captures_iter(content).any(|c| {
+                let host = c[1].to_lowercase();
+                host == *domain || host.ends_with(&format!(".{}", domain))
+            });
+            linked
+        }
+        CaptureCondition::Tag(tag) => {
+            tags.iter().any(|t| t.eq_ignore_ascii_case(tag))
+                || Regex::new(&format!(r"(?i)(^|\s)#{}\b", regex::escape(tag))).is_ok_and(|re| re.is_match(content))
+        }
+    }
+}
+
+/// Category for a note saved without one: the first enabled capture rule that matches,
+/// otherwise the configured default category
+pub fn category_for_new_note(title: &str, content: &str, tags: &[String]) -> Vec<String> {
+    let settings = current_settings();
+    settings.capture_rules.iter()
+        .filter(|rule| rule.enabled)
+        .find(|rule| matches(&rule.condition, title, content, tags))
+        .map(|rule| rule.category_path.clone())
+        .unwrap_or_else(|| default_category_of(settings.default_category))
+}
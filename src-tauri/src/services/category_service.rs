@@ -1,9 +1,13 @@
 use chrono::Utc;
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
-use crate::models::{Category, CategoriesDatabase, NotesDatabase};
-use crate::services::storage_service::{get_categories_file_path, save_categories, get_notes_file_path, save_notes};
+use crate::models::{AppSettings, Category, CategoriesDatabase, EmptyCategoryCleanup, Note};
+use crate::services::audit_service::{category_deleted, note_deleted, record_deletions};
+use crate::services::settings_service::current_settings;
+use crate::services::storage_service::{get_categories_file_path, load_settings, save_categories, save_notes, save_settings};
 use crate::services::note_service;
 use std::fs;
+use crate::services::locale_service::tr;
 
 pub fn load_categories() -> Result<CategoriesDatabase, String> {
     let file_path = get_categories_file_path()?;
@@ -34,6 +38,11 @@ pub fn load_categories() -> Result<CategoriesDatabase, String> {
         }
         // Note: created_at will be set by serde default if missing
     }
+    // Files from before direct/total counts were split only have `note_count`
+    if database.categories.iter().any(|c| c.note_count != c.total_note_count) {
+        needs_migration = true;
+        count_notes(&mut database.categories, &note_service::load_notes()?.notes);
+    }
     
     if needs_migration {
         save_categories(&database)?;
@@ -42,32 +51,120 @@ pub fn load_categories() -> Result<CategoriesDatabase, String> {
     Ok(database)
 }
 
+/// Full recount from the notes file. Single-note changes use
+/// `adjust_category_note_counts` instead; this is for bulk changes and repairs.
 pub fn update_category_note_counts() -> Result<(), String> {
-    let notes_file_path = get_notes_file_path()?;
-    let notes_content = fs::read_to_string(&notes_file_path)
-        .map_err(|e| format!("Failed to read notes file: {}", e))?;
-    let notes_db: NotesDatabase = serde_json::from_str(&notes_content)
-        .map_err(|e| format!("Failed to parse notes file: {}", e))?;
+    let notes_db = note_service::load_notes()?;
     
     let mut categories_db = load_categories()?;
+    count_notes(&mut categories_db.categories, &notes_db.notes);
+    tidy_empty_categories(&mut categories_db.categories);
     
+    save_categories(&categories_db)?;
+    Ok(())
+}
+
+/// Add `delta` notes to the category at `path` and every parent of it, without reading
+/// the notes. Called for each note created, deleted or moved.
+pub fn adjust_category_note_counts(path: &[String], delta: i32) -> Result<(), String> {
+    let mut categories_db = load_categories()?;
+    let mut changed = false;
+    for category in categories_db.categories.iter_mut().filter(|c| path.starts_with(&c.path)) {
+        category.total_note_count = category.total_note_count.saturating_add_signed(delta);
+        if category.path.len() == path.len() {
+            category.direct_note_count = category.direct_note_count.saturating_add_signed(delta);
+        }
+        category.note_count = category.total_note_count;
+        changed = true;
+    }
+    if changed {
+        tidy_empty_categories(&mut categories_db.categories);
+        save_categories(&categories_db)?;
+    }
+    Ok(())
+}
+
+fn is_empty_leaf(categories: &[Category], category: &Category) -> bool {
+    category.total_note_count == 0
+        && !categories.iter().any(|c| c.parent_id.as_deref() == Some(category.id.as_str()))
+}
+
+/// Apply the empty-category setting after counts changed. Pruning repeats so a chain of
+/// auto-created parents goes with its last leaf.
+fn tidy_empty_categories(categories: &mut Vec<Category>) {
+    match current_settings().empty_categories {
+        EmptyCategoryCleanup::Keep => {}
+        EmptyCategoryCleanup::Flag => {
+            let flags: Vec<bool> = categories.iter().map(|c| c.auto_created && is_empty_leaf(categories, c)).collect();
+            for (category, flagged) in categories.iter_mut().zip(flags) {
+                category.flagged_empty = flagged;
+            }
+        }
+        EmptyCategoryCleanup::Prune => loop {
+            let prunable: Vec<String> = categories.iter()
+                .filter(|c| c.auto_created && is_empty_leaf(categories, c))
+                .map(|c| c.id.clone())
+                .collect();
+            if prunable.is_empty() {
+                break;
+            }
+            record_deletions(categories.iter().filter(|c| prunable.contains(&c.id)).map(category_deleted).collect());
+            categories.retain(|c| !prunable.contains(&c.id));
+        },
+    }
+}
+
+/// Leaf categories without any notes, auto-created or not
+pub async fn find_empty_categories() -> Result<Vec<Category>, String> {
+    let categories = load_categories()?.categories;
+    Ok(categories.iter().filter(|c| is_empty_leaf(&categories, c)).cloned().collect())
+}
+
+/// Choose what happens to empty auto-created categories, and apply it right away
+pub async fn set_empty_category_cleanup(mode: EmptyCategoryCleanup) -> Result<AppSettings, String> {
+    let mut database = load_settings()?;
+    database.settings.empty_categories = mode;
+    save_settings(&database)?;
+    if mode == EmptyCategoryCleanup::Keep {
+        let mut categories_db = load_categories()?;
+        categories_db.categories.iter_mut().for_each(|c| c.flagged_empty = false);
+        save_categories(&categories_db)?;
+    } else {
+        update_category_note_counts()?;
+    }
+    Ok(database.settings)
+}
+
+/// Recount every category from scratch, e.g. after notes were edited outside the app.
+/// Returns the corrected categories.
+pub async fn recount_categories() -> Result<Vec<Category>, String> {
+    update_category_note_counts()?;
+    Ok(load_categories()?.categories)
+}
+
+/// Recount every category's direct and total (including subcategories) notes
+fn count_notes(categories: &mut [Category], notes: &[Note]) {
     // Reset all counts
-    for category in &mut categories_db.categories {
-        category.note_count = 0;
+    for category in categories.iter_mut() {
+        category.direct_note_count = 0;
+        category.total_note_count = 0;
     }
     
     // Count notes for each category path
-    for note in &notes_db.notes {
+    for note in notes {
         // Update count for the exact category and all parent categories
-        for category in &mut categories_db.categories {
+        for category in categories.iter_mut() {
             if note.category_path.starts_with(&category.path) {
-                category.note_count += 1;
+                category.total_note_count += 1;
+                if note.category_path.len() == category.path.len() {
+                    category.direct_note_count += 1;
+                }
             }
         }
     }
-    
-    save_categories(&categories_db)?;
-    Ok(())
+    for category in categories.iter_mut() {
+        category.note_count = category.total_note_count;
+    }
 }
 
 /// Get a category by its ID
@@ -119,23 +216,50 @@ pub fn validate_category_path(path: &[String]) -> Result<bool, String> {
     Ok(true)
 }
 
+/// Create any missing levels of a category path, parents first
+pub fn ensure_category_path(path: &[String]) -> Result<(), String> {
+    if path.is_empty() || validate_category_path(path)? {
+        return Ok(());
+    }
+    
+    let mut current_path = Vec::new();
+    for segment in path {
+        current_path.push(segment.clone());
+        
+        // Check if this level exists
+        if !validate_category_path(&current_path)? {
+            // Create this level
+            let parent_path = if current_path.len() > 1 {
+                Some(current_path[..current_path.len() - 1].to_vec())
+            } else {
+                None
+            };
+            add_category(segment.clone(), parent_path, true)?;
+        }
+    }
+    
+    Ok(())
+}
+
 /// Safely delete a category and handle all dependent data
 pub fn safe_delete_category(category_id: &str) -> Result<(), String> {
     let mut categories_db = load_categories()?;
     
     // Read notes database
-    let notes_file_path = get_notes_file_path()?;
-    let notes_content = fs::read_to_string(&notes_file_path)
-        .map_err(|e| format!("Failed to read notes file: {}", e))?;
-    let mut notes_db: NotesDatabase = serde_json::from_str(&notes_content)
-        .map_err(|e| format!("Failed to parse notes file: {}", e))?;
+    let mut notes_db = note_service::load_notes()?;
     
     // Find the category to delete
     let category = categories_db.categories.iter()
         .find(|cat| cat.id == category_id)
-        .ok_or("Category not found")?
+        .ok_or_else(|| tr("error.category_not_found", &[]))?
         .clone();
     
+    let mut deleted: Vec<_> = notes_db.notes.iter()
+        .filter(|note| note.category_path.starts_with(&category.path))
+        .map(note_deleted)
+        .collect();
+    deleted.extend(categories_db.categories.iter().filter(|cat| cat.path.starts_with(&category.path)).map(category_deleted));
+
     // Delete all notes from this category and subcategories
     notes_db.notes.retain(|note| {
         !note.category_path.starts_with(&category.path)
@@ -148,6 +272,7 @@ pub fn safe_delete_category(category_id: &str) -> Result<(), String> {
     
     save_categories(&categories_db)?;
     save_notes(&notes_db)?;
+    record_deletions(deleted);
     update_category_note_counts()?;
     
     Ok(())
@@ -210,8 +335,45 @@ pub fn find_category_by_name_fuzzy(search_name: &str) -> Result<Vec<Category>, S
     Ok(matches)
 }
 
+/// A fresh category at `path`, under its parent in `categories` if that exists
+fn new_category(categories: &[Category], path: Vec<String>) -> Category {
+    // Find parent ID if there is one
+    let parent_id = if path.len() > 1 {
+        let parent_path = &path[..path.len() - 1];
+        categories.iter()
+            .find(|cat| cat.path == parent_path)
+            .map(|cat| cat.id.clone())
+    } else {
+        None
+    };
+
+    Category {
+        id: Uuid::new_v4().to_string(),
+        name: path.last().cloned().unwrap_or_default(),
+        parent_id,
+        full_path: path.join(" → "),
+        level: (path.len() as u32).saturating_sub(1),
+        path,
+        note_count: 0,
+        direct_note_count: 0,
+        total_note_count: 0,
+        created_at: Utc::now(),
+        color: None,
+        auto_created: false,
+        flagged_empty: false,
+        visibility: None,
+        ai_excluded: false,
+    }
+}
+
 /// Create a new category with proper validation and hierarchy setup
 pub fn create_category_safe(name: String, parent_path: Option<Vec<String>>) -> Result<Category, String> {
+    add_category(name, parent_path, false)
+}
+
+/// Create one category under an existing parent. `auto_created` marks categories that
+/// only exist because a note was filed there.
+fn add_category(name: String, parent_path: Option<Vec<String>>, auto_created: bool) -> Result<Category, String> {
     let mut categories_db = load_categories()?;
     
     // Build the full path
@@ -237,31 +399,7 @@ pub fn create_category_safe(name: String, parent_path: Option<Vec<String>>) -> R
         return Err(format!("Category with path {:?} already exists", full_path));
     }
     
-    // Find parent ID if there is one
-    let parent_id = if full_path.len() > 1 {
-        let parent_path = &full_path[..full_path.len() - 1];
-        categories_db.categories.iter()
-            .find(|cat| cat.path == parent_path)
-            .map(|cat| cat.id.clone())
-    } else {
-        None
-    };
-    
-    let level = (full_path.len() as u32).saturating_sub(1);
-    let display_path = full_path.join(" → ");
-    
-    let category = Category {
-        id: Uuid::new_v4().to_string(),
-        name,
-        parent_id,
-        full_path: display_path,
-        path: full_path,
-        level,
-        note_count: 0,
-        created_at: Utc::now(),
-        color: None,
-    };
-    
+    let category = Category { auto_created, ..new_category(&categories_db.categories, full_path) };
     categories_db.categories.push(category.clone());
     save_categories(&categories_db)?;
     
@@ -275,7 +413,7 @@ pub fn rename_category(category_id: String, new_name: String) -> Result<(), Stri
     // Find the category to rename
     let category_index = categories_db.categories.iter()
         .position(|cat| cat.id == category_id)
-        .ok_or("Category not found")?;
+        .ok_or_else(|| tr("error.category_not_found", &[]))?;
     
     let old_path = categories_db.categories[category_index].path.clone();
     let mut new_path = old_path.clone();
@@ -304,10 +442,159 @@ pub fn rename_category(category_id: String, new_name: String) -> Result<(), Stri
             let mut updated_path = new_path.clone();
             updated_path.extend_from_slice(&note.category_path[old_path.len()..]);
             note.category_path = updated_path;
+            note.revision += 1;
         }
     }
     
     save_categories(&categories_db)?;
     save_notes(&notes_db)?;
     Ok(())
-}
\ No newline at end of file
+}
+/// One category of a shared taxonomy, with its subcategories. Carries no notes or counts.
+#[derive(Serialize, Deserialize)]
+pub struct CategoryTreeNode {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<CategoryTreeNode>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CategoryTreeFile {
+    version: u32,
+    categories: Vec<CategoryTreeNode>,
+}
+
+#[derive(Serialize)]
+pub struct CategoryTreeImport {
+    pub created: usize,
+    pub updated: usize, // Existing categories whose color changed
+    pub removed: usize, // Replace mode: empty categories that weren't in the tree
+    pub kept: usize,    // Replace mode: categories missing from the tree but still holding notes
+}
+
+const CATEGORY_TREE_VERSION: u32 = 1;
+
+fn tree_below(categories: &[Category], parent: &[String]) -> Vec<CategoryTreeNode> {
+    let mut children: Vec<&Category> = categories.iter()
+        .filter(|c| c.path.len() == parent.len() + 1 && c.path.starts_with(parent))
+        .collect();
+    children.sort_by(|a, b| a.name.cmp(&b.name));
+    children.into_iter()
+        .map(|c| CategoryTreeNode {
+            name: c.name.clone(),
+            color: c.color.clone(),
+            children: tree_below(categories, &c.path),
+        })
+        .collect()
+}
+
+/// The category tree as JSON, without any note content, for sharing a taxonomy
+pub fn export_category_tree() -> Result<String, String> {
+    let categories = load_categories()?.categories;
+    let file = CategoryTreeFile { version: CATEGORY_TREE_VERSION, categories: tree_below(&categories, &[]) };
+    serde_json::to_string_pretty(&file).map_err(|e| format!("Failed to serialize category tree: {}", e))
+}
+
+/// Every path in the tree, parents before their children, with the color given for it
+fn flatten_tree(nodes: Vec<CategoryTreeNode>, parent: &[String], out: &mut Vec<(Vec<String>, Option<String>)>) -> Result<(), String> {
+    for node in nodes {
+        let name = node.name.trim().to_string();
+        if name.is_empty() {
+            return Err("Category names in the tree cannot be empty".to_string());
+        }
+        let mut path = parent.to_vec();
+        path.push(name);
+        out.push((path.clone(), node.color));
+        flatten_tree(node.children, &path, out)?;
+    }
+    Ok(())
+}
+
+/// Bring in a tree written by `export_category_tree`. With `merge` missing categories
+/// are added next to the existing ones; without it the vault ends up with just the
+/// imported tree, except that categories still holding notes are never removed.
+pub fn import_category_tree(json: &str, merge: bool) -> Result<CategoryTreeImport, String> {
+    let file: CategoryTreeFile = serde_json::from_str(json)
+        .map_err(|e| format!("Not a category tree: {}", e))?;
+    if file.version > CATEGORY_TREE_VERSION {
+        return Err(format!("Category tree version {} is newer than this app supports", file.version));
+    }
+    let mut imported = Vec::new();
+    flatten_tree(file.categories, &[], &mut imported)?;
+    install_category_paths(&imported, merge)
+}
+
+/// Add `paths` (parents first, each with an optional color) to the categories; see
+/// `import_category_tree` for what `merge` does
+fn install_category_paths(imported: &[(Vec<String>, Option<String>)], merge: bool) -> Result<CategoryTreeImport, String> {
+    let mut categories_db = load_categories()?;
+    let mut summary = CategoryTreeImport { created: 0, updated: 0, removed: 0, kept: 0 };
+    for (path, color) in imported {
+        match categories_db.categories.iter_mut().find(|c| &c.path == path) {
+            Some(existing) => {
+                if color.is_some() && existing.color != *color {
+                    existing.color = color.clone();
+                    summary.updated += 1;
+                }
+            }
+            None => {
+                let mut category = new_category(&categories_db.categories, path.clone());
+                category.color = color.clone();
+                categories_db.categories.push(category);
+                summary.created += 1;
+            }
+        }
+    }
+
+    if !merge {
+        let notes = note_service::load_notes()?.notes;
+        let mut removed = Vec::new();
+        categories_db.categories.retain(|category| {
+            if imported.iter().any(|(path, _)| *path == category.path) {
+                return true;
+            }
+            let has_notes = notes.iter().any(|n| n.category_path.starts_with(&category.path));
+            if has_notes {
+                summary.kept += 1;
+            } else {
+                removed.push(category_deleted(category));
+            }
+            has_notes
+        });
+        summary.removed = removed.len();
+        record_deletions(removed);
+    }
+
+    save_categories(&categories_db)?;
+    update_category_note_counts()?;
+    Ok(summary)
+}
+
+/// Starter category trees. Each entry is a path with levels separated by " / ".
+const TAXONOMY_TEMPLATES: &[(&str, &[&str])] = &[
+    ("PARA", &["Projects", "Areas", "Areas / Health", "Areas / Finances", "Areas / Home", "Resources", "Archive"]),
+    ("Zettelkasten", &["Fleeting Notes", "Literature Notes", "Permanent Notes", "Structure Notes", "Index"]),
+    ("Johnny.Decimal", &[
+        "10-19 Life Admin", "10-19 Life Admin / 11 Finances", "10-19 Life Admin / 12 Health", "10-19 Life Admin / 13 Home",
+        "20-29 Work", "20-29 Work / 21 Projects", "20-29 Work / 22 Meetings", "20-29 Work / 23 Reference",
+        "30-39 Learning", "30-39 Learning / 31 Courses", "30-39 Learning / 32 Books", "30-39 Learning / 33 Ideas",
+    ]),
+];
+
+pub fn list_taxonomy_templates() -> Vec<String> {
+    TAXONOMY_TEMPLATES.iter().map(|(name, _)| name.to_string()).collect()
+}
+
+/// Install a starter tree (see `list_taxonomy_templates`). Existing categories are
+/// never removed or changed - the template's categories are only added where missing.
+pub fn apply_taxonomy_template(name: &str) -> Result<CategoryTreeImport, String> {
+    let (_, paths) = TAXONOMY_TEMPLATES.iter()
+        .find(|(template, _)| template.eq_ignore_ascii_case(name.trim()))
+        .ok_or_else(|| format!("Unknown taxonomy template '{}'. Available: {}", name, list_taxonomy_templates().join(", ")))?;
+    let imported: Vec<(Vec<String>, Option<String>)> = paths.iter()
+        .map(|path| (path.split(" / ").map(str::to_string).collect(), None))
+        .collect();
+    install_category_paths(&imported, true)
+}
@@ -0,0 +1,577 @@
+use regex::Regex;
+use std::collections::HashMap;
+use std::fs;
+use crate::models::{Citation, Note, NoteKind};
+use crate::services::import_service::{persist_imported_notes, ImportReport};
+use crate::services::note_service::load_notes;
+use crate::services::storage_service::save_notes;
+use crate::services::locale_service::tr;
+
+const REFERENCES_CATEGORY: &str = "References";
+
+/// One `@type{key, field = value, ...}` entry with raw (still LaTeX-ish) field values
+struct BibEntry {
+    entry_type: String,
+    key: String,
+    fields: HashMap<String, String>,
+}
+
+pub enum CitationStyle {
+    Apa,
+    Mla,
+    Chicago,
+    Bibtex,
+}
+
+impl CitationStyle {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.trim().to_lowercase().as_str() {
+            "apa" => Ok(CitationStyle::Apa),
+            "mla" => Ok(CitationStyle::Mla),
+            "chicago" => Ok(CitationStyle::Chicago),
+            "bibtex" => Ok(CitationStyle::Bibtex),
+            _ => Err(format!("Unknown citation style '{}', expected apa, mla, chicago or bibtex", value)),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// BibTeX parsing
+// ---------------------------------------------------------------------------
+
+struct BibCursor<'a> {
+    text: &'a str,
+    position: usize,
+}
+
+impl<'a> BibCursor<'a> {
+    fn peek(&self) -> Option<char> {
+        self.text[self.position..].chars().next()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(c) = self.peek().filter(|c| c.is_whitespace()) {
+            self.position += c.len_utf8();
+        }
+    }
+
+    fn eat(&mut self, expected: char) -> bool {
+        self.skip_whitespace();
+        if self.peek() == Some(expected) {
+            self.position += expected.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Run of characters allowed in entry types, keys and field names
+    fn identifier(&mut self) -> &'a str {
+        self.skip_whitespace();
+        let start = self.position;
+        while let Some(c) = self.peek() {
+            if c.is_whitespace() || matches!(c, '{' | '}' | '(' | ')' | ',' | '=' | '#' | '"') {
+                break;
+            }
+            self.position += c.len_utf8();
+        }
+        &self.text[start..self.position]
+    }
+
+    /// Contents of a `{...}` group with nested braces kept; the cursor is on the `{`
+    fn braced(&mut self) -> Result<&'a str, String> {
+        let start = self.position + 1;
+        let mut depth = 0;
+        for (offset, c) in self.text[self.position..].char_indices() {
+            match c {
+                '{' => depth += 1,
+                '}' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        let end = self.position + offset;
+                        self.position = end + 1;
+                        return Ok(&self.text[start..end]);
+                    }
+                }
+                _ => {}
+            }
+        }
+        Err("Unbalanced braces".to_string())
+    }
+
+    /// Contents of a `"..."` string; braces inside may hide quotes
+    fn quoted(&mut self) -> Result<&'a str, String> {
+        let start = self.position + 1;
+        let mut depth = 0;
+        for (offset, c) in self.text[start..].char_indices() {
+            match c {
+                '{' => depth += 1,
+                '}' => depth -= 1,
+                '"' if depth == 0 => {
+                    let end = start + offset;
+                    self.position = end + 1;
+                    return Ok(&self.text[start..end]);
+                }
+                _ => {}
+            }
+        }
+        Err("Unterminated string".to_string())
+    }
+
+    /// A field value: pieces joined with `#`, each braced, quoted, a number or a @string macro
+    fn value(&mut self, macros: &HashMap<String, String>) -> Result<String, String> {
+        let mut value = String::new();
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                Some('{') => value.push_str(self.braced()?),
+                Some('"') => value.push_str(self.quoted()?),
+                Some(_) => {
+                    let word = self.identifier();
+                    if word.is_empty() {
+                        return Err("Expected a field value".to_string());
+                    }
+                    match macros.get(&word.to_lowercase()) {
+                        Some(expansion) => value.push_str(expansion),
+                        None => value.push_str(word),
+                    }
+                }
+                None => return Err("Unexpected end of file".to_string()),
+            }
+            if !self.eat('#') {
+                return Ok(value);
+            }
+        }
+    }
+}
+
+/// Parse every entry in a .bib file. Broken entries are reported and skipped.
+fn parse_bibtex(text: &str) -> (Vec<BibEntry>, Vec<String>) {
+    let mut entries = Vec::new();
+    let mut errors = Vec::new();
+    let mut macros: HashMap<String, String> = HashMap::new();
+    let mut cursor = BibCursor { text, position: 0 };
+
+    while let Some(at) = text[cursor.position..].find('@') {
+        cursor.position += at + 1;
+        let entry_type = cursor.identifier().to_lowercase();
+        if !cursor.eat('{') && !cursor.eat('(') {
+            continue;
+        }
+
+        match entry_type.as_str() {
+            "comment" | "preamble" => continue,
+            "string" => {
+                let name = cursor.identifier().to_lowercase();
+                if cursor.eat('=') {
+                    if let Ok(value) = cursor.value(&macros) {
+                        macros.insert(name, value);
+                    }
+                }
+                continue;
+            }
+            _ => {}
+        }
+
+        let key = cursor.identifier().to_string();
+        let mut fields = HashMap::new();
+        let mut failed = None;
+        cursor.eat(',');
+
+        loop {
+            if cursor.eat('}') || cursor.eat(')') {
+                break;
+            }
+            let name = cursor.identifier().to_lowercase();
+            if name.is_empty() || !cursor.eat('=') {
+                failed = Some(format!("expected a field near byte {}", cursor.position));
+                break;
+            }
+            match cursor.value(&macros) {
+                Ok(value) => {
+                    fields.insert(name, value);
+                }
+                Err(e) => {
+                    failed = Some(e);
+                    break;
+                }
+            }
+            cursor.eat(',');
+        }
+
+        match failed {
+            Some(reason) => errors.push(format!("{}: {}", if key.is_empty() { "entry" } else { &key }, reason)),
+            None => entries.push(BibEntry { entry_type, key, fields }),
+        }
+    }
+
+    (entries, errors)
+}
+
+/// Turn common LaTeX markup into plain text: accents, escapes, case-protecting braces
+fn clean_latex(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' | '}' => {}
+            '~' => result.push(' '),
+            '\\' => {
+                let Some(&next) = chars.peek() else { break };
+                let accent = match next {
+                    '"' => Some('\u{0308}'),
+                    '\'' => Some('\u{0301}'),
+                    '`' => Some('\u{0300}'),
+                    '^' => Some('\u{0302}'),
+                    '~' => Some('\u{0303}'),
+                    _ => None,
+                };
+                chars.next();
+                if let Some(accent) = accent {
+                    while chars.peek() == Some(&'{') {
+                        chars.next();
+                    }
+                    if let Some(letter) = chars.next() {
+                        result.push(letter);
+                        result.push(accent);
+                    }
+                } else if next.is_alphabetic() {
+                    // Drop commands like \textit - their argument stays
+                    while chars.peek().is_some_and(|c| c.is_alphabetic()) {
+                        chars.next();
+                    }
+                } else {
+                    result.push(next); // \& \% \_ \$
+                }
+            }
+            _ => result.push(c),
+        }
+    }
+
+    result.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn split_authors(value: &str) -> Vec<String> {
+    let separator = Regex::new(r"(?i)\s+and\s+").expect("valid author separator");
+    separator.split(&clean_latex(value))
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+fn citation_from_entry(entry: &BibEntry) -> Citation {
+    let field = |name: &str| entry.fields.get(name).map(|v| clean_latex(v)).filter(|v| !v.is_empty());
+    let year = field("year").and_then(|y| y.chars().filter(char::is_ascii_digit).collect::<String>().parse().ok());
+
+    Citation {
+        entry_type: entry.entry_type.clone(),
+        citation_key: entry.key.clone(),
+        authors: entry.fields.get("author").or(entry.fields.get("editor"))
+            .map(|a| split_authors(a))
+            .unwrap_or_default(),
+        title: field("title").unwrap_or_default(),
+        year,
+        journal: field("journal").or_else(|| field("booktitle")),
+        publisher: field("publisher").or_else(|| field("institution")).or_else(|| field("school")),
+        volume: field("volume"),
+        issue: field("number"),
+        pages: field("pages").map(|p| p.replace("--", "–")),
+        doi: field("doi"),
+        url: field("url"),
+        isbn: field("isbn"),
+    }
+}
+
+/// Build a reference note for a citation, with the abstract (if any) as its content
+pub fn reference_note(citation: Citation, summary: String, tags: Vec<String>) -> Note {
+    let title = if citation.title.is_empty() { citation.citation_key.clone() } else { citation.title.clone() };
+    Note {
+        kind: NoteKind::Reference,
+        citation: Some(citation),
+        tags,
+        ..Note::new(title, summary, vec![REFERENCES_CATEGORY.to_string()])
+    }
+}
+
+/// Whether a library already holds this work - same DOI, or same citation key
+pub fn find_existing_reference<'a>(notes: &'a [Note], citation: &Citation) -> Option<&'a Note> {
+    notes.iter().find(|n| {
+        n.citation.as_ref().is_some_and(|existing| {
+            let same_doi = matches!((&existing.doi, &citation.doi), (Some(a), Some(b)) if a.eq_ignore_ascii_case(b));
+            let same_key = !citation.citation_key.is_empty() && existing.citation_key == citation.citation_key;
+            same_doi || same_key
+        })
+    })
+}
+
+/// Import every entry of a .bib file as a reference note. Works already in the library
+/// (matched by DOI or citation key) are skipped.
+pub async fn import_bibtex(path: String) -> Result<ImportReport, String> {
+    let text = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read BibTeX file: {}", e))?;
+    let (entries, errors) = parse_bibtex(&text);
+
+    let existing = load_notes()?.notes;
+    let mut report = ImportReport { skipped: errors, ..Default::default() };
+    let mut notes: Vec<Note> = Vec::new();
+
+    for entry in &entries {
+        let citation = citation_from_entry(entry);
+        if find_existing_reference(&existing, &citation).is_some() || find_existing_reference(&notes, &citation).is_some() {
+            report.skipped.push(format!("{}: already in library", entry.key));
+            continue;
+        }
+
+        let summary = entry.fields.get("abstract").map(|a| clean_latex(a)).unwrap_or_default();
+        let tags = entry.fields.get("keywords")
+            .map(|k| clean_latex(k).split([',', ';']).map(|t| t.trim().to_string()).filter(|t| !t.is_empty()).collect())
+            .unwrap_or_default();
+        notes.push(reference_note(citation, summary, tags));
+    }
+
+    report.notes_imported = notes.len();
+    persist_imported_notes(notes)?;
+    Ok(report)
+}
+
+/// Attach (or replace) citation data on a note and mark it as a reference
+pub async fn set_note_citation(note_id: String, citation: Citation) -> Result<Note, String> {
+    let mut database = load_notes()?;
+    let note = database.notes.iter_mut()
+        .find(|n| n.id == note_id)
+        .ok_or_else(|| tr("error.note_not_found", &[&note_id]))?;
+
+    note.kind = NoteKind::Reference;
+    note.citation = Some(citation);
+    note.revision += 1;
+    let updated = note.clone();
+    save_notes(&database)?;
+    Ok(updated)
+}
+
+// ---------------------------------------------------------------------------
+// Formatting
+// ---------------------------------------------------------------------------
+
+/// (family name, given names) from "Last, First" or "First Last"
+fn name_parts(name: &str) -> (String, String) {
+    if let Some((last, given)) = name.split_once(',') {
+        return (last.trim().to_string(), given.trim().to_string());
+    }
+    let mut words: Vec<&str> = name.split_whitespace().collect();
+    let last = words.pop().unwrap_or("").to_string();
+    (last, words.join(" "))
+}
+
+fn initials(given: &str) -> String {
+    given.split([' ', '-'])
+        .filter_map(|part| part.chars().next())
+        .map(|c| format!("{}.", c))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Join a list as "a, b, <conjunction> c" (two items get no comma unless `serial_two`)
+fn join_names(names: &[String], conjunction: &str, serial_two: bool) -> String {
+    match names {
+        [] => String::new(),
+        [only] => only.clone(),
+        [first, second] if !serial_two => format!("{} {} {}", first, conjunction, second),
+        _ => {
+            let (last, rest) = names.split_last().unwrap();
+            format!("{}, {} {}", rest.join(", "), conjunction, last)
+        }
+    }
+}
+
+fn doi_link(citation: &Citation) -> Option<String> {
+    citation.doi.as_ref()
+        .map(|doi| format!("https://doi.org/{}", doi.trim_start_matches("https://doi.org/")))
+        .or_else(|| citation.url.clone())
+}
+
+fn format_apa(c: &Citation) -> String {
+    let names: Vec<String> = c.authors.iter()
+        .map(|a| {
+            let (last, given) = name_parts(a);
+            if given.is_empty() { last } else { format!("{}, {}", last, initials(&given)) }
+        })
+        .collect();
+    let mut parts = Vec::new();
+    if !names.is_empty() {
+        parts.push(join_names(&names, "&", true).trim_end_matches('.').to_string() + ".");
+    }
+    parts.push(format!("({}).", c.year.map(|y| y.to_string()).unwrap_or_else(|| "n.d.".to_string())));
+    parts.push(format!("{}.", c.title.trim_end_matches('.')));
+
+    if let Some(journal) = &c.journal {
+        let mut source = journal.clone();
+        if let Some(volume) = &c.volume {
+            source.push_str(&format!(", {}", volume));
+        }
+        if let Some(issue) = &c.issue {
+            source.push_str(&format!("({})", issue));
+        }
+        if let Some(pages) = &c.pages {
+            source.push_str(&format!(", {}", pages));
+        }
+        parts.push(format!("{}.", source));
+    } else if let Some(publisher) = &c.publisher {
+        parts.push(format!("{}.", publisher));
+    }
+    if let Some(link) = doi_link(c) {
+        parts.push(link);
+    }
+    parts.join(" ")
+}
+
+fn format_mla(c: &Citation) -> String {
+    let authors = match c.authors.as_slice() {
+        [] => String::new(),
+        [only] => {
+            let (last, given) = name_parts(only);
+            if given.is_empty() { last } else { format!("{}, {}", last, given) }
+        }
+        [first, second] => {
+            let (last, given) = name_parts(first);
+            let (second_last, second_given) = name_parts(second);
+            format!("{}, {}, and {} {}", last, given, second_given, second_last)
+        }
+        [first, ..] => {
+            let (last, given) = name_parts(first);
+            format!("{}, {}, et al", last, given)
+        }
+    };
+
+    let mut parts = Vec::new();
+    if !authors.is_empty() {
+        parts.push(format!("{}.", authors.trim_end_matches('.')));
+    }
+    parts.push(format!("\"{}.\"", c.title.trim_end_matches('.')));
+
+    let mut container = Vec::new();
+    if let Some(journal) = &c.journal {
+        container.push(journal.clone());
+    } else if let Some(publisher) = &c.publisher {
+        container.push(publisher.clone());
+    }
+    if let Some(volume) = &c.volume {
+        container.push(format!("vol. {}", volume));
+    }
+    if let Some(issue) = &c.issue {
+        container.push(format!("no. {}", issue));
+    }
+    if let Some(year) = c.year {
+        container.push(year.to_string());
+    }
+    if let Some(pages) = &c.pages {
+        container.push(format!("pp. {}", pages));
+    }
+    if !container.is_empty() {
+        parts.push(format!("{}.", container.join(", ")));
+    }
+    parts.join(" ")
+}
+
+fn format_chicago(c: &Citation) -> String {
+    let names: Vec<String> = c.authors.iter().enumerate()
+        .map(|(i, a)| {
+            let (last, given) = name_parts(a);
+            match (i, given.is_empty()) {
+                (_, true) => last,
+                (0, false) => format!("{}, {}", last, given),
+                _ => format!("{} {}", given, last),
+            }
+        })
+        .collect();
+
+    let mut parts = Vec::new();
+    if !names.is_empty() {
+        parts.push(format!("{}.", join_names(&names, "and", true).trim_end_matches('.')));
+    }
+    if let Some(year) = c.year {
+        parts.push(format!("{}.", year));
+    }
+    parts.push(format!("\"{}.\"", c.title.trim_end_matches('.')));
+
+    if let Some(journal) = &c.journal {
+        let mut source = journal.clone();
+        if let Some(volume) = &c.volume {
+            source.push_str(&format!(" {}", volume));
+        }
+        if let Some(issue) = &c.issue {
+            source.push_str(&format!(" ({})", issue));
+        }
+        if let Some(pages) = &c.pages {
+            source.push_str(&format!(": {}", pages));
+        }
+        parts.push(format!("{}.", source));
+    } else if let Some(publisher) = &c.publisher {
+        parts.push(format!("{}.", publisher));
+    }
+    if let Some(link) = doi_link(c) {
+        parts.push(format!("{}.", link));
+    }
+    parts.join(" ")
+}
+
+fn format_bibtex(c: &Citation) -> String {
+    let key = if c.citation_key.is_empty() {
+        let author = c.authors.first().map(|a| name_parts(a).0.to_lowercase()).unwrap_or_else(|| "anon".to_string());
+        format!("{}{}", author, c.year.map(|y| y.to_string()).unwrap_or_default())
+    } else {
+        c.citation_key.clone()
+    };
+    let entry_type = if c.entry_type.is_empty() { "misc" } else { c.entry_type.as_str() };
+
+    let mut fields: Vec<(&str, String)> = Vec::new();
+    if !c.authors.is_empty() {
+        fields.push(("author", c.authors.join(" and ")));
+    }
+    fields.push(("title", c.title.clone()));
+    let optional = [
+        ("year", c.year.map(|y| y.to_string())),
+        (if entry_type == "article" { "journal" } else { "booktitle" }, c.journal.clone()),
+        ("publisher", c.publisher.clone()),
+        ("volume", c.volume.clone()),
+        ("number", c.issue.clone()),
+        ("pages", c.pages.as_ref().map(|p| p.replace('–', "--"))),
+        ("doi", c.doi.clone()),
+        ("url", c.url.clone()),
+        ("isbn", c.isbn.clone()),
+    ];
+    fields.extend(optional.into_iter().filter_map(|(name, value)| value.map(|v| (name, v))));
+
+    let body = fields.iter()
+        .map(|(name, value)| {
+            // Links keep their characters; everywhere else & and % are LaTeX specials
+            let value = if matches!(*name, "url" | "doi") { value.clone() } else { value.replace('&', "\\&").replace('%', "\\%") };
+            format!("  {} = {{{}}}", name, value)
+        })
+        .collect::<Vec<_>>()
+        .join(",\n");
+    format!("@{}{{{},\n{}\n}}", entry_type, key, body)
+}
+
+pub fn format_citation_text(citation: &Citation, style: &CitationStyle) -> String {
+    match style {
+        CitationStyle::Apa => format_apa(citation),
+        CitationStyle::Mla => format_mla(citation),
+        CitationStyle::Chicago => format_chicago(citation),
+        CitationStyle::Bibtex => format_bibtex(citation),
+    }
+}
+
+/// A reference note's citation in `style` (apa, mla, chicago or bibtex)
+pub async fn format_citation(note_id: String, style: String) -> Result<String, String> {
+    let style = CitationStyle::parse(&style)?;
+    let database = load_notes()?;
+    let note = database.notes.iter()
+        .find(|n| n.id == note_id)
+        .ok_or_else(|| tr("error.note_not_found", &[&note_id]))?;
+    let citation = note.citation.as_ref()
+        .ok_or_else(|| format!("Note {} has no citation data", note_id))?;
+
+    Ok(format_citation_text(citation, &style))
+}
@@ -0,0 +1,114 @@
+use pulldown_cmark::{CodeBlockKind, Event, Options, Parser, Tag};
+use regex::RegexBuilder;
+use serde::{Deserialize, Serialize};
+use crate::models::{Note, NoteKind};
+use crate::services::note_service::load_notes;
+
+/// Upper bound on hits so a pattern like `.` can't flood the UI
+const MAX_CODE_HITS: usize = 500;
+
+/// A fenced code block inside a note
+pub struct CodeBlock {
+    pub language: Option<String>,
+    pub code: String,
+    pub start_line: usize, // 1-based line of the first code line in the note
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct CodeSearchQuery {
+    pub pattern: String,
+    pub language: Option<String>, // Only blocks fenced with this language
+    pub regex: bool,              // Treat `pattern` as a regular expression
+    pub case_sensitive: bool,
+}
+
+#[derive(Serialize, Clone)]
+pub struct CodeSearchHit {
+    pub note_id: String,
+    pub note_title: String,
+    pub language: Option<String>,
+    pub line_number: usize, // 1-based, within the note
+    pub line: String,
+}
+
+/// Fenced and indented code blocks of a note. Snippet notes are one block of their language.
+pub fn extract_code_blocks(note: &Note) -> Vec<CodeBlock> {
+    if note.kind == NoteKind::Snippet {
+        return vec![CodeBlock { language: note.language.clone(), code: note.content.clone(), start_line: 1 }];
+    }
+
+    let content = &note.content;
+    let mut blocks = Vec::new();
+    let mut current: Option<CodeBlock> = None;
+
+    for (event, range) in Parser::new_ext(content, Options::empty()).into_offset_iter() {
+        match event {
+            Event::Start(Tag::CodeBlock(kind)) => {
+                let language = match kind {
+                    CodeBlockKind::Fenced(info) => info.split_whitespace().next().map(|l| l.to_lowercase()),
+                    CodeBlockKind::Indented => None,
+                };
+                // Fenced code starts on the line after the opening fence
+                let fence_line = content[..range.start].matches('\n').count() + 1;
+                let start_line = if content[range.start..].trim_start().starts_with(['`', '~']) { fence_line + 1 } else { fence_line };
+                current = Some(CodeBlock { language, code: String::new(), start_line });
+            }
+            Event::Text(text) => {
+                if let Some(block) = current.as_mut() {
+                    block.code.push_str(&text);
+                }
+            }
+            Event::End(Tag::CodeBlock(_)) => {
+                if let Some(block) = current.take() {
+                    blocks.push(block);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    blocks
+}
+
+/// Search only inside code: each matching line is returned with its note and line number
+pub async fn search_code_blocks(query: CodeSearchQuery) -> Result<Vec<CodeSearchHit>, String> {
+    if query.pattern.is_empty() {
+        return Ok(Vec::new());
+    }
+    let pattern = if query.regex { query.pattern.clone() } else { regex::escape(&query.pattern) };
+    let matcher = RegexBuilder::new(&pattern)
+        .case_insensitive(!query.case_sensitive)
+        .build()
+        .map_err(|e| format!("Invalid search pattern: {}", e))?;
+    let language = query.language.as_ref()
+        .map(|l| l.trim().to_lowercase())
+        .filter(|l| !l.is_empty());
+
+    let database = load_notes()?;
+    let mut hits = Vec::new();
+
+    for note in &database.notes {
+        for block in extract_code_blocks(note) {
+            if language.is_some() && block.language != language {
+                continue;
+            }
+            for (offset, line) in block.code.lines().enumerate() {
+                if matcher.is_match(line) {
+                    hits.push(CodeSearchHit {
+                        note_id: note.id.clone(),
+                        note_title: note.title.clone(),
+                        language: block.language.clone(),
+                        line_number: block.start_line + offset,
+                        line: line.to_string(),
+                    });
+                    if hits.len() >= MAX_CODE_HITS {
+                        return Ok(hits);
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(hits)
+}
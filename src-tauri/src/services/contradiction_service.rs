@@ -0,0 +1,168 @@
+use chrono::Utc;
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+use crate::models::{ContradictionProposal, LinkType, Note, NoteLink};
+use crate::services::ai_config::{AiConfig, ModelRole};
+use crate::services::ai_queue_service::is_offline;
+use crate::services::ai_service::{parse_json_reply, run_prompt};
+use crate::services::job_service::{finish_job, start_job, update_job_file, FileState, ImportJob, JobState};
+use crate::services::link_service::{create_note_link_with_options, overlap, text_terms};
+use crate::services::note_service::load_notes;
+use crate::services::ai_privacy_service::ai_allowed_notes;
+use crate::services::storage_service::{load_contradictions, load_links, save_contradictions};
+
+/// Pairs sent to the AI per run - comparing every pair of a big category would cost a fortune
+const MAX_PAIRS: usize = 30;
+/// Vocabulary two notes must share before they're compared - notes about different
+/// things can't contradict each other
+const MIN_PAIR_OVERLAP: f32 = 0.1;
+/// Characters of each note included in the comparison
+const MAX_NOTE_CHARS: usize = 4_000;
+/// Longest explanation kept as a link label
+const MAX_EXPLANATION_CHARS: usize = 120;
+
+#[derive(Deserialize)]
+struct Verdict {
+    contradicts: bool,
+    #[serde(default)]
+    explanation: String,
+}
+
+/// Unordered pair key, so A-B and B-A count as the same pair
+fn pair_key(a: &str, b: &str) -> (String, String) {
+    if a <= b { (a.to_string(), b.to_string()) } else { (b.to_string(), a.to_string()) }
+}
+
+/// The pairs most worth comparing: similar vocabulary, not already linked as
+/// contradicting, not already proposed or dismissed. Most similar first.
+fn candidate_pairs<'a>(notes: &[&'a Note], links: &[NoteLink], known: &HashSet<(String, String)>) -> Vec<(&'a Note, &'a Note)> {
+    let linked: HashSet<(String, String)> = links.iter()
+        .filter(|l| l.link_type == LinkType::Contradicts)
+        .map(|l| pair_key(&l.source_id, &l.target_id))
+        .collect();
+    let terms: HashMap<&str, HashSet<String>> = notes.iter()
+        .map(|n| (n.id.as_str(), text_terms(&format!("{} {}", n.title, n.content))))
+        .collect();
+
+    let mut pairs = Vec::new();
+    for (index, a) in notes.iter().enumerate() {
+        for b in &notes[index + 1..] {
+            let key = pair_key(&a.id, &b.id);
+            if linked.contains(&key) || known.contains(&key) {
+                continue;
+            }
+            let score = overlap(&terms[a.id.as_str()], &terms[b.id.as_str()]);
+            if score >= MIN_PAIR_OVERLAP {
+                pairs.push((*a, *b, score));
+            }
+        }
+    }
+    pairs.sort_by(|x, y| y.2.total_cmp(&x.2));
+    pairs.into_iter().take(MAX_PAIRS).map(|(a, b, _)| (a, b)).collect()
+}
+
+async fn compare_notes(a: &Note, b: &Note) -> Result<Verdict, String> {
+    let excerpt = |note: &Note| note.content.chars().take(MAX_NOTE_CHARS).collect::<String>();
+    let prompt = format!(
+        "Do these two notes make claims that contradict each other? Differences in scope or detail are not \
+        contradictions - only statements that can't both be true. Respond with ONLY a JSON object with \
+        \"contradicts\" (true or false) and \"explanation\" (one short sentence naming the conflicting claims, \
+        empty if none).\n\nNote A: {}\n{}\n\nNote B: {}\n{}",
+        a.title, excerpt(a), b.title, excerpt(b)
+    );
+    let config = AiConfig::load();
+    let reply = run_prompt("contradictions", &prompt, &config.model_for(ModelRole::Chat), config.brief_tokens, 0.1).await?;
+    parse_json_reply(&reply)
+}
+
+/// Look for notes in `scope` (a category and its subcategories, the whole vault when
+/// empty) that contradict each other. The comparison runs as a background job - one
+/// entry per compared pair - and every conflict found becomes a proposal.
+pub async fn detect_contradictions(scope: Vec<String>) -> Result<ImportJob, String> {
+    if is_offline() {
+        return Err("Contradiction detection needs the AI provider - you are offline".to_string());
+    }
+    let notes = ai_allowed_notes(load_notes()?.notes)?;
+    let in_scope: Vec<&Note> = notes.iter().filter(|n| n.category_path.starts_with(&scope)).collect();
+    let known: HashSet<(String, String)> = load_contradictions()?.proposals.iter()
+        .map(|p| pair_key(&p.source_id, &p.target_id))
+        .collect();
+    let pairs: Vec<(Note, Note)> = candidate_pairs(&in_scope, &load_links()?.links, &known).into_iter()
+        .map(|(a, b)| (a.clone(), b.clone()))
+        .collect();
+    if pairs.is_empty() {
+        return Err("No notes in this category are close enough to compare".to_string());
+    }
+
+    let source = if scope.is_empty() { "All notes".to_string() } else { scope.join(" > ") };
+    let labels = pairs.iter().map(|(a, b)| format!("{} / {}", a.title, b.title)).collect();
+    let job = start_job("contradictions", &source, labels);
+
+    let job_id = job.id.clone();
+    tokio::spawn(async move {
+        for (index, (a, b)) in pairs.iter().enumerate() {
+            let result = compare_notes(a, b).await.and_then(|verdict| {
+                if !verdict.contradicts {
+                    return Ok(None);
+                }
+                let explanation: String = verdict.explanation.trim().chars().take(MAX_EXPLANATION_CHARS).collect();
+                let mut database = load_contradictions()?;
+                database.proposals.push(ContradictionProposal {
+                    id: Uuid::new_v4().to_string(),
+                    source_id: a.id.clone(),
+                    target_id: b.id.clone(),
+                    explanation: explanation.clone(),
+                    proposed_at: Utc::now(),
+                    dismissed: false,
+                });
+                save_contradictions(&database)?;
+                Ok(Some(explanation))
+            });
+            match result {
+                Ok(Some(explanation)) => update_job_file(&job_id, index, FileState::Imported, None, Some(explanation)),
+                Ok(None) => update_job_file(&job_id, index, FileState::Skipped("No contradiction".to_string()), None, None),
+                Err(e) => update_job_file(&job_id, index, FileState::Failed(e), None, None),
+            }
+        }
+        finish_job(&job_id, JobState::Completed);
+    });
+
+    Ok(job)
+}
+
+/// Proposals waiting for a decision, newest first. Proposals whose notes were deleted are left out.
+pub async fn list_contradiction_proposals() -> Result<Vec<ContradictionProposal>, String> {
+    let notes: HashSet<String> = load_notes()?.notes.into_iter().map(|n| n.id).collect();
+    let mut proposals: Vec<ContradictionProposal> = load_contradictions()?.proposals.into_iter()
+        .filter(|p| !p.dismissed && notes.contains(&p.source_id) && notes.contains(&p.target_id))
+        .collect();
+    proposals.reverse();
+    Ok(proposals)
+}
+
+/// Turn a proposal into a `Contradicts` link labelled with its explanation
+pub async fn accept_contradiction(proposal_id: String) -> Result<NoteLink, String> {
+    let mut database = load_contradictions()?;
+    let index = database.proposals.iter()
+        .position(|p| p.id == proposal_id && !p.dismissed)
+        .ok_or_else(|| format!("Contradiction proposal {} not found", proposal_id))?;
+    let proposal = database.proposals[index].clone();
+
+    let label = Some(proposal.explanation.clone()).filter(|e| !e.is_empty());
+    let link = create_note_link_with_options(proposal.source_id, proposal.target_id, "Contradicts".to_string(), label, None, Some(false)).await?;
+    // The link now records the pair, so the proposal isn't needed to block re-proposing it
+    database.proposals.remove(index);
+    save_contradictions(&database)?;
+    Ok(link)
+}
+
+/// Reject a proposal. The pair won't be proposed again.
+pub async fn dismiss_contradiction(proposal_id: String) -> Result<(), String> {
+    let mut database = load_contradictions()?;
+    let proposal = database.proposals.iter_mut()
+        .find(|p| p.id == proposal_id)
+        .ok_or_else(|| format!("Contradiction proposal {} not found", proposal_id))?;
+    proposal.dismissed = true;
+    save_contradictions(&database)
+}
@@ -0,0 +1,85 @@
+use serde::Serialize;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
+use crate::models::{DuplicateDetection, Note};
+use crate::services::settings_service::current_settings;
+
+/// Words per phrase compared by `Similar` detection
+const SHINGLE_WORDS: usize = 3;
+
+/// What saving returns: the note, and whether it already existed. Serialized as the note
+/// itself plus a `duplicate` field, so callers expecting a note keep working.
+#[derive(Serialize)]
+pub struct SavedNote {
+    #[serde(flatten)]
+    pub note: Note,
+    pub duplicate: bool, // Nothing was created - `note` is the existing copy
+}
+
+impl SavedNote {
+    pub fn created(note: Note) -> Self {
+        SavedNote { note, duplicate: false }
+    }
+}
+
+/// Lowercased words, so spacing and case don't make text look different
+fn normalized_words(content: &str) -> Vec<String> {
+    content.split_whitespace().map(str::to_lowercase).collect()
+}
+
+fn content_hash(words: &[String]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    words.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn shingles(words: &[String]) -> HashSet<&[String]> {
+    words.windows(SHINGLE_WORDS).collect()
+}
+
+/// Share of phrases two texts have in common
+fn similarity(a: &HashSet<&[String]>, b: &HashSet<&[String]>) -> f32 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(b).count() as f32 / union as f32
+}
+
+/// An existing note with the same (or, with `Similar` detection, nearly the same)
+/// content, following the duplicate settings
+pub fn find_duplicate<'a>(notes: &'a [Note], content: &str) -> Option<&'a Note> {
+    let settings = current_settings().duplicates;
+    if settings.detection == DuplicateDetection::Off {
+        return None;
+    }
+    let words = normalized_words(content);
+    if words.is_empty() {
+        return None;
+    }
+
+    let hash = content_hash(&words);
+    let exact = notes.iter().find(|n| {
+        let other = normalized_words(&n.content);
+        content_hash(&other) == hash && other == words
+    });
+    if exact.is_some() || settings.detection == DuplicateDetection::Exact || words.len() < SHINGLE_WORDS {
+        return exact;
+    }
+
+    let phrases = shingles(&words);
+    notes.iter()
+        .filter_map(|note| {
+            let other = normalized_words(&note.content);
+            // Texts of very different length can't reach the threshold
+            let (shorter, longer) = (words.len().min(other.len()), words.len().max(other.len()));
+            if (shorter as f32) < longer as f32 * settings.threshold {
+                return None;
+            }
+            let score = similarity(&phrases, &shingles(&other));
+            (score >= settings.threshold).then_some((note, score))
+        })
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(note, _)| note)
+}
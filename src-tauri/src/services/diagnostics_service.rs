@@ -0,0 +1,161 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::env;
+use std::fs;
+use crate::services::ai_queue_service::provider_reachable;
+use crate::services::category_service::load_categories;
+use crate::services::note_service::load_notes;
+use crate::services::settings_service::current_settings;
+use crate::services::storage_service::{
+    get_app_data_dir, load_activity, load_ai_jobs, load_ai_log, load_boards, load_feeds,
+    load_links, load_settings, load_ui_state, load_webhooks,
+};
+
+/// Free space below these thresholds is reported as a warning / error
+const LOW_DISK_WARNING_BYTES: u64 = 500 * 1024 * 1024;
+const LOW_DISK_ERROR_BYTES: u64 = 100 * 1024 * 1024;
+
+/// Backups older than this are flagged
+const BACKUP_STALE_DAYS: i64 = 7;
+
+#[derive(Serialize, Clone, Copy, PartialEq, PartialOrd)]
+pub enum CheckStatus {
+    Ok,
+    Warning,
+    Error,
+}
+
+#[derive(Serialize)]
+pub struct DiagnosticCheck {
+    pub name: String,
+    pub status: CheckStatus,
+    pub message: String,
+}
+
+#[derive(Serialize)]
+pub struct DiagnosticsReport {
+    pub generated_at: DateTime<Utc>,
+    pub app_version: String,
+    pub data_dir: String,
+    pub overall: CheckStatus, // Worst status among the checks
+    pub checks: Vec<DiagnosticCheck>,
+}
+
+fn check(name: &str, status: CheckStatus, message: impl Into<String>) -> DiagnosticCheck {
+    DiagnosticCheck { name: name.to_string(), status, message: message.into() }
+}
+
+fn check_data_dir_writable() -> DiagnosticCheck {
+    let result = get_app_data_dir().and_then(|dir| {
+        let probe = dir.join(".diagnostics-probe");
+        fs::write(&probe, b"ok").map_err(|e| format!("Cannot write to {}: {}", dir.display(), e))?;
+        fs::remove_file(&probe).map_err(|e| format!("Cannot delete files in {}: {}", dir.display(), e))?;
+        Ok(dir)
+    });
+    match result {
+        Ok(dir) => check("Data directory", CheckStatus::Ok, format!("{} is writable", dir.display())),
+        Err(e) => check("Data directory", CheckStatus::Error, e),
+    }
+}
+
+/// Every store must parse - a corrupt file would otherwise surface as a confusing error later
+fn check_stores() -> Vec<DiagnosticCheck> {
+    fn store<T>(name: &str, result: Result<T, String>) -> DiagnosticCheck {
+        match result {
+            Ok(_) => check(name, CheckStatus::Ok, "Loads correctly"),
+            Err(e) => check(name, CheckStatus::Error, e),
+        }
+    }
+
+    vec![
+        store("Notes store", load_notes()),
+        store("Categories store", load_categories()),
+        store("Links store", load_links()),
+        store("Boards store", load_boards()),
+        store("Feeds store", load_feeds()),
+        store("Webhooks store", load_webhooks()),
+        store("Settings store", load_settings()),
+        store("Activity store", load_activity()),
+        store("AI log store", load_ai_log()),
+        store("AI queue store", load_ai_jobs()),
+        store("UI state store", load_ui_state()),
+    ]
+}
+
+fn check_api_key() -> DiagnosticCheck {
+    match env::var("OPENROUTER_API_KEY") {
+        Ok(key) if !key.trim().is_empty() && key != "YOUR_API_KEY_HERE" => {
+            check("API key", CheckStatus::Ok, "OPENROUTER_API_KEY is set")
+        }
+        Ok(_) => check("API key", CheckStatus::Error, "OPENROUTER_API_KEY still has the placeholder value from .env.example"),
+        Err(_) => check("API key", CheckStatus::Error, "OPENROUTER_API_KEY is not set - add it to your .env file"),
+    }
+}
+
+async fn check_provider() -> DiagnosticCheck {
+    if current_settings().offline_mode {
+        return check("AI provider", CheckStatus::Warning, "Skipped - offline mode is on");
+    }
+    if provider_reachable().await {
+        check("AI provider", CheckStatus::Ok, "openrouter.ai is reachable")
+    } else {
+        check("AI provider", CheckStatus::Error, "Could not reach openrouter.ai - check your network connection")
+    }
+}
+
+fn check_disk_space() -> DiagnosticCheck {
+    let available = get_app_data_dir().and_then(|dir| {
+        fs2::available_space(&dir).map_err(|e| format!("Failed to read free disk space: {}", e))
+    });
+    match available {
+        Ok(bytes) => {
+            let message = format!("{:.1} GB free", bytes as f64 / 1024.0 / 1024.0 / 1024.0);
+            let status = if bytes < LOW_DISK_ERROR_BYTES {
+                CheckStatus::Error
+            } else if bytes < LOW_DISK_WARNING_BYTES {
+                CheckStatus::Warning
+            } else {
+                CheckStatus::Ok
+            };
+            check("Disk space", status, message)
+        }
+        Err(e) => check("Disk space", CheckStatus::Warning, e),
+    }
+}
+
+fn check_backup_freshness() -> DiagnosticCheck {
+    match current_settings().last_vault_export {
+        None => check("Backups", CheckStatus::Warning, "No vault export has been made yet"),
+        Some(exported) => {
+            let age_days = (Utc::now() - exported).num_days();
+            let message = format!("Last vault export {} ({} days ago)", exported.format("%Y-%m-%d"), age_days);
+            if age_days > BACKUP_STALE_DAYS {
+                check("Backups", CheckStatus::Warning, message)
+            } else {
+                check("Backups", CheckStatus::Ok, message)
+            }
+        }
+    }
+}
+
+/// Health report for the Help → Diagnostics screen
+pub async fn run_diagnostics() -> Result<DiagnosticsReport, String> {
+    let mut checks = vec![check_data_dir_writable()];
+    checks.extend(check_stores());
+    checks.push(check_api_key());
+    checks.push(check_provider().await);
+    checks.push(check_disk_space());
+    checks.push(check_backup_freshness());
+
+    let overall = checks.iter()
+        .map(|c| c.status)
+        .fold(CheckStatus::Ok, |worst, status| if status > worst { status } else { worst });
+
+    Ok(DiagnosticsReport {
+        generated_at: Utc::now(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        data_dir: get_app_data_dir().map(|d| d.to_string_lossy().to_string()).unwrap_or_default(),
+        overall,
+        checks,
+    })
+}
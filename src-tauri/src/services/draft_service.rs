@@ -0,0 +1,49 @@
+use chrono::Utc;
+use crate::models::Draft;
+use crate::services::storage_service::{load_drafts, save_drafts};
+
+/// Create or overwrite a draft. Empty content discards it, since there's nothing to recover.
+pub async fn save_draft(draft_id: String, content: String, note_id: Option<String>) -> Result<Option<Draft>, String> {
+    let draft_id = draft_id.trim().to_string();
+    if draft_id.is_empty() {
+        return Err("Draft id cannot be empty".to_string());
+    }
+
+    let mut database = load_drafts()?;
+    if content.trim().is_empty() {
+        database.drafts.retain(|d| d.id != draft_id);
+        save_drafts(&database)?;
+        return Ok(None);
+    }
+
+    let now = Utc::now();
+    let draft = match database.drafts.iter_mut().find(|d| d.id == draft_id) {
+        Some(existing) => {
+            existing.content = content;
+            existing.note_id = note_id.or(existing.note_id.take());
+            existing.updated_at = now;
+            existing.clone()
+        }
+        None => {
+            let draft = Draft { id: draft_id, content, note_id, created_at: now, updated_at: now };
+            database.drafts.push(draft.clone());
+            draft
+        }
+    };
+
+    save_drafts(&database)?;
+    Ok(Some(draft))
+}
+
+/// All drafts, most recently edited first
+pub async fn get_drafts() -> Result<Vec<Draft>, String> {
+    let mut drafts = load_drafts()?.drafts;
+    drafts.sort_by_key(|d| std::cmp::Reverse(d.updated_at));
+    Ok(drafts)
+}
+
+pub async fn discard_draft(draft_id: String) -> Result<(), String> {
+    let mut database = load_drafts()?;
+    database.drafts.retain(|d| d.id != draft_id);
+    save_drafts(&database)
+}
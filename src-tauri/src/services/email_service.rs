@@ -0,0 +1,161 @@
+use chrono::{TimeZone, Utc};
+use mailparse::MailHeaderMap;
+use std::env;
+use tracing::error;
+use crate::models::{Attachment, EmailCaptureSettings, Note};
+use crate::services::attachment_service::{attachment_markdown, store_attachment_bytes};
+use crate::services::import_service::{html_to_markdown, persist_imported_notes, ImportReport};
+use crate::services::settings_service::current_settings;
+
+/// Fetch the raw RFC 822 bytes of every unseen message matching the settings.
+/// Fetching the full body marks messages as seen, so each mail is captured once.
+fn fetch_unseen_messages(settings: &EmailCaptureSettings) -> Result<Vec<Vec<u8>>, String> {
+    if settings.imap_host.trim().is_empty() || settings.username.trim().is_empty() {
+        return Err("Email capture needs an IMAP host and username in settings".to_string());
+    }
+    let password = env::var("IMAP_PASSWORD")
+        .map_err(|_| "IMAP_PASSWORD environment variable not set. Please check your .env file.".to_string())?;
+
+    let tls = native_tls::TlsConnector::builder().build()
+        .map_err(|e| format!("Failed to set up TLS: {}", e))?;
+    let client = imap::connect((settings.imap_host.as_str(), settings.imap_port), settings.imap_host.as_str(), &tls)
+        .map_err(|e| format!("Failed to connect to {}: {}", settings.imap_host, e))?;
+    let mut session = client.login(&settings.username, &password)
+        .map_err(|(e, _)| format!("IMAP login failed: {}", e))?;
+
+    session.select(&settings.mailbox)
+        .map_err(|e| format!("Failed to open mailbox {}: {}", settings.mailbox, e))?;
+
+    let query = match settings.recipient_filter.as_deref().map(str::trim).filter(|f| !f.is_empty()) {
+        Some(address) => format!("UNSEEN TO \"{}\"", address.replace('"', "")),
+        None => "UNSEEN".to_string(),
+    };
+    let uids = session.uid_search(&query)
+        .map_err(|e| format!("IMAP search failed: {}", e))?;
+
+    let mut messages = Vec::new();
+    if !uids.is_empty() {
+        let uid_set = uids.iter().map(|uid| uid.to_string()).collect::<Vec<_>>().join(",");
+        let fetched = session.uid_fetch(&uid_set, "RFC822")
+            .map_err(|e| format!("IMAP fetch failed: {}", e))?;
+        for message in fetched.iter() {
+            if let Some(body) = message.body() {
+                messages.push(body.to_vec());
+            }
+        }
+    }
+
+    let _ = session.logout();
+    Ok(messages)
+}
+
+/// Walk the MIME tree collecting the best text body and any attachments
+fn collect_parts(part: &mailparse::ParsedMail, plain: &mut Option<String>, html: &mut Option<String>, attachments: &mut Vec<Attachment>) {
+    let disposition = part.get_content_disposition();
+    let file_name = disposition.params.get("filename").cloned()
+        .or_else(|| part.ctype.params.get("name").cloned());
+
+    if disposition.disposition == mailparse::DispositionType::Attachment || (file_name.is_some() && part.subparts.is_empty()) {
+        if let Ok(bytes) = part.get_body_raw() {
+            let name = file_name.unwrap_or_else(|| "attachment".to_string());
+            match store_attachment_bytes(&name, &bytes) {
+                Ok(attachment) => attachments.push(attachment),
+                Err(e) => error!("Failed to store email attachment {}: {}", name, e),
+            }
+        }
+        return;
+    }
+
+    match part.ctype.mimetype.as_str() {
+        "text/plain" if plain.is_none() => *plain = part.get_body().ok(),
+        "text/html" if html.is_none() => *html = part.get_body().ok(),
+        _ => {}
+    }
+
+    for subpart in &part.subparts {
+        collect_parts(subpart, plain, html, attachments);
+    }
+}
+
+fn note_from_message(raw: &[u8], category_path: &[String]) -> Result<Note, String> {
+    let parsed = mailparse::parse_mail(raw)
+        .map_err(|e| format!("Failed to parse email: {}", e))?;
+
+    let header = |name: &str| -> String {
+        parsed.headers.get_first_value(name).unwrap_or_default()
+    };
+    let subject = header("Subject");
+    let from = header("From");
+
+    let mut plain = None;
+    let mut html = None;
+    let mut attachments = Vec::new();
+    collect_parts(&parsed, &mut plain, &mut html, &mut attachments);
+
+    let body = plain
+        .or_else(|| html.map(|h| html_to_markdown(&h, &mut |_, _| None)))
+        .unwrap_or_default();
+
+    let mut content = String::new();
+    if !from.is_empty() {
+        content.push_str(&format!("From: {}\n\n", from));
+    }
+    content.push_str(body.trim());
+    for attachment in &attachments {
+        content.push_str(&format!("\n\n{}", attachment_markdown(attachment)));
+    }
+
+    let title = if subject.trim().is_empty() { "(no subject)".to_string() } else { subject.trim().to_string() };
+    let mut note = Note::new(title, content, category_path.to_vec());
+    if let Some(sent) = mailparse::dateparse(&header("Date")).ok().and_then(|secs| Utc.timestamp_opt(secs, 0).single()) {
+        note.timestamp = sent;
+    }
+    note.attachments = attachments;
+    note.metadata.insert("source".to_string(), "email".into());
+    if !from.is_empty() {
+        note.metadata.insert("email_from".to_string(), from.into());
+    }
+    Ok(note)
+}
+
+/// Poll the configured inbox once and turn new messages into notes
+pub async fn check_email_now() -> Result<ImportReport, String> {
+    let settings = current_settings().email_capture;
+    let fetch_settings = settings.clone();
+    let messages = tokio::task::spawn_blocking(move || fetch_unseen_messages(&fetch_settings))
+        .await
+        .map_err(|e| format!("Email fetch task failed: {}", e))??;
+
+    let category_path = if settings.category_path.is_empty() { vec!["Inbox".to_string()] } else { settings.category_path.clone() };
+    let mut report = ImportReport::default();
+    let mut notes = Vec::new();
+
+    for raw in messages {
+        match note_from_message(&raw, &category_path) {
+            Ok(note) => {
+                report.attachments_imported += note.attachments.len();
+                notes.push(note);
+            }
+            Err(e) => report.skipped.push(e),
+        }
+    }
+
+    report.notes_imported = notes.len();
+    persist_imported_notes(notes)?;
+    Ok(report)
+}
+
+/// Background job started at launch: polls while email capture is enabled.
+/// Settings are re-read every cycle so changes apply without a restart.
+pub async fn run_email_capture_loop() {
+    loop {
+        let settings = current_settings().email_capture;
+        if settings.enabled {
+            if let Err(e) = check_email_now().await {
+                error!("Email capture failed: {}", e);
+            }
+        }
+        let minutes = settings.poll_interval_minutes.max(1) as u64;
+        tokio::time::sleep(std::time::Duration::from_secs(minutes * 60)).await;
+    }
+}
@@ -0,0 +1,139 @@
+use chrono::Utc;
+use tracing::warn;
+use uuid::Uuid;
+use crate::models::{LinkType, Note, NoteLink};
+use crate::services::audit_service::{link_deleted, record_deletions};
+use crate::services::block_service::parse_blocks;
+use crate::services::markdown_service::find_embeds;
+use crate::services::note_service::{load_notes, resolve_note_reference};
+use crate::services::storage_service::{load_links, save_links};
+use crate::services::locale_service::tr;
+
+/// Embeds nested deeper than this are left as plain `![[...]]` markers
+const MAX_EMBED_DEPTH: usize = 5;
+
+/// Embed targets may carry `#heading` / `^block` suffixes - the note part comes first
+fn embed_note_reference(target: &str) -> &str {
+    target.split(['#', '^']).next().unwrap_or(target).trim()
+}
+
+fn expand(notes: &[Note], note: &Note, stack: &mut Vec<String>) -> String {
+    let content = &note.content;
+    let mut result = String::with_capacity(content.len());
+    let mut last = 0;
+
+    for (start, end, target) in find_embeds(content) {
+        result.push_str(&content[last..start]);
+        last = end;
+
+        let Some(embedded) = resolve_note_reference(notes, embed_note_reference(&target)) else {
+            result.push_str(&format!("> Missing embed: {}", target));
+            continue;
+        };
+        if stack.contains(&embedded.id) {
+            result.push_str(&format!("> Embed cycle: {} is already included above", embedded.title));
+            continue;
+        }
+        if stack.len() >= MAX_EMBED_DEPTH {
+            result.push_str(&content[start..end]);
+            continue;
+        }
+
+        // `![[note^block-id]]` pulls in just that block
+        let block_id = target.split_once('^').map(|(_, id)| id.trim());
+        let embedded_content = match block_id {
+            Some(block_id) => match parse_blocks(&embedded.content).into_iter().find(|b| b.id.as_deref() == Some(block_id)) {
+                Some(block) => block.content,
+                None => {
+                    result.push_str(&format!("> Missing embed: {}", target));
+                    continue;
+                }
+            },
+            None => embedded.content.clone(),
+        };
+
+        stack.push(embedded.id.clone());
+        let embedded_note = Note { content: embedded_content, ..embedded.clone() };
+        result.push_str(expand(notes, &embedded_note, stack).trim());
+        stack.pop();
+    }
+
+    result.push_str(&content[last..]);
+    result
+}
+
+/// A note's content with every `![[note-id-or-title]]` embed replaced by the embedded
+/// note's (recursively expanded) content. Cycles and overly deep nesting are cut off.
+pub async fn resolve_embeds(note_id: String) -> Result<String, String> {
+    let database = load_notes()?;
+    let note = database.notes.iter()
+        .find(|n| n.id == note_id)
+        .ok_or_else(|| tr("error.note_not_found", &[&note_id]))?;
+
+    Ok(expand_embeds(&database.notes, note))
+}
+
+/// `note`'s content with embeds expanded against an already-loaded note list
+pub fn expand_embeds(notes: &[Note], note: &Note) -> String {
+    let mut stack = vec![note.id.clone()];
+    expand(notes, note, &mut stack)
+}
+
+/// Keep a note's outgoing `Embed` links in step with the embeds in its content.
+/// Failures are logged - a save must never fail because the graph couldn't be updated.
+pub fn sync_embed_links(note: &Note) {
+    if let Err(e) = try_sync_embed_links(note) {
+        warn!("Failed to update embed links for {}: {}", note.id, e);
+    }
+}
+
+fn try_sync_embed_links(note: &Note) -> Result<(), String> {
+    let mut links_db = load_links()?;
+    let has_embed_links = links_db.links.iter()
+        .any(|l| l.source_id == note.id && matches!(l.link_type, LinkType::Embed));
+    let embeds = find_embeds(&note.content);
+    if embeds.is_empty() && !has_embed_links {
+        return Ok(());
+    }
+
+    let notes_db = load_notes()?;
+    let mut targets: Vec<String> = embeds.iter()
+        .filter_map(|(_, _, target)| resolve_note_reference(&notes_db.notes, embed_note_reference(target)))
+        .map(|n| n.id.clone())
+        .filter(|id| id != &note.id)
+        .collect();
+    targets.sort();
+    targets.dedup();
+
+    let stale = |l: &NoteLink| l.source_id == note.id && matches!(l.link_type, LinkType::Embed) && !targets.contains(&l.target_id);
+    let removed: Vec<_> = links_db.links.iter().filter(|l| stale(l)).map(link_deleted).collect();
+    links_db.links.retain(|l| !stale(l));
+    let mut changed = !removed.is_empty();
+
+    for target_id in targets {
+        let exists = links_db.links.iter()
+            .any(|l| l.source_id == note.id && l.target_id == target_id && matches!(l.link_type, LinkType::Embed));
+        if !exists {
+            links_db.links.push(NoteLink {
+                id: Uuid::new_v4().to_string(),
+                source_id: note.id.clone(),
+                target_id,
+                link_type: LinkType::Embed,
+                label: None,
+                color: None,
+                directional: Some(true),
+                created_at: Utc::now(),
+                target_block: None,
+                weight: None,
+                weight_is_manual: false,
+            });
+            changed = true;
+        }
+    }
+
+    if changed {
+        save_links(&links_db)?;
+        record_deletions(removed);
+    }
+    Ok(())
+}
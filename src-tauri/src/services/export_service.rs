@@ -0,0 +1,370 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use crate::models::{Attachment, Note};
+use crate::services::attachment_service::{attachment_file_path, sanitize_file_name};
+use crate::services::markdown_service::{escape_html, rewrite_markdown_targets};
+use crate::services::note_service::load_notes;
+use crate::services::render_service::{markdown_to_plain_text, prepare_note_markdown, provenance_line, render_note_html, render_note_html_with};
+use crate::services::storage_service::{get_app_data_dir, load_links};
+use crate::services::stats_service::{daily_stats, word_count};
+use crate::services::locale_service::tr;
+use crate::services::visibility_service::{ensure_shareable, shareable_notes, SharingPath};
+
+/// Columns exported when the caller doesn't pick any
+pub const DEFAULT_CSV_COLUMNS: &[&str] = &[
+    "id", "title", "category_path", "category_depth", "timestamp", "tags", "word_count", "link_degree",
+];
+
+/// Every column `export_notes_csv` understands
+pub const AVAILABLE_CSV_COLUMNS: &[&str] = &[
+    "id", "title", "content", "category_path", "category_depth", "timestamp", "tags",
+    "word_count", "char_count", "link_degree", "ai_confidence", "x", "y", "source_app", "source_url",
+];
+
+/// Number of links touching each note, in either direction
+fn link_degrees() -> Result<HashMap<String, usize>, String> {
+    let links_db = load_links()?;
+    let mut degrees: HashMap<String, usize> = HashMap::new();
+    for link in &links_db.links {
+        *degrees.entry(link.source_id.clone()).or_insert(0) += 1;
+        *degrees.entry(link.target_id.clone()).or_insert(0) += 1;
+    }
+    Ok(degrees)
+}
+
+fn column_value(note: &Note, column: &str, degrees: &HashMap<String, usize>) -> String {
+    match column {
+        "id" => note.id.clone(),
+        "title" => note.title.clone(),
+        "content" => note.content.clone(),
+        "category_path" => note.category_path.join("/"),
+        "category_depth" => note.category_path.len().to_string(),
+        "timestamp" => note.timestamp.to_rfc3339(),
+        "tags" => note.tags.join(";"),
+        "word_count" => word_count(&note.content).to_string(),
+        "char_count" => note.content.chars().count().to_string(),
+        "link_degree" => degrees.get(&note.id).copied().unwrap_or(0).to_string(),
+        "ai_confidence" => note.ai_confidence.map(|c| c.to_string()).unwrap_or_default(),
+        "x" => note.position.as_ref().map(|p| p.x.to_string()).unwrap_or_default(),
+        "y" => note.position.as_ref().map(|p| p.y.to_string()).unwrap_or_default(),
+        "source_app" => note.provenance.as_ref().and_then(|p| p.app.clone()).unwrap_or_default(),
+        "source_url" => note.provenance.as_ref().and_then(|p| p.url.clone()).unwrap_or_default(),
+        _ => String::new(),
+    }
+}
+
+/// Quote a field per RFC 4180 when it contains separators, quotes or newlines
+fn escape_csv_field(value: &str) -> String {
+    if value.contains(',') || value.contains('"') || value.contains('\n') || value.contains('\r') {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Export notes as CSV with the requested columns. Returns the number of rows written.
+pub async fn export_notes_csv(dest: String, columns: Option<Vec<String>>) -> Result<usize, String> {
+    let columns: Vec<String> = columns
+        .filter(|c| !c.is_empty())
+        .unwrap_or_else(|| DEFAULT_CSV_COLUMNS.iter().map(|c| c.to_string()).collect());
+
+    if let Some(unknown) = columns.iter().find(|c| !AVAILABLE_CSV_COLUMNS.contains(&c.as_str())) {
+        return Err(format!("Unknown export column '{}'. Available: {}", unknown, AVAILABLE_CSV_COLUMNS.join(", ")));
+    }
+
+    let notes = shareable_notes(load_notes()?.notes, SharingPath::Export)?;
+    let degrees = link_degrees()?;
+
+    let mut output = String::new();
+    output.push_str(&columns.iter().map(|c| escape_csv_field(c)).collect::<Vec<_>>().join(","));
+    output.push('\n');
+
+    for note in &notes {
+        let row: Vec<String> = columns.iter()
+            .map(|column| escape_csv_field(&column_value(note, column, &degrees)))
+            .collect();
+        output.push_str(&row.join(","));
+        output.push('\n');
+    }
+
+    fs::write(&dest, output)
+        .map_err(|e| format!("Failed to write CSV export: {}", e))?;
+
+    Ok(notes.len())
+}
+
+/// Export daily metrics (notes added, words, edits, AI calls, reviews) for `period` -
+/// "week", "month", "quarter", "year" or "all". Returns the number of days written.
+pub async fn export_stats_csv(dest: String, period: String) -> Result<usize, String> {
+    let days = match period.trim().to_lowercase().as_str() {
+        "week" => Some(7),
+        "month" => Some(30),
+        "quarter" => Some(90),
+        "year" => Some(365),
+        "all" => None,
+        other => return Err(format!("Unknown period '{}', expected week, month, quarter, year or all", other)),
+    };
+    let stats = daily_stats(days)?;
+
+    let mut output = String::from("date,notes_added,words_written,edits,ai_calls,reviews\n");
+    for day in &stats {
+        output.push_str(&format!(
+            "{},{},{},{},{},{}\n",
+            day.date, day.notes_created, day.words_written, day.edits, day.ai_calls, day.reviews
+        ));
+    }
+
+    fs::write(&dest, output)
+        .map_err(|e| format!("Failed to write stats export: {}", e))?;
+
+    Ok(stats.len())
+}
+
+/// Export notes as JSON Lines - one full note object per line plus derived fields
+pub async fn export_notes_jsonl(dest: String) -> Result<usize, String> {
+    let notes = shareable_notes(load_notes()?.notes, SharingPath::Export)?;
+    let degrees = link_degrees()?;
+
+    let mut output = String::new();
+    for note in &notes {
+        let mut value = serde_json::to_value(note)
+            .map_err(|e| format!("Failed to serialize note: {}", e))?;
+
+        if let Some(object) = value.as_object_mut() {
+            object.insert("word_count".to_string(), word_count(&note.content).into());
+            object.insert("link_degree".to_string(), degrees.get(&note.id).copied().unwrap_or(0).into());
+            object.insert("category_depth".to_string(), note.category_path.len().into());
+        }
+
+        let line = serde_json::to_string(&value)
+            .map_err(|e| format!("Failed to serialize note: {}", e))?;
+        output.push_str(&line);
+        output.push('\n');
+    }
+
+    fs::write(&dest, output)
+        .map_err(|e| format!("Failed to write JSONL export: {}", e))?;
+
+    Ok(notes.len())
+}
+
+const SHARE_STYLES: &str = "body{font-family:-apple-system,BlinkMacSystemFont,'Segoe UI',Roboto,sans-serif;max-width:760px;margin:40px auto;padding:0 20px;color:#1f2328;line-height:1.6}\
+h1{font-size:1.8em;margin-bottom:4px}\
+.meta{color:#656d76;font-size:.9em;margin-bottom:24px}\
+pre{background:#f6f8fa;padding:12px;border-radius:6px;overflow-x:auto}\
+code{background:#f6f8fa;padding:2px 4px;border-radius:4px;font-size:.9em}\
+pre code{padding:0;background:none}\
+img{max-width:100%}\
+blockquote{border-left:4px solid #d0d7de;margin:0;padding-left:16px;color:#656d76}\
+table{border-collapse:collapse}td,th{border:1px solid #d0d7de;padding:6px 12px}\
+.wikilink-missing{color:#cf222e;text-decoration:underline dotted}";
+
+/// Render a note into a complete standalone HTML document
+pub fn render_note_document(note: &Note, notes: &[Note]) -> String {
+    note_document(note, &render_note_html(note, notes))
+}
+
+fn note_document(note: &Note, body: &str) -> String {
+    let mut meta = note.timestamp.format("%Y-%m-%d").to_string();
+    if !note.category_path.is_empty() {
+        meta = format!("{} · {}", note.category_path.join(" → "), meta);
+    }
+
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n<title>{title}</title>\n<style>{styles}</style>\n</head>\n<body>\n<h1>{title}</h1>\n<div class=\"meta\">{meta}</div>\n{body}</body>\n</html>\n",
+        title = escape_html(&note.title),
+        styles = SHARE_STYLES,
+        meta = escape_html(&meta),
+        body = body,
+    )
+}
+
+/// File-system friendly version of a title
+pub fn slugify(text: &str) -> String {
+    let slug: String = text.to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '-' })
+        .collect();
+    let slug = slug.split('-').filter(|s| !s.is_empty()).collect::<Vec<_>>().join("-");
+    if slug.is_empty() { "note".to_string() } else { slug.chars().take(60).collect() }
+}
+
+/// Where each note goes in a folder export: a folder per category level, then the
+/// title, e.g. `technical/python/list-comprehensions.md`. Clashing names get a number.
+fn export_file_names(notes: &[&Note], extension: &str) -> HashMap<String, String> {
+    let mut taken = HashSet::new();
+    let mut names = HashMap::new();
+    for note in notes {
+        let base = note.category_path.iter()
+            .map(|segment| slugify(segment))
+            .chain(std::iter::once(slugify(&note.title)))
+            .collect::<Vec<_>>()
+            .join("/");
+        let mut name = format!("{}.{}", base, extension);
+        let mut counter = 2;
+        while !taken.insert(name.clone()) {
+            name = format!("{}-{}.{}", base, counter, extension);
+            counter += 1;
+        }
+        names.insert(note.id.clone(), name);
+    }
+    names
+}
+
+/// `target` (relative to the export folder) as a link from the file `from`
+fn relative_link(from: &str, target: &str) -> String {
+    format!("{}{}", "../".repeat(from.matches('/').count()), target.replace(' ', "%20"))
+}
+
+/// Point `note:` and `attachment:` targets at the exported files. Links to notes outside
+/// the export lose their target but keep their text.
+fn rewrite_export_links(content: &str, from: &str, files: &HashMap<String, String>, attachments: &HashMap<String, String>) -> String {
+    rewrite_markdown_targets(content, &mut |target| {
+        if let Some(attachment_id) = target.strip_prefix("attachment:") {
+            return Some(attachments.get(attachment_id).map(|path| relative_link(from, path)).unwrap_or_else(|| "#".to_string()));
+        }
+        let reference = target.strip_prefix("note:")?;
+        let (note_id, fragment) = match reference.find('#') {
+            Some(position) => reference.split_at(position),
+            None => (reference, ""),
+        };
+        Some(match files.get(note_id) {
+            Some(file) => format!("{}{}", relative_link(from, file), fragment),
+            None => "#".to_string(),
+        })
+    })
+}
+
+/// Export notes (all, or one category and its subcategories) as a folder of "markdown"
+/// or "html" files that works outside the app: attachments are copied into
+/// `attachments/` and links between notes point at the exported files. Returns the
+/// number of notes written.
+pub async fn export_notes_folder(dest: String, format: String, category_path: Option<Vec<String>>) -> Result<usize, String> {
+    let extension = match format.trim().to_lowercase().as_str() {
+        "markdown" | "md" => "md",
+        "html" => "html",
+        other => return Err(format!("Unknown export format '{}', expected markdown or html", other)),
+    };
+    // Private notes stay out entirely, including as embeds and attachments of other notes
+    let notes = shareable_notes(load_notes()?.notes, SharingPath::Export)?;
+    let exported: Vec<&Note> = notes.iter()
+        .filter(|n| category_path.as_ref().is_none_or(|path| n.category_path.starts_with(path)))
+        .collect();
+    let files = export_file_names(&exported, extension);
+    let root = Path::new(&dest);
+
+    // Embedded notes can show attachments of notes outside the export, so those are
+    // copied too
+    let known: HashMap<&str, &Attachment> = notes.iter()
+        .flat_map(|n| &n.attachments)
+        .map(|a| (a.id.as_str(), a))
+        .collect();
+    let contents: Vec<String> = exported.iter().map(|n| prepare_note_markdown(n, &notes)).collect();
+    let mut wanted: Vec<&Attachment> = exported.iter().flat_map(|n| &n.attachments).collect();
+    for content in &contents {
+        rewrite_markdown_targets(content, &mut |target| {
+            if let Some(attachment) = target.strip_prefix("attachment:").and_then(|id| known.get(id)) {
+                wanted.push(attachment);
+            }
+            None
+        });
+    }
+
+    let mut attachments: HashMap<String, String> = HashMap::new();
+    for attachment in wanted {
+        if attachments.contains_key(&attachment.id) {
+            continue;
+        }
+        // Built from sanitized parts rather than `stored_path`, which may not be trustworthy
+        let relative = format!("attachments/{}/{}", sanitize_file_name(&attachment.id), sanitize_file_name(&attachment.file_name));
+        let target = root.join(&relative);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create export folder: {}", e))?;
+        }
+        fs::copy(attachment_file_path(attachment)?, &target)
+            .map_err(|e| format!("Failed to copy attachment {}: {}", attachment.file_name, e))?;
+        attachments.insert(attachment.id.clone(), relative);
+    }
+
+    for (note, content) in exported.iter().zip(&contents) {
+        let file = &files[&note.id];
+        let output = if extension == "md" {
+            let content = rewrite_export_links(content, file, &files, &attachments);
+            format!("# {}\n\n{}\n", note.title, content.trim_end())
+        } else {
+            let body = render_note_html_with(note, &notes, &mut |content| rewrite_export_links(content, file, &files, &attachments));
+            note_document(note, &body)
+        };
+        let path = root.join(file);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create export folder: {}", e))?;
+        }
+        fs::write(&path, output)
+            .map_err(|e| format!("Failed to write {}: {}", file, e))?;
+    }
+
+    Ok(exported.len())
+}
+
+/// Write a note as a single self-contained HTML file (in the app's `shared/` folder) and
+/// return its path. With `copy_to_clipboard` the rendered HTML is also put on the clipboard.
+/// Only public notes can be published, and only public notes are embedded in them.
+pub async fn share_note_as_html(note_id: String, copy_to_clipboard: Option<bool>) -> Result<String, String> {
+    let database = load_notes()?;
+    let note = database.notes.iter()
+        .find(|n| n.id == note_id)
+        .ok_or_else(|| tr("error.note_not_found", &[&note_id]))?;
+    ensure_shareable(note, SharingPath::Publish)?;
+
+    let public = shareable_notes(database.notes.clone(), SharingPath::Publish)?;
+    let document = render_note_document(note, &public);
+
+    let mut dir = get_app_data_dir()?;
+    dir.push("shared");
+    fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create shared folder: {}", e))?;
+    let file_path = dir.join(format!("{}.html", slugify(&note.title)));
+    fs::write(&file_path, &document)
+        .map_err(|e| format!("Failed to write shared HTML: {}", e))?;
+
+    if copy_to_clipboard.unwrap_or(false) {
+        let mut clipboard = arboard::Clipboard::new()
+            .map_err(|e| tr("error.clipboard_unavailable", &[&e]))?;
+        clipboard.set_html(&document, Some(&note.content))
+            .map_err(|e| format!("Failed to copy to clipboard: {}", e))?;
+    }
+
+    Ok(file_path.to_string_lossy().to_string())
+}
+
+/// Copy a note to the clipboard as "markdown", "text", "html" (with a plain-text fallback)
+/// or "share" - a quote of the note followed by its title and a `note:` link
+pub async fn copy_note_to_clipboard(note_id: String, format: String) -> Result<(), String> {
+    let database = load_notes()?;
+    let note = database.notes.iter()
+        .find(|n| n.id == note_id)
+        .ok_or_else(|| tr("error.note_not_found", &[&note_id]))?;
+
+    let citation = provenance_line(note).map(|line| format!("\n\n{}", line)).unwrap_or_default();
+
+    let mut clipboard = arboard::Clipboard::new()
+        .map_err(|e| tr("error.clipboard_unavailable", &[&e]))?;
+    let result = match format.trim().to_lowercase().as_str() {
+        "markdown" | "md" => clipboard.set_text(format!("# {}\n\n{}{}", note.title, note.content, citation)),
+        "text" | "plain" => clipboard.set_text(format!("{}{}", markdown_to_plain_text(&note.content), citation)),
+        "html" => clipboard.set_html(render_note_html(note, &database.notes), Some(markdown_to_plain_text(&note.content))),
+        "share" => {
+            let quoted = markdown_to_plain_text(&note.content).lines()
+                .map(|line| if line.is_empty() { ">".to_string() } else { format!("> {}", line) })
+                .collect::<Vec<_>>()
+                .join("\n");
+            clipboard.set_text(format!("{}\n\n— {} (note:{}){}", quoted, note.title, note.id, citation))
+        }
+        other => return Err(format!("Unknown clipboard format '{}', expected markdown, text, html or share", other)),
+    };
+
+    result.map_err(|e| format!("Failed to copy to clipboard: {}", e))
+}
@@ -0,0 +1,170 @@
+use chrono::Utc;
+use uuid::Uuid;
+use tracing::error;
+use crate::models::{FeedSubscription, Note};
+use crate::services::import_service::{html_to_markdown, persist_imported_notes, ImportReport};
+use crate::services::settings_service::current_settings;
+use crate::services::storage_service::{load_feeds, save_feeds};
+
+const FEEDS_ROOT_CATEGORY: &str = "Feeds";
+
+/// How many GUIDs to remember per feed - far more than any feed keeps in its window
+const MAX_SEEN_GUIDS: usize = 2000;
+
+async fn fetch_feed(url: &str) -> Result<feed_rs::model::Feed, String> {
+    let response = reqwest::Client::new()
+        .get(url)
+        .timeout(std::time::Duration::from_secs(20))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch feed {}: {}", url, e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("Fetching feed {} failed with status {}", url, response.status()));
+    }
+
+    let bytes = response.bytes().await
+        .map_err(|e| format!("Failed to read feed body: {}", e))?;
+    feed_rs::parser::parse(&bytes[..])
+        .map_err(|e| format!("Failed to parse feed {}: {}", url, e))
+}
+
+fn truncate_chars(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let cut: String = text.chars().take(max_chars).collect();
+    let cut = cut.rfind(char::is_whitespace).map(|i| cut[..i].to_string()).unwrap_or(cut);
+    format!("{}…", cut.trim_end())
+}
+
+fn note_from_entry(entry: &feed_rs::model::Entry, subscription: &FeedSubscription, max_chars: usize) -> Note {
+    let link = entry.links.first().map(|l| l.href.clone()).unwrap_or_default();
+    let raw_summary = entry.summary.as_ref().map(|s| s.content.clone())
+        .or_else(|| entry.content.as_ref().and_then(|c| c.body.clone()))
+        .unwrap_or_default();
+    let summary = truncate_chars(&html_to_markdown(&raw_summary, &mut |_, _| None), max_chars);
+
+    let title = entry.title.as_ref()
+        .map(|t| html_to_markdown(&t.content, &mut |_, _| None))
+        .filter(|t| !t.trim().is_empty())
+        .unwrap_or_else(|| if link.is_empty() { "Untitled item".to_string() } else { link.clone() });
+
+    let mut content = summary;
+    if !link.is_empty() {
+        content.push_str(&format!("\n\nSource: {}", link));
+    }
+
+    let mut note = Note::new(title, content.trim().to_string(), subscription.category_path.clone());
+    if let Some(published) = entry.published.or(entry.updated) {
+        note.timestamp = published;
+    }
+    note.metadata.insert("source".to_string(), "feed".into());
+    note.metadata.insert("feed_id".to_string(), subscription.id.clone().into());
+    if !link.is_empty() {
+        note.metadata.insert("source_url".to_string(), link.into());
+    }
+    note
+}
+
+/// Turn unseen entries into notes and remember their GUIDs
+fn capture_entries(feed: &feed_rs::model::Feed, subscription: &mut FeedSubscription, max_chars: usize) -> Vec<Note> {
+    let mut notes = Vec::new();
+    for entry in &feed.entries {
+        if subscription.seen_guids.contains(&entry.id) {
+            continue;
+        }
+        notes.push(note_from_entry(entry, subscription, max_chars));
+        subscription.seen_guids.push(entry.id.clone());
+    }
+
+    let overflow = subscription.seen_guids.len().saturating_sub(MAX_SEEN_GUIDS);
+    subscription.seen_guids.drain(..overflow);
+    subscription.last_fetched = Some(Utc::now());
+    notes
+}
+
+/// Subscribe to an RSS/Atom feed and capture its current items
+pub async fn subscribe_feed(url: String, category_path: Option<Vec<String>>) -> Result<FeedSubscription, String> {
+    let url = url.trim().to_string();
+    let mut feeds_db = load_feeds()?;
+    if feeds_db.feeds.iter().any(|f| f.url == url) {
+        return Err("Already subscribed to this feed".to_string());
+    }
+
+    let feed = fetch_feed(&url).await?;
+    let title = feed.title.as_ref()
+        .map(|t| t.content.trim().to_string())
+        .filter(|t| !t.is_empty())
+        .unwrap_or_else(|| url.clone());
+
+    let mut subscription = FeedSubscription {
+        id: Uuid::new_v4().to_string(),
+        url,
+        category_path: category_path
+            .filter(|p| !p.is_empty())
+            .unwrap_or_else(|| vec![FEEDS_ROOT_CATEGORY.to_string(), title.clone()]),
+        title,
+        seen_guids: Vec::new(),
+        last_fetched: None,
+        created_at: Utc::now(),
+    };
+
+    let notes = capture_entries(&feed, &mut subscription, current_settings().feeds.max_content_chars);
+    persist_imported_notes(notes)?;
+
+    feeds_db.feeds.push(subscription.clone());
+    save_feeds(&feeds_db)?;
+    Ok(subscription)
+}
+
+pub async fn list_feeds() -> Result<Vec<FeedSubscription>, String> {
+    Ok(load_feeds()?.feeds)
+}
+
+pub async fn unsubscribe_feed(feed_id: String) -> Result<(), String> {
+    let mut feeds_db = load_feeds()?;
+    let initial_len = feeds_db.feeds.len();
+    feeds_db.feeds.retain(|f| f.id != feed_id);
+    if feeds_db.feeds.len() == initial_len {
+        return Err(format!("Feed with id {} not found", feed_id));
+    }
+    save_feeds(&feeds_db)
+}
+
+/// Fetch every subscription once, creating notes for new items
+pub async fn refresh_feeds() -> Result<ImportReport, String> {
+    let max_chars = current_settings().feeds.max_content_chars;
+    let mut feeds_db = load_feeds()?;
+    let mut report = ImportReport::default();
+    let mut notes = Vec::new();
+
+    for subscription in &mut feeds_db.feeds {
+        match fetch_feed(&subscription.url).await {
+            Ok(feed) => notes.extend(capture_entries(&feed, subscription, max_chars)),
+            Err(e) => report.skipped.push(e),
+        }
+    }
+
+    report.notes_imported = notes.len();
+    persist_imported_notes(notes)?;
+    save_feeds(&feeds_db)?;
+    Ok(report)
+}
+
+/// Background job started at launch, mirroring the email capture loop
+pub async fn run_feed_refresh_loop() {
+    loop {
+        let settings = current_settings().feeds;
+        if settings.enabled {
+            let has_feeds = load_feeds().map(|db| !db.feeds.is_empty()).unwrap_or(false);
+            if has_feeds {
+                if let Err(e) = refresh_feeds().await {
+                    error!("Feed refresh failed: {}", e);
+                }
+            }
+        }
+        let minutes = settings.poll_interval_minutes.max(5) as u64;
+        tokio::time::sleep(std::time::Duration::from_secs(minutes * 60)).await;
+    }
+}
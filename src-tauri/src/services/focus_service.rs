@@ -0,0 +1,147 @@
+use chrono::{DateTime, Duration as ChronoDuration, Local, Utc};
+use serde::Serialize;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use tracing::error;
+use crate::models::Note;
+use crate::services::category_service::ensure_category_path;
+use crate::services::note_service::{insert_note, load_notes};
+use crate::services::locale_service::{format_date, tr};
+
+const FOCUS_CATEGORY: &str = "Focus Sessions";
+/// Longest session that can be started - a forgotten session shouldn't mute reminders for days
+const MAX_FOCUS_MINUTES: u32 = 480;
+/// How often the remaining time is re-announced (tray tooltip) and expiry is checked
+const TICK_SECS: u64 = 15;
+
+#[derive(Serialize, Clone)]
+pub struct FocusSession {
+    pub started_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    pub minutes: u32,
+    pub label: Option<String>, // What the session is for, used in the logged note's title
+    pub log_note: bool,        // Write a session note when it ends
+    pub remaining_seconds: i64,
+}
+
+/// The running session, if any. Only lives for the current run of the app.
+static CURRENT_SESSION: Mutex<Option<FocusSession>> = Mutex::new(None);
+
+/// Called with the running session (or `None` when it ends) on every change and tick.
+/// main.rs installs one that emits `focus:updated` and updates the tray tooltip.
+type FocusListener = Box<dyn Fn(Option<&FocusSession>) + Send + Sync>;
+static FOCUS_LISTENER: OnceLock<FocusListener> = OnceLock::new();
+
+pub fn set_focus_listener(listener: impl Fn(Option<&FocusSession>) + Send + Sync + 'static) {
+    let _ = FOCUS_LISTENER.set(Box::new(listener));
+}
+
+fn notify(session: Option<&FocusSession>) {
+    if let Some(listener) = FOCUS_LISTENER.get() {
+        listener(session);
+    }
+}
+
+/// The running session with its remaining time brought up to date
+fn current_session() -> Option<FocusSession> {
+    let mut session = CURRENT_SESSION.lock().unwrap_or_else(|e| e.into_inner()).clone()?;
+    session.remaining_seconds = (session.ends_at - Utc::now()).num_seconds().max(0);
+    Some(session)
+}
+
+/// True while a focus session runs - reminders and notifications hold off until it ends
+pub fn is_focusing() -> bool {
+    current_session().is_some_and(|s| s.remaining_seconds > 0)
+}
+
+/// Start a do-not-disturb session of `minutes`. With `log_note` a note summarising the
+/// session, with the notes created during it, is written when it ends.
+pub async fn start_focus_session(minutes: u32, label: Option<String>, log_note: Option<bool>) -> Result<FocusSession, String> {
+    if minutes == 0 || minutes > MAX_FOCUS_MINUTES {
+        return Err(format!("Focus sessions last 1 to {} minutes", MAX_FOCUS_MINUTES));
+    }
+    if is_focusing() {
+        return Err("A focus session is already running".to_string());
+    }
+
+    let started_at = Utc::now();
+    let session = FocusSession {
+        started_at,
+        ends_at: started_at + ChronoDuration::minutes(minutes as i64),
+        minutes,
+        label: label.map(|l| l.trim().to_string()).filter(|l| !l.is_empty()),
+        log_note: log_note.unwrap_or(true),
+        remaining_seconds: minutes as i64 * 60,
+    };
+    *CURRENT_SESSION.lock().unwrap_or_else(|e| e.into_inner()) = Some(session.clone());
+    notify(Some(&session));
+    Ok(session)
+}
+
+pub async fn get_focus_session() -> Result<Option<FocusSession>, String> {
+    Ok(current_session())
+}
+
+/// End the running session early. Returns the session note if one was logged.
+pub async fn stop_focus_session() -> Result<Option<Note>, String> {
+    let session = CURRENT_SESSION.lock().unwrap_or_else(|e| e.into_inner()).take()
+        .ok_or("No focus session is running")?;
+    notify(None);
+    finish_session(&session, Utc::now())
+}
+
+/// Write the session note, listing the notes created while focusing
+fn finish_session(session: &FocusSession, ended_at: DateTime<Utc>) -> Result<Option<Note>, String> {
+    if !session.log_note {
+        return Ok(None);
+    }
+
+    let produced: Vec<Note> = load_notes()?.notes.into_iter()
+        .filter(|n| n.timestamp >= session.started_at && n.timestamp <= ended_at)
+        .collect();
+    let worked = (ended_at - session.started_at).num_minutes().max(1);
+    let started_local = session.started_at.with_timezone(&Local);
+
+    let mut content = tr("focus.summary", &[
+        &worked, &session.minutes, &started_local.format("%H:%M"), &ended_at.with_timezone(&Local).format("%H:%M"),
+    ]);
+    content.push_str("\n\n");
+    if produced.is_empty() {
+        content.push_str(&tr("focus.no_notes", &[]));
+    } else {
+        content.push_str(&tr("focus.notes_created", &[]));
+        content.push('\n');
+        for note in &produced {
+            content.push_str(&format!("- [[{}]]\n", note.title));
+        }
+    }
+
+    let title = match &session.label {
+        Some(label) => tr("title.focus_session_labeled", &[label, &format_date(&started_local, "date.short")]),
+        None => tr("title.focus_session", &[&format_date(&started_local, "date.short")]),
+    };
+    let category_path = vec![FOCUS_CATEGORY.to_string()];
+    ensure_category_path(&category_path)?;
+    let mut note = Note::new(title, content, category_path);
+    note.tags.push("focus".to_string());
+    insert_note(note, false).map(Some)
+}
+
+/// Background job started at launch: ends sessions when their time is up and keeps the
+/// remaining time shown in the tray current
+pub async fn run_focus_loop() {
+    loop {
+        tokio::time::sleep(Duration::from_secs(TICK_SECS)).await;
+        let Some(session) = current_session() else { continue };
+        if session.remaining_seconds > 0 {
+            notify(Some(&session));
+            continue;
+        }
+
+        CURRENT_SESSION.lock().unwrap_or_else(|e| e.into_inner()).take();
+        notify(None);
+        if let Err(e) = finish_session(&session, session.ends_at) {
+            error!("Failed to log focus session: {}", e);
+        }
+    }
+}
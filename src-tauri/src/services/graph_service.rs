@@ -0,0 +1,372 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use crate::models::{AuditEvent, Note, NoteLink};
+use crate::services::category_service::load_categories;
+use crate::services::link_service::DEFAULT_LINK_WEIGHT;
+use crate::services::note_service::load_notes;
+use crate::services::storage_service::{load_audit_log, load_links};
+use crate::services::locale_service::tr;
+
+/// Hops walked from the focus note when the filter doesn't say
+const DEFAULT_HOPS: usize = 1;
+/// Related notes returned when the caller doesn't say
+const DEFAULT_RELATED_LIMIT: usize = 10;
+/// Hops a local graph can reach - beyond this it's most of the vault anyway
+const MAX_NEIGHBORHOOD_DEPTH: usize = 5;
+
+/// What part of the graph to draw - every provided field must match
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct GraphFilter {
+    pub category_path: Option<Vec<String>>, // The category and its subcategories
+    pub tags: Option<Vec<String>>,          // Note must carry at least one of these
+    pub from: Option<DateTime<Utc>>,        // Created at or after
+    pub to: Option<DateTime<Utc>>,          // Created before
+    pub focus_note_id: Option<String>,      // Only notes within `hops` links of this one
+    pub hops: Option<usize>,
+    pub author: Option<String>,             // Profile id the note was created under
+}
+
+#[derive(Serialize)]
+pub struct GraphSubset {
+    pub notes: Vec<Note>,
+    pub links: Vec<NoteLink>, // Only links with both ends in `notes`
+    pub total_notes: usize,   // Size of the whole vault, for "showing 40 of 3000"
+}
+
+#[derive(Serialize)]
+pub struct NoteNeighborhood {
+    pub note_id: String,
+    pub notes: Vec<Note>,                 // Includes the note itself
+    pub links: Vec<NoteLink>,             // Only links between the notes
+    pub distances: HashMap<String, usize>, // Hops from the note, by note id
+}
+
+/// One way of getting from one note to another, following links
+#[derive(Serialize)]
+pub struct NotePath {
+    pub notes: Vec<Note>,     // From the start note to the end note
+    pub links: Vec<NoteLink>, // links[i] joins notes[i] and notes[i + 1]
+}
+
+#[derive(Serialize)]
+pub struct RelatedNote {
+    pub note: Note,
+    pub weight: f32,
+    pub link_id: String, // The strongest link between the two
+}
+
+/// Links from notes in one category to notes in another, added up
+#[derive(Serialize)]
+pub struct CategoryEdge {
+    pub source: Vec<String>,
+    pub target: Vec<String>,
+    pub links: usize,
+    pub weight: f32, // Sum of the link weights
+}
+
+#[derive(Serialize)]
+pub struct CategoryLinkMatrix {
+    pub categories: Vec<CategoryNode>,
+    pub edges: Vec<CategoryEdge>, // Strongest first; links within a category aren't edges
+}
+
+#[derive(Serialize)]
+pub struct CategoryNode {
+    pub path: Vec<String>,
+    pub notes: usize,
+    pub internal_links: usize, // Links between two notes of this category
+}
+
+/// A note as it appears in a snapshot - deleted notes only leave this much behind
+#[derive(Serialize)]
+pub struct SnapshotNode {
+    pub id: String,
+    pub title: String,
+    pub category_path: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub deleted_at: Option<DateTime<Utc>>, // Set if it has been deleted since
+}
+
+/// The graph as it stood at one moment, for playing back how it grew
+#[derive(Serialize)]
+pub struct GraphSnapshot {
+    pub at: DateTime<Utc>,
+    pub notes: Vec<SnapshotNode>,     // Oldest first
+    pub links: Vec<NoteLink>,         // Only links with both ends in `notes`
+    pub categories: Vec<Vec<String>>, // Category paths that existed
+}
+
+/// Each note's neighbours, as (note id, index of the link leading there)
+type Adjacency<'a> = HashMap<&'a str, Vec<(&'a str, usize)>>;
+/// Reached note id -> (hops from the start, the neighbour and link it was reached through)
+type Reached<'a> = HashMap<&'a str, (usize, Option<(&'a str, usize)>)>;
+
+/// Notes reachable from each note in one step. With `respect_direction`, directional
+/// links are only followed source to target.
+fn adjacency(links: &[NoteLink], respect_direction: bool) -> Adjacency<'_> {
+    let mut neighbours = Adjacency::new();
+    for (index, link) in links.iter().enumerate() {
+        neighbours.entry(link.source_id.as_str()).or_default().push((link.target_id.as_str(), index));
+        if !respect_direction || link.directional != Some(true) {
+            neighbours.entry(link.target_id.as_str()).or_default().push((link.source_id.as_str(), index));
+        }
+    }
+    neighbours
+}
+
+/// Breadth-first walk from `start`, at most `hops` links deep
+fn walk<'a>(neighbours: &Adjacency<'a>, start: &'a str, hops: usize) -> Reached<'a> {
+    let mut reached = Reached::from([(start, (0, None))]);
+    let mut queue = VecDeque::from([start]);
+    while let Some(id) = queue.pop_front() {
+        let distance = reached[id].0;
+        if distance == hops {
+            continue;
+        }
+        for &(next, link) in neighbours.get(id).into_iter().flatten() {
+            if !reached.contains_key(next) {
+                reached.insert(next, (distance + 1, Some((id, link))));
+                queue.push_back(next);
+            }
+        }
+    }
+    reached
+}
+
+fn note_matches_filter(note: &Note, filter: &GraphFilter) -> bool {
+    if let Some(path) = &filter.category_path {
+        if !note.category_path.starts_with(path) {
+            return false;
+        }
+    }
+
+    if let Some(tags) = filter.tags.as_ref().filter(|t| !t.is_empty()) {
+        if !tags.iter().any(|tag| note.tags.iter().any(|t| t.eq_ignore_ascii_case(tag))) {
+            return false;
+        }
+    }
+
+    if filter.from.is_some_and(|from| note.timestamp < from) || filter.to.is_some_and(|to| note.timestamp >= to) {
+        return false;
+    }
+
+    if filter.author.is_some() && note.author != filter.author {
+        return false;
+    }
+
+    true
+}
+
+/// The notes and links matching `filter`, so large vaults only send the canvas what it
+/// shows. The focus note itself is always included, even if the other filters exclude it.
+pub async fn get_graph_subset(filter: GraphFilter) -> Result<GraphSubset, String> {
+    let notes = load_notes()?.notes;
+    let links = load_links()?.links;
+    let total_notes = notes.len();
+
+    let nearby: Option<HashSet<String>> = match &filter.focus_note_id {
+        Some(focus) => {
+            if !notes.iter().any(|n| &n.id == focus) {
+                return Err(tr("error.note_not_found", &[focus]));
+            }
+            let neighbours = adjacency(&links, false);
+            let hops = filter.hops.unwrap_or(DEFAULT_HOPS);
+            Some(walk(&neighbours, focus, hops).into_keys().map(str::to_string).collect())
+        }
+        None => None,
+    };
+
+    let notes: Vec<Note> = notes.into_iter()
+        .filter(|note| match &nearby {
+            Some(nearby) => nearby.contains(&note.id)
+                && (filter.focus_note_id.as_ref() == Some(&note.id) || note_matches_filter(note, &filter)),
+            None => note_matches_filter(note, &filter),
+        })
+        .collect();
+
+    let included: HashSet<&str> = notes.iter().map(|n| n.id.as_str()).collect();
+    let links: Vec<NoteLink> = links.into_iter()
+        .filter(|l| included.contains(l.source_id.as_str()) && included.contains(l.target_id.as_str()))
+        .collect();
+
+    Ok(GraphSubset { notes, links, total_notes })
+}
+
+/// The local graph around a note: everything reachable within `depth` links, following
+/// directional links only forwards
+pub async fn get_note_neighborhood(note_id: String, depth: usize) -> Result<NoteNeighborhood, String> {
+    let notes = load_notes()?.notes;
+    if !notes.iter().any(|n| n.id == note_id) {
+        return Err(tr("error.note_not_found", &[&note_id]));
+    }
+    let links = load_links()?.links;
+
+    let neighbours = adjacency(&links, true);
+    let distances: HashMap<String, usize> = walk(&neighbours, &note_id, depth.min(MAX_NEIGHBORHOOD_DEPTH))
+        .into_iter()
+        .map(|(id, (distance, _))| (id.to_string(), distance))
+        .collect();
+
+    let notes: Vec<Note> = notes.into_iter().filter(|n| distances.contains_key(&n.id)).collect();
+    let links: Vec<NoteLink> = links.into_iter()
+        .filter(|l| distances.contains_key(&l.source_id) && distances.contains_key(&l.target_id))
+        .collect();
+
+    Ok(NoteNeighborhood { note_id, notes, links, distances })
+}
+
+/// The shortest chain of links from note `from` to note `to`, if they're connected.
+/// Directional links can only be followed source to target.
+pub async fn find_path_between_notes(from: String, to: String) -> Result<Option<NotePath>, String> {
+    let notes = load_notes()?.notes;
+    for id in [&from, &to] {
+        if !notes.iter().any(|n| &n.id == id) {
+            return Err(tr("error.note_not_found", &[id]));
+        }
+    }
+    let links = load_links()?.links;
+
+    let neighbours = adjacency(&links, true);
+    let reached = walk(&neighbours, &from, usize::MAX);
+    if !reached.contains_key(to.as_str()) {
+        return Ok(None);
+    }
+
+    // Follow the breadcrumbs back from the end
+    let mut note_ids = vec![to.as_str()];
+    let mut link_indices = Vec::new();
+    while let Some((_, Some((previous, link)))) = reached.get(note_ids[note_ids.len() - 1]) {
+        note_ids.push(previous);
+        link_indices.push(*link);
+    }
+    note_ids.reverse();
+    link_indices.reverse();
+
+    let path_notes = note_ids.iter()
+        .filter_map(|id| notes.iter().find(|n| n.id == *id).cloned())
+        .collect();
+    let path_links = link_indices.into_iter().map(|index| links[index].clone()).collect();
+
+    Ok(Some(NotePath { notes: path_notes, links: path_links }))
+}
+
+/// Notes linked to `note_id`, strongest link first. Links that were never weighed count
+/// as middling.
+pub async fn get_related_notes(note_id: String, limit: Option<usize>) -> Result<Vec<RelatedNote>, String> {
+    let notes = load_notes()?.notes;
+    if !notes.iter().any(|n| n.id == note_id) {
+        return Err(tr("error.note_not_found", &[&note_id]));
+    }
+
+    let mut strongest: HashMap<&str, (f32, &NoteLink)> = HashMap::new();
+    let links = load_links()?.links;
+    for link in &links {
+        let other = if link.source_id == note_id {
+            &link.target_id
+        } else if link.target_id == note_id {
+            &link.source_id
+        } else {
+            continue;
+        };
+        let weight = link.weight.unwrap_or(DEFAULT_LINK_WEIGHT);
+        let entry = strongest.entry(other.as_str()).or_insert((weight, link));
+        if weight > entry.0 {
+            *entry = (weight, link);
+        }
+    }
+
+    let mut related: Vec<RelatedNote> = notes.into_iter()
+        .filter_map(|note| {
+            let (weight, link) = strongest.get(note.id.as_str())?;
+            Some(RelatedNote { weight: *weight, link_id: link.id.clone(), note })
+        })
+        .collect();
+    related.sort_by(|a, b| b.weight.total_cmp(&a.weight).then_with(|| b.note.timestamp.cmp(&a.note.timestamp)));
+    related.truncate(limit.unwrap_or(DEFAULT_RELATED_LIMIT));
+    Ok(related)
+}
+
+/// Note links rolled up into links between categories, for a topic-level map. With a
+/// `depth`, categories are cut to that many levels ("Technical/Python/Flask" at depth 1
+/// counts as "Technical").
+pub async fn get_category_link_matrix(depth: Option<usize>) -> Result<CategoryLinkMatrix, String> {
+    let notes = load_notes()?.notes;
+    let links = load_links()?.links;
+    let cut = |path: &[String]| -> Vec<String> {
+        match depth {
+            Some(depth) => path.iter().take(depth.max(1)).cloned().collect(),
+            None => path.to_vec(),
+        }
+    };
+
+    let category_of: HashMap<&str, Vec<String>> = notes.iter().map(|n| (n.id.as_str(), cut(&n.category_path))).collect();
+    let mut categories: HashMap<Vec<String>, CategoryNode> = HashMap::new();
+    for path in category_of.values() {
+        categories.entry(path.clone())
+            .or_insert_with(|| CategoryNode { path: path.clone(), notes: 0, internal_links: 0 })
+            .notes += 1;
+    }
+
+    let mut edges: HashMap<(Vec<String>, Vec<String>), CategoryEdge> = HashMap::new();
+    for link in &links {
+        let (Some(source), Some(target)) = (category_of.get(link.source_id.as_str()), category_of.get(link.target_id.as_str())) else {
+            continue;
+        };
+        if source == target {
+            if let Some(category) = categories.get_mut(source) {
+                category.internal_links += 1;
+            }
+            continue;
+        }
+        let edge = edges.entry((source.clone(), target.clone()))
+            .or_insert_with(|| CategoryEdge { source: source.clone(), target: target.clone(), links: 0, weight: 0.0 });
+        edge.links += 1;
+        edge.weight += link.weight.unwrap_or(DEFAULT_LINK_WEIGHT);
+    }
+
+    let mut categories: Vec<CategoryNode> = categories.into_values().collect();
+    categories.sort_by(|a, b| a.path.cmp(&b.path));
+    let mut edges: Vec<CategoryEdge> = edges.into_values().collect();
+    edges.sort_by(|a, b| b.weight.total_cmp(&a.weight).then_with(|| a.source.cmp(&b.source)));
+
+    Ok(CategoryLinkMatrix { categories, edges })
+}
+
+/// The notes, links and categories that existed at `at`, including ones deleted since
+/// (known from the audit log). Notes and links edited since appear as they are now.
+pub async fn get_graph_snapshot(at: DateTime<Utc>) -> Result<GraphSnapshot, String> {
+    let mut notes: Vec<SnapshotNode> = load_notes()?.notes.into_iter()
+        .filter(|n| n.timestamp <= at)
+        .map(|n| SnapshotNode { id: n.id, title: n.title, category_path: n.category_path, created_at: n.timestamp, deleted_at: None })
+        .collect();
+    let mut links: Vec<NoteLink> = load_links()?.links.into_iter().filter(|l| l.created_at <= at).collect();
+    let mut categories: Vec<Vec<String>> = load_categories()?.categories.into_iter()
+        .filter(|c| c.created_at <= at)
+        .map(|c| c.path)
+        .collect();
+
+    // Anything deleted after `at` was still there at the time
+    for entry in load_audit_log()?.entries.into_iter().filter(|e| e.at > at) {
+        match entry.event {
+            AuditEvent::Note { note_id, title, category_path, created_at } if created_at <= at => {
+                notes.push(SnapshotNode { id: note_id, title, category_path, created_at, deleted_at: Some(entry.at) });
+            }
+            AuditEvent::Link { link } if link.created_at <= at => links.push(link),
+            AuditEvent::Category { category } if category.created_at <= at => categories.push(category.path),
+            _ => {}
+        }
+    }
+
+    notes.sort_by_key(|n| n.created_at);
+    let mut seen = HashSet::new();
+    notes.retain(|n| seen.insert(n.id.clone()));
+    let included: HashSet<&str> = notes.iter().map(|n| n.id.as_str()).collect();
+    let mut seen = HashSet::new();
+    links.retain(|l| included.contains(l.source_id.as_str()) && included.contains(l.target_id.as_str()) && seen.insert(l.id.clone()));
+    categories.sort();
+    categories.dedup();
+
+    Ok(GraphSnapshot { at, notes, links, categories })
+}
@@ -0,0 +1,300 @@
+use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
+use serde::Serialize;
+use std::fs;
+use uuid::Uuid;
+use crate::models::{Citation, Highlight, Note, NoteKind};
+use crate::services::citation_service::reference_note;
+use crate::services::import_service::{parse_csv, parse_flexible_date, persist_imported_notes};
+use crate::services::note_service::load_notes;
+use crate::services::storage_service::save_notes;
+use crate::services::locale_service::tr;
+
+#[derive(Serialize, Clone, Default)]
+pub struct HighlightImportReport {
+    pub books_created: usize,
+    pub books_updated: usize,
+    pub highlights_imported: usize,
+    pub skipped: Vec<String>,
+}
+
+/// A highlight read from an export, not yet attached to a note
+struct ImportedHighlight {
+    book_title: String,
+    author: Option<String>,
+    highlight: Highlight,
+}
+
+fn new_highlight(text: String, location: Option<String>, comment: Option<String>, created_at: DateTime<Utc>) -> Highlight {
+    Highlight {
+        id: Uuid::new_v4().to_string(),
+        text,
+        location: location.filter(|l| !l.trim().is_empty()),
+        comment: comment.filter(|c| !c.trim().is_empty()),
+        created_at,
+    }
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value.map(|v| v.trim().to_string()).filter(|v| !v.is_empty())
+}
+
+pub async fn get_highlights(note_id: String) -> Result<Vec<Highlight>, String> {
+    let database = load_notes()?;
+    let note = database.notes.iter()
+        .find(|n| n.id == note_id)
+        .ok_or_else(|| tr("error.note_not_found", &[&note_id]))?;
+    Ok(note.highlights.clone())
+}
+
+pub async fn add_highlight(note_id: String, text: String, location: Option<String>, comment: Option<String>) -> Result<Highlight, String> {
+    if text.trim().is_empty() {
+        return Err("Highlight text cannot be empty".to_string());
+    }
+
+    let mut database = load_notes()?;
+    let note = database.notes.iter_mut()
+        .find(|n| n.id == note_id)
+        .ok_or_else(|| tr("error.note_not_found", &[&note_id]))?;
+
+    let highlight = new_highlight(text.trim().to_string(), non_empty(location), non_empty(comment), Utc::now());
+    note.highlights.push(highlight.clone());
+    note.revision += 1;
+    save_notes(&database)?;
+    Ok(highlight)
+}
+
+/// Change a highlight's text, location or comment. Fields left as `None` are kept;
+/// an empty string clears the location/comment.
+pub async fn update_highlight(note_id: String, highlight_id: String, text: Option<String>, location: Option<String>, comment: Option<String>) -> Result<Highlight, String> {
+    let mut database = load_notes()?;
+    let note = database.notes.iter_mut()
+        .find(|n| n.id == note_id)
+        .ok_or_else(|| tr("error.note_not_found", &[&note_id]))?;
+    let highlight = note.highlights.iter_mut()
+        .find(|h| h.id == highlight_id)
+        .ok_or_else(|| format!("Highlight with id {} not found", highlight_id))?;
+
+    if let Some(text) = text {
+        if text.trim().is_empty() {
+            return Err("Highlight text cannot be empty".to_string());
+        }
+        highlight.text = text.trim().to_string();
+    }
+    if let Some(location) = location {
+        highlight.location = non_empty(Some(location));
+    }
+    if let Some(comment) = comment {
+        highlight.comment = non_empty(Some(comment));
+    }
+
+    let updated = highlight.clone();
+    note.revision += 1;
+    save_notes(&database)?;
+    Ok(updated)
+}
+
+pub async fn delete_highlight(note_id: String, highlight_id: String) -> Result<(), String> {
+    let mut database = load_notes()?;
+    let note = database.notes.iter_mut()
+        .find(|n| n.id == note_id)
+        .ok_or_else(|| tr("error.note_not_found", &[&note_id]))?;
+
+    let before = note.highlights.len();
+    note.highlights.retain(|h| h.id != highlight_id);
+    if note.highlights.len() == before {
+        return Err(format!("Highlight with id {} not found", highlight_id));
+    }
+    note.revision += 1;
+    save_notes(&database)
+}
+
+// ---------------------------------------------------------------------------
+// Importers
+// ---------------------------------------------------------------------------
+
+/// "Added on Sunday, 1 January 2020 12:00:00" / "Added on Sunday, January 1, 2020 12:00:00 PM"
+fn parse_kindle_date(value: &str) -> Option<DateTime<Utc>> {
+    let value = value.trim().trim_start_matches("Added on").trim();
+    let value = value.split_once(", ").map(|(_, rest)| rest).unwrap_or(value);
+    ["%d %B %Y %H:%M:%S", "%B %d, %Y %I:%M:%S %p", "%B %d, %Y %H:%M:%S"].iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+        .map(|date| Utc.from_utc_datetime(&date))
+}
+
+/// "Book Title (Author Name)" - the author is the last parenthesised group
+fn split_kindle_title(line: &str) -> (String, Option<String>) {
+    let line = line.trim().trim_start_matches('\u{feff}');
+    if line.ends_with(')') {
+        if let Some(open) = line.rfind('(') {
+            let title = line[..open].trim();
+            if !title.is_empty() {
+                return (title.to_string(), non_empty(Some(line[open + 1..line.len() - 1].to_string())));
+            }
+        }
+    }
+    (line.to_string(), None)
+}
+
+/// Last number in a location string: "page 12, location 123-125" -> "125"
+fn location_end(location: &str) -> Option<&str> {
+    location.split(|c: char| !c.is_ascii_digit()).rfind(|part| !part.is_empty())
+}
+
+/// Kindle `My Clippings.txt`: entries separated by `==========`. Notes are attached as the
+/// comment of the highlight at the same location; bookmarks are ignored.
+fn parse_kindle_clippings(text: &str) -> Vec<ImportedHighlight> {
+    let mut highlights: Vec<ImportedHighlight> = Vec::new();
+
+    for entry in text.split("==========") {
+        let mut lines = entry.lines().map(str::trim).filter(|l| !l.is_empty());
+        let (Some(title_line), Some(meta)) = (lines.next(), lines.next()) else { continue };
+        let body = lines.collect::<Vec<_>>().join("\n");
+        if body.is_empty() {
+            continue;
+        }
+
+        let (book_title, author) = split_kindle_title(title_line);
+        let mut parts = meta.trim_start_matches('-').split('|').map(str::trim);
+        let kind = parts.next().unwrap_or("").to_lowercase();
+        // "Your Highlight on page 12 | Location 123-125 | Added on ..." or "... at location 123-125 | ..."
+        let mut location: Vec<String> = kind.split_once(" on ").or_else(|| kind.split_once(" at "))
+            .map(|(_, place)| vec![place.to_string()])
+            .unwrap_or_default();
+        let mut created_at = None;
+        for part in parts {
+            if part.starts_with("Added on") {
+                created_at = parse_kindle_date(part);
+            } else if !part.is_empty() {
+                location.push(part.to_lowercase());
+            }
+        }
+        let location = (!location.is_empty()).then(|| location.join(", "));
+
+        if kind.contains("your note") {
+            // Kindle records a note at the last location of the highlight it belongs to
+            let note_end = location.as_deref().and_then(location_end);
+            let target = highlights.iter_mut().rev()
+                .filter(|h| h.book_title == book_title && h.highlight.comment.is_none())
+                .find(|h| h.highlight.location == location || (note_end.is_some() && h.highlight.location.as_deref().and_then(location_end) == note_end));
+            match target {
+                Some(target) => target.highlight.comment = Some(body),
+                None => highlights.push(ImportedHighlight {
+                    book_title,
+                    author,
+                    highlight: new_highlight(String::new(), location, Some(body), created_at.unwrap_or_else(Utc::now)),
+                }),
+            }
+        } else if kind.contains("highlight") {
+            highlights.push(ImportedHighlight {
+                book_title,
+                author,
+                highlight: new_highlight(body, location, None, created_at.unwrap_or_else(Utc::now)),
+            });
+        }
+    }
+
+    // A note without a highlight at its location keeps its text as the quote
+    for item in &mut highlights {
+        if item.highlight.text.is_empty() {
+            item.highlight.text = item.highlight.comment.take().unwrap_or_default();
+        }
+    }
+    highlights
+}
+
+/// Readwise CSV export (Highlight, Book Title, Book Author, Note, Location, Highlighted at...)
+fn parse_readwise_csv(text: &str) -> Result<Vec<ImportedHighlight>, String> {
+    let mut rows = parse_csv(text).into_iter();
+    let header: Vec<String> = rows.next()
+        .ok_or("CSV file is empty")?
+        .into_iter()
+        .map(|h| h.trim().to_lowercase())
+        .collect();
+    let column = |name: &str| header.iter().position(|h| h == name);
+    let text_column = column("highlight").ok_or("Not a Readwise export: missing 'Highlight' column")?;
+    let title_column = column("book title").or_else(|| column("title")).ok_or("Not a Readwise export: missing 'Book Title' column")?;
+    let (author_column, note_column) = (column("book author").or_else(|| column("author")), column("note"));
+    let (location_type_column, location_column) = (column("location type"), column("location"));
+    let date_column = column("highlighted at");
+
+    let mut highlights = Vec::new();
+    for row in rows {
+        let cell = |index: Option<usize>| index.and_then(|i| row.get(i)).map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+        let (Some(text), Some(book_title)) = (cell(Some(text_column)), cell(Some(title_column))) else { continue };
+        let location = cell(location_column).map(|l| match cell(location_type_column) {
+            Some(kind) => format!("{} {}", kind, l),
+            None => l,
+        });
+        let created_at = cell(date_column).and_then(|d| parse_flexible_date(&d)).unwrap_or_else(Utc::now);
+
+        highlights.push(ImportedHighlight {
+            book_title,
+            author: cell(author_column),
+            highlight: new_highlight(text, location, cell(note_column), created_at),
+        });
+    }
+    Ok(highlights)
+}
+
+/// Attach imported highlights to reference notes - an existing reference with the same
+/// title gets them appended (duplicates skipped), otherwise a new reference note is made
+fn attach_highlights(imported: Vec<ImportedHighlight>) -> Result<HighlightImportReport, String> {
+    let mut report = HighlightImportReport::default();
+    let mut database = load_notes()?;
+    let mut new_notes: Vec<Note> = Vec::new();
+    let mut updated_ids: Vec<String> = Vec::new();
+
+    for item in imported {
+        let matches_book = |n: &Note| n.kind == NoteKind::Reference && n.title.eq_ignore_ascii_case(&item.book_title);
+        let (note, is_new) = match database.notes.iter_mut().find(|n| matches_book(n)) {
+            Some(note) => (note, false),
+            None => match new_notes.iter_mut().position(|n| matches_book(n)) {
+                Some(index) => (&mut new_notes[index], true),
+                None => {
+                    let citation = Citation {
+                        entry_type: "book".to_string(),
+                        title: item.book_title.clone(),
+                        authors: item.author.clone().into_iter().collect(),
+                        ..Default::default()
+                    };
+                    new_notes.push(reference_note(citation, String::new(), Vec::new()));
+                    (new_notes.last_mut().unwrap(), true)
+                }
+            },
+        };
+
+        let duplicate = note.highlights.iter()
+            .any(|h| h.text == item.highlight.text && h.location == item.highlight.location);
+        if duplicate {
+            report.skipped.push(format!("{}: highlight already imported", item.book_title));
+            continue;
+        }
+
+        note.highlights.push(item.highlight);
+        report.highlights_imported += 1;
+        if !is_new && !updated_ids.contains(&note.id) {
+            note.revision += 1;
+            updated_ids.push(note.id.clone());
+        }
+    }
+
+    if !updated_ids.is_empty() {
+        save_notes(&database)?;
+    }
+    report.books_updated = updated_ids.len();
+    report.books_created = new_notes.len();
+    persist_imported_notes(new_notes)?;
+    Ok(report)
+}
+
+pub async fn import_kindle_clippings(path: String) -> Result<HighlightImportReport, String> {
+    let text = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read clippings file: {}", e))?;
+    attach_highlights(parse_kindle_clippings(&text))
+}
+
+pub async fn import_readwise_csv(path: String) -> Result<HighlightImportReport, String> {
+    let text = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read Readwise export: {}", e))?;
+    attach_highlights(parse_readwise_csv(&text)?)
+}
@@ -0,0 +1,1445 @@
+use base64::Engine;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+use tracing::error;
+use crate::models::{Attachment, LinkType, Note, NoteLink};
+use crate::services::ai_service::generate_simple_title;
+use crate::services::attachment_service::{attachment_markdown, store_attachment_bytes, store_attachment_file};
+use crate::services::category_service::{ensure_category_path, update_category_note_counts};
+use crate::services::job_service::{finish_job, start_job, update_job_file, FileState, ImportJob, JobState};
+use crate::services::markdown_service::{extract_wikilinks, rewrite_markdown_targets};
+use crate::services::note_service::load_notes;
+use crate::services::plugin_service::run_import_hooks;
+use crate::services::profile_service::attribute_note;
+use crate::services::storage_service::{load_links, save_links, save_notes};
+
+/// Outcome of an import run, shared by every importer
+#[derive(Serialize, Clone, Default)]
+pub struct ImportReport {
+    pub notes_imported: usize,
+    pub attachments_imported: usize,
+    pub links_created: usize,
+    pub skipped: Vec<String>, // Human-readable "file: reason" entries
+}
+
+// ---------------------------------------------------------------------------
+// Shared helpers
+// ---------------------------------------------------------------------------
+
+/// Parse the date formats commonly found in exports (RFC 3339, RFC 2822, plain dates)
+pub fn parse_flexible_date(value: &str) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+    if value.is_empty() {
+        return None;
+    }
+    if let Ok(date) = DateTime::parse_from_rfc3339(value) {
+        return Some(date.with_timezone(&Utc));
+    }
+    if let Ok(date) = DateTime::parse_from_rfc2822(value) {
+        return Some(date.with_timezone(&Utc));
+    }
+    for format in ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M"] {
+        if let Ok(date) = NaiveDateTime::parse_from_str(value, format) {
+            return Some(Utc.from_utc_datetime(&date));
+        }
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return date.and_hms_opt(0, 0, 0).map(|d| Utc.from_utc_datetime(&d));
+    }
+    None
+}
+
+/// Rows of an RFC 4180 CSV document - quoted fields may contain commas, quotes and newlines
+pub fn parse_csv(text: &str) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = text.trim_start_matches('\u{feff}').chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => row.push(std::mem::take(&mut field)),
+            '\r' if !in_quotes => {}
+            '\n' if !in_quotes => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            _ => field.push(c),
+        }
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+
+    rows
+}
+
+/// Best-effort creation time of a file, falling back to modification time
+pub fn file_timestamp(path: &Path) -> DateTime<Utc> {
+    fs::metadata(path)
+        .ok()
+        .and_then(|meta| meta.created().or_else(|_| meta.modified()).ok())
+        .map(DateTime::<Utc>::from)
+        .unwrap_or_else(Utc::now)
+}
+
+/// Category path for a file, derived from its directory relative to the import root
+pub fn category_path_for(root: &Path, file: &Path, fallback: &str) -> Vec<String> {
+    let path: Vec<String> = file.parent()
+        .and_then(|parent| parent.strip_prefix(root).ok())
+        .map(|relative| relative.components()
+            .map(|c| c.as_os_str().to_string_lossy().to_string())
+            .filter(|segment| !segment.is_empty())
+            .collect())
+        .unwrap_or_default();
+
+    if path.is_empty() { vec![fallback.to_string()] } else { path }
+}
+
+/// Save imported notes in one pass, creating their categories first
+pub fn persist_imported_notes(mut notes: Vec<Note>) -> Result<(), String> {
+    if notes.is_empty() {
+        return Ok(());
+    }
+
+    for note in &mut notes {
+        attribute_note(note);
+    }
+    run_import_hooks(&mut notes);
+
+    for note in &notes {
+        ensure_category_path(&note.category_path)?;
+    }
+
+    let mut database = load_notes()?;
+    database.notes.extend(notes);
+    save_notes(&database)?;
+    update_category_note_counts()?;
+    Ok(())
+}
+
+pub fn decode_html_entities(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find('&') {
+        result.push_str(&rest[..start]);
+        let after = &rest[start..];
+        let Some(end) = after.find(';').filter(|&end| end <= 10) else {
+            result.push('&');
+            rest = &after[1..];
+            continue;
+        };
+
+        let entity = &after[1..end];
+        let decoded = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" | "#39" => Some('\''),
+            "nbsp" => Some(' '),
+            _ if entity.starts_with("#x") || entity.starts_with("#X") => {
+                u32::from_str_radix(&entity[2..], 16).ok().and_then(char::from_u32)
+            }
+            _ if entity.starts_with('#') => entity[1..].parse::<u32>().ok().and_then(char::from_u32),
+            _ => None,
+        };
+
+        match decoded {
+            Some(c) => {
+                result.push(c);
+                rest = &after[end + 1..];
+            }
+            None => {
+                result.push('&');
+                rest = &after[1..];
+            }
+        }
+    }
+
+    result.push_str(rest);
+    result
+}
+
+/// Read an attribute value out of a raw tag body such as `a href="x" class='y'`
+pub fn tag_attribute(tag: &str, name: &str) -> Option<String> {
+    let lower = tag.to_ascii_lowercase();
+    let needle = format!("{}=", name.to_ascii_lowercase());
+    let mut search_from = 0;
+
+    while let Some(found) = lower[search_from..].find(&needle) {
+        let start = search_from + found;
+        let preceded_ok = start == 0 || lower[..start].ends_with(|c: char| c.is_whitespace());
+        let value_start = start + needle.len();
+        search_from = value_start;
+        if !preceded_ok {
+            continue;
+        }
+
+        let value = &tag[value_start..];
+        let parsed = match value.chars().next() {
+            Some(quote @ ('"' | '\'')) => value[1..].find(quote).map(|end| &value[1..end + 1]),
+            Some(_) => Some(value.split(|c: char| c.is_whitespace() || c == '>').next().unwrap_or("")),
+            None => None,
+        };
+        return parsed.map(decode_html_entities);
+    }
+
+    None
+}
+
+/// Text inside the first `<tag>...</tag>` pair, with entities decoded
+pub fn first_tag_text(html: &str, tag: &str) -> Option<String> {
+    let lower = html.to_ascii_lowercase();
+    let open = lower.find(&format!("<{}", tag))?;
+    let content_start = open + lower[open..].find('>')? + 1;
+    let content_end = content_start + lower[content_start..].find(&format!("</{}", tag))?;
+    let text = html_to_markdown(&html[content_start..content_end], &mut |_, _| None);
+    let text = text.trim().to_string();
+    if text.is_empty() { None } else { Some(text) }
+}
+
+/// Convert a fragment of HTML into readable Markdown.
+///
+/// `resolve_image` receives each `<img>`'s `src` and `alt` and returns the Markdown to
+/// emit in its place (typically an attachment reference), or `None` to drop it.
+pub fn html_to_markdown(html: &str, resolve_image: &mut dyn FnMut(&str, &str) -> Option<String>) -> String {
+    let mut output = String::new();
+    let mut link_targets: Vec<Option<String>> = Vec::new();
+    let mut skip_until: Option<String> = None;
+    let mut in_pre = false;
+    let mut rest = html;
+
+    while !rest.is_empty() {
+        let Some(tag_start) = rest.find('<') else {
+            if skip_until.is_none() {
+                push_text(&mut output, rest, in_pre);
+            }
+            break;
+        };
+
+        if skip_until.is_none() {
+            push_text(&mut output, &rest[..tag_start], in_pre);
+        }
+
+        let after = &rest[tag_start..];
+        // Comments can contain '>' so they need their own terminator
+        if after.starts_with("<!--") {
+            rest = after.find("-->").map(|end| &after[end + 3..]).unwrap_or("");
+            continue;
+        }
+
+        let Some(tag_end) = after.find('>') else {
+            break;
+        };
+        let raw_tag = &after[1..tag_end];
+        rest = &after[tag_end + 1..];
+
+        let closing = raw_tag.starts_with('/');
+        let body = raw_tag.trim_start_matches('/').trim_end_matches('/');
+        let name: String = body.chars()
+            .take_while(|c| c.is_alphanumeric())
+            .collect::<String>()
+            .to_lowercase();
+
+        if let Some(waiting_for) = &skip_until {
+            if closing && &name == waiting_for {
+                skip_until = None;
+            }
+            continue;
+        }
+
+        match (name.as_str(), closing) {
+            ("script" | "style" | "head", false) => skip_until = Some(name.clone()),
+            ("br", _) => output.push('\n'),
+            ("hr", _) => output.push_str("\n\n---\n\n"),
+            ("p" | "div" | "section" | "article" | "blockquote" | "tr" | "ul" | "ol" | "table", _) => ensure_newlines(&mut output, 2),
+            ("h1" | "h2" | "h3" | "h4" | "h5" | "h6", false) => {
+                ensure_newlines(&mut output, 2);
+                let level = name[1..].parse::<usize>().unwrap_or(1);
+                output.push_str(&"#".repeat(level));
+                output.push(' ');
+            }
+            ("h1" | "h2" | "h3" | "h4" | "h5" | "h6", true) => ensure_newlines(&mut output, 2),
+            ("li", false) => {
+                ensure_newlines(&mut output, 1);
+                output.push_str("- ");
+            }
+            ("li", true) => ensure_newlines(&mut output, 1),
+            ("td" | "th", true) => output.push_str(" | "),
+            ("strong" | "b", _) => output.push_str("**"),
+            ("em" | "i", _) => output.push('*'),
+            ("pre", false) => {
+                ensure_newlines(&mut output, 2);
+                output.push_str("```\n");
+                in_pre = true;
+            }
+            ("pre", true) => {
+                ensure_newlines(&mut output, 1);
+                output.push_str("```\n\n");
+                in_pre = false;
+            }
+            ("code", _) if !in_pre => output.push('`'),
+            ("a", false) => {
+                link_targets.push(tag_attribute(body, "href"));
+                output.push('[');
+            }
+            ("a", true) => match link_targets.pop().flatten() {
+                Some(href) => output.push_str(&format!("]({})", href)),
+                None => output.push(']'),
+            },
+            ("img", _) => {
+                let src = tag_attribute(body, "src").unwrap_or_default();
+                let alt = tag_attribute(body, "alt").unwrap_or_default();
+                if let Some(markdown) = resolve_image(&src, &alt) {
+                    output.push_str(&markdown);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    collapse_blank_lines(&output)
+}
+
+fn push_text(output: &mut String, text: &str, preserve_whitespace: bool) {
+    let decoded = decode_html_entities(text);
+    if preserve_whitespace {
+        output.push_str(&decoded);
+        return;
+    }
+
+    // Outside <pre>, HTML whitespace collapses into single spaces
+    let mut last_was_space = output.ends_with(' ') || output.ends_with('\n') || output.is_empty();
+    for c in decoded.chars() {
+        if c.is_whitespace() {
+            if !last_was_space {
+                output.push(' ');
+                last_was_space = true;
+            }
+        } else {
+            output.push(c);
+            last_was_space = false;
+        }
+    }
+}
+
+fn ensure_newlines(output: &mut String, count: usize) {
+    while output.ends_with(' ') {
+        output.pop();
+    }
+    if output.is_empty() {
+        return;
+    }
+    let existing = output.chars().rev().take_while(|&c| c == '\n').count();
+    for _ in existing..count {
+        output.push('\n');
+    }
+}
+
+fn collapse_blank_lines(text: &str) -> String {
+    let mut result = String::new();
+    let mut blank_run = 0;
+    for line in text.lines() {
+        let line = line.trim_end();
+        if line.is_empty() {
+            blank_run += 1;
+            if blank_run > 1 {
+                continue;
+            }
+        } else {
+            blank_run = 0;
+        }
+        result.push_str(line);
+        result.push('\n');
+    }
+    result.trim().to_string()
+}
+
+/// Decode a `data:<mime>;base64,<payload>` URI into (mime type, bytes)
+pub fn decode_data_uri(uri: &str) -> Option<(String, Vec<u8>)> {
+    let rest = uri.strip_prefix("data:")?;
+    let (header, payload) = rest.split_once(',')?;
+    let mime = header.split(';').next().unwrap_or("application/octet-stream").to_string();
+    if !header.contains(";base64") {
+        return None;
+    }
+    let bytes = base64::engine::general_purpose::STANDARD.decode(payload.trim()).ok()?;
+    Some((mime, bytes))
+}
+
+fn extension_for_mime(mime: &str) -> &'static str {
+    match mime {
+        "image/png" => "png",
+        "image/jpeg" => "jpg",
+        "image/gif" => "gif",
+        "image/webp" => "webp",
+        "image/svg+xml" => "svg",
+        "image/heic" => "heic",
+        "image/tiff" => "tiff",
+        _ => "bin",
+    }
+}
+
+/// Turn an `<img src>` into a stored attachment - handles data URIs and files relative to
+/// `base_dir`, as long as they lie inside the export folder `root`
+fn import_image(src: &str, alt: &str, root: &Path, base_dir: &Path, attachments: &mut Vec<Attachment>) -> Option<String> {
+    let attachment = if let Some((mime, bytes)) = decode_data_uri(src) {
+        let stem = if alt.trim().is_empty() { "image" } else { alt.trim() };
+        let file_name = format!("{}.{}", stem, extension_for_mime(&mime));
+        store_attachment_bytes(&file_name, &bytes).ok()?
+    } else if !src.contains("://") && !src.is_empty() {
+        let decoded = src.replace("%20", " ");
+        let local = local_file_under(root, base_dir, decoded.trim_start_matches("file://"))?;
+        store_attachment_file(&local).ok()?
+    } else {
+        // Remote images stay remote
+        return Some(format!("![{}]({})", alt, src));
+    };
+
+    let markdown = attachment_markdown(&attachment);
+    attachments.push(attachment);
+    Some(markdown)
+}
+
+/// Split YAML-style front matter (`---` fenced block at the top) from the body.
+///
+/// Only the flat subset exports actually use is supported: `key: value` scalars,
+/// inline `[a, b]` lists and indented `- item` lists.
+pub fn parse_frontmatter(text: &str) -> (serde_json::Map<String, serde_json::Value>, String) {
+    let mut fields = serde_json::Map::new();
+    let trimmed = text.trim_start_matches('\u{feff}');
+
+    let Some(after_open) = trimmed.strip_prefix("---\n").or_else(|| trimmed.strip_prefix("---\r\n")) else {
+        return (fields, text.to_string());
+    };
+
+    let mut body_start = None;
+    let mut offset = 0;
+    for line in after_open.split_inclusive('\n') {
+        let bare = line.trim_end();
+        if bare == "---" || bare == "..." {
+            body_start = Some(offset + line.len());
+            break;
+        }
+        offset += line.len();
+    }
+    let Some(body_start) = body_start else {
+        return (fields, text.to_string());
+    };
+
+    let mut current_list: Option<String> = None;
+    for line in after_open[..offset].lines() {
+        let stripped = line.trim();
+        if stripped.is_empty() || stripped.starts_with('#') {
+            continue;
+        }
+
+        if let (Some(key), Some(item)) = (&current_list, stripped.strip_prefix("- ")) {
+            if let Some(serde_json::Value::Array(items)) = fields.get_mut(key) {
+                items.push(serde_json::Value::String(unquote(item).to_string()));
+            }
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let key = key.trim().to_string();
+        let value = value.trim();
+
+        if value.is_empty() {
+            fields.insert(key.clone(), serde_json::Value::Array(Vec::new()));
+            current_list = Some(key);
+        } else {
+            fields.insert(key, parse_frontmatter_value(value));
+            current_list = None;
+        }
+    }
+
+    // Empty lists that never got items were really empty scalars
+    for value in fields.values_mut() {
+        if matches!(value, serde_json::Value::Array(items) if items.is_empty()) {
+            *value = serde_json::Value::String(String::new());
+        }
+    }
+
+    (fields, after_open[body_start..].trim_start_matches(['\r', '\n']).to_string())
+}
+
+fn unquote(value: &str) -> &str {
+    let value = value.trim();
+    if value.len() >= 2 && ((value.starts_with('"') && value.ends_with('"')) || (value.starts_with('\'') && value.ends_with('\''))) {
+        &value[1..value.len() - 1]
+    } else {
+        value
+    }
+}
+
+fn parse_frontmatter_value(value: &str) -> serde_json::Value {
+    if let Some(inner) = value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+        return serde_json::Value::Array(inner.split(',')
+            .map(|item| unquote(item).to_string())
+            .filter(|item| !item.is_empty())
+            .map(serde_json::Value::String)
+            .collect());
+    }
+    match value {
+        "true" => return serde_json::Value::Bool(true),
+        "false" => return serde_json::Value::Bool(false),
+        _ => {}
+    }
+    if !value.starts_with(['"', '\'']) {
+        if let Ok(number) = value.parse::<i64>() {
+            return number.into();
+        }
+        if let Ok(number) = value.parse::<f64>() {
+            return number.into();
+        }
+    }
+    serde_json::Value::String(unquote(value).to_string())
+}
+
+/// Front matter value as a list of strings (accepts lists or comma-separated scalars)
+pub fn frontmatter_list(fields: &serde_json::Map<String, serde_json::Value>, key: &str) -> Vec<String> {
+    match fields.get(key) {
+        Some(serde_json::Value::Array(items)) => items.iter()
+            .filter_map(|item| item.as_str().map(|s| s.trim().to_string()))
+            .filter(|s| !s.is_empty())
+            .collect(),
+        Some(serde_json::Value::String(value)) => value.split(',')
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Apple Notes
+// ---------------------------------------------------------------------------
+
+const APPLE_NOTES_ROOT_CATEGORY: &str = "Apple Notes";
+
+/// Import an Apple Notes export: a folder of per-note HTML files and/or `.textbundle` folders.
+/// Sub-folders become categories; notes at the top level land in "Apple Notes".
+pub async fn import_apple_notes(path: String) -> Result<ImportReport, String> {
+    let root = PathBuf::from(&path);
+    if !root.is_dir() {
+        return Err(format!("{} is not a folder", path));
+    }
+
+    let mut report = ImportReport::default();
+    let mut notes = Vec::new();
+    let mut pending = vec![root.clone()];
+
+    while let Some(dir) = pending.pop() {
+        let entries = fs::read_dir(&dir)
+            .map_err(|e| format!("Failed to read directory {}: {}", dir.display(), e))?;
+
+        for entry in entries.flatten() {
+            let entry_path = entry.path();
+            let extension = entry_path.extension()
+                .map(|e| e.to_string_lossy().to_lowercase())
+                .unwrap_or_default();
+
+            let imported = if entry_path.is_dir() && extension == "textbundle" {
+                import_textbundle(&root, &entry_path)
+            } else if entry_path.is_dir() {
+                pending.push(entry_path.clone());
+                continue;
+            } else if extension == "html" || extension == "htm" {
+                import_apple_html_note(&root, &entry_path)
+            } else {
+                // Loose images are picked up through the notes that reference them
+                continue;
+            };
+
+            match imported {
+                Ok(note) => {
+                    report.attachments_imported += note.attachments.len();
+                    notes.push(note);
+                }
+                Err(e) => report.skipped.push(format!("{}: {}", entry_path.display(), e)),
+            }
+        }
+    }
+
+    report.notes_imported = notes.len();
+    persist_imported_notes(notes)?;
+    Ok(report)
+}
+
+fn import_apple_html_note(root: &Path, file: &Path) -> Result<Note, String> {
+    let html = fs::read_to_string(file)
+        .map_err(|e| format!("Failed to read file: {}", e))?;
+    let base_dir = file.parent().unwrap_or(root).to_path_buf();
+
+    let body = {
+        let lower = html.to_ascii_lowercase();
+        match (lower.find("<body"), lower.rfind("</body>")) {
+            (Some(start), Some(end)) if start < end => {
+                let content_start = start + lower[start..].find('>').unwrap_or(0) + 1;
+                html[content_start..end].to_string()
+            }
+            _ => html.clone(),
+        }
+    };
+
+    let mut attachments = Vec::new();
+    let content = html_to_markdown(&body, &mut |src, alt| import_image(src, alt, root, &base_dir, &mut attachments));
+
+    let stem = file.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+    let title = first_tag_text(&html, "title")
+        .or_else(|| first_tag_text(&body, "h1"))
+        .unwrap_or_else(|| if stem.is_empty() { generate_simple_title(&content) } else { stem });
+
+    // Some exporters record the original dates as meta tags
+    let created = ["created", "date", "creation-date"].iter()
+        .find_map(|key| meta_content(&html, key))
+        .and_then(|value| parse_flexible_date(&value))
+        .unwrap_or_else(|| file_timestamp(file));
+
+    let mut note = Note::new(title, content, category_path_for(root, file, APPLE_NOTES_ROOT_CATEGORY));
+    note.timestamp = created;
+    note.attachments = attachments;
+    Ok(note)
+}
+
+fn meta_content(html: &str, name: &str) -> Option<String> {
+    let lower = html.to_ascii_lowercase();
+    let mut search_from = 0;
+    while let Some(found) = lower[search_from..].find("<meta") {
+        let start = search_from + found;
+        let end = start + lower[start..].find('>')?;
+        let tag = &html[start + 1..end];
+        if tag_attribute(tag, "name").map(|n| n.eq_ignore_ascii_case(name)).unwrap_or(false) {
+            return tag_attribute(tag, "content");
+        }
+        search_from = end;
+    }
+    None
+}
+
+/// A TextBundle is a folder with `text.md` (or `.markdown`/`.txt`) plus an `assets/` folder
+fn import_textbundle(root: &Path, bundle: &Path) -> Result<Note, String> {
+    let text_file = ["text.md", "text.markdown", "text.txt"].iter()
+        .map(|name| bundle.join(name))
+        .find(|candidate| candidate.is_file())
+        .ok_or("TextBundle has no text file")?;
+
+    let mut content = fs::read_to_string(&text_file)
+        .map_err(|e| format!("Failed to read bundle text: {}", e))?;
+
+    let mut attachments = Vec::new();
+    let assets_dir = bundle.join("assets");
+    if let Ok(assets) = fs::read_dir(&assets_dir) {
+        for asset in assets.flatten() {
+            let asset_path = asset.path();
+            if !asset_path.is_file() {
+                continue;
+            }
+            let file_name = asset.file_name().to_string_lossy().to_string();
+            let attachment = store_attachment_file(&asset_path)?;
+            // Point the bundle-relative references at the stored attachment
+            for reference in [format!("assets/{}", file_name), format!("assets/{}", file_name.replace(' ', "%20"))] {
+                content = content.replace(&format!("({})", reference), &format!("(attachment:{})", attachment.id));
+            }
+            attachments.push(attachment);
+        }
+    }
+
+    let stem = bundle.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+    let title = if stem.is_empty() { generate_simple_title(&content) } else { stem };
+
+    let mut note = Note::new(title, content.trim().to_string(), category_path_for(root, bundle, APPLE_NOTES_ROOT_CATEGORY));
+    note.timestamp = file_timestamp(&text_file);
+    note.attachments = attachments;
+    Ok(note)
+}
+
+// ---------------------------------------------------------------------------
+// Joplin
+// ---------------------------------------------------------------------------
+
+const JOPLIN_ROOT_CATEGORY: &str = "Joplin";
+
+// Joplin item types as written in the `type_` property
+const JOPLIN_TYPE_NOTE: &str = "1";
+const JOPLIN_TYPE_FOLDER: &str = "2";
+const JOPLIN_TYPE_RESOURCE: &str = "4";
+const JOPLIN_TYPE_TAG: &str = "5";
+const JOPLIN_TYPE_NOTE_TAG: &str = "6";
+
+/// One item from Joplin's raw format: title/body text followed by `key: value` properties
+struct JoplinItem {
+    title: String,
+    body: String,
+    properties: std::collections::HashMap<String, String>,
+}
+
+impl JoplinItem {
+    fn prop(&self, key: &str) -> &str {
+        self.properties.get(key).map(|v| v.as_str()).unwrap_or("")
+    }
+}
+
+fn parse_joplin_item(text: &str) -> Option<JoplinItem> {
+    let lines: Vec<&str> = text.lines().collect();
+
+    // Properties are the trailing block of `key: value` lines
+    let mut split = lines.len();
+    while split > 0 {
+        let line = lines[split - 1];
+        let is_property = line.split_once(':')
+            .map(|(key, _)| !key.is_empty() && key.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_'))
+            .unwrap_or(false);
+        if !is_property {
+            break;
+        }
+        split -= 1;
+    }
+
+    let properties: std::collections::HashMap<String, String> = lines[split..].iter()
+        .filter_map(|line| line.split_once(':'))
+        .map(|(key, value)| (key.to_string(), value.trim().to_string()))
+        .collect();
+    if !properties.contains_key("type_") {
+        return None;
+    }
+
+    let head: Vec<&str> = lines[..split].to_vec();
+    let title = head.first().map(|t| t.trim().to_string()).unwrap_or_default();
+    let body = head.iter().skip(1).copied().collect::<Vec<_>>().join("\n").trim().to_string();
+
+    Some(JoplinItem { title, body, properties })
+}
+
+/// Import a Joplin export: a `.jex` archive, a raw export directory, or a
+/// "Markdown + Front Matter" export directory. Notebooks become categories.
+pub async fn import_joplin(path: String) -> Result<ImportReport, String> {
+    let source = PathBuf::from(&path);
+
+    if source.is_file() {
+        let file = fs::File::open(&source)
+            .map_err(|e| format!("Failed to open JEX archive: {}", e))?;
+        let mut archive = tar::Archive::new(file);
+        let mut files = std::collections::HashMap::new();
+
+        let entries = archive.entries()
+            .map_err(|e| format!("Failed to read JEX archive: {}", e))?;
+        for entry in entries {
+            let mut entry = entry.map_err(|e| format!("Failed to read JEX entry: {}", e))?;
+            let name = entry.path()
+                .map(|p| p.to_string_lossy().replace('\\', "/"))
+                .map_err(|e| format!("Invalid JEX entry name: {}", e))?;
+            let mut content = Vec::new();
+            std::io::Read::read_to_end(&mut entry, &mut content)
+                .map_err(|e| format!("Failed to read JEX entry {}: {}", name, e))?;
+            files.insert(name, content);
+        }
+
+        return import_joplin_raw(files);
+    }
+
+    if !source.is_dir() {
+        return Err(format!("{} is neither a JEX file nor a folder", path));
+    }
+
+    // Raw exports are a flat folder of <id>.md items plus resources/
+    let mut files = std::collections::HashMap::new();
+    let mut looks_raw = false;
+    for entry in fs::read_dir(&source).map_err(|e| format!("Failed to read folder: {}", e))?.flatten() {
+        let entry_path = entry.path();
+        if entry_path.is_file() {
+            if let Ok(content) = fs::read(&entry_path) {
+                let text = String::from_utf8_lossy(&content);
+                looks_raw |= text.lines().any(|line| line.starts_with("type_: "));
+                files.insert(entry.file_name().to_string_lossy().to_string(), content);
+            }
+        }
+    }
+
+    if !looks_raw {
+        return import_markdown_tree(&source, JOPLIN_ROOT_CATEGORY, true);
+    }
+
+    let resources_dir = source.join("resources");
+    if let Ok(resources) = fs::read_dir(&resources_dir) {
+        for resource in resources.flatten() {
+            if let Ok(content) = fs::read(resource.path()) {
+                files.insert(format!("resources/{}", resource.file_name().to_string_lossy()), content);
+            }
+        }
+    }
+
+    import_joplin_raw(files)
+}
+
+/// Shared path for JEX and raw-directory exports, given every file keyed by relative name
+fn import_joplin_raw(files: std::collections::HashMap<String, Vec<u8>>) -> Result<ImportReport, String> {
+    let mut report = ImportReport::default();
+    let mut folders: std::collections::HashMap<String, (String, String)> = std::collections::HashMap::new();
+    let mut tags: std::collections::HashMap<String, String> = std::collections::HashMap::new();
+    let mut note_tags: Vec<(String, String)> = Vec::new();
+    let mut resources: std::collections::HashMap<String, JoplinItem> = std::collections::HashMap::new();
+    let mut joplin_notes: Vec<JoplinItem> = Vec::new();
+
+    for (name, content) in &files {
+        if name.contains('/') || !name.ends_with(".md") {
+            continue;
+        }
+        let text = String::from_utf8_lossy(content);
+        let Some(item) = parse_joplin_item(&text) else {
+            report.skipped.push(format!("{}: not a Joplin item", name));
+            continue;
+        };
+        let id = item.prop("id").to_string();
+
+        match item.prop("type_") {
+            JOPLIN_TYPE_NOTE => joplin_notes.push(item),
+            JOPLIN_TYPE_FOLDER => {
+                folders.insert(id, (item.title.clone(), item.prop("parent_id").to_string()));
+            }
+            JOPLIN_TYPE_TAG => {
+                tags.insert(id, item.title.clone());
+            }
+            JOPLIN_TYPE_NOTE_TAG => note_tags.push((item.prop("note_id").to_string(), item.prop("tag_id").to_string())),
+            JOPLIN_TYPE_RESOURCE => {
+                resources.insert(id, item);
+            }
+            _ => {}
+        }
+    }
+
+    let folder_path = |folder_id: &str| -> Vec<String> {
+        let mut path = Vec::new();
+        let mut current = folder_id.to_string();
+        // Bounded walk so a corrupt parent cycle can't hang the import
+        for _ in 0..32 {
+            let Some((title, parent)) = folders.get(&current) else { break };
+            path.insert(0, title.clone());
+            if parent.is_empty() {
+                break;
+            }
+            current = parent.clone();
+        }
+        if path.is_empty() { vec![JOPLIN_ROOT_CATEGORY.to_string()] } else { path }
+    };
+
+    let mut stored_resources: std::collections::HashMap<String, Attachment> = std::collections::HashMap::new();
+    let mut notes = Vec::new();
+
+    for item in joplin_notes {
+        let joplin_id = item.prop("id").to_string();
+        let mut attachments = Vec::new();
+
+        // Resources are referenced as `:/<resource id>`
+        let content = rewrite_markdown_targets(&item.body, &mut |target| {
+            let resource_id = target.strip_prefix(":/")?;
+            if let Some(existing) = stored_resources.get(resource_id) {
+                attachments.push(existing.clone());
+                return Some(format!("attachment:{}", existing.id));
+            }
+            let resource = resources.get(resource_id)?;
+            let extension = resource.prop("file_extension");
+            let stored_name = if extension.is_empty() {
+                format!("resources/{}", resource_id)
+            } else {
+                format!("resources/{}.{}", resource_id, extension)
+            };
+            let bytes = files.get(&stored_name)?;
+            let file_name = if resource.title.is_empty() { stored_name.trim_start_matches("resources/").to_string() } else { resource.title.clone() };
+            let attachment = store_attachment_bytes(&file_name, bytes).ok()?;
+            stored_resources.insert(resource_id.to_string(), attachment.clone());
+            attachments.push(attachment.clone());
+            Some(format!("attachment:{}", attachment.id))
+        });
+
+        let title = if item.title.is_empty() { generate_simple_title(&content) } else { item.title.clone() };
+        let mut note = Note::new(title, content, folder_path(item.prop("parent_id")));
+        if let Some(created) = parse_flexible_date(item.prop("created_time")) {
+            note.timestamp = created;
+        }
+        note.tags = note_tags.iter()
+            .filter(|(note_id, _)| *note_id == joplin_id)
+            .filter_map(|(_, tag_id)| tags.get(tag_id).cloned())
+            .collect();
+
+        report.attachments_imported += attachments.len();
+        note.attachments = attachments;
+        notes.push(note);
+    }
+
+    report.notes_imported = notes.len();
+    persist_imported_notes(notes)?;
+    Ok(report)
+}
+
+/// `target` resolved against `base_dir`, if that is a file inside `root`. Absolute and
+/// `../` targets leading out of the imported folder give `None`, so a crafted export
+/// can't pull arbitrary files the user can read into the vault.
+fn local_file_under(root: &Path, base_dir: &Path, target: &str) -> Option<PathBuf> {
+    let candidate = fs::canonicalize(base_dir.join(target)).ok()?;
+    let root = fs::canonicalize(root).ok()?;
+    (candidate.starts_with(&root) && candidate.is_file()).then_some(candidate)
+}
+
+/// Build a note from a Markdown file with optional front matter.
+/// Local files referenced by links/images (relative to the file) are stored as attachments.
+fn markdown_note_from_file(root: &Path, file: &Path, fallback_category: &str) -> Result<Note, String> {
+    let text = fs::read_to_string(file)
+        .map_err(|e| format!("Failed to read file: {}", e))?;
+    let (fields, body) = parse_frontmatter(&text);
+    let base_dir = file.parent().unwrap_or(root).to_path_buf();
+
+    let mut attachments: Vec<Attachment> = Vec::new();
+    let content = rewrite_markdown_targets(&body, &mut |target| {
+        if target.contains("://") || target.starts_with('#') || target.starts_with("mailto:") || target.is_empty() {
+            return None;
+        }
+        let decoded = target.replace("%20", " ");
+        let candidate = local_file_under(root, &base_dir, &decoded)?;
+        let is_markdown = candidate.extension().map(|e| e == "md" || e == "markdown").unwrap_or(false);
+        if is_markdown {
+            return None;
+        }
+        let attachment = store_attachment_file(&candidate).ok()?;
+        let reference = format!("attachment:{}", attachment.id);
+        attachments.push(attachment);
+        Some(reference)
+    });
+
+    let stem = file.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+    let title = fields.get("title")
+        .and_then(|t| t.as_str())
+        .filter(|t| !t.trim().is_empty())
+        .map(|t| t.trim().to_string())
+        .unwrap_or_else(|| if stem.is_empty() { generate_simple_title(&content) } else { stem });
+
+    let mut note = Note::new(title, content.trim().to_string(), category_path_for(root, file, fallback_category));
+    note.timestamp = ["created", "date", "created_at"].iter()
+        .find_map(|key| fields.get(*key).and_then(|v| v.as_str()).and_then(parse_flexible_date))
+        .unwrap_or_else(|| file_timestamp(file));
+    note.tags = frontmatter_list(&fields, "tags");
+    note.attachments = attachments;
+    // Anything we don't map to a first-class field is kept as custom metadata
+    note.metadata = fields.into_iter()
+        .filter(|(key, _)| !["title", "tags", "created", "date", "created_at"].contains(&key.as_str()))
+        .collect();
+    Ok(note)
+}
+
+/// Recursively import every Markdown file below `root`, folders becoming categories.
+/// Folders starting with `.` or `_` (e.g. `_resources`) hold assets, not notes.
+fn import_markdown_tree(root: &Path, fallback_category: &str, resolve_links: bool) -> Result<ImportReport, String> {
+    let mut report = ImportReport::default();
+    let mut imported: Vec<(PathBuf, Note)> = Vec::new();
+
+    for file in collect_markdown_files(root)? {
+        match markdown_note_from_file(root, &file, fallback_category) {
+            Ok(note) => {
+                report.attachments_imported += note.attachments.len();
+                imported.push((file, note));
+            }
+            Err(e) => report.skipped.push(format!("{}: {}", file.display(), e)),
+        }
+    }
+
+    let links = if resolve_links { resolve_imported_links(root, &imported) } else { Vec::new() };
+
+    report.notes_imported = imported.len();
+    report.links_created = links.len();
+    persist_imported_notes(imported.into_iter().map(|(_, note)| note).collect())?;
+
+    if !links.is_empty() {
+        let mut links_db = load_links()?;
+        links_db.links.extend(links);
+        save_links(&links_db)?;
+    }
+
+    Ok(report)
+}
+
+/// Lexically resolve `.` and `..` without touching the filesystem
+fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            std::path::Component::CurDir => {}
+            std::path::Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other.as_os_str()),
+        }
+    }
+    normalized
+}
+
+/// Turn relative Markdown links and wikilinks between imported files into `Reference` links
+fn resolve_imported_links(root: &Path, imported: &[(PathBuf, Note)]) -> Vec<NoteLink> {
+    let mut by_path: std::collections::HashMap<PathBuf, String> = std::collections::HashMap::new();
+    let mut by_name: std::collections::HashMap<String, String> = std::collections::HashMap::new();
+
+    for (file, note) in imported {
+        by_path.insert(normalize_path(file), note.id.clone());
+        if let Some(stem) = file.file_stem() {
+            by_name.entry(stem.to_string_lossy().to_lowercase()).or_insert_with(|| note.id.clone());
+        }
+        by_name.entry(note.title.to_lowercase()).or_insert_with(|| note.id.clone());
+        // Wikilinks may also spell out the root-relative path without extension
+        if let Ok(relative) = file.with_extension("").strip_prefix(root) {
+            by_name.entry(relative.to_string_lossy().replace('\\', "/").to_lowercase()).or_insert_with(|| note.id.clone());
+        }
+    }
+
+    let mut links: Vec<NoteLink> = Vec::new();
+    for (file, note) in imported {
+        let base_dir = file.parent().unwrap_or(root);
+        let mut targets: Vec<String> = Vec::new();
+
+        // Relative links such as [see](../other.md#section)
+        rewrite_markdown_targets(&note.content, &mut |target| {
+            let path_part = target.split('#').next().unwrap_or("").replace("%20", " ");
+            if path_part.ends_with(".md") || path_part.ends_with(".markdown") {
+                if let Some(id) = by_path.get(&normalize_path(&base_dir.join(&path_part))) {
+                    targets.push(id.clone());
+                }
+            }
+            None
+        });
+
+        for wikilink in extract_wikilinks(&note.content) {
+            let key = wikilink.trim_end_matches(".md").to_lowercase();
+            if let Some(id) = by_name.get(&key) {
+                targets.push(id.clone());
+            }
+        }
+
+        for target_id in targets {
+            let duplicate = links.iter().any(|l| l.source_id == note.id && l.target_id == target_id);
+            if target_id != note.id && !duplicate {
+                links.push(NoteLink {
+                    id: Uuid::new_v4().to_string(),
+                    source_id: note.id.clone(),
+                    target_id,
+                    link_type: LinkType::Reference,
+                    label: None,
+                    color: None,
+                    directional: Some(true),
+                    created_at: Utc::now(),
+                    target_block: None,
+                    weight: None,
+                    weight_is_manual: false,
+                });
+            }
+        }
+    }
+
+    links
+}
+
+fn collect_markdown_files(root: &Path) -> Result<Vec<PathBuf>, String> {
+    let mut files = Vec::new();
+    let mut pending = vec![root.to_path_buf()];
+
+    while let Some(dir) = pending.pop() {
+        let entries = fs::read_dir(&dir)
+            .map_err(|e| format!("Failed to read directory {}: {}", dir.display(), e))?;
+        for entry in entries.flatten() {
+            let entry_path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+            if entry_path.is_dir() {
+                if !name.starts_with('.') && !name.starts_with('_') {
+                    pending.push(entry_path);
+                }
+            } else if name.ends_with(".md") || name.ends_with(".markdown") {
+                files.push(entry_path);
+            }
+        }
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+// ---------------------------------------------------------------------------
+// Generic Markdown folder
+// ---------------------------------------------------------------------------
+
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct MarkdownImportOptions {
+    /// Category for files sitting directly in the chosen folder
+    pub root_category: String,
+    /// Create `NoteLink`s for relative links and wikilinks between imported files
+    pub resolve_links: bool,
+}
+
+impl Default for MarkdownImportOptions {
+    fn default() -> Self {
+        Self {
+            root_category: "Imported".to_string(),
+            resolve_links: true,
+        }
+    }
+}
+
+/// Import a folder of Markdown files (Obsidian, Logseq, Bear, plain notes...).
+/// Directories map to categories and front matter supplies titles, tags and dates.
+pub async fn import_markdown_folder(path: String, options: Option<MarkdownImportOptions>) -> Result<ImportReport, String> {
+    let root = PathBuf::from(&path);
+    if !root.is_dir() {
+        return Err(format!("{} is not a folder", path));
+    }
+
+    let options = options.unwrap_or_default();
+    let root_category = if options.root_category.trim().is_empty() {
+        MarkdownImportOptions::default().root_category
+    } else {
+        options.root_category.trim().to_string()
+    };
+
+    import_markdown_tree(&root, &root_category, options.resolve_links)
+}
+
+// ---------------------------------------------------------------------------
+// Browser bookmarks (Netscape bookmark file format)
+// ---------------------------------------------------------------------------
+
+const BOOKMARKS_ROOT_CATEGORY: &str = "Bookmarks";
+
+struct Bookmark {
+    title: String,
+    url: String,
+    added: Option<DateTime<Utc>>,
+    tags: Vec<String>,
+    description: String,
+    folders: Vec<String>,
+}
+
+fn parse_bookmarks_html(html: &str) -> Vec<Bookmark> {
+    let mut bookmarks: Vec<Bookmark> = Vec::new();
+    let mut folders: Vec<Option<String>> = Vec::new();
+    let mut pending_folder: Option<String> = None;
+    // Lowercased once for finding closing tags; ASCII lowercasing keeps byte offsets, so
+    // both strings are sliced at the same positions
+    let lower = html.to_ascii_lowercase();
+    let mut position = 0;
+
+    while let Some(tag_start) = html[position..].find('<').map(|i| position + i) {
+        let Some(tag_end) = html[tag_start..].find('>').map(|i| tag_start + i) else { break };
+        let raw_tag = &html[tag_start + 1..tag_end];
+        position = tag_end + 1;
+        let rest = &html[position..];
+
+        let closing = raw_tag.starts_with('/');
+        let body = raw_tag.trim_start_matches('/');
+        let name: String = body.chars().take_while(|c| c.is_alphanumeric()).collect::<String>().to_ascii_lowercase();
+
+        match (name.as_str(), closing) {
+            ("h3", false) => {
+                let end = lower[position..].find("</h3").unwrap_or(rest.len());
+                pending_folder = Some(decode_html_entities(rest[..end].trim()));
+            }
+            ("dl", false) => folders.push(pending_folder.take()),
+            ("dl", true) => {
+                folders.pop();
+            }
+            ("a", false) => {
+                let Some(url) = tag_attribute(body, "href") else { continue };
+                let end = lower[position..].find("</a").unwrap_or(rest.len());
+                let title = decode_html_entities(rest[..end].trim());
+                let added = tag_attribute(body, "add_date")
+                    .and_then(|d| d.parse::<i64>().ok())
+                    .and_then(|secs| Utc.timestamp_opt(secs, 0).single());
+                let tags = tag_attribute(body, "tags")
+                    .map(|t| t.split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect())
+                    .unwrap_or_default();
+
+                bookmarks.push(Bookmark {
+                    title,
+                    url,
+                    added,
+                    tags,
+                    description: String::new(),
+                    folders: folders.iter().flatten().cloned().collect(),
+                });
+            }
+            ("dd", false) => {
+                // Descriptions run until the next tag
+                let end = rest.find('<').unwrap_or(rest.len());
+                if let Some(last) = bookmarks.last_mut() {
+                    last.description = decode_html_entities(rest[..end].trim());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    bookmarks
+}
+
+/// Import a Chrome/Firefox/Safari bookmark export. Folders become categories under
+/// "Bookmarks"; with `fetch_content` each page is clipped in the background.
+pub async fn import_bookmarks_html(path: String, fetch_content: Option<bool>) -> Result<ImportReport, String> {
+    let html = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read bookmarks file: {}", e))?;
+
+    let mut report = ImportReport::default();
+    let mut notes = Vec::new();
+
+    for bookmark in parse_bookmarks_html(&html) {
+        if !bookmark.url.starts_with("http://") && !bookmark.url.starts_with("https://") {
+            // javascript: bookmarklets, place: queries and the like aren't worth keeping
+            report.skipped.push(format!("{}: unsupported URL", bookmark.url));
+            continue;
+        }
+
+        let title = if bookmark.title.is_empty() { bookmark.url.clone() } else { bookmark.title.clone() };
+        let mut content = format!("[{}]({})", title, bookmark.url);
+        if !bookmark.description.is_empty() {
+            content.push_str("\n\n");
+            content.push_str(&bookmark.description);
+        }
+
+        let mut category_path = vec![BOOKMARKS_ROOT_CATEGORY.to_string()];
+        category_path.extend(bookmark.folders);
+
+        let mut note = Note::new(title, content, category_path);
+        if let Some(added) = bookmark.added {
+            note.timestamp = added;
+        }
+        note.tags = bookmark.tags;
+        notes.push((note, bookmark.url));
+    }
+
+    let to_clip: Vec<(String, String)> = notes.iter().map(|(note, url)| (note.id.clone(), url.clone())).collect();
+    report.notes_imported = notes.len();
+    persist_imported_notes(notes.into_iter().map(|(note, _)| note).collect())?;
+
+    if fetch_content.unwrap_or(false) && !to_clip.is_empty() {
+        tokio::spawn(clip_pages_into_notes(to_clip));
+    }
+
+    Ok(report)
+}
+
+/// Fetch a web page and convert its main HTML into Markdown
+pub async fn fetch_page_as_markdown(url: &str) -> Result<String, String> {
+    let response = reqwest::Client::new()
+        .get(url)
+        .timeout(std::time::Duration::from_secs(20))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch {}: {}", url, e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("Fetching {} failed with status {}", url, response.status()));
+    }
+
+    let html = response.text().await
+        .map_err(|e| format!("Failed to read page body: {}", e))?;
+
+    // Prefer <article>/<main> when the page has them, otherwise use the whole body
+    let lower = html.to_ascii_lowercase();
+    let fragment = ["article", "main", "body"].iter()
+        .find_map(|tag| {
+            let start = lower.find(&format!("<{}", tag))?;
+            let end = lower.rfind(&format!("</{}>", tag))?;
+            (start < end).then(|| &html[start..end])
+        })
+        .unwrap_or(&html);
+
+    Ok(html_to_markdown(fragment, &mut |_, _| None))
+}
+
+/// Background job: append clipped page content to each (note id, url) pair
+async fn clip_pages_into_notes(targets: Vec<(String, String)>) {
+    for (note_id, url) in targets {
+        let clipped = match fetch_page_as_markdown(&url).await {
+            Ok(markdown) if !markdown.trim().is_empty() => markdown,
+            Ok(_) => continue,
+            Err(e) => {
+                error!("Failed to clip {}: {}", url, e);
+                continue;
+            }
+        };
+
+        let result = load_notes().and_then(|mut database| {
+            if let Some(note) = database.notes.iter_mut().find(|n| n.id == note_id) {
+                note.content = format!("{}\n\n---\n\n{}", note.content, clipped);
+                note.revision += 1;
+                save_notes(&database)?;
+            }
+            Ok(())
+        });
+        if let Err(e) = result {
+            error!("Failed to save clipped content for {}: {}", url, e);
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Attachment folders (PDFs, images, text files)
+// ---------------------------------------------------------------------------
+
+const TEXT_EXTENSIONS: &[&str] = &["txt", "md", "markdown", "csv", "json", "log", "rst", "org"];
+const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "webp", "bmp", "tif", "tiff"];
+
+/// Every importable file under `root`, sorted for a stable order
+fn collect_attachment_files(root: &Path) -> Result<Vec<PathBuf>, String> {
+    let mut files = Vec::new();
+    let mut pending = vec![root.to_path_buf()];
+
+    while let Some(dir) = pending.pop() {
+        let entries = fs::read_dir(&dir)
+            .map_err(|e| format!("Failed to read directory {}: {}", dir.display(), e))?;
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let hidden = path.file_name().is_some_and(|n| n.to_string_lossy().starts_with('.'));
+            if hidden {
+                continue;
+            }
+            if path.is_dir() {
+                pending.push(path);
+            } else {
+                files.push(path);
+            }
+        }
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+/// PDF text via pdf-extract. Malformed PDFs can make the parser panic, so that's contained.
+fn extract_pdf_text(bytes: &[u8]) -> Result<String, String> {
+    std::panic::catch_unwind(|| pdf_extract::extract_text_from_mem(bytes))
+        .map_err(|_| "PDF parser crashed on this file".to_string())?
+        .map_err(|e| format!("Failed to extract PDF text: {}", e))
+}
+
+/// Image text through a locally installed `tesseract`. `Ok(None)` when no OCR engine is available.
+pub fn ocr_image(path: &Path) -> Result<Option<String>, String> {
+    match std::process::Command::new("tesseract").arg(path).arg("stdout").output() {
+        Ok(output) if output.status.success() => Ok(Some(String::from_utf8_lossy(&output.stdout).trim().to_string())),
+        Ok(output) => Err(format!("OCR failed: {}", String::from_utf8_lossy(&output.stderr).trim())),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(format!("Failed to run OCR: {}", e)),
+    }
+}
+
+/// Turn one file into a note with the original attached. Returns the note and an optional
+/// detail for the job report.
+fn note_from_attachment_file(path: &Path, category_path: Vec<String>) -> Result<(Note, Option<String>), FileState> {
+    let extension = path.extension().map(|e| e.to_string_lossy().to_lowercase()).unwrap_or_default();
+    let is_pdf = extension == "pdf";
+    let is_image = IMAGE_EXTENSIONS.contains(&extension.as_str());
+    let is_text = TEXT_EXTENSIONS.contains(&extension.as_str());
+    if !is_pdf && !is_image && !is_text {
+        return Err(FileState::Skipped(format!("unsupported file type '.{}'", extension)));
+    }
+
+    let mut detail = None;
+    let text = if is_text {
+        fs::read_to_string(path).map_err(|e| FileState::Failed(format!("Failed to read file: {}", e)))?
+    } else if is_pdf {
+        let bytes = fs::read(path).map_err(|e| FileState::Failed(format!("Failed to read file: {}", e)))?;
+        extract_pdf_text(&bytes).unwrap_or_else(|e| {
+            detail = Some(e);
+            String::new()
+        })
+    } else {
+        match ocr_image(path) {
+            Ok(Some(text)) => text,
+            Ok(None) => {
+                detail = Some("no OCR engine installed - image attached without text".to_string());
+                String::new()
+            }
+            Err(e) => {
+                detail = Some(e);
+                String::new()
+            }
+        }
+    };
+
+    let title = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_else(|| "Imported file".to_string());
+    let text = text.trim().to_string();
+
+    let mut note = Note::new(title, String::new(), category_path);
+    note.timestamp = file_timestamp(path);
+    // Text files are the note; PDFs and images are kept as attachments with their text below
+    if !is_text {
+        let attachment = store_attachment_file(path).map_err(FileState::Failed)?;
+        note.content = attachment_markdown(&attachment);
+        note.attachments.push(attachment);
+        if !text.is_empty() {
+            note.content.push_str("\n\n");
+        }
+    }
+    note.content.push_str(&text);
+    Ok((note, detail))
+}
+
+/// Import a folder of PDFs, images and text files in the background - one note per file,
+/// with extracted text and the original attached. Progress is reported on the returned
+/// job (`get_import_job`, `import:progress` events). Subfolders become subcategories.
+pub async fn import_attachments_folder(path: String, category_path: Option<Vec<String>>) -> Result<ImportJob, String> {
+    let root = PathBuf::from(&path);
+    if !root.is_dir() {
+        return Err(format!("{} is not a folder", path));
+    }
+    let base_path = category_path.filter(|p| !p.is_empty()).unwrap_or_else(|| vec!["Imported".to_string()]);
+    let files = collect_attachment_files(&root)?;
+    let job = start_job("attachments_folder", &path, files.iter().map(|f| f.to_string_lossy().to_string()).collect());
+
+    let job_id = job.id.clone();
+    tokio::spawn(async move {
+        let mut imported = 0;
+        for (index, file) in files.iter().enumerate() {
+            let mut category = base_path.clone();
+            if let Some(relative) = file.parent().and_then(|p| p.strip_prefix(&root).ok()) {
+                category.extend(relative.components().map(|c| c.as_os_str().to_string_lossy().to_string()));
+            }
+
+            let file_path = file.clone();
+            let result = tokio::task::spawn_blocking(move || note_from_attachment_file(&file_path, category))
+                .await
+                .unwrap_or_else(|e| Err(FileState::Failed(format!("Import task failed: {}", e))));
+
+            match result {
+                Ok((note, detail)) => {
+                    let note_id = note.id.clone();
+                    match persist_imported_notes(vec![note]) {
+                        Ok(()) => {
+                            imported += 1;
+                            update_job_file(&job_id, index, FileState::Imported, Some(note_id), detail);
+                        }
+                        Err(e) => update_job_file(&job_id, index, FileState::Failed(e), None, None),
+                    }
+                }
+                Err(state) => update_job_file(&job_id, index, state, None, None),
+            }
+        }
+        let state = if imported == 0 && !files.is_empty() {
+            JobState::Failed(format!("None of the {} files could be imported", files.len()))
+        } else {
+            JobState::Completed
+        };
+        finish_job(&job_id, state);
+    });
+
+    Ok(job)
+}
@@ -0,0 +1,121 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::sync::{Mutex, OnceLock};
+use uuid::Uuid;
+use crate::services::tray_service::{set_tray_badge, TrayBadge};
+
+/// Finished jobs kept around for the UI to inspect
+const MAX_KEPT_JOBS: usize = 20;
+
+/// Long-running imports of the current session, newest last
+static JOBS: Mutex<Vec<ImportJob>> = Mutex::new(Vec::new());
+
+/// Called with the job after every change. main.rs installs one that emits `import:progress`.
+type JobListener = Box<dyn Fn(&ImportJob) + Send + Sync>;
+static JOB_LISTENER: OnceLock<JobListener> = OnceLock::new();
+
+#[derive(Serialize, Clone, PartialEq)]
+pub enum JobState {
+    Running,
+    Completed,
+    Failed(String),
+}
+
+#[derive(Serialize, Clone, PartialEq)]
+pub enum FileState {
+    Pending,
+    Imported,
+    Skipped(String),
+    Failed(String),
+}
+
+#[derive(Serialize, Clone)]
+pub struct JobFile {
+    pub path: String,
+    pub state: FileState,
+    pub note_id: Option<String>,
+    pub detail: Option<String>, // Extra info, e.g. "no OCR engine installed"
+}
+
+#[derive(Serialize, Clone)]
+pub struct ImportJob {
+    pub id: String,
+    pub kind: String,   // What started it, e.g. "attachments_folder"
+    pub source: String, // Folder or file being imported
+    pub state: JobState,
+    pub files: Vec<JobFile>,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+fn with_job(job_id: &str, change: impl FnOnce(&mut ImportJob)) {
+    let updated = {
+        let mut jobs = JOBS.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(job) = jobs.iter_mut().find(|j| j.id == job_id) else { return };
+        change(job);
+        job.clone()
+    };
+    if let Some(listener) = JOB_LISTENER.get() {
+        listener(&updated);
+    }
+}
+
+/// Register a job with every file it will process, all `Pending`
+pub fn start_job(kind: &str, source: &str, files: Vec<String>) -> ImportJob {
+    let job = ImportJob {
+        id: Uuid::new_v4().to_string(),
+        kind: kind.to_string(),
+        source: source.to_string(),
+        state: JobState::Running,
+        files: files.into_iter()
+            .map(|path| JobFile { path, state: FileState::Pending, note_id: None, detail: None })
+            .collect(),
+        started_at: Utc::now(),
+        finished_at: None,
+    };
+
+    let mut jobs = JOBS.lock().unwrap_or_else(|e| e.into_inner());
+    jobs.push(job.clone());
+    // Drop the oldest finished jobs once there are too many
+    while jobs.len() > MAX_KEPT_JOBS {
+        match jobs.iter().position(|j| j.state != JobState::Running) {
+            Some(index) => { jobs.remove(index); }
+            None => break,
+        }
+    }
+    job
+}
+
+pub fn update_job_file(job_id: &str, index: usize, state: FileState, note_id: Option<String>, detail: Option<String>) {
+    with_job(job_id, |job| {
+        if let Some(file) = job.files.get_mut(index) {
+            file.state = state;
+            file.note_id = note_id;
+            file.detail = detail;
+        }
+    });
+}
+
+pub fn finish_job(job_id: &str, state: JobState) {
+    with_job(job_id, |job| {
+        job.state = state;
+        job.finished_at = Some(Utc::now());
+    });
+    set_tray_badge(TrayBadge::JobFinished, true);
+}
+
+pub fn set_job_listener(listener: impl Fn(&ImportJob) + Send + Sync + 'static) {
+    let _ = JOB_LISTENER.set(Box::new(listener));
+}
+
+pub async fn get_import_job(job_id: String) -> Result<ImportJob, String> {
+    JOBS.lock().unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .find(|j| j.id == job_id)
+        .cloned()
+        .ok_or_else(|| format!("Import job {} not found", job_id))
+}
+
+pub async fn list_import_jobs() -> Result<Vec<ImportJob>, String> {
+    Ok(JOBS.lock().unwrap_or_else(|e| e.into_inner()).clone())
+}
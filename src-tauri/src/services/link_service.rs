@@ -1,9 +1,21 @@
 use chrono::Utc;
+use std::collections::{HashMap, HashSet};
+use tracing::warn;
 use uuid::Uuid;
-use crate::models::{NoteLink, LinkType, LinkColor};
-use crate::services::storage_service::{load_links, save_links};
+use crate::models::{AutoLinkSettings, Note, NoteLink, LinkType, LinkColor};
+use crate::services::audit_service::{link_deleted, record_deletions};
+use crate::services::markdown_service::extract_wikilinks;
+use crate::services::settings_service::current_settings;
+use crate::services::storage_service::{load_links, save_links, load_settings, save_settings};
 use crate::services::note_service::load_notes;
 
+/// Weight a link counts with until it has been computed or set
+pub const DEFAULT_LINK_WEIGHT: f32 = 0.5;
+/// Shorter words are too common to say anything about how related two notes are
+const MIN_TERM_LENGTH: usize = 4;
+/// Label on links made by auto-linking, so they can be told apart from the user's
+const AUTO_LINK_LABEL: &str = "Auto-linked";
+
 pub async fn create_note_link(source_id: String, target_id: String, link_type: String, label: Option<String>) -> Result<NoteLink, String> {
     create_note_link_with_options(source_id, target_id, link_type, label, None, None).await
 }
@@ -29,6 +41,7 @@ pub async fn create_note_link_with_options(source_id: String, target_id: String,
         "FollowUp" => LinkType::FollowUp,
         "Contradicts" => LinkType::Contradicts,
         "Supports" => LinkType::Supports,
+        "Embed" => LinkType::Embed,
         _ => LinkType::Custom(link_type.clone()),
     };
     
@@ -61,6 +74,9 @@ pub async fn create_note_link_with_options(source_id: String, target_id: String,
         color: parsed_color,
         directional,
         created_at: Utc::now(),
+        target_block: None,
+        weight: None,
+        weight_is_manual: false,
     };
     
     links_db.links.push(new_link.clone());
@@ -72,14 +88,13 @@ pub async fn create_note_link_with_options(source_id: String, target_id: String,
 pub async fn delete_note_link(link_id: String) -> Result<(), String> {
     let mut links_db = load_links()?;
     
-    let initial_len = links_db.links.len();
-    links_db.links.retain(|link| link.id != link_id);
-    
-    if links_db.links.len() == initial_len {
-        return Err(format!("Link with id {} not found", link_id));
-    }
+    let index = links_db.links.iter()
+        .position(|link| link.id == link_id)
+        .ok_or_else(|| format!("Link with id {} not found", link_id))?;
+    let removed = links_db.links.remove(index);
     
     save_links(&links_db)?;
+    record_deletions(vec![link_deleted(&removed)]);
     Ok(())
 }
 
@@ -95,4 +110,173 @@ pub async fn get_note_links(note_id: String) -> Result<Vec<NoteLink>, String> {
         .filter(|link| link.source_id == note_id || link.target_id == note_id)
         .collect();
     Ok(note_links)
-}
\ No newline at end of file
+}
+/// Set how strong a link is (0-1) by hand, or hand it back to `recompute_link_weights`
+/// with `None`
+pub async fn set_link_weight(link_id: String, weight: Option<f32>) -> Result<NoteLink, String> {
+    if weight.is_some_and(|w| !(0.0..=1.0).contains(&w)) {
+        return Err("Link weight must be between 0 and 1".to_string());
+    }
+
+    let mut links_db = load_links()?;
+    let link = links_db.links.iter_mut()
+        .find(|link| link.id == link_id)
+        .ok_or_else(|| format!("Link with id {} not found", link_id))?;
+    link.weight = weight;
+    link.weight_is_manual = weight.is_some();
+    let updated = link.clone();
+    save_links(&links_db)?;
+    Ok(updated)
+}
+
+fn note_terms(note: &Note) -> HashSet<String> {
+    text_terms(&format!("{} {}", note.title, note.content))
+}
+
+/// Distinct lowercased words long enough to say something about what `text` is about
+pub fn text_terms(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| word.chars().count() >= MIN_TERM_LENGTH)
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Share of `a` and `b` they have in common, 0 when both are empty
+pub fn overlap<T: Eq + std::hash::Hash>(a: &HashSet<T>, b: &HashSet<T>) -> f32 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(b).count() as f32 / union as f32
+}
+
+/// How strongly two notes belong together, from what they share: tags, category,
+/// vocabulary and whether either mentions the other with a [[wikilink]]
+fn derived_weight(a: &Note, b: &Note, terms: &HashMap<&str, HashSet<String>>) -> f32 {
+    let tags = |note: &Note| note.tags.iter().map(|t| t.to_lowercase()).collect::<HashSet<_>>();
+    let tag_score = overlap(&tags(a), &tags(b));
+
+    let depth = a.category_path.len().max(b.category_path.len());
+    let shared_depth = a.category_path.iter().zip(&b.category_path).take_while(|(x, y)| x == y).count();
+    let category_score = if depth == 0 { 0.0 } else { shared_depth as f32 / depth as f32 };
+
+    let term_score = match (terms.get(a.id.as_str()), terms.get(b.id.as_str())) {
+        (Some(a_terms), Some(b_terms)) => overlap(a_terms, b_terms),
+        _ => 0.0,
+    };
+
+    let mentions = |from: &Note, to: &Note| extract_wikilinks(&from.content).iter().any(|t| t.eq_ignore_ascii_case(&to.title));
+    let mention_score = if mentions(a, b) || mentions(b, a) { 1.0 } else { 0.0 };
+
+    (0.1 + 0.3 * tag_score + 0.2 * category_score + 0.3 * term_score + 0.1 * mention_score).min(1.0)
+}
+
+/// Re-derive the weight of every link that wasn't set by hand. Returns how many changed.
+pub async fn recompute_link_weights() -> Result<usize, String> {
+    let notes = load_notes()?.notes;
+    let by_id: HashMap<&str, &Note> = notes.iter().map(|n| (n.id.as_str(), n)).collect();
+    let terms: HashMap<&str, HashSet<String>> = notes.iter().map(|n| (n.id.as_str(), note_terms(n))).collect();
+
+    let mut links_db = load_links()?;
+    let mut changed = 0;
+    for link in links_db.links.iter_mut().filter(|l| !l.weight_is_manual) {
+        let (Some(source), Some(target)) = (by_id.get(link.source_id.as_str()), by_id.get(link.target_id.as_str())) else {
+            continue;
+        };
+        // Rounded so recomputing an unchanged vault doesn't rewrite every link
+        let weight = (derived_weight(source, target, &terms) * 100.0).round() / 100.0;
+        if link.weight != Some(weight) {
+            link.weight = Some(weight);
+            changed += 1;
+        }
+    }
+
+    if changed > 0 {
+        save_links(&links_db)?;
+    }
+    Ok(changed)
+}
+
+pub async fn set_auto_link_settings(settings: AutoLinkSettings) -> Result<AutoLinkSettings, String> {
+    if !(0.0..=1.0).contains(&settings.min_similarity) {
+        return Err("Similarity must be between 0 and 1".to_string());
+    }
+    let mut database = load_settings()?;
+    database.settings.auto_links = settings.clone();
+    save_settings(&database)?;
+    Ok(settings)
+}
+
+/// Link a saved note to the notes it clearly belongs with, if auto-linking is switched on.
+/// Failures are logged - a save must never fail because the graph couldn't be updated.
+pub fn auto_link_note(note: &Note) {
+    let settings = current_settings().auto_links;
+    if !settings.enabled || settings.max_links_per_note == 0 {
+        return;
+    }
+    if let Err(e) = try_auto_link_note(note, &settings) {
+        warn!("Failed to auto-link {}: {}", note.id, e);
+    }
+}
+
+fn try_auto_link_note(note: &Note, settings: &AutoLinkSettings) -> Result<(), String> {
+    let mut links_db = load_links()?;
+    let existing = links_db.links.iter()
+        .filter(|l| l.label.as_deref() == Some(AUTO_LINK_LABEL) && (l.source_id == note.id || l.target_id == note.id))
+        .count();
+    let room = settings.max_links_per_note.saturating_sub(existing);
+    if room == 0 {
+        return Ok(());
+    }
+    let linked: HashSet<&str> = links_db.links.iter()
+        .filter_map(|l| {
+            if l.source_id == note.id { Some(l.target_id.as_str()) }
+            else if l.target_id == note.id { Some(l.source_id.as_str()) }
+            else { None }
+        })
+        .collect();
+
+    let notes = load_notes()?.notes;
+    let mut tag_counts: HashMap<String, usize> = HashMap::new();
+    for tag in notes.iter().flat_map(|n| n.tags.iter().map(|t| t.to_lowercase()).collect::<HashSet<_>>()) {
+        *tag_counts.entry(tag).or_default() += 1;
+    }
+    let rare_tags: HashSet<String> = note.tags.iter()
+        .map(|t| t.to_lowercase())
+        .filter(|t| tag_counts.get(t).is_some_and(|count| *count <= settings.rare_tag_max_notes))
+        .collect();
+    let title_terms = text_terms(&note.title);
+    let content_terms = note_terms(note);
+
+    // A shared rare tag beats any amount of shared vocabulary
+    let mut candidates: Vec<(&Note, f32)> = notes.iter()
+        .filter(|other| other.id != note.id && !linked.contains(other.id.as_str()))
+        .filter_map(|other| {
+            let shares_rare_tag = other.tags.iter().any(|t| rare_tags.contains(&t.to_lowercase()));
+            let similarity = overlap(&title_terms, &text_terms(&other.title)).max(overlap(&content_terms, &note_terms(other)));
+            let score = if shares_rare_tag { 1.0 + similarity } else { similarity };
+            (shares_rare_tag || similarity >= settings.min_similarity).then_some((other, score))
+        })
+        .collect();
+    if candidates.is_empty() {
+        return Ok(());
+    }
+    candidates.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    for (other, _) in candidates.into_iter().take(room) {
+        links_db.links.push(NoteLink {
+            id: Uuid::new_v4().to_string(),
+            source_id: note.id.clone(),
+            target_id: other.id.clone(),
+            link_type: LinkType::Related,
+            label: Some(AUTO_LINK_LABEL.to_string()),
+            color: None,
+            directional: None,
+            created_at: Utc::now(),
+            target_block: None,
+            weight: None,
+            weight_is_manual: false,
+        });
+    }
+    save_links(&links_db)
+}
@@ -0,0 +1,104 @@
+use chrono::{DateTime, Local};
+use std::fmt::Display;
+use std::sync::Mutex;
+use crate::services::settings_service::current_settings;
+use crate::services::storage_service::{load_settings, save_settings};
+
+/// Languages backend text is available in, in the column order of `MESSAGES`
+pub const SUPPORTED_LOCALES: [&str; 4] = ["en", "de", "fr", "es"];
+
+/// User-facing text produced by the backend: key, then the text per `SUPPORTED_LOCALES`.
+/// `{}` placeholders are filled in order. `date.*` entries are chrono format strings.
+const MESSAGES: &[(&str, [&str; 4])] = &[
+    ("category.default", ["General", "Allgemein", "Général", "General"]),
+    ("error.note_not_found", ["Note with id {} not found", "Notiz mit der ID {} nicht gefunden", "Note avec l'identifiant {} introuvable", "No se encontró la nota con id {}"]),
+    ("error.category_not_found", ["Category not found", "Kategorie nicht gefunden", "Catégorie introuvable", "Categoría no encontrada"]),
+    ("error.question_empty", ["Question cannot be empty", "Die Frage darf nicht leer sein", "La question ne peut pas être vide", "La pregunta no puede estar vacía"]),
+    ("error.title_required", ["Please enter a title for this note", "Bitte gib dieser Notiz einen Titel", "Veuillez saisir un titre pour cette note", "Introduce un título para esta nota"]),
+    ("error.clipboard_unavailable", ["Clipboard unavailable: {}", "Zwischenablage nicht verfügbar: {}", "Presse-papiers indisponible : {}", "Portapapeles no disponible: {}"]),
+    ("error.unsupported_locale", ["Unsupported language '{}'. Available: {}", "Nicht unterstützte Sprache '{}'. Verfügbar: {}", "Langue non prise en charge « {} ». Disponibles : {}", "Idioma no compatible '{}'. Disponibles: {}"]),
+    ("title.screenshot", ["Screenshot: {} {}", "Bildschirmfoto: {} {}", "Capture d'écran : {} {}", "Captura de pantalla: {} {}"]),
+    ("title.focus_session", ["Focus session {}", "Fokussitzung {}", "Session de concentration {}", "Sesión de concentración {}"]),
+    ("title.focus_session_labeled", ["Focus: {} {}", "Fokus: {} {}", "Concentration : {} {}", "Concentración: {} {}"]),
+    ("focus.summary", ["Focused for {} of {} planned minutes ({} - {}).", "{} von {} geplanten Minuten fokussiert ({} - {}).", "Concentré pendant {} des {} minutes prévues ({} - {}).", "Concentración durante {} de {} minutos previstos ({} - {})."]),
+    ("focus.no_notes", ["No notes were created during the session.", "Während der Sitzung wurden keine Notizen erstellt.", "Aucune note n'a été créée pendant la session.", "No se crearon notas durante la sesión."]),
+    ("focus.notes_created", ["Notes created:", "Erstellte Notizen:", "Notes créées :", "Notas creadas:"]),
+    ("provenance.line", ["Source: {}, captured {}", "Quelle: {}, erfasst am {}", "Source : {}, capturé le {}", "Fuente: {}, capturado el {}"]),
+    ("calendar.all_day", ["{} (all day)", "{} (ganztägig)", "{} (toute la journée)", "{} (todo el día)"]),
+    ("date.day", ["%Y-%m-%d", "%d.%m.%Y", "%d/%m/%Y", "%d/%m/%Y"]),
+    ("date.short", ["%Y-%m-%d %H:%M", "%d.%m.%Y %H:%M", "%d/%m/%Y %H:%M", "%d/%m/%Y %H:%M"]),
+    ("date.long", ["%A, %-d %B %Y at %H:%M", "%A, %-d. %B %Y um %H:%M", "%A %-d %B %Y à %H:%M", "%A, %-d de %B de %Y a las %H:%M"]),
+];
+
+/// Resolved language, cached since errors and titles are produced all over the place
+static LOCALE: Mutex<Option<&'static str>> = Mutex::new(None);
+
+/// "de-DE", "de_DE.UTF-8" and "DE" all mean "de"
+fn normalize_locale(lang: &str) -> Option<&'static str> {
+    let code = lang.trim().split(['-', '_', '.']).next()?.to_lowercase();
+    SUPPORTED_LOCALES.iter().copied().find(|l| *l == code)
+}
+
+/// The language set with `set_locale`, else the system's (`LANG`) if supported, else English
+pub fn current_locale() -> &'static str {
+    let mut cached = LOCALE.lock().unwrap_or_else(|e| e.into_inner());
+    cached.get_or_insert_with(|| {
+        current_settings().locale
+            .or_else(|| std::env::var("LANG").ok())
+            .as_deref()
+            .and_then(normalize_locale)
+            .unwrap_or("en")
+    })
+}
+
+/// Resolve the language again on next use, e.g. after the settings were restored from an archive
+pub fn forget_cached_locale() {
+    *LOCALE.lock().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+fn message(key: &str) -> &'static str {
+    let column = SUPPORTED_LOCALES.iter().position(|l| *l == current_locale()).unwrap_or(0);
+    MESSAGES.iter()
+        .find(|(k, _)| *k == key)
+        .map(|(_, texts)| texts[column])
+        .unwrap_or("")
+}
+
+/// Text for `key` in the current language with `{}` filled from `args`
+pub fn tr(key: &str, args: &[&dyn Display]) -> String {
+    let mut parts = message(key).split("{}");
+    let mut text = parts.next().unwrap_or("").to_string();
+    for (index, part) in parts.enumerate() {
+        if let Some(arg) = args.get(index) {
+            text.push_str(&arg.to_string());
+        }
+        text.push_str(part);
+    }
+    if text.is_empty() { key.to_string() } else { text }
+}
+
+/// Format a date with one of the `date.*` patterns, with month and day names in the current language
+pub fn format_date(date: &DateTime<Local>, key: &str) -> String {
+    let locale = match current_locale() {
+        "de" => chrono::Locale::de_DE,
+        "fr" => chrono::Locale::fr_FR,
+        "es" => chrono::Locale::es_ES,
+        _ => chrono::Locale::en_US,
+    };
+    date.format_localized(message(key), locale).to_string()
+}
+
+pub async fn get_locale() -> Result<String, String> {
+    Ok(current_locale().to_string())
+}
+
+/// Switch the language of backend text (errors, default names, dates in saved notes)
+pub async fn set_locale(lang: String) -> Result<String, String> {
+    let locale = normalize_locale(&lang)
+        .ok_or_else(|| tr("error.unsupported_locale", &[&lang, &SUPPORTED_LOCALES.join(", ")]))?;
+    let mut database = load_settings()?;
+    database.settings.locale = Some(locale.to_string());
+    save_settings(&database)?;
+    *LOCALE.lock().unwrap_or_else(|e| e.into_inner()) = Some(locale);
+    Ok(locale.to_string())
+}
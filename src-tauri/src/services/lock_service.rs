@@ -0,0 +1,79 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use crate::services::note_service::load_notes;
+use crate::services::locale_service::tr;
+
+/// Sessions not refreshed for this long are treated as abandoned (e.g. a window that crashed)
+const EDIT_SESSION_TTL_MINUTES: i64 = 30;
+
+struct EditLock {
+    editor_id: String,
+    revision: u64, // Revision the editor's copy is based on
+    touched_at: DateTime<Utc>,
+}
+
+/// Advisory per-note edit sessions, kept in Tauri managed state. Nothing is blocked -
+/// editors are told who else has the note open, and stale saves are caught via revisions.
+#[derive(Default)]
+pub struct NoteEditLocks {
+    sessions: Mutex<HashMap<String, Vec<EditLock>>>,
+}
+
+#[derive(Serialize)]
+pub struct EditSession {
+    pub note_id: String,
+    pub editor_id: String,
+    pub revision: u64,
+    pub other_editors: Vec<String>, // Windows that already have this note open
+}
+
+impl NoteEditLocks {
+    pub fn begin(&self, note_id: &str, editor_id: &str) -> Result<EditSession, String> {
+        let revision = load_notes()?.notes.iter()
+            .find(|n| n.id == note_id)
+            .map(|n| n.revision)
+            .ok_or_else(|| tr("error.note_not_found", &[&note_id]))?;
+
+        let mut sessions = self.sessions.lock().map_err(|_| "Edit lock state is poisoned".to_string())?;
+        let cutoff = Utc::now() - Duration::minutes(EDIT_SESSION_TTL_MINUTES);
+        let locks = sessions.entry(note_id.to_string()).or_default();
+        locks.retain(|l| l.touched_at > cutoff && l.editor_id != editor_id);
+
+        let other_editors = locks.iter().map(|l| l.editor_id.clone()).collect();
+        locks.push(EditLock { editor_id: editor_id.to_string(), revision, touched_at: Utc::now() });
+
+        Ok(EditSession { note_id: note_id.to_string(), editor_id: editor_id.to_string(), revision, other_editors })
+    }
+
+    pub fn end(&self, note_id: &str, editor_id: &str) {
+        if let Ok(mut sessions) = self.sessions.lock() {
+            if let Some(locks) = sessions.get_mut(note_id) {
+                locks.retain(|l| l.editor_id != editor_id);
+                if locks.is_empty() {
+                    sessions.remove(note_id);
+                }
+            }
+        }
+    }
+
+    /// Revision the editor's copy is based on, if it has an open session
+    pub fn base_revision(&self, note_id: &str, editor_id: &str) -> Option<u64> {
+        let sessions = self.sessions.lock().ok()?;
+        sessions.get(note_id)?
+            .iter()
+            .find(|l| l.editor_id == editor_id)
+            .map(|l| l.revision)
+    }
+
+    /// After a successful save the editor's copy is current again
+    pub fn advance(&self, note_id: &str, editor_id: &str, revision: u64) {
+        if let Ok(mut sessions) = self.sessions.lock() {
+            if let Some(lock) = sessions.get_mut(note_id).and_then(|locks| locks.iter_mut().find(|l| l.editor_id == editor_id)) {
+                lock.revision = revision;
+                lock.touched_at = Utc::now();
+            }
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use std::fs;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use tracing::info;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::layer::{Layer, SubscriberExt};
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, reload, Registry};
+use crate::services::metrics_service::timing_layer;
+use crate::services::settings_service::current_settings;
+use crate::services::storage_service::{get_app_data_dir, load_settings, save_settings};
+
+const LOG_FILE_PREFIX: &str = "nowledgebase";
+
+/// Daily log files kept before the oldest is deleted
+const MAX_LOG_FILES: usize = 7;
+
+const DEFAULT_RECENT_LINES: usize = 200;
+
+static LEVEL_HANDLE: OnceLock<reload::Handle<LevelFilter, Registry>> = OnceLock::new();
+
+/// Keeps the background writer alive - dropping it would lose buffered lines
+static WRITER_GUARD: OnceLock<WorkerGuard> = OnceLock::new();
+
+pub fn get_logs_dir() -> Result<PathBuf, String> {
+    let mut path = get_app_data_dir()?;
+    path.push("logs");
+    if !path.exists() {
+        fs::create_dir_all(&path)
+            .map_err(|e| format!("Failed to create logs directory: {}", e))?;
+    }
+    Ok(path)
+}
+
+fn parse_level(level: &str) -> Result<LevelFilter, String> {
+    match level.trim().to_ascii_lowercase().as_str() {
+        "error" => Ok(LevelFilter::ERROR),
+        "warn" | "warning" => Ok(LevelFilter::WARN),
+        "info" => Ok(LevelFilter::INFO),
+        "debug" => Ok(LevelFilter::DEBUG),
+        "trace" => Ok(LevelFilter::TRACE),
+        "off" => Ok(LevelFilter::OFF),
+        _ => Err(format!("Unknown log level '{}'. Expected error, warn, info, debug, trace or off", level)),
+    }
+}
+
+/// Install the global logger: daily-rotated files in `logs/` plus stderr for development.
+/// Call once at startup, before anything logs.
+pub fn init_logging() -> Result<(), String> {
+    let level = current_settings().log_level
+        .and_then(|l| parse_level(&l).ok())
+        .unwrap_or(LevelFilter::INFO);
+
+    let appender = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(LOG_FILE_PREFIX)
+        .filename_suffix("log")
+        .max_log_files(MAX_LOG_FILES)
+        .build(get_logs_dir()?)
+        .map_err(|e| format!("Failed to open log file: {}", e))?;
+    let (writer, guard) = tracing_appender::non_blocking(appender);
+
+    // The level only filters what's written; the timing layer sees every timed span
+    let (level_filter, handle) = reload::Layer::new(level);
+    tracing_subscriber::registry()
+        .with(fmt::layer().with_writer(writer).with_ansi(false)
+            .and_then(fmt::layer().with_writer(std::io::stderr))
+            .with_filter(level_filter))
+        .with(timing_layer())
+        .try_init()
+        .map_err(|e| format!("Failed to initialise logging: {}", e))?;
+
+    let _ = LEVEL_HANDLE.set(handle);
+    let _ = WRITER_GUARD.set(guard);
+    info!("Logging started at level {}", level);
+    Ok(())
+}
+
+/// Change the log level at runtime and remember it for the next launch
+pub async fn set_log_level(level: String) -> Result<String, String> {
+    let filter = parse_level(&level)?;
+    if let Some(handle) = LEVEL_HANDLE.get() {
+        handle.reload(filter)
+            .map_err(|e| format!("Failed to change log level: {}", e))?;
+    }
+
+    let mut database = load_settings()?;
+    database.settings.log_level = Some(filter.to_string().to_lowercase());
+    save_settings(&database)?;
+
+    info!("Log level set to {}", filter);
+    Ok(filter.to_string().to_lowercase())
+}
+
+/// The last `lines` log lines (default 200), oldest first, reading back across rotated files
+pub async fn get_recent_logs(lines: Option<usize>) -> Result<Vec<String>, String> {
+    let wanted = lines.unwrap_or(DEFAULT_RECENT_LINES);
+    let mut files: Vec<PathBuf> = fs::read_dir(get_logs_dir()?)
+        .map_err(|e| format!("Failed to read logs directory: {}", e))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.file_name()
+            .map(|n| n.to_string_lossy().starts_with(LOG_FILE_PREFIX))
+            .unwrap_or(false))
+        .collect();
+    // Rotated names carry the date, so name order is chronological
+    files.sort();
+
+    let mut collected: Vec<String> = Vec::new();
+    for file in files.iter().rev() {
+        if collected.len() >= wanted {
+            break;
+        }
+        let content = fs::read_to_string(file)
+            .map_err(|e| format!("Failed to read log file: {}", e))?;
+        let mut chunk: Vec<String> = content.lines().map(|l| l.to_string()).collect();
+        chunk.append(&mut collected);
+        collected = chunk;
+    }
+
+    let skip = collected.len().saturating_sub(wanted);
+    Ok(collected.split_off(skip))
+}
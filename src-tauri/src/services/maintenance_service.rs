@@ -0,0 +1,175 @@
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+use tracing::{error, info};
+use crate::services::audit_service::{link_deleted, record_deletions};
+use crate::services::category_service::update_category_note_counts;
+use crate::services::note_service::load_notes;
+use crate::services::settings_service::current_settings;
+use crate::services::sync_service::prune_sync_docs;
+use crate::services::storage_service::{
+    compact_note_segments, get_app_data_dir, get_attachments_dir, load_drafts, load_links,
+    load_mounted_vaults, load_pinned_pastes, load_settings, save_drafts, save_links,
+    save_mounted_vaults, save_pinned_pastes, save_settings,
+};
+
+/// Unreferenced attachments younger than this are left alone: imports store their files
+/// before the notes that use them are saved
+const ORPHAN_GRACE_HOURS: u64 = 24;
+const CHECK_INTERVAL_SECS: u64 = 60 * 60;
+const RUN_EVERY_DAYS: i64 = 7;
+
+#[derive(Serialize)]
+pub struct MaintenanceReport {
+    pub ran_at: DateTime<Utc>,
+    pub bytes_before: u64,
+    pub bytes_after: u64,
+    pub bytes_reclaimed: u64,
+    pub segments_removed: usize,
+    pub attachments_removed: usize,
+    pub sync_docs_removed: usize,
+    pub links_removed: usize, // Note links and cross-vault links to or from deleted notes
+    pub drafts_removed: usize,
+    pub pins_removed: usize,
+}
+
+/// Total size of the files under `path`
+fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(path) else { return 0 };
+    entries.filter_map(|entry| entry.ok())
+        .map(|entry| match entry.metadata() {
+            Ok(meta) if meta.is_dir() => dir_size(&entry.path()),
+            Ok(meta) => meta.len(),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+/// Delete attachment folders no note points at. Each attachment is stored as
+/// `attachments/<id>/<file>`, so a folder holding no referenced file goes as a whole.
+fn remove_orphaned_attachments(referenced: &HashSet<String>) -> Result<usize, String> {
+    let root = get_app_data_dir()?;
+    let dir = get_attachments_dir()?;
+    let grace = Duration::from_secs(ORPHAN_GRACE_HOURS * 60 * 60);
+    let mut removed = 0;
+    for entry in fs::read_dir(&dir).map_err(|e| format!("Failed to read attachments directory: {}", e))? {
+        let Ok(entry) = entry else { continue };
+        let path = entry.path();
+        let recent = entry.metadata().ok()
+            .and_then(|meta| meta.modified().ok())
+            .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+            .is_none_or(|age| age < grace);
+        if recent {
+            continue;
+        }
+        let in_use = fs::read_dir(&path).into_iter().flatten()
+            .filter_map(|file| file.ok())
+            .filter_map(|file| file.path().strip_prefix(&root).ok().map(|p| p.to_string_lossy().replace('\\', "/")))
+            .any(|relative| referenced.contains(&relative));
+        if in_use {
+            continue;
+        }
+        let result = if path.is_dir() { fs::remove_dir_all(&path) } else { fs::remove_file(&path) };
+        if result.is_ok() {
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
+/// Compact the stores and clear out what deleted notes left behind: part-empty note
+/// segments, unreferenced attachments, sync documents, links, drafts and pins. Category
+/// counts are rebuilt from scratch afterwards. The deletion records are left alone - graph
+/// snapshots rebuild the past from them.
+pub async fn run_maintenance() -> Result<MaintenanceReport, String> {
+    let data_dir = get_app_data_dir()?;
+    let bytes_before = dir_size(&data_dir);
+
+    let notes = load_notes()?;
+    let note_ids: HashSet<&str> = notes.notes.iter().map(|n| n.id.as_str()).collect();
+    let referenced: HashSet<String> = notes.notes.iter()
+        .flat_map(|n| n.attachments.iter().map(|a| a.stored_path.replace('\\', "/")))
+        .collect();
+
+    let segments_removed = compact_note_segments(&notes)?;
+    let attachments_removed = remove_orphaned_attachments(&referenced)?;
+    let sync_docs_removed = prune_sync_docs(&note_ids)?;
+
+    let mut links = load_links()?;
+    let (kept, removed): (Vec<_>, Vec<_>) = links.links.into_iter()
+        .partition(|l| note_ids.contains(l.source_id.as_str()) && note_ids.contains(l.target_id.as_str()));
+    links.links = kept;
+    let mut links_removed = removed.len();
+    if links_removed > 0 {
+        save_links(&links)?;
+        // Recorded like any other link deletion, so a snapshot still shows the edge
+        record_deletions(removed.iter().map(link_deleted).collect());
+    }
+    let mut vaults = load_mounted_vaults()?;
+    let before = vaults.links.len();
+    vaults.links.retain(|l| note_ids.contains(l.source_id.as_str()));
+    if vaults.links.len() != before {
+        links_removed += before - vaults.links.len();
+        save_mounted_vaults(&vaults)?;
+    }
+
+    let mut drafts = load_drafts()?;
+    let before = drafts.drafts.len();
+    drafts.drafts.retain(|d| d.note_id.as_deref().is_none_or(|id| note_ids.contains(id)));
+    let drafts_removed = before - drafts.drafts.len();
+    if drafts_removed > 0 {
+        save_drafts(&drafts)?;
+    }
+
+    let mut pins = load_pinned_pastes()?;
+    let before = pins.pins.len();
+    pins.pins.retain(|p| note_ids.contains(p.note_id.as_str()));
+    let pins_removed = before - pins.pins.len();
+    if pins_removed > 0 {
+        save_pinned_pastes(&pins)?;
+    }
+
+    update_category_note_counts()?;
+
+    let bytes_after = dir_size(&data_dir);
+    Ok(MaintenanceReport {
+        ran_at: Utc::now(),
+        bytes_before,
+        bytes_after,
+        bytes_reclaimed: bytes_before.saturating_sub(bytes_after),
+        segments_removed,
+        attachments_removed,
+        sync_docs_removed,
+        links_removed,
+        drafts_removed,
+        pins_removed,
+    })
+}
+
+/// Background job started at launch: runs maintenance once a week, first checking an
+/// interval after launch
+pub async fn run_maintenance_loop() {
+    loop {
+        tokio::time::sleep(Duration::from_secs(CHECK_INTERVAL_SECS)).await;
+        let due = current_settings().last_maintenance_run
+            .is_none_or(|at| Utc::now() - at >= ChronoDuration::days(RUN_EVERY_DAYS));
+        if due {
+            match run_maintenance().await {
+                Ok(report) => {
+                    info!("Maintenance reclaimed {} bytes", report.bytes_reclaimed);
+                    let result = load_settings().and_then(|mut database| {
+                        database.settings.last_maintenance_run = Some(report.ran_at);
+                        save_settings(&database)
+                    });
+                    if let Err(e) = result {
+                        error!("Failed to record maintenance run: {}", e);
+                    }
+                }
+                Err(e) => error!("Maintenance run failed: {}", e),
+            }
+        }
+    }
+}
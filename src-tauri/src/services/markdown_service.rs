@@ -0,0 +1,98 @@
+/// Escape text for safe inclusion in HTML element content or attribute values
+pub fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Rewrite the target of every Markdown link/image (`[text](target)`).
+/// `rewrite` gets the bare target and returns a replacement, or `None` to leave it alone.
+pub fn rewrite_markdown_targets(content: &str, rewrite: &mut dyn FnMut(&str) -> Option<String>) -> String {
+    let mut result = String::with_capacity(content.len());
+    let mut rest = content;
+
+    while let Some(start) = rest.find("](") {
+        let target_start = start + 2;
+        result.push_str(&rest[..target_start]);
+        let after = &rest[target_start..];
+
+        let Some(end) = after.find(')') else {
+            rest = after;
+            break;
+        };
+
+        let inside = &after[..end];
+        // Keep optional link titles: [text](target "title")
+        let (target, suffix) = match inside.find(' ') {
+            Some(space) => (&inside[..space], &inside[space..]),
+            None => (inside, ""),
+        };
+        let target = target.trim_start_matches('<').trim_end_matches('>');
+
+        match rewrite(target) {
+            Some(replacement) => {
+                result.push_str(&replacement);
+                result.push_str(suffix);
+            }
+            None => result.push_str(inside),
+        }
+        result.push(')');
+        rest = &after[end + 1..];
+    }
+
+    result.push_str(rest);
+    result
+}
+
+/// Targets of every `[[wikilink]]` / `![[embed]]` in the content, without aliases or heading anchors
+pub fn extract_wikilinks(content: &str) -> Vec<String> {
+    let mut targets = Vec::new();
+    let mut rest = content;
+
+    while let Some(start) = rest.find("[[") {
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("]]") else { break };
+        let inner = &after[..end];
+        let target = inner.split('|').next().unwrap_or("")
+            .split('#').next().unwrap_or("")
+            .split('^').next().unwrap_or("")
+            .trim();
+        if !target.is_empty() && !inner.contains('\n') {
+            targets.push(target.to_string());
+        }
+        rest = &after[end + 2..];
+    }
+
+    targets
+}
+
+/// `![[target]]` embeds as (start, end, target) - byte range of the whole marker plus the
+/// target with any `|alias` removed
+pub fn find_embeds(content: &str) -> Vec<(usize, usize, String)> {
+    let mut embeds = Vec::new();
+    let mut offset = 0;
+
+    while let Some(start) = content[offset..].find("![[") {
+        let start = offset + start;
+        let inner_start = start + 3;
+        let Some(length) = content[inner_start..].find("]]") else { break };
+        let inner = &content[inner_start..inner_start + length];
+        let end = inner_start + length + 2;
+        let target = inner.split('|').next().unwrap_or("").trim();
+        if !target.is_empty() && !inner.contains('\n') {
+            embeds.push((start, end, target.to_string()));
+        }
+        offset = end;
+    }
+
+    embeds
+}
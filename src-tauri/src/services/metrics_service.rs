@@ -0,0 +1,128 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::span::{Attributes, Id};
+use tracing::Subscriber;
+use tracing_subscriber::filter::filter_fn;
+use tracing_subscriber::layer::{Context, Layer};
+use tracing_subscriber::registry::LookupSpan;
+
+/// Spans with this target are timed. Every command in main.rs carries
+/// `#[instrument(target = "timing", skip_all)]`, as do the storage and AI hot paths.
+pub const TIMING_TARGET: &str = "timing";
+
+/// Durations kept per operation - percentiles are over the latest calls only
+const SAMPLES_KEPT: usize = 500;
+const SLOWEST_KEPT: usize = 20;
+
+#[derive(Serialize, Clone)]
+pub struct SlowOperation {
+    pub name: String,
+    pub duration_ms: f64,
+    pub at: DateTime<Utc>,
+}
+
+#[derive(Serialize)]
+pub struct OperationLatency {
+    pub name: String,
+    pub calls: u64, // Since launch
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub max_ms: f64,
+}
+
+#[derive(Serialize)]
+pub struct PerformanceMetrics {
+    pub operations: Vec<OperationLatency>, // Slowest p95 first
+    pub slowest: Vec<SlowOperation>,       // Slowest single calls since launch
+}
+
+#[derive(Default)]
+struct Timings {
+    samples: HashMap<&'static str, (u64, VecDeque<Duration>)>, // Calls so far, latest durations
+    slowest: Vec<SlowOperation>,
+}
+
+static TIMINGS: Mutex<Option<Timings>> = Mutex::new(None);
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+fn record(name: &'static str, duration: Duration) {
+    let mut guard = TIMINGS.lock().unwrap_or_else(|e| e.into_inner());
+    let timings = guard.get_or_insert_with(Timings::default);
+
+    let (calls, latest) = timings.samples.entry(name).or_default();
+    *calls += 1;
+    if latest.len() == SAMPLES_KEPT {
+        latest.pop_front();
+    }
+    latest.push_back(duration);
+
+    let duration_ms = millis(duration);
+    if timings.slowest.len() < SLOWEST_KEPT || timings.slowest.last().is_some_and(|s| duration_ms > s.duration_ms) {
+        timings.slowest.push(SlowOperation { name: name.to_string(), duration_ms, at: Utc::now() });
+        timings.slowest.sort_by(|a, b| b.duration_ms.total_cmp(&a.duration_ms));
+        timings.slowest.truncate(SLOWEST_KEPT);
+    }
+}
+
+/// When a timed span was created - it closes once the command's future has finished
+struct SpanStarted(Instant);
+
+struct TimingLayer;
+
+impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for TimingLayer {
+    fn on_new_span(&self, _attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(SpanStarted(Instant::now()));
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else { return };
+        let started = span.extensions().get::<SpanStarted>().map(|s| s.0);
+        if let Some(started) = started {
+            record(span.name(), started.elapsed());
+        }
+    }
+}
+
+/// Layer installed by `init_logging`. It has its own filter, so timings are collected
+/// whatever the log level is.
+pub fn timing_layer<S: Subscriber + for<'a> LookupSpan<'a>>() -> impl Layer<S> {
+    TimingLayer.with_filter(filter_fn(|metadata| metadata.target() == TIMING_TARGET))
+}
+
+fn percentile(sorted: &[Duration], p: f64) -> Duration {
+    sorted[((sorted.len() - 1) as f64 * p).round() as usize]
+}
+
+/// Latency of every command (and storage/AI operation) called since launch
+pub async fn get_performance_metrics() -> Result<PerformanceMetrics, String> {
+    let guard = TIMINGS.lock().unwrap_or_else(|e| e.into_inner());
+    let Some(timings) = guard.as_ref() else {
+        return Ok(PerformanceMetrics { operations: Vec::new(), slowest: Vec::new() });
+    };
+
+    let mut operations: Vec<OperationLatency> = timings.samples.iter()
+        .filter(|(_, (_, latest))| !latest.is_empty())
+        .map(|(name, (calls, latest))| {
+            let mut sorted: Vec<Duration> = latest.iter().copied().collect();
+            sorted.sort();
+            OperationLatency {
+                name: name.to_string(),
+                calls: *calls,
+                p50_ms: millis(percentile(&sorted, 0.5)),
+                p95_ms: millis(percentile(&sorted, 0.95)),
+                max_ms: millis(sorted[sorted.len() - 1]),
+            }
+        })
+        .collect();
+    operations.sort_by(|a, b| b.p95_ms.total_cmp(&a.p95_ms));
+
+    Ok(PerformanceMetrics { operations, slowest: timings.slowest.clone() })
+}
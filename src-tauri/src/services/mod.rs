@@ -4,22 +4,138 @@ pub mod storage_service;
 pub mod note_service;
 pub mod category_service;
 pub mod link_service;
+pub mod archive_service;
+pub mod export_service;
+pub mod attachment_service;
+pub mod import_service;
+pub mod board_service;
+pub mod calendar_service;
+pub mod stats_service;
+pub mod markdown_service;
+pub mod settings_service;
+pub mod email_service;
+pub mod feed_service;
+pub mod webhook_service;
+pub mod plugin_service;
+pub mod ai_action_service;
+pub mod ai_log_service;
+pub mod ai_queue_service;
+pub mod diagnostics_service;
+pub mod logging_service;
+pub mod draft_service;
+pub mod lock_service;
+pub mod qa_service;
+pub mod ai_note_service;
+pub mod embed_service;
+pub mod block_service;
+pub mod outline_service;
+pub mod render_service;
+pub mod snippet_service;
+pub mod code_search_service;
+pub mod citation_service;
+pub mod reference_lookup_service;
+pub mod highlight_service;
+pub mod job_service;
+pub mod reminder_service;
+pub mod paste_service;
+pub mod text_expansion_service;
+pub mod quick_lookup_service;
+pub mod screenshot_service;
+pub mod window_service;
+pub mod focus_service;
+pub mod locale_service;
+pub mod capture_rule_service;
+pub mod graph_service;
+pub mod timeline_service;
+pub mod reading_service;
+pub mod dedup_service;
+pub mod tray_service;
+pub mod quick_ask_service;
+pub mod answer_service;
+pub mod contradiction_service;
+pub mod audit_service;
+pub mod rename_service;
+pub mod app_lock_service;
+pub mod profile_service;
+pub mod sync_service;
+pub mod vault_service;
+pub mod visibility_service;
+pub mod ai_privacy_service;
+pub mod retention_service;
+pub mod maintenance_service;
+pub mod metrics_service;
+pub mod quick_switch_service;
 
 // Re-export commonly used functions for easy importing
 pub use storage_service::{save_notes, save_categories};
-pub use note_service::{save_note_simplified, update_note, update_note_with_title, delete_note, get_notes, get_notes_by_category, save_note_position, get_all_note_positions};
-pub use category_service::{load_categories, create_category_safe, get_category_by_id, get_category_hierarchy, validate_category_path, safe_delete_category, rebuild_hierarchy, find_category_by_name_fuzzy};
-pub use link_service::{create_note_link, create_note_link_with_options, delete_note_link, get_all_note_links, get_note_links};
+pub use note_service::{save_note_simplified, create_note_with_source, update_note, update_note_with_title, merge_notes, delete_note, get_notes, get_notes_by_category, save_note_position, save_note_positions, bring_note_to_front, send_note_to_back, set_note_size, set_note_collapsed, get_all_note_positions, set_note_metadata, get_note_metadata, query_notes, set_note_status, set_note_rating, set_note_due_date, add_note_tag, remove_note_tag, set_title_strategy, clear_category_title_strategy, regenerate_note_title, append_to_note, prepend_to_note};
+pub use category_service::{load_categories, create_category_safe, get_category_by_id, get_category_hierarchy, validate_category_path, safe_delete_category, rebuild_hierarchy, find_category_by_name_fuzzy, export_category_tree, import_category_tree, list_taxonomy_templates, apply_taxonomy_template, recount_categories, find_empty_categories, set_empty_category_cleanup};
+pub use link_service::{create_note_link, create_note_link_with_options, delete_note_link, get_all_note_links, get_note_links, set_link_weight, recompute_link_weights, set_auto_link_settings};
+pub use archive_service::{export_vault_archive, import_vault_archive};
+pub use export_service::{export_notes_csv, export_notes_jsonl, export_notes_folder, export_stats_csv, share_note_as_html, copy_note_to_clipboard};
+pub use import_service::{import_apple_notes, import_joplin, import_markdown_folder, import_bookmarks_html, import_attachments_folder};
+pub use board_service::{create_board, list_boards, delete_board, get_board, move_note_to_column};
+pub use calendar_service::{get_notes_calendar, import_calendar_events};
+pub use stats_service::{get_activity_heatmap, get_writing_streak, get_vault_summary};
+pub use settings_service::{get_settings, update_settings};
+pub use email_service::{check_email_now};
+pub use feed_service::{subscribe_feed, list_feeds, unsubscribe_feed, refresh_feeds};
+pub use webhook_service::{register_webhook, list_webhooks, delete_webhook, set_webhook_enabled, test_webhook};
+pub use plugin_service::{list_plugins, run_plugin_command};
+pub use ai_action_service::{list_ai_actions, save_ai_action, delete_ai_action, run_ai_action};
+pub use ai_log_service::{get_ai_log, clear_ai_log};
+pub use ai_queue_service::{get_offline_status, set_offline_mode};
+pub use diagnostics_service::{run_diagnostics};
+pub use logging_service::{set_log_level, get_recent_logs};
+pub use draft_service::{save_draft, get_drafts, discard_draft};
+pub use qa_service::{save_qa_note, append_followup};
+pub use ai_note_service::{ask_ai_about_note, atomize_note, summarize_note, translate_note};
+pub use embed_service::{resolve_embeds};
+pub use block_service::{get_note_blocks, get_block, assign_block_id, create_block_link};
+pub use outline_service::{get_note_outline};
+pub use render_service::{render_markdown};
+pub use snippet_service::{save_snippet, get_snippets, copy_snippet_to_clipboard};
+pub use code_search_service::{search_code_blocks};
+pub use citation_service::{import_bibtex, set_note_citation, format_citation};
+pub use reference_lookup_service::{lookup_reference};
+pub use highlight_service::{get_highlights, add_highlight, update_highlight, delete_highlight, import_kindle_clippings, import_readwise_csv};
+pub use job_service::{get_import_job, list_import_jobs};
+pub use reminder_service::{parse_natural_date, set_note_reminder, set_note_recurrence, complete_reminder, get_upcoming_reminders};
+pub use paste_service::{pin_note_for_paste, unpin_note_for_paste, get_pinned_pastes, paste_pinned_note};
+pub use text_expansion_service::{list_expansions, set_snippet_trigger, set_text_expansion_enabled};
+pub use quick_lookup_service::{quick_lookup, get_quick_lookup};
+pub use screenshot_service::{capture_screenshot_to_note};
+pub use window_service::{get_window_appearance, set_always_on_top, set_window_opacity, set_window_vibrancy};
+pub use focus_service::{start_focus_session, get_focus_session, stop_focus_session};
+pub use locale_service::{get_locale, set_locale};
+pub use capture_rule_service::{list_capture_rules, save_capture_rule, delete_capture_rule, set_default_category};
+pub use graph_service::{get_graph_snapshot, get_graph_subset, get_note_neighborhood, find_path_between_notes, get_related_notes, get_category_link_matrix};
+pub use timeline_service::{set_timeline_links, get_note_timeline};
+pub use reading_service::{enqueue_for_reading, remove_from_reading_queue, get_reading_queue, set_reading_progress, mark_read};
+pub use tray_service::{get_tray_icon_state, set_tray_icon_style};
+pub use quick_ask_service::{quick_ask, get_quick_ask};
+pub use answer_service::{ask_vault, get_answer_history, answer_to_note};
+pub use contradiction_service::{detect_contradictions, list_contradiction_proposals, accept_contradiction, dismiss_contradiction};
+pub use audit_service::{get_audit_log};
+pub use rename_service::{rename_term};
+pub use app_lock_service::{get_app_lock_status, lock_app, set_app_lock, unlock_app, record_user_activity, get_idle_hide_settings, set_idle_hide_settings};
+pub use profile_service::{list_profiles, get_active_profile, create_profile, switch_profile, get_notes_by_author};
+pub use sync_service::{get_sync_status, get_note_sync_status, set_sync_settings, sync_now};
+pub use vault_service::{list_mounted_vaults, mount_shared_vault, unmount_shared_vault, get_shared_vault_notes, search_shared_vaults, update_shared_note, create_cross_vault_link, delete_cross_vault_link, get_cross_vault_links};
+pub use visibility_service::{set_note_visibility, set_category_visibility, get_effective_visibility};
+pub use ai_privacy_service::{set_note_ai_excluded, set_category_ai_excluded};
+pub use retention_service::{list_retention_rules, save_retention_rule, delete_retention_rule, run_retention};
+pub use maintenance_service::run_maintenance;
+pub use metrics_service::get_performance_metrics;
+pub use quick_switch_service::quick_switch;
 
 // UI state functions
-use crate::models::{GraphViewport, UIState, UIStateDatabase};
+use crate::models::GraphViewport;
 
 pub async fn save_graph_viewport(x: f64, y: f64, zoom: f64) -> Result<(), String> {
-    let ui_state = UIStateDatabase {
-        ui_state: UIState {
-            graph_viewport: GraphViewport { x, y, zoom },
-        },
-    };
+    // Keep the rest of the UI state (window placements) intact
+    let mut ui_state = storage_service::load_ui_state()?;
+    ui_state.ui_state.graph_viewport = GraphViewport { x, y, zoom };
     storage_service::save_ui_state(&ui_state)
 }
 
@@ -1,12 +1,35 @@
-use chrono::Utc;
+use chrono::{DateTime, Local, NaiveDate, Utc};
 use uuid::Uuid;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
-use crate::models::{Note, NotesDatabase, GraphPosition};
-use crate::services::storage_service::{get_notes_file_path, save_notes};
-use crate::services::category_service::{validate_category_path, create_category_safe, update_category_note_counts};
-use crate::services::ai_service::{generate_ai_title, generate_simple_title};
+use crate::models::{AiJobKind, AppSettings, CategoryTitleStrategy, Note, NoteLink, NoteStatus, NotesDatabase, GraphPosition, Provenance, QaThread, TitlePolicy, TitleStrategy, WebhookEvent};
+use crate::services::storage_service::{get_notes_file_path, load_links, load_note_segments, load_settings, pending_notes, save_links, save_notes, save_notes_deferred, save_settings};
+use crate::services::settings_service::current_settings;
+use crate::services::category_service::{adjust_category_note_counts, ensure_category_path};
+use crate::services::ai_service::{generate_note_title, generate_simple_title};
+use crate::services::stats_service::{record_activity, record_review, word_count};
+use crate::services::webhook_service::{emit_webhook_event, note_payload, tag_added_payload};
+use crate::services::plugin_service::run_note_save_hooks;
+use crate::services::timeline_service::link_into_timeline;
+use crate::services::link_service::auto_link_note;
+use crate::services::audit_service::{link_deleted, note_deleted, record_deletions};
+use crate::services::embed_service::sync_embed_links;
+use crate::services::ai_queue_service::{enqueue_ai_job, is_offline, spawn_queue_processing};
+use crate::services::locale_service::{format_date, tr};
+use crate::services::capture_rule_service::category_for_new_note;
+use crate::services::dedup_service::{find_duplicate, SavedNote};
+use crate::services::profile_service::attribute_note;
 
 pub fn load_notes() -> Result<NotesDatabase, String> {
+    if let Some(database) = pending_notes() {
+        return Ok(database);
+    }
+    if let Some(database) = load_note_segments()? {
+        return Ok(database);
+    }
+
+    // Not split into segments yet - read the old single file; the next save migrates it
     let file_path = get_notes_file_path()?;
     
     if !file_path.exists() {
@@ -26,6 +49,13 @@ pub fn load_notes() -> Result<NotesDatabase, String> {
                     note.title = generate_simple_title(&note.content);
                     needs_migration = true;
                 }
+                // Chat captures saved as plain "Q: … A: …" text become structured threads
+                if note.qa.is_none() && note.content.starts_with("Q:") {
+                    if let Some(thread) = QaThread::parse(&note.content, None, note.timestamp) {
+                        note.qa = Some(thread);
+                        needs_migration = true;
+                    }
+                }
                 note
             }).collect();
             
@@ -81,6 +111,7 @@ pub fn load_notes() -> Result<NotesDatabase, String> {
                                     tags: old_note_with_path.tags,
                                     ai_confidence: old_note_with_path.ai_confidence,
                                     position: None,
+                                    ..Default::default()
                                 });
                             } else if let Ok(old_note) = serde_json::from_value::<OldNote>(note_value.clone()) {
                                 // Migrate very old note format
@@ -93,6 +124,7 @@ pub fn load_notes() -> Result<NotesDatabase, String> {
                                     tags: old_note.tags,
                                     ai_confidence: None,
                                     position: None,
+                                    ..Default::default()
                                 });
                             }
                         }
@@ -119,6 +151,7 @@ pub fn load_notes() -> Result<NotesDatabase, String> {
                             tags: old_note.tags,
                             ai_confidence: None,
                             position: None,
+                            ..Default::default()
                         }
                     }).collect();
                     
@@ -135,148 +168,534 @@ pub fn load_notes() -> Result<NotesDatabase, String> {
     }
 }
 
-/// Save a note with simplified categorization (user chooses category, no slow AI calls)
-pub async fn save_note_simplified(content: String, category_path: Option<Vec<String>>, custom_title: Option<String>) -> Result<Note, String> {
-    let mut database = load_notes()?;
-    
-    // Use provided category path or default to "General"
-    let final_category_path = if let Some(path) = category_path {
-        // Check if provided path exists, if not create it
-        if !validate_category_path(&path)? {
-            // Create the category path step by step
-            let mut current_path = Vec::new();
-            for segment in &path {
-                current_path.push(segment.clone());
-                
-                // Check if this level exists
-                if !validate_category_path(&current_path)? {
-                    // Create this level
-                    let parent_path = if current_path.len() > 1 {
-                        Some(current_path[..current_path.len() - 1].to_vec())
-                    } else {
-                        None
-                    };
-                    create_category_safe(segment.clone(), parent_path)?;
-                }
-            }
+/// Returned by `update_note*` when the note changed since the editor loaded it
+#[derive(Serialize)]
+pub struct NoteConflict {
+    pub note_id: String,
+    pub expected_revision: u64,
+    pub current_revision: u64,
+    pub your_content: String, // What the stale editor tried to save
+    pub current: Note,        // What's stored now
+}
+
+/// Refused title under the `Reject` and `Merge` title policies
+#[derive(Serialize)]
+pub struct TitleCollision {
+    pub title: String,
+    pub category_path: Vec<String>,
+    pub existing_note_id: String,
+    pub can_merge: bool, // Merge policy: offer `merge_notes` into the existing note
+}
+
+impl std::fmt::Display for TitleCollision {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "A note titled \"{}\" already exists in {}", self.title, self.category_path.join(" / "))?;
+        if self.can_merge {
+            write!(f, " - merge into it instead? (note {})", self.existing_note_id)?;
         }
-        path
-    } else {
-        // Default to "General" category
-        vec!["General".to_string()]
-    };
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+#[serde(tag = "kind", content = "details")]
+pub enum NoteUpdateError {
+    Conflict(Box<NoteConflict>),
+    TitleTaken(Box<TitleCollision>),
+    Failed(String),
+}
+
+impl From<TitleCollision> for NoteUpdateError {
+    fn from(collision: TitleCollision) -> Self {
+        NoteUpdateError::TitleTaken(Box::new(collision))
+    }
+}
+
+impl From<String> for NoteUpdateError {
+    fn from(message: String) -> Self {
+        NoteUpdateError::Failed(message)
+    }
+}
+
+impl From<&str> for NoteUpdateError {
+    fn from(message: &str) -> Self {
+        NoteUpdateError::Failed(message.to_string())
+    }
+}
+
+impl std::fmt::Display for NoteUpdateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NoteUpdateError::Conflict(conflict) => write!(
+                f,
+                "Note {} was changed elsewhere (expected revision {}, found {})",
+                conflict.note_id, conflict.expected_revision, conflict.current_revision
+            ),
+            NoteUpdateError::TitleTaken(collision) => write!(f, "{}", collision),
+            NoteUpdateError::Failed(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+/// Apply the title policy to a note's new title. Titles the user typed may be refused
+/// (`Reject`, `Merge`); generated titles always fall back to a "(2)" suffix instead,
+/// since there's nobody to ask.
+pub fn apply_title_policy(notes: &[Note], note_id: &str, category_path: &[String], title: String, user_title: bool) -> Result<String, TitleCollision> {
+    let policy = current_settings().title_policy;
+    let taken = |candidate: &str| notes.iter().find(|n| {
+        n.id != note_id && n.category_path == category_path && n.title.trim().eq_ignore_ascii_case(candidate.trim())
+    });
+    let Some(existing) = taken(&title).filter(|_| policy != TitlePolicy::Allow) else { return Ok(title) };
+
+    if user_title && matches!(policy, TitlePolicy::Reject | TitlePolicy::Merge) {
+        return Err(TitleCollision {
+            title,
+            category_path: category_path.to_vec(),
+            existing_note_id: existing.id.clone(),
+            can_merge: policy == TitlePolicy::Merge,
+        });
+    }
+    Ok((2..)
+        .map(|n| format!("{} ({})", title, n))
+        .find(|candidate| taken(candidate).is_none())
+        .unwrap_or(title))
+}
+
+/// Fail with a conflict if the stored note moved past the revision the caller edited
+fn check_revision(note: &Note, base_revision: Option<u64>, content: &str) -> Result<(), NoteUpdateError> {
+    match base_revision {
+        Some(expected) if expected != note.revision => Err(NoteUpdateError::Conflict(Box::new(NoteConflict {
+            note_id: note.id.clone(),
+            expected_revision: expected,
+            current_revision: note.revision,
+            your_content: content.to_string(),
+            current: note.clone(),
+        }))),
+        _ => Ok(()),
+    }
+}
+
+/// Keep a Q&A note's thread in step with hand-edited content. If the text no longer has
+/// the Q&A shape the note simply becomes a regular note.
+fn sync_qa_thread(note: &mut Note) {
+    if let Some(previous) = note.qa.take() {
+        note.qa = QaThread::parse(&note.content, Some(&previous), Utc::now());
+    }
+}
+
+/// The title strategy for notes in `category_path`: the deepest matching override, else
+/// the general setting
+pub fn title_strategy_for(category_path: &[String]) -> TitleStrategy {
+    let settings = current_settings();
+    settings.category_title_strategies.iter()
+        .filter(|o| category_path.starts_with(&o.category_path))
+        .max_by_key(|o| o.category_path.len())
+        .map(|o| o.strategy)
+        .unwrap_or(settings.title_strategy)
+}
+
+/// Title from a note's creation time, e.g. "2026-10-14 09:30"
+fn date_title(timestamp: DateTime<Utc>) -> String {
+    format_date(&timestamp.with_timezone(&Local), "date.short")
+}
+
+/// Choose the title strategy for every note, or with `category_path` only for that
+/// category and its subcategories
+pub async fn set_title_strategy(strategy: TitleStrategy, category_path: Option<Vec<String>>) -> Result<AppSettings, String> {
+    let mut database = load_settings()?;
+    let settings = &mut database.settings;
+    match category_path {
+        None => settings.title_strategy = strategy,
+        Some(category_path) => match settings.category_title_strategies.iter_mut().find(|o| o.category_path == category_path) {
+            Some(existing) => existing.strategy = strategy,
+            None => settings.category_title_strategies.push(CategoryTitleStrategy { category_path, strategy }),
+        },
+    }
+    save_settings(&database)?;
+    Ok(database.settings)
+}
+
+/// Drop a category's override so it follows the general title strategy again
+pub async fn clear_category_title_strategy(category_path: Vec<String>) -> Result<AppSettings, String> {
+    let mut database = load_settings()?;
+    database.settings.category_title_strategies.retain(|o| o.category_path != category_path);
+    save_settings(&database)?;
+    Ok(database.settings)
+}
+
+/// Title for saved content, following the category's title strategy. With the AI strategy
+/// substantial content gets an AI title when online; offline (or if the AI call fails
+/// for any reason) the simple title is used right away and the AI title is queued. `Ask`
+/// keeps the note's current title.
+async fn title_for_content(note: &Note) -> String {
+    title_with_strategy(note, title_strategy_for(&note.category_path)).await
+}
+
+async fn title_with_strategy(note: &Note, strategy: TitleStrategy) -> String {
+    let content = &note.content;
+    match strategy {
+        TitleStrategy::Ask => return note.title.clone(),
+        TitleStrategy::Date => return date_title(note.timestamp),
+        TitleStrategy::FirstLine => return generate_simple_title(content),
+        TitleStrategy::Ai => {}
+    }
+    if content.len() <= 20 {
+        // For very short content, just use it as-is
+        return content.trim().to_string();
+    }
+    if !is_offline() {
+        if let Ok(title) = generate_note_title(note).await {
+            return title;
+        }
+    }
+    // Offline or the AI call failed - the queue tries again later
+    enqueue_ai_job(AiJobKind::Title, &note.id);
+    generate_simple_title(content)
+}
+
+/// Save a note with simplified categorization (user chooses category, no slow AI calls).
+/// Without a custom title the note is saved with a simple title and an AI title job is
+/// queued; `note:title-updated` fires once the better title lands. Content already in
+/// the vault (see the duplicate settings) returns the existing note, flagged `duplicate`.
+pub async fn save_note_simplified(content: String, category_path: Option<Vec<String>>, custom_title: Option<String>) -> Result<SavedNote, String> {
+    if let Some(existing) = find_duplicate(&load_notes()?.notes, &content) {
+        return Ok(SavedNote { note: existing.clone(), duplicate: true });
+    }
+    let (note, needs_ai_title) = build_simplified_note(content, category_path, custom_title)?;
+    insert_note(note, needs_ai_title).map(SavedNote::created)
+}
+
+/// Save captured text together with where it came from. Capture rules see the source URL,
+/// so domain rules file the note when no category is given.
+pub async fn create_note_with_source(content: String, source_app: Option<String>, source_url: Option<String>, category_path: Option<Vec<String>>) -> Result<SavedNote, String> {
+    if let Some(existing) = find_duplicate(&load_notes()?.notes, &content) {
+        return Ok(SavedNote { note: existing.clone(), duplicate: true });
+    }
+    let clean = |value: Option<String>| value.map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+    let (app, url) = (clean(source_app), clean(source_url));
+    let category_path = category_path.or_else(|| {
+        url.as_ref().map(|url| category_for_new_note("", &format!("{}\n{}", content, url), &[]))
+    });
+
+    let (mut note, needs_ai_title) = build_simplified_note(content, category_path, None)?;
+    note.provenance = Some(Provenance { app, url, captured_at: note.timestamp });
+    insert_note(note, needs_ai_title).map(SavedNote::created)
+}
+
+/// The note `save_note_simplified` stores, and whether it still needs an AI title
+fn build_simplified_note(content: String, category_path: Option<Vec<String>>, custom_title: Option<String>) -> Result<(Note, bool), String> {
+    // Use provided category path, else what the capture rules or the default category say
+    let final_category_path = category_path.unwrap_or_else(|| {
+        category_for_new_note(custom_title.as_deref().unwrap_or(""), &content, &[])
+    });
+    // Check if the path exists, if not create it
+    ensure_category_path(&final_category_path)?;
     
-    // Generate title for the note - use custom title if provided and not empty, otherwise generate
-    let title = if let Some(custom) = custom_title {
-        let trimmed_custom = custom.trim();
-        if !trimmed_custom.is_empty() {
-            // Use user-provided title if available and not empty
-            trimmed_custom.to_string()
-        } else {
-            // Custom title was empty, generate default from content
+    // Use the custom title if one was given, otherwise what the title strategy says
+    let note_id = Uuid::new_v4().to_string();
+    let timestamp = Utc::now();
+    let mut needs_ai_title = false;
+    let custom_title = custom_title.map(|t| t.trim().to_string()).filter(|t| !t.is_empty());
+    let user_title = custom_title.is_some();
+    let title = match (custom_title, title_strategy_for(&final_category_path)) {
+        (Some(custom), _) => custom,
+        (None, TitleStrategy::Ask) => return Err(tr("error.title_required", &[])),
+        (None, TitleStrategy::Date) => date_title(timestamp),
+        (None, TitleStrategy::FirstLine) => generate_simple_title(&content),
+        (None, TitleStrategy::Ai) if content.len() > 20 => {
+            // Save straight away with a simple title - the AI title is generated in the background
+            needs_ai_title = true;
             generate_simple_title(&content)
         }
-    } else if content.len() > 20 {
-        // Use AI title generation for any substantial content
-        generate_ai_title(&content).await
-            .unwrap_or_else(|_| generate_simple_title(&content))
-    } else {
         // For very short content, just use it as-is
-        content.trim().to_string()
+        (None, TitleStrategy::Ai) => content.trim().to_string(),
+    };
+
+    // Placeholder titles are checked once the AI title replaces them
+    let title = if needs_ai_title {
+        title
+    } else {
+        apply_title_policy(&load_notes()?.notes, &note_id, &final_category_path, title, user_title)
+            .map_err(|collision| collision.to_string())?
     };
 
     let note = Note {
-        id: Uuid::new_v4().to_string(),
+        id: note_id,
         title,
+        title_is_custom: user_title,
         content,
         category_path: final_category_path,
-        timestamp: Utc::now(),
+        timestamp,
         tags: Vec::new(), // No automatic tag extraction - user can add manually if needed
         ai_confidence: None,
         position: None,
+        ..Default::default()
     };
+
+    Ok((note, needs_ai_title))
+}
+
+/// Store a freshly built note and run everything that follows a create: save hooks,
+/// category counts, activity, embed links, webhooks and (optionally) the AI title job
+pub fn insert_note(mut note: Note, needs_ai_title: bool) -> Result<Note, String> {
+    let mut database = load_notes()?;
+    attribute_note(&mut note);
+    run_note_save_hooks(&mut note);
     
     database.notes.push(note.clone());
     save_notes(&database)?;
     
-    // Update category note counts
-    update_category_note_counts()?;
+    adjust_category_note_counts(&note.category_path, 1)?;
+    record_activity(1, word_count(&note.content) as i64);
+    sync_embed_links(&note);
+    link_into_timeline(&note);
+    auto_link_note(&note);
+    emit_webhook_event(WebhookEvent::NoteCreated, note_payload(&note));
+    
+    if needs_ai_title {
+        enqueue_ai_job(AiJobKind::Title, &note.id);
+        if !is_offline() {
+            spawn_queue_processing();
+        }
+    }
     
     Ok(note)
 }
 
-pub async fn update_note(id: String, content: String) -> Result<Note, String> {
+/// The title `content` would regenerate for note `id`, or `None` if the user chose its
+/// title. Worked out before the notes are loaded for the update: the AI title can take
+/// seconds, and changes saved in the meantime would be lost if the notes were held across it.
+async fn regenerated_title(id: &str, content: &str, base_revision: Option<u64>) -> Result<Option<String>, NoteUpdateError> {
+    let database = load_notes()?;
+    let note = database.notes.iter()
+        .find(|note| note.id == id)
+        .ok_or_else(|| tr("error.note_not_found", &[&id]))?;
+    check_revision(note, base_revision, content)?;
+    if note.title_is_custom {
+        return Ok(None);
+    }
+    let draft = Note { content: content.to_string(), ..note.clone() };
+    Ok(Some(title_for_content(&draft).await))
+}
+
+/// `base_revision` is the revision the caller's copy is based on - when given and stale,
+/// the update is refused with a `Conflict` carrying both versions. Every change to a note
+/// (content, title, tags, status, metadata...) bumps its revision.
+pub async fn update_note(id: String, content: String, base_revision: Option<u64>) -> Result<Note, NoteUpdateError> {
+    let generated_title = regenerated_title(&id, &content, base_revision).await?;
+    // Loaded again after the await, so the revision is checked against what's saved now
     let mut database = load_notes()?;
     
     let note_index = database.notes.iter()
         .position(|note| note.id == id)
-        .ok_or("Note not found")?;
+        .ok_or_else(|| tr("error.note_not_found", &[&id]))?;
+    check_revision(&database.notes[note_index], base_revision, &content)?;
     
+    let previous_words = word_count(&database.notes[note_index].content);
     database.notes[note_index].content = content.clone();
     
-    // Regenerate title if content changed significantly
-    let new_title = if content.len() > 20 {
-        // Use AI title generation for any substantial content
-        generate_ai_title(&content).await
-            .unwrap_or_else(|_| generate_simple_title(&content))
-    } else {
-        // For very short content, just use it as-is
-        content.trim().to_string()
-    };
-    
-    database.notes[note_index].title = new_title;
+    // Regenerate the title from the new content, unless the user chose it
+    if let Some(new_title) = generated_title.filter(|_| !database.notes[note_index].title_is_custom) {
+        let category_path = database.notes[note_index].category_path.clone();
+        let new_title = apply_title_policy(&database.notes, &id, &category_path, new_title, false)?;
+        database.notes[note_index].title = new_title;
+    }
+    sync_qa_thread(&mut database.notes[note_index]);
+    run_note_save_hooks(&mut database.notes[note_index]);
+    database.notes[note_index].revision += 1;
     
     save_notes(&database)?;
-    update_category_note_counts()?;
+    record_activity(0, word_count(&content) as i64 - previous_words as i64);
+    sync_embed_links(&database.notes[note_index]);
+    auto_link_note(&database.notes[note_index]);
+    emit_webhook_event(WebhookEvent::NoteUpdated, note_payload(&database.notes[note_index]));
     
     Ok(database.notes[note_index].clone())
 }
 
-pub async fn update_note_with_title(id: String, content: String, title: Option<String>) -> Result<Note, String> {
+pub async fn update_note_with_title(id: String, content: String, title: Option<String>, base_revision: Option<u64>) -> Result<Note, NoteUpdateError> {
+    let title = title.map(|t| t.trim().to_string()).filter(|t| !t.is_empty());
+    let generated_title = match title {
+        Some(_) => None,
+        None => regenerated_title(&id, &content, base_revision).await?,
+    };
+    // Loaded again after the await, so the revision is checked against what's saved now
     let mut database = load_notes()?;
     
     let note_index = database.notes.iter()
         .position(|note| note.id == id)
-        .ok_or("Note not found")?;
+        .ok_or_else(|| tr("error.note_not_found", &[&id]))?;
+    check_revision(&database.notes[note_index], base_revision, &content)?;
     
+    let previous_words = word_count(&database.notes[note_index].content);
     database.notes[note_index].content = content.clone();
     
-    // Use provided title or regenerate if not provided
-    let new_title = if let Some(custom_title) = title {
-        if !custom_title.trim().is_empty() {
-            custom_title.trim().to_string()
-        } else {
-            // If empty title provided, regenerate from content
-            if content.len() > 20 {
-                generate_ai_title(&content).await
-                    .unwrap_or_else(|_| generate_simple_title(&content))
-            } else {
-                content.trim().to_string()
-            }
-        }
-    } else {
-        // No title provided, regenerate from content
-        if content.len() > 20 {
-            generate_ai_title(&content).await
-                .unwrap_or_else(|_| generate_simple_title(&content))
-        } else {
-            content.trim().to_string()
-        }
+    // A title that differs from the current one is the user's choice; without one the
+    // title is regenerated, unless the user chose the current one
+    let note = &database.notes[note_index];
+    let user_title = title.as_ref().is_some_and(|t| *t != note.title) || note.title_is_custom;
+    let new_title = match title {
+        Some(title) => Some(title),
+        None if note.title_is_custom => None,
+        None => generated_title,
     };
-    
-    database.notes[note_index].title = new_title;
+    if let Some(new_title) = new_title {
+        let category_path = database.notes[note_index].category_path.clone();
+        let new_title = apply_title_policy(&database.notes, &id, &category_path, new_title, user_title)?;
+        database.notes[note_index].title = new_title;
+    }
+    database.notes[note_index].title_is_custom = user_title;
+    sync_qa_thread(&mut database.notes[note_index]);
+    run_note_save_hooks(&mut database.notes[note_index]);
+    database.notes[note_index].revision += 1;
     
     save_notes(&database)?;
-    update_category_note_counts()?;
+    record_activity(0, word_count(&content) as i64 - previous_words as i64);
+    sync_embed_links(&database.notes[note_index]);
+    auto_link_note(&database.notes[note_index]);
+    emit_webhook_event(WebhookEvent::NoteUpdated, note_payload(&database.notes[note_index]));
     
     Ok(database.notes[note_index].clone())
 }
 
+/// Add `text` to the end of a note as its own paragraph, e.g. for a running daily log.
+/// `with_timestamp` starts the paragraph with the current date and time.
+pub async fn append_to_note(note_id: String, text: String, with_timestamp: bool) -> Result<Note, String> {
+    add_to_note(&note_id, &text, with_timestamp, false)
+}
+
+/// Like `append_to_note`, but the paragraph goes at the top
+pub async fn prepend_to_note(note_id: String, text: String, with_timestamp: bool) -> Result<Note, String> {
+    add_to_note(&note_id, &text, with_timestamp, true)
+}
+
+fn add_to_note(note_id: &str, text: &str, with_timestamp: bool, at_start: bool) -> Result<Note, String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err("Nothing to add".to_string());
+    }
+    let entry = if with_timestamp {
+        format!("**{}** {}", format_date(&Local::now(), "date.short"), text)
+    } else {
+        text.to_string()
+    };
+
+    let mut database = load_notes()?;
+    let note = database.notes.iter_mut()
+        .find(|n| n.id == note_id)
+        .ok_or_else(|| tr("error.note_not_found", &[&note_id]))?;
+
+    let existing = note.content.trim();
+    note.content = match (existing.is_empty(), at_start) {
+        (true, _) => entry,
+        (false, false) => format!("{}\n\n{}", existing, entry),
+        (false, true) => format!("{}\n\n{}", entry, existing),
+    };
+    sync_qa_thread(note);
+    run_note_save_hooks(note);
+    note.revision += 1;
+
+    let updated = note.clone();
+    save_notes(&database)?;
+    record_activity(0, word_count(text) as i64);
+    sync_embed_links(&updated);
+    emit_webhook_event(WebhookEvent::NoteUpdated, note_payload(&updated));
+    Ok(updated)
+}
+
+/// Replace a note's title with a freshly generated one and let content edits update it
+/// again. Categories set to ask for titles get an AI title here, as the user asked for one.
+pub async fn regenerate_note_title(note_id: String) -> Result<Note, String> {
+    let database = load_notes()?;
+    let note = database.notes.iter()
+        .find(|n| n.id == note_id)
+        .ok_or_else(|| tr("error.note_not_found", &[&note_id]))?;
+    let strategy = match title_strategy_for(&note.category_path) {
+        TitleStrategy::Ask => TitleStrategy::Ai,
+        strategy => strategy,
+    };
+    let title = title_with_strategy(note, strategy).await;
+    let title = apply_title_policy(&database.notes, &note_id, &note.category_path, title, false)
+        .map_err(|collision| collision.to_string())?;
+
+    // Reload - generating an AI title can take a while
+    let mut database = load_notes()?;
+    let note = database.notes.iter_mut()
+        .find(|n| n.id == note_id)
+        .ok_or_else(|| tr("error.note_not_found", &[&note_id]))?;
+    note.title = title;
+    note.title_is_custom = false;
+    note.revision += 1;
+    let updated = note.clone();
+    save_notes(&database)?;
+    emit_webhook_event(WebhookEvent::NoteUpdated, note_payload(&updated));
+    Ok(updated)
+}
+
+/// Merge `source_id` into `target_id`: the source's content is appended and its tags,
+/// attachments and links move over, then the source is deleted. Returns the merged note.
+pub async fn merge_notes(source_id: String, target_id: String) -> Result<Note, String> {
+    if source_id == target_id {
+        return Err("Cannot merge a note into itself".to_string());
+    }
+    let mut database = load_notes()?;
+    let source_index = database.notes.iter()
+        .position(|n| n.id == source_id)
+        .ok_or_else(|| tr("error.note_not_found", &[&source_id]))?;
+    let source = database.notes.remove(source_index);
+    let mut deleted = vec![note_deleted(&source)];
+    let target = database.notes.iter_mut()
+        .find(|n| n.id == target_id)
+        .ok_or_else(|| tr("error.note_not_found", &[&target_id]))?;
+
+    if !source.content.trim().is_empty() {
+        target.content = format!("{}\n\n{}", target.content.trim_end(), source.content.trim());
+    }
+    for tag in source.tags {
+        if !target.tags.iter().any(|t| t.eq_ignore_ascii_case(&tag)) {
+            target.tags.push(tag);
+        }
+    }
+    target.attachments.extend(source.attachments);
+    target.revision += 1;
+    let merged = target.clone();
+
+    // Links to or from the source now belong to the target; self-links and duplicates go
+    let mut links_db = load_links()?;
+    let mut kept: Vec<NoteLink> = Vec::new();
+    for mut link in links_db.links {
+        if link.source_id == source_id {
+            link.source_id = target_id.clone();
+        }
+        if link.target_id == source_id {
+            link.target_id = target_id.clone();
+        }
+        let duplicate = kept.iter().any(|k| k.source_id == link.source_id && k.target_id == link.target_id && k.link_type == link.link_type);
+        if link.source_id != link.target_id && !duplicate {
+            kept.push(link);
+        } else {
+            deleted.push(link_deleted(&link));
+        }
+    }
+    links_db.links = kept;
+
+    save_notes(&database)?;
+    save_links(&links_db)?;
+    record_deletions(deleted);
+    adjust_category_note_counts(&source.category_path, -1)?;
+    emit_webhook_event(WebhookEvent::NoteUpdated, note_payload(&merged));
+    Ok(merged)
+}
+
 pub async fn delete_note(id: String) -> Result<(), String> {
     let mut database = load_notes()?;
-    database.notes.retain(|note| note.id != id);
+    let Some(index) = database.notes.iter().position(|note| note.id == id) else {
+        return Ok(());
+    };
+    let removed = database.notes.remove(index);
     save_notes(&database)?;
-    update_category_note_counts()?;
+    record_deletions(vec![note_deleted(&removed)]);
+    adjust_category_note_counts(&removed.category_path, -1)?;
     Ok(())
 }
 
@@ -293,22 +712,341 @@ pub async fn get_notes_by_category(category_path: Vec<String>) -> Result<Vec<Not
     Ok(notes)
 }
 
+/// Set (or with `None`/null, remove) a single custom metadata field on a note
+pub async fn set_note_metadata(note_id: String, key: String, value: Option<serde_json::Value>) -> Result<Note, String> {
+    let key = key.trim().to_string();
+    if key.is_empty() {
+        return Err("Metadata key cannot be empty".to_string());
+    }
+    
+    let mut database = load_notes()?;
+    let note = database.notes.iter_mut()
+        .find(|n| n.id == note_id)
+        .ok_or_else(|| tr("error.note_not_found", &[&note_id]))?;
+    
+    match value {
+        Some(value) if !value.is_null() => {
+            note.metadata.insert(key, value);
+        }
+        _ => {
+            note.metadata.remove(&key);
+        }
+    }
+    
+    note.revision += 1;
+    let updated = note.clone();
+    save_notes(&database)?;
+    Ok(updated)
+}
+
+pub async fn get_note_metadata(note_id: String) -> Result<HashMap<String, serde_json::Value>, String> {
+    let database = load_notes()?;
+    database.notes.into_iter()
+        .find(|n| n.id == note_id)
+        .map(|n| n.metadata)
+        .ok_or_else(|| tr("error.note_not_found", &[&note_id]))
+}
+
+/// Add a tag to a note (no-op if it already carries it, compared case-insensitively)
+pub async fn add_note_tag(note_id: String, tag: String) -> Result<Note, String> {
+    let tag = tag.trim().trim_start_matches('#').to_string();
+    if tag.is_empty() {
+        return Err("Tag cannot be empty".to_string());
+    }
+    
+    let mut database = load_notes()?;
+    let note = database.notes.iter_mut()
+        .find(|n| n.id == note_id)
+        .ok_or_else(|| tr("error.note_not_found", &[&note_id]))?;
+    
+    if note.tags.iter().any(|t| t.eq_ignore_ascii_case(&tag)) {
+        return Ok(note.clone());
+    }
+    note.tags.push(tag.clone());
+    
+    note.revision += 1;
+    let updated = note.clone();
+    save_notes(&database)?;
+    emit_webhook_event(WebhookEvent::TagAdded, tag_added_payload(&updated, &tag));
+    Ok(updated)
+}
+
+pub async fn remove_note_tag(note_id: String, tag: String) -> Result<Note, String> {
+    let mut database = load_notes()?;
+    let note = database.notes.iter_mut()
+        .find(|n| n.id == note_id)
+        .ok_or_else(|| tr("error.note_not_found", &[&note_id]))?;
+    
+    note.tags.retain(|t| !t.eq_ignore_ascii_case(tag.trim()));
+    
+    note.revision += 1;
+    let updated = note.clone();
+    save_notes(&database)?;
+    Ok(updated)
+}
+
+/// Set or clear (`None`/empty) a note's workflow status
+pub async fn set_note_status(note_id: String, status: Option<String>) -> Result<Note, String> {
+    let mut database = load_notes()?;
+    let note = database.notes.iter_mut()
+        .find(|n| n.id == note_id)
+        .ok_or_else(|| tr("error.note_not_found", &[&note_id]))?;
+    
+    note.status = status
+        .filter(|s| !s.trim().is_empty())
+        .map(|s| NoteStatus::parse(&s));
+    
+    note.revision += 1;
+    let updated = note.clone();
+    save_notes(&database)?;
+    record_review();
+    Ok(updated)
+}
+
+/// Set or clear a note's 1-5 rating
+pub async fn set_note_rating(note_id: String, rating: Option<u8>) -> Result<Note, String> {
+    if let Some(value) = rating {
+        if !(1..=5).contains(&value) {
+            return Err(format!("Rating must be between 1 and 5, got {}", value));
+        }
+    }
+    
+    let mut database = load_notes()?;
+    let note = database.notes.iter_mut()
+        .find(|n| n.id == note_id)
+        .ok_or_else(|| tr("error.note_not_found", &[&note_id]))?;
+    
+    note.rating = rating;
+    
+    note.revision += 1;
+    let updated = note.clone();
+    save_notes(&database)?;
+    record_review();
+    Ok(updated)
+}
+
+/// Set or clear a note's due date
+pub async fn set_note_due_date(note_id: String, due_date: Option<DateTime<Utc>>) -> Result<Note, String> {
+    let mut database = load_notes()?;
+    let note = database.notes.iter_mut()
+        .find(|n| n.id == note_id)
+        .ok_or_else(|| tr("error.note_not_found", &[&note_id]))?;
+    
+    note.due_date = due_date;
+    
+    note.revision += 1;
+    let updated = note.clone();
+    save_notes(&database)?;
+    Ok(updated)
+}
+
+/// Category holding one note per day, titled with its date (YYYY-MM-DD)
+pub const DAILY_NOTES_CATEGORY: &str = "Daily Notes";
+
+/// Find the note a link/embed refers to - by id first, then by title (case-insensitive)
+pub fn resolve_note_reference<'a>(notes: &'a [Note], reference: &str) -> Option<&'a Note> {
+    let reference = reference.trim();
+    notes.iter().find(|n| n.id == reference)
+        .or_else(|| notes.iter().find(|n| n.title.trim().eq_ignore_ascii_case(reference)))
+}
+
+/// The day a daily note stands for, if this is one
+pub fn daily_note_date(note: &Note) -> Option<NaiveDate> {
+    if note.category_path.first().map(|c| c.as_str()) != Some(DAILY_NOTES_CATEGORY) {
+        return None;
+    }
+    NaiveDate::parse_from_str(note.title.trim(), "%Y-%m-%d").ok()
+}
+
+/// Filters for `query_notes` - every provided field must match
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct NoteQuery {
+    pub category_path: Option<Vec<String>>, // Matches the category and its subcategories
+    pub tags: Option<Vec<String>>,          // Note must carry all of these tags
+    pub text: Option<String>,               // Case-insensitive match on title or content
+    pub metadata: Option<HashMap<String, serde_json::Value>>, // Field equality (array fields match if they contain the value)
+    pub status: Option<String>,
+    pub min_rating: Option<u8>,
+}
+
+fn metadata_matches(actual: Option<&serde_json::Value>, expected: &serde_json::Value) -> bool {
+    match (actual, expected) {
+        // Filtering on null means "field not set"
+        (None, serde_json::Value::Null) => true,
+        (None, _) => false,
+        (Some(serde_json::Value::Array(items)), expected) if !expected.is_array() => items.contains(expected),
+        (Some(actual), expected) => actual == expected,
+    }
+}
+
+pub fn note_matches_query(note: &Note, query: &NoteQuery) -> bool {
+    if let Some(path) = &query.category_path {
+        if !note.category_path.starts_with(path) {
+            return false;
+        }
+    }
+    
+    if let Some(tags) = &query.tags {
+        if !tags.iter().all(|tag| note.tags.iter().any(|t| t.eq_ignore_ascii_case(tag))) {
+            return false;
+        }
+    }
+    
+    if let Some(text) = &query.text {
+        let needle = text.to_lowercase();
+        if !note.title.to_lowercase().contains(&needle) && !note.content.to_lowercase().contains(&needle) {
+            return false;
+        }
+    }
+    
+    if let Some(metadata) = &query.metadata {
+        if !metadata.iter().all(|(key, expected)| metadata_matches(note.metadata.get(key), expected)) {
+            return false;
+        }
+    }
+    
+    if let Some(status) = &query.status {
+        if note.status.as_ref() != Some(&NoteStatus::parse(status)) {
+            return false;
+        }
+    }
+    
+    if let Some(min_rating) = query.min_rating {
+        if note.rating.unwrap_or(0) < min_rating {
+            return false;
+        }
+    }
+    
+    true
+}
+
+pub async fn query_notes(query: NoteQuery) -> Result<Vec<Note>, String> {
+    let database = load_notes()?;
+    Ok(database.notes.into_iter()
+        .filter(|note| note_matches_query(note, &query))
+        .collect())
+}
+
+/// Place a note in the graph. Size, collapsed state and (without a new `z_index`) the
+/// stacking order are kept - dragging a card doesn't reshape or restack it.
+fn move_note(note: &mut Note, x: f64, y: f64, z_index: Option<i32>) {
+    let position = note.position.get_or_insert_with(GraphPosition::default);
+    position.x = x;
+    position.y = y;
+    if z_index.is_some() {
+        position.z_index = z_index;
+    }
+}
+
 pub async fn save_note_position(note_id: String, x: f64, y: f64) -> Result<(), String> {
     let mut database = load_notes()?;
     
     if let Some(note) = database.notes.iter_mut().find(|n| n.id == note_id) {
-        note.position = Some(GraphPosition {
-            x,
-            y,
-            z_index: None,
-        });
-        save_notes(&database)?;
+        move_note(note, x, y, None);
+        // Dragging sends a stream of these - written once the graph settles
+        save_notes_deferred(&database);
         Ok(())
     } else {
-        Err(format!("Note with id {} not found", note_id))
+        Err(tr("error.note_not_found", &[&note_id]))
     }
 }
 
+/// Move several notes in one load/save cycle, e.g. after an auto-layout. Positions are
+/// `(note_id, x, y, z_index)`; unknown ids are skipped and a `None` z index keeps the
+/// note's current one. Returns how many notes moved.
+pub async fn save_note_positions(positions: Vec<(String, f64, f64, Option<i32>)>) -> Result<usize, String> {
+    let mut database = load_notes()?;
+    let mut updated = 0;
+
+    for (note_id, x, y, z_index) in positions {
+        if let Some(note) = database.notes.iter_mut().find(|n| n.id == note_id) {
+            move_note(note, x, y, z_index);
+            updated += 1;
+        }
+    }
+
+    if updated > 0 {
+        save_notes_deferred(&database);
+    }
+    Ok(updated)
+}
+
+/// Move a note to the top or bottom of the graph's stacking order, then renumber every
+/// placed note 0..n so z indices never drift. Notes without a z index count as 0 and
+/// keep their relative order. Returns all positions, as the others may have shifted.
+fn restack_note(note_id: &str, to_front: bool) -> Result<Vec<(String, GraphPosition)>, String> {
+    let mut database = load_notes()?;
+    let target = database.notes.iter()
+        .position(|n| n.id == note_id)
+        .ok_or_else(|| tr("error.note_not_found", &[&note_id]))?;
+    if database.notes[target].position.is_none() {
+        return Err(format!("Note {} has no position in the graph yet", note_id));
+    }
+
+    let mut stack: Vec<usize> = (0..database.notes.len())
+        .filter(|&i| i != target && database.notes[i].position.is_some())
+        .collect();
+    stack.sort_by_key(|&i| database.notes[i].position.as_ref().and_then(|p| p.z_index).unwrap_or(0));
+    if to_front {
+        stack.push(target);
+    } else {
+        stack.insert(0, target);
+    }
+
+    for (z_index, &i) in stack.iter().enumerate() {
+        if let Some(position) = database.notes[i].position.as_mut() {
+            position.z_index = Some(z_index as i32);
+        }
+    }
+    save_notes_deferred(&database);
+
+    Ok(stack.iter()
+        .filter_map(|&i| {
+            let note = &database.notes[i];
+            note.position.clone().map(|p| (note.id.clone(), p))
+        })
+        .collect())
+}
+
+pub async fn bring_note_to_front(note_id: String) -> Result<Vec<(String, GraphPosition)>, String> {
+    restack_note(&note_id, true)
+}
+
+pub async fn send_note_to_back(note_id: String) -> Result<Vec<(String, GraphPosition)>, String> {
+    restack_note(&note_id, false)
+}
+
+/// Change how a note's card is drawn in the graph. Only notes that have been placed
+/// can be resized or collapsed.
+fn update_note_view(note_id: &str, change: impl FnOnce(&mut GraphPosition)) -> Result<GraphPosition, String> {
+    let mut database = load_notes()?;
+    let note = database.notes.iter_mut()
+        .find(|n| n.id == note_id)
+        .ok_or_else(|| tr("error.note_not_found", &[&note_id]))?;
+    let position = note.position.as_mut()
+        .ok_or_else(|| format!("Note {} has no position in the graph yet", note_id))?;
+    change(position);
+    let updated = position.clone();
+    save_notes_deferred(&database);
+    Ok(updated)
+}
+
+/// Persist a resized card. `None` for both goes back to the natural size.
+pub async fn set_note_size(note_id: String, width: Option<f64>, height: Option<f64>) -> Result<GraphPosition, String> {
+    if width.into_iter().chain(height).any(|v| !v.is_finite() || v <= 0.0) {
+        return Err("Width and height must be positive".to_string());
+    }
+    update_note_view(&note_id, |position| {
+        position.width = width;
+        position.height = height;
+    })
+}
+
+pub async fn set_note_collapsed(note_id: String, collapsed: bool) -> Result<GraphPosition, String> {
+    update_note_view(&note_id, |position| position.collapsed = collapsed)
+}
+
 pub async fn get_all_note_positions() -> Result<Vec<(String, GraphPosition)>, String> {
     let database = load_notes()?;
     let positions: Vec<(String, GraphPosition)> = database.notes
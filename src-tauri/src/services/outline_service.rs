@@ -0,0 +1,118 @@
+use pulldown_cmark::{Event, HeadingLevel, Options, Parser, Tag};
+use serde::Serialize;
+use crate::services::export_service::slugify;
+use crate::services::note_service::load_notes;
+use crate::services::locale_service::tr;
+
+#[derive(Serialize, Clone)]
+pub struct OutlineHeading {
+    pub level: u8,
+    pub text: String,
+    pub anchor: String,        // Unique within the note, usable as `[[note#anchor]]`
+    pub start: usize,          // Byte offset where the heading line starts
+    pub section_end: usize,    // Byte offset where its section (incl. subsections) ends
+    pub children: Vec<OutlineHeading>,
+}
+
+fn heading_level(level: HeadingLevel) -> u8 {
+    match level {
+        HeadingLevel::H1 => 1,
+        HeadingLevel::H2 => 2,
+        HeadingLevel::H3 => 3,
+        HeadingLevel::H4 => 4,
+        HeadingLevel::H5 => 5,
+        HeadingLevel::H6 => 6,
+    }
+}
+
+/// Every heading in document order, without nesting
+pub fn extract_headings(content: &str) -> Vec<OutlineHeading> {
+    let mut headings: Vec<OutlineHeading> = Vec::new();
+    let mut current: Option<OutlineHeading> = None;
+
+    for (event, range) in Parser::new_ext(content, Options::empty()).into_offset_iter() {
+        match event {
+            Event::Start(Tag::Heading(level, _, _)) => {
+                current = Some(OutlineHeading {
+                    level: heading_level(level),
+                    text: String::new(),
+                    anchor: String::new(),
+                    start: range.start,
+                    section_end: content.len(),
+                    children: Vec::new(),
+                });
+            }
+            Event::Text(text) | Event::Code(text) => {
+                if let Some(heading) = current.as_mut() {
+                    heading.text.push_str(&text);
+                }
+            }
+            Event::End(Tag::Heading(..)) => {
+                if let Some(mut heading) = current.take() {
+                    heading.text = heading.text.trim().to_string();
+                    headings.push(heading);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // A section runs until the next heading at the same or a higher level
+    for i in 0..headings.len() {
+        let level = headings[i].level;
+        if let Some(next) = headings[i + 1..].iter().find(|h| h.level <= level) {
+            headings[i].section_end = next.start;
+        }
+    }
+
+    // Anchors follow the slug of the text, numbered when a heading repeats
+    let mut used: Vec<String> = Vec::new();
+    for heading in &mut headings {
+        let base = slugify(&heading.text);
+        let mut anchor = base.clone();
+        let mut n = 1;
+        while used.contains(&anchor) {
+            anchor = format!("{}-{}", base, n);
+            n += 1;
+        }
+        used.push(anchor.clone());
+        heading.anchor = anchor;
+    }
+
+    headings
+}
+
+/// Nest a flat heading list so each heading holds the deeper headings that follow it
+fn nest_headings(headings: Vec<OutlineHeading>) -> Vec<OutlineHeading> {
+    let mut roots: Vec<OutlineHeading> = Vec::new();
+    let mut stack: Vec<OutlineHeading> = Vec::new();
+
+    for heading in headings {
+        while stack.last().is_some_and(|top| top.level >= heading.level) {
+            let done = stack.pop().unwrap();
+            match stack.last_mut() {
+                Some(parent) => parent.children.push(done),
+                None => roots.push(done),
+            }
+        }
+        stack.push(heading);
+    }
+    while let Some(done) = stack.pop() {
+        match stack.last_mut() {
+            Some(parent) => parent.children.push(done),
+            None => roots.push(done),
+        }
+    }
+
+    roots
+}
+
+/// The note's headings as a table-of-contents tree with byte offsets into the content
+pub async fn get_note_outline(note_id: String) -> Result<Vec<OutlineHeading>, String> {
+    let database = load_notes()?;
+    let note = database.notes.iter()
+        .find(|n| n.id == note_id)
+        .ok_or_else(|| tr("error.note_not_found", &[&note_id]))?;
+
+    Ok(nest_headings(extract_headings(&note.content)))
+}
@@ -0,0 +1,186 @@
+use chrono::Utc;
+use enigo::{Direction, Enigo, Key, Keyboard, Settings};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri_plugin_global_shortcut::Shortcut;
+use tracing::warn;
+use crate::models::PinnedPaste;
+use crate::services::note_service::load_notes;
+use crate::services::storage_service::{load_pinned_pastes, save_pinned_pastes};
+use crate::services::locale_service::tr;
+use crate::services::app_lock_service::{is_locked, LOCKED_MESSAGE};
+
+/// Shortcuts handed out in order when a note is pinned without one
+const DEFAULT_PIN_SHORTCUTS: [&str; 9] = [
+    "CmdOrCtrl+Alt+1", "CmdOrCtrl+Alt+2", "CmdOrCtrl+Alt+3", "CmdOrCtrl+Alt+4", "CmdOrCtrl+Alt+5",
+    "CmdOrCtrl+Alt+6", "CmdOrCtrl+Alt+7", "CmdOrCtrl+Alt+8", "CmdOrCtrl+Alt+9",
+];
+
+/// Already taken by the app itself (show/hide window, quick lookup)
+const RESERVED_SHORTCUTS: [&str; 2] = ["CmdOrCtrl+Alt+N", "CmdOrCtrl+Alt+L"];
+
+/// Wait for the triggering shortcut's own keys to be released, or the target app sees Ctrl+Alt+V
+const PASTE_DELAY_MS: u64 = 150;
+/// How long the target app gets to read the clipboard before the user's copy is put back
+const RESTORE_DELAY_MS: u64 = 500;
+/// How long the frontmost app gets to answer a copy keystroke
+const COPY_DELAY_MS: u64 = 200;
+
+/// Shortcuts compare case- and space-insensitively ("cmdorctrl + alt + 1")
+fn normalize_shortcut(shortcut: &str) -> String {
+    shortcut.split('+').map(|part| part.trim().to_lowercase()).collect::<Vec<_>>().join("+")
+}
+
+/// Pin shortcuts and the notes they paste, kept in Tauri managed state so the global
+/// shortcut handler doesn't read the pins file on every key press. Reloaded whenever the
+/// pins are saved.
+#[derive(Default)]
+pub struct PinnedShortcuts {
+    pins: Mutex<Vec<(Shortcut, String)>>, // Shortcut, note id
+}
+
+impl PinnedShortcuts {
+    pub fn reload(&self) {
+        let pins = match load_pinned_pastes() {
+            Ok(database) => database.pins.into_iter()
+                .filter_map(|pin| Some((pin.shortcut.parse::<Shortcut>().ok()?, pin.note_id)))
+                .collect(),
+            Err(e) => {
+                warn!("Failed to load pinned pastes: {}", e);
+                return;
+            }
+        };
+        *self.pins.lock().unwrap_or_else(|e| e.into_inner()) = pins;
+    }
+
+    /// The pinned note `shortcut` pastes, if it's one of the pin shortcuts
+    pub fn note_for(&self, shortcut: &Shortcut) -> Option<String> {
+        self.pins.lock().unwrap_or_else(|e| e.into_inner()).iter()
+            .find(|(pinned, _)| pinned == shortcut)
+            .map(|(_, note_id)| note_id.clone())
+    }
+}
+
+/// Pin a note so a global shortcut pastes it into the frontmost app. Without a shortcut
+/// the first free one of CmdOrCtrl+Alt+1..9 is used. Re-pinning a note changes its
+/// shortcut. Returns the pin and the shortcut it replaced, if any, for unregistering.
+pub async fn pin_note_for_paste(note_id: String, shortcut: Option<String>) -> Result<(PinnedPaste, Option<String>), String> {
+    if !load_notes()?.notes.iter().any(|n| n.id == note_id) {
+        return Err(tr("error.note_not_found", &[&note_id]));
+    }
+
+    let mut database = load_pinned_pastes()?;
+    let replaced = database.pins.iter()
+        .position(|p| p.note_id == note_id)
+        .map(|index| database.pins.remove(index).shortcut);
+    let taken = |candidate: &str| {
+        let candidate = normalize_shortcut(candidate);
+        RESERVED_SHORTCUTS.iter().any(|s| normalize_shortcut(s) == candidate)
+            || database.pins.iter().any(|p| normalize_shortcut(&p.shortcut) == candidate)
+    };
+
+    let shortcut = match shortcut.map(|s| s.trim().to_string()).filter(|s| !s.is_empty()) {
+        Some(shortcut) if taken(&shortcut) => return Err(format!("Shortcut {} is already in use", shortcut)),
+        Some(shortcut) => shortcut,
+        None => DEFAULT_PIN_SHORTCUTS.iter()
+            .find(|s| !taken(s))
+            .map(|s| s.to_string())
+            .ok_or("All default paste shortcuts are in use - choose one or unpin a note")?,
+    };
+
+    let pin = PinnedPaste { note_id, shortcut, pinned_at: Utc::now() };
+    database.pins.push(pin.clone());
+    save_pinned_pastes(&database)?;
+    Ok((pin, replaced))
+}
+
+/// Remove a note's pin and return it, so its shortcut can be unregistered
+pub async fn unpin_note_for_paste(note_id: String) -> Result<PinnedPaste, String> {
+    let mut database = load_pinned_pastes()?;
+    let index = database.pins.iter()
+        .position(|p| p.note_id == note_id)
+        .ok_or_else(|| format!("Note {} is not pinned", note_id))?;
+    let pin = database.pins.remove(index);
+    save_pinned_pastes(&database)?;
+    Ok(pin)
+}
+
+/// Pins whose note still exists, in the order they were pinned
+pub async fn get_pinned_pastes() -> Result<Vec<PinnedPaste>, String> {
+    let notes = load_notes()?.notes;
+    Ok(load_pinned_pastes()?.pins.into_iter()
+        .filter(|p| notes.iter().any(|n| n.id == p.note_id))
+        .collect())
+}
+
+/// Send Cmd+`key` (macOS) or Ctrl+`key` to the frontmost app
+fn send_shortcut(key: char) -> Result<(), String> {
+    let mut enigo = Enigo::new(&Settings::default())
+        .map_err(|e| format!("Keyboard input unavailable: {}", e))?;
+    let modifier = if cfg!(target_os = "macos") { Key::Meta } else { Key::Control };
+    let pressed = enigo.key(modifier, Direction::Press)
+        .and_then(|_| enigo.key(Key::Unicode(key), Direction::Click));
+    // Always let go of the modifier, even if the click failed
+    let released = enigo.key(modifier, Direction::Release);
+    pressed.and(released).map_err(|e| format!("Failed to send keystroke: {}", e))
+}
+
+/// Put `text` on the clipboard, send the platform paste keystroke and then restore
+/// whatever text the clipboard held before
+pub fn inject_paste(text: String) -> Result<(), String> {
+    let mut clipboard = arboard::Clipboard::new()
+        .map_err(|e| tr("error.clipboard_unavailable", &[&e]))?;
+    let previous = clipboard.get_text().ok();
+    clipboard.set_text(text)
+        .map_err(|e| format!("Failed to copy to clipboard: {}", e))?;
+
+    std::thread::sleep(Duration::from_millis(PASTE_DELAY_MS));
+    send_shortcut('v')?;
+
+    std::thread::sleep(Duration::from_millis(RESTORE_DELAY_MS));
+    if let Some(previous) = previous {
+        let _ = clipboard.set_text(previous);
+    }
+    Ok(())
+}
+
+/// Text currently selected in the frontmost app, read by simulating a copy. The
+/// clipboard is restored afterwards.
+pub fn capture_selection() -> Result<String, String> {
+    let mut clipboard = arboard::Clipboard::new()
+        .map_err(|e| tr("error.clipboard_unavailable", &[&e]))?;
+    let previous = clipboard.get_text().ok();
+    // Cleared first so an empty selection isn't mistaken for the old clipboard text
+    let _ = clipboard.clear();
+
+    std::thread::sleep(Duration::from_millis(PASTE_DELAY_MS));
+    send_shortcut('c')?;
+    std::thread::sleep(Duration::from_millis(COPY_DELAY_MS));
+    let selection = clipboard.get_text().unwrap_or_default();
+
+    if let Some(previous) = previous {
+        let _ = clipboard.set_text(previous);
+    }
+    let selection = selection.trim().to_string();
+    if selection.is_empty() {
+        return Err("No text is selected".to_string());
+    }
+    Ok(selection)
+}
+
+/// Paste a pinned note's content into the frontmost app
+pub async fn paste_pinned_note(note_id: String) -> Result<(), String> {
+    // Reached from the global shortcut and the tray as well, which the command gate doesn't cover
+    if is_locked() {
+        return Err(LOCKED_MESSAGE.to_string());
+    }
+    let database = load_notes()?;
+    let note = database.notes.iter()
+        .find(|n| n.id == note_id)
+        .ok_or_else(|| tr("error.note_not_found", &[&note_id]))?;
+    let text = note.content.clone();
+
+    tokio::task::spawn_blocking(move || inject_paste(text))
+        .await
+        .map_err(|e| format!("Paste task failed: {}", e))?
+}
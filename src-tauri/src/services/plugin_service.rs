@@ -0,0 +1,240 @@
+use rhai::{Array, Dynamic, Engine, Scope, AST};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+use tracing::{debug, error, info};
+use crate::models::Note;
+use crate::services::note_service::load_notes;
+use crate::services::storage_service::get_app_data_dir;
+
+/// Functions named `command_<name>` in a script are exposed as custom commands
+const COMMAND_PREFIX: &str = "command_";
+
+// Sandbox limits - a runaway script is stopped instead of hanging the app
+const MAX_OPERATIONS: u64 = 500_000;
+const MAX_CALL_LEVELS: usize = 32;
+const MAX_STRING_SIZE: usize = 1_000_000;
+const MAX_COLLECTION_SIZE: usize = 50_000;
+
+#[derive(Serialize, Clone)]
+pub struct PluginInfo {
+    pub name: String,
+    pub file_path: String,
+    pub hooks: Vec<String>,      // Which of on_note_save / on_import the script implements
+    pub commands: Vec<String>,
+    pub error: Option<String>,   // Compile error, if the script failed to load
+}
+
+struct LoadedPlugin {
+    name: String,
+    ast: AST,
+}
+
+/// Size and modification time of a script when it was compiled
+type ScriptStamp = (u64, Option<SystemTime>);
+type CompiledScript = (ScriptStamp, Result<Arc<LoadedPlugin>, String>);
+
+/// Compiled scripts (or why they didn't compile) by path. Hooks run on every note save,
+/// so a script is only compiled again once its file changes.
+static COMPILED: Mutex<Option<HashMap<PathBuf, CompiledScript>>> = Mutex::new(None);
+
+pub fn get_plugins_dir() -> Result<PathBuf, String> {
+    let mut path = get_app_data_dir()?;
+    path.push("plugins");
+    if !path.exists() {
+        fs::create_dir_all(&path)
+            .map_err(|e| format!("Failed to create plugins directory: {}", e))?;
+    }
+    Ok(path)
+}
+
+fn to_script_value<T: Serialize>(value: &T) -> Dynamic {
+    serde_json::to_value(value).ok()
+        .and_then(|json| rhai::serde::to_dynamic(json).ok())
+        .unwrap_or(Dynamic::UNIT)
+}
+
+/// Engine with no file or network access and only a read-only view of the vault
+fn sandboxed_engine(plugin_name: &str) -> Engine {
+    let mut engine = Engine::new();
+    engine.set_max_operations(MAX_OPERATIONS);
+    engine.set_max_call_levels(MAX_CALL_LEVELS);
+    engine.set_max_string_size(MAX_STRING_SIZE);
+    engine.set_max_array_size(MAX_COLLECTION_SIZE);
+    engine.set_max_map_size(MAX_COLLECTION_SIZE);
+
+    let name = plugin_name.to_string();
+    engine.on_print(move |text| info!("[plugin {}] {}", name, text));
+    let name = plugin_name.to_string();
+    engine.on_debug(move |text, _, _| debug!("[plugin {}] {}", name, text));
+
+    engine.register_fn("get_notes", || -> Array {
+        load_notes().map(|db| db.notes.iter().map(to_script_value).collect()).unwrap_or_default()
+    });
+    engine.register_fn("get_note", |id: &str| -> Dynamic {
+        load_notes().ok()
+            .and_then(|db| db.notes.into_iter().find(|n| n.id == id))
+            .map(|n| to_script_value(&n))
+            .unwrap_or(Dynamic::UNIT)
+    });
+    engine.register_fn("find_notes", |text: &str| -> Array {
+        let needle = text.to_lowercase();
+        load_notes().map(|db| db.notes.iter()
+            .filter(|n| n.title.to_lowercase().contains(&needle) || n.content.to_lowercase().contains(&needle))
+            .map(to_script_value)
+            .collect())
+            .unwrap_or_default()
+    });
+
+    engine
+}
+
+fn plugin_files() -> Result<Vec<PathBuf>, String> {
+    let dir = get_plugins_dir()?;
+    let mut files: Vec<PathBuf> = fs::read_dir(&dir)
+        .map_err(|e| format!("Failed to read plugins directory: {}", e))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().map(|ext| ext == "rhai").unwrap_or(false))
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+fn compile_plugin(path: &PathBuf) -> Result<LoadedPlugin, String> {
+    let name = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+    let source = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read plugin {}: {}", name, e))?;
+    let ast = sandboxed_engine(&name).compile(&source)
+        .map_err(|e| format!("Failed to compile plugin {}: {}", name, e))?;
+    Ok(LoadedPlugin { name, ast })
+}
+
+/// Every plugin that compiles - broken scripts are reported by `list_plugins` and skipped
+/// here (logged once per change to the file)
+fn load_plugins() -> Vec<Arc<LoadedPlugin>> {
+    let files = match plugin_files() {
+        Ok(files) => files,
+        Err(e) => {
+            error!("{}", e);
+            return Vec::new();
+        }
+    };
+
+    let mut guard = COMPILED.lock().unwrap_or_else(|e| e.into_inner());
+    let compiled = guard.get_or_insert_with(HashMap::new);
+    compiled.retain(|path, _| files.contains(path));
+    files.into_iter().filter_map(|path| {
+        let meta = fs::metadata(&path).ok();
+        let stamp = (meta.as_ref().map_or(0, |m| m.len()), meta.and_then(|m| m.modified().ok()));
+        if !compiled.get(&path).is_some_and(|(cached, _)| *cached == stamp) {
+            let plugin = compile_plugin(&path).map(Arc::new);
+            if let Err(e) = &plugin {
+                error!("{}", e);
+            }
+            compiled.insert(path.clone(), (stamp, plugin));
+        }
+        compiled.get(&path).and_then(|(_, plugin)| plugin.as_ref().ok().cloned())
+    }).collect()
+}
+
+fn has_function(ast: &AST, name: &str) -> bool {
+    ast.iter_functions().any(|f| f.name == name)
+}
+
+fn call_hook(plugin: &LoadedPlugin, hook: &str, argument: Dynamic) -> Result<Dynamic, String> {
+    let engine = sandboxed_engine(&plugin.name);
+    let mut scope = Scope::new();
+    engine.call_fn::<Dynamic>(&mut scope, &plugin.ast, hook, (argument,))
+        .map_err(|e| format!("Plugin {} failed in {}: {}", plugin.name, hook, e))
+}
+
+fn note_from_script(value: Dynamic) -> Result<Note, String> {
+    let json: serde_json::Value = rhai::serde::from_dynamic(&value)
+        .map_err(|e| format!("Hook returned an invalid note: {}", e))?;
+    serde_json::from_value(json)
+        .map_err(|e| format!("Hook returned an invalid note: {}", e))
+}
+
+/// Run every `on_note_save(note)` hook in turn. A hook may return a modified note map,
+/// or nothing to leave it unchanged. Failing hooks are logged and never block the save.
+pub fn run_note_save_hooks(note: &mut Note) {
+    for plugin in load_plugins().iter().filter(|p| has_function(&p.ast, "on_note_save")) {
+        let result = call_hook(plugin, "on_note_save", to_script_value(note))
+            .and_then(|value| if value.is_unit() { Ok(None) } else { note_from_script(value).map(Some) });
+        match result {
+            Ok(Some(changed)) => {
+                // Plugins may only change the note's contents - identity, revision,
+                // visibility and AI exclusion stay with the app
+                note.title = changed.title;
+                note.content = changed.content;
+                note.tags = changed.tags;
+                note.metadata = changed.metadata;
+            }
+            Ok(None) => {}
+            Err(e) => error!("{}", e),
+        }
+    }
+}
+
+/// Run every `on_import(notes)` hook over a batch of imported notes. A hook may return
+/// a (filtered or modified) array of notes, or nothing to keep the batch as-is.
+pub fn run_import_hooks(notes: &mut Vec<Note>) {
+    for plugin in load_plugins().iter().filter(|p| has_function(&p.ast, "on_import")) {
+        let batch: Array = notes.iter().map(to_script_value).collect();
+        let result = call_hook(plugin, "on_import", batch.into()).and_then(|value| {
+            if value.is_unit() {
+                return Ok(None);
+            }
+            let array = value.try_cast::<Array>()
+                .ok_or_else(|| format!("Plugin {} returned something other than an array from on_import", plugin.name))?;
+            array.into_iter().map(note_from_script).collect::<Result<Vec<_>, _>>().map(Some)
+        });
+        match result {
+            Ok(Some(changed)) => *notes = changed,
+            Ok(None) => {}
+            Err(e) => error!("{}", e),
+        }
+    }
+}
+
+pub async fn list_plugins() -> Result<Vec<PluginInfo>, String> {
+    let mut plugins = Vec::new();
+    for path in plugin_files()? {
+        let name = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+        let file_path = path.to_string_lossy().to_string();
+        match compile_plugin(&path) {
+            Ok(plugin) => {
+                let hooks = ["on_note_save", "on_import"].iter()
+                    .filter(|hook| has_function(&plugin.ast, hook))
+                    .map(|hook| hook.to_string())
+                    .collect();
+                let commands = plugin.ast.iter_functions()
+                    .filter_map(|f| f.name.strip_prefix(COMMAND_PREFIX).map(|c| c.to_string()))
+                    .collect();
+                plugins.push(PluginInfo { name, file_path, hooks, commands, error: None });
+            }
+            Err(e) => plugins.push(PluginInfo { name, file_path, hooks: Vec::new(), commands: Vec::new(), error: Some(e) }),
+        }
+    }
+    Ok(plugins)
+}
+
+/// Run a plugin's custom command (`command_<name>(args)`) and return its result as JSON
+pub async fn run_plugin_command(plugin: String, command: String, args: Option<serde_json::Value>) -> Result<serde_json::Value, String> {
+    let loaded = load_plugins().into_iter()
+        .find(|p| p.name == plugin)
+        .ok_or_else(|| format!("Plugin {} not found or failed to load", plugin))?;
+
+    let function = format!("{}{}", COMMAND_PREFIX, command);
+    if !has_function(&loaded.ast, &function) {
+        return Err(format!("Plugin {} has no command {}", plugin, command));
+    }
+
+    let argument = to_script_value(&args.unwrap_or(serde_json::Value::Null));
+    let result = call_hook(&loaded, &function, argument)?;
+    rhai::serde::from_dynamic(&result)
+        .map_err(|e| format!("Plugin {} returned a value that can't be sent back: {}", plugin, e))
+}
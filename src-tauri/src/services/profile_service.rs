@@ -0,0 +1,72 @@
+use chrono::Utc;
+use uuid::Uuid;
+use crate::models::{Note, Profile};
+use crate::services::note_service::load_notes;
+use crate::services::storage_service::{load_profiles, save_profiles};
+
+/// Id of the profile new notes are attributed to. Errors reading the profiles file are
+/// treated as "no profile" - saving a note must not fail over attribution.
+pub fn active_profile_id() -> Option<String> {
+    load_profiles().ok()?.active_profile_id
+}
+
+/// Attribute a note that doesn't have an author yet to the active profile
+pub fn attribute_note(note: &mut Note) {
+    if note.author.is_none() {
+        note.author = active_profile_id();
+    }
+}
+
+pub async fn list_profiles() -> Result<Vec<Profile>, String> {
+    Ok(load_profiles()?.profiles)
+}
+
+pub async fn get_active_profile() -> Result<Option<Profile>, String> {
+    let database = load_profiles()?;
+    let active = database.active_profile_id;
+    Ok(database.profiles.into_iter().find(|p| Some(&p.id) == active.as_ref()))
+}
+
+/// Add a profile. The first one becomes active right away so notes start being attributed.
+pub async fn create_profile(name: String) -> Result<Profile, String> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("Profile name cannot be empty".to_string());
+    }
+
+    let mut database = load_profiles()?;
+    if database.profiles.iter().any(|p| p.name.eq_ignore_ascii_case(&name)) {
+        return Err(format!("A profile named '{}' already exists", name));
+    }
+
+    let profile = Profile {
+        id: Uuid::new_v4().to_string(),
+        name,
+        created_at: Utc::now(),
+    };
+    database.profiles.push(profile.clone());
+    if database.active_profile_id.is_none() {
+        database.active_profile_id = Some(profile.id.clone());
+    }
+    save_profiles(&database)?;
+    Ok(profile)
+}
+
+/// Make `profile_id` the author of notes created from now on
+pub async fn switch_profile(profile_id: String) -> Result<Profile, String> {
+    let mut database = load_profiles()?;
+    let profile = database.profiles.iter()
+        .find(|p| p.id == profile_id)
+        .cloned()
+        .ok_or_else(|| format!("Profile {} not found", profile_id))?;
+    database.active_profile_id = Some(profile.id.clone());
+    save_profiles(&database)?;
+    Ok(profile)
+}
+
+/// Notes created under `author` (a profile id), or with `None` the unattributed ones
+pub async fn get_notes_by_author(author: Option<String>) -> Result<Vec<Note>, String> {
+    Ok(load_notes()?.notes.into_iter()
+        .filter(|note| note.author == author)
+        .collect())
+}
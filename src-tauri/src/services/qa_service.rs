@@ -0,0 +1,64 @@
+use chrono::Utc;
+use crate::models::{Note, QaExchange, QaThread, WebhookEvent};
+use crate::services::note_service::{load_notes, save_note_simplified};
+use crate::services::stats_service::{record_activity, word_count};
+use crate::services::storage_service::save_notes;
+use crate::services::webhook_service::{emit_webhook_event, note_payload};
+use crate::services::locale_service::tr;
+
+fn exchange(question: String, answer: String, model: Option<String>) -> QaExchange {
+    QaExchange {
+        question: question.trim().to_string(),
+        answer: answer.trim().to_string(),
+        model: model.filter(|m| !m.trim().is_empty()),
+        asked_at: Utc::now(),
+    }
+}
+
+/// Capture a chat answer as a structured Q&A note
+pub async fn save_qa_note(question: String, answer: String, model: Option<String>, category_path: Option<Vec<String>>) -> Result<Note, String> {
+    if question.trim().is_empty() {
+        return Err(tr("error.question_empty", &[]));
+    }
+
+    let thread = QaThread { exchanges: vec![exchange(question, answer, model)] };
+    let saved = save_note_simplified(thread.render(), category_path, None).await?.note;
+
+    let mut database = load_notes()?;
+    let note = database.notes.iter_mut()
+        .find(|n| n.id == saved.id)
+        .ok_or_else(|| tr("error.note_not_found", &[&saved.id]))?;
+    note.qa = Some(thread);
+    let updated = note.clone();
+    save_notes(&database)?;
+    Ok(updated)
+}
+
+/// Add a follow-up exchange to an existing Q&A note, keeping the whole thread in one note
+pub async fn append_followup(note_id: String, question: String, answer: String, model: Option<String>) -> Result<Note, String> {
+    if question.trim().is_empty() {
+        return Err(tr("error.question_empty", &[]));
+    }
+
+    let mut database = load_notes()?;
+    let note = database.notes.iter_mut()
+        .find(|n| n.id == note_id)
+        .ok_or_else(|| tr("error.note_not_found", &[&note_id]))?;
+
+    // A plain note with Q&A-shaped text can still grow a thread
+    let mut thread = note.qa.take()
+        .or_else(|| QaThread::parse(&note.content, None, note.timestamp))
+        .ok_or("This note is not a Q&A note")?;
+
+    let previous_words = word_count(&note.content);
+    thread.exchanges.push(exchange(question, answer, model));
+    note.content = thread.render();
+    note.qa = Some(thread);
+    note.revision += 1;
+
+    let updated = note.clone();
+    save_notes(&database)?;
+    record_activity(0, word_count(&updated.content) as i64 - previous_words as i64);
+    emit_webhook_event(WebhookEvent::NoteUpdated, note_payload(&updated));
+    Ok(updated)
+}
@@ -0,0 +1,53 @@
+use serde::Serialize;
+use std::sync::{Mutex, OnceLock};
+use crate::services::ai_service::ask_ai;
+
+/// The popover's question and answer, kept until the next question
+static LAST_ASK: Mutex<Option<QuickAsk>> = Mutex::new(None);
+
+/// Called after every change to the question. main.rs installs one that emits `quick-ask:updated`.
+type AskListener = Box<dyn Fn(&QuickAsk) + Send + Sync>;
+static ASK_LISTENER: OnceLock<AskListener> = OnceLock::new();
+
+#[derive(Serialize, Clone)]
+pub struct QuickAsk {
+    pub question: String,
+    pub response_type: String,
+    pub answer: Option<String>, // None while the AI is still answering
+    pub error: Option<String>,
+}
+
+fn publish(ask: QuickAsk) {
+    *LAST_ASK.lock().unwrap_or_else(|e| e.into_inner()) = Some(ask.clone());
+    if let Some(listener) = ASK_LISTENER.get() {
+        listener(&ask);
+    }
+}
+
+pub fn set_quick_ask_listener(listener: impl Fn(&QuickAsk) + Send + Sync + 'static) {
+    let _ = ASK_LISTENER.set(Box::new(listener));
+}
+
+/// Ask from the tray popover. The pending and then the answered question are published,
+/// so the answer survives the popover being dismissed and reopened meanwhile.
+pub async fn quick_ask(question: String, response_type: Option<String>) -> Result<QuickAsk, String> {
+    let question = question.trim().to_string();
+    if question.is_empty() {
+        return Err("Nothing to ask".to_string());
+    }
+    let response_type = response_type.unwrap_or_else(|| "brief".to_string());
+
+    let mut ask = QuickAsk { question: question.clone(), response_type: response_type.clone(), answer: None, error: None };
+    publish(ask.clone());
+    match ask_ai(question, Some(response_type)).await {
+        Ok(answer) => ask.answer = Some(answer),
+        Err(e) => ask.error = Some(e),
+    }
+    publish(ask.clone());
+    Ok(ask)
+}
+
+/// What the popover should be showing, if anything was asked yet
+pub async fn get_quick_ask() -> Result<Option<QuickAsk>, String> {
+    Ok(LAST_ASK.lock().unwrap_or_else(|e| e.into_inner()).clone())
+}
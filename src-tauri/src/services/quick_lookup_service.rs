@@ -0,0 +1,68 @@
+use serde::Serialize;
+use std::sync::{Mutex, OnceLock};
+use crate::services::ai_service::ask_ai;
+use crate::services::paste_service::capture_selection;
+
+/// Longest selection sent to the AI - anything longer is cut, not rejected
+const MAX_SELECTION_CHARS: usize = 2000;
+
+/// The lookup shown in the popup window, replaced by every new one
+static CURRENT_LOOKUP: Mutex<Option<QuickLookup>> = Mutex::new(None);
+
+/// Called after every change to the lookup. main.rs installs one that emits `quick-lookup:updated`.
+type LookupListener = Box<dyn Fn(&QuickLookup) + Send + Sync>;
+static LOOKUP_LISTENER: OnceLock<LookupListener> = OnceLock::new();
+
+#[derive(Serialize, Clone)]
+pub struct QuickLookup {
+    pub selection: String,
+    pub question: String,       // What was asked - saved as the note's question
+    pub answer: Option<String>, // None while the AI is still answering
+    pub error: Option<String>,
+}
+
+fn publish(lookup: QuickLookup) {
+    *CURRENT_LOOKUP.lock().unwrap_or_else(|e| e.into_inner()) = Some(lookup.clone());
+    if let Some(listener) = LOOKUP_LISTENER.get() {
+        listener(&lookup);
+    }
+}
+
+pub fn set_quick_lookup_listener(listener: impl Fn(&QuickLookup) + Send + Sync + 'static) {
+    let _ = LOOKUP_LISTENER.set(Box::new(listener));
+}
+
+/// Copy the current selection out of the frontmost app. Must run before the popup takes focus.
+pub async fn read_selection() -> Result<String, String> {
+    tokio::task::spawn_blocking(capture_selection)
+        .await
+        .map_err(|e| format!("Selection task failed: {}", e))?
+}
+
+/// Ask the AI to define/explain `selection`, publishing the pending and then the answered
+/// lookup so the popup can show progress
+pub async fn quick_lookup(selection: String) -> Result<QuickLookup, String> {
+    let selection: String = selection.trim().chars().take(MAX_SELECTION_CHARS).collect();
+    if selection.is_empty() {
+        return Err("Nothing to look up".to_string());
+    }
+    let question = if selection.split_whitespace().count() <= 3 {
+        format!("Define \"{}\"", selection)
+    } else {
+        format!("Explain this:\n\n{}", selection)
+    };
+
+    let mut lookup = QuickLookup { selection, question: question.clone(), answer: None, error: None };
+    publish(lookup.clone());
+    match ask_ai(question, Some("brief".to_string())).await {
+        Ok(answer) => lookup.answer = Some(answer),
+        Err(e) => lookup.error = Some(e),
+    }
+    publish(lookup.clone());
+    Ok(lookup)
+}
+
+/// The lookup the popup should be showing, if any
+pub async fn get_quick_lookup() -> Result<Option<QuickLookup>, String> {
+    Ok(CURRENT_LOOKUP.lock().unwrap_or_else(|e| e.into_inner()).clone())
+}
@@ -0,0 +1,218 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::sync::Mutex;
+use crate::models::Note;
+use crate::services::category_service::load_categories;
+use crate::services::note_service::load_notes;
+use crate::services::storage_service::store_generation;
+
+/// Metadata key holding a note's other names (see rename_service)
+const ALIASES_KEY: &str = "aliases";
+const DEFAULT_LIMIT: usize = 20;
+const MAX_LIMIT: usize = 200;
+
+#[derive(Serialize, Clone, Copy, PartialEq)]
+pub enum SwitchTarget {
+    Note,
+    Category,
+}
+
+#[derive(Serialize, Clone, Copy, PartialEq)]
+pub enum SwitchField {
+    Title,
+    Alias,
+    Tag,
+    Category,
+}
+
+impl SwitchField {
+    /// Taken off the score, so a title match outranks an equally good alias or tag match
+    fn penalty(self) -> i64 {
+        match self {
+            SwitchField::Title => 0,
+            SwitchField::Alias => 4,
+            SwitchField::Category => 8,
+            SwitchField::Tag => 12,
+        }
+    }
+}
+
+#[derive(Serialize, Clone)]
+pub struct SwitchResult {
+    pub target: SwitchTarget,
+    pub id: String, // Note id, or category id
+    pub title: String,
+    pub category_path: Vec<String>,
+    pub matched_field: SwitchField,
+    pub matched_text: String,
+    pub score: i64,
+}
+
+/// One searchable name, lowercased once when the index is built
+struct Term {
+    field: SwitchField,
+    text: String,
+    chars: Vec<char>,
+}
+
+struct Entry {
+    target: SwitchTarget,
+    id: String,
+    title: String,
+    category_path: Vec<String>,
+    created_at: DateTime<Utc>,
+    terms: Vec<Term>,
+}
+
+/// Every note and category with the names they can be found by, and the store
+/// generation it was built from
+struct SwitcherIndex {
+    generation: u64,
+    entries: Vec<Entry>,
+}
+
+static INDEX: Mutex<Option<SwitcherIndex>> = Mutex::new(None);
+
+fn term(field: SwitchField, text: &str) -> Option<Term> {
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+    Some(Term { field, text: text.to_string(), chars: text.to_lowercase().chars().collect() })
+}
+
+fn note_aliases(note: &Note) -> Vec<String> {
+    match note.metadata.get(ALIASES_KEY) {
+        Some(serde_json::Value::Array(aliases)) => aliases.iter()
+            .filter_map(|a| a.as_str().map(|s| s.to_string()))
+            .collect(),
+        Some(serde_json::Value::String(alias)) => vec![alias.clone()],
+        _ => Vec::new(),
+    }
+}
+
+fn build_index(generation: u64) -> Result<SwitcherIndex, String> {
+    let notes = load_notes()?.notes;
+    let categories = load_categories()?.categories;
+
+    let mut entries: Vec<Entry> = notes.into_iter()
+        .map(|note| {
+            let mut terms: Vec<Term> = term(SwitchField::Title, &note.title).into_iter().collect();
+            terms.extend(note_aliases(&note).iter().filter_map(|a| term(SwitchField::Alias, a)));
+            terms.extend(note.tags.iter().filter_map(|t| term(SwitchField::Tag, t.trim_start_matches('#'))));
+            Entry {
+                target: SwitchTarget::Note,
+                id: note.id,
+                title: note.title,
+                category_path: note.category_path,
+                created_at: note.timestamp,
+                terms,
+            }
+        })
+        .collect();
+    entries.extend(categories.into_iter().map(|category| Entry {
+        target: SwitchTarget::Category,
+        terms: term(SwitchField::Category, &category.name).into_iter().collect(),
+        id: category.id,
+        title: category.name,
+        category_path: category.path,
+        created_at: category.created_at,
+    }));
+    // Newest first, which is also the order for an empty query
+    entries.sort_by_key(|e| std::cmp::Reverse(e.created_at));
+
+    Ok(SwitcherIndex { generation, entries })
+}
+
+/// Build the index now if the notes or categories changed since it was last built.
+/// Also called in the background at launch so the first Cmd+K doesn't pay for it.
+pub fn warm_switcher_index() -> Result<(), String> {
+    let generation = store_generation();
+    let mut guard = INDEX.lock().unwrap_or_else(|e| e.into_inner());
+    if guard.as_ref().is_none_or(|index| index.generation != generation) {
+        *guard = Some(build_index(generation)?);
+    }
+    Ok(())
+}
+
+fn is_word_start(chars: &[char], at: usize) -> bool {
+    at == 0 || !chars[at - 1].is_alphanumeric()
+}
+
+/// Score `query` as a subsequence of `candidate` (both lowercased), or `None` if it isn't
+/// one - so "mtgn" finds "Meeting notes". Matches on word starts and runs of consecutive
+/// characters score higher, and exact and prefix matches get a bonus.
+fn fuzzy_score(query: &[char], candidate: &[char]) -> Option<i64> {
+    if query.len() > candidate.len() {
+        return None;
+    }
+    let mut score = 0;
+    let mut position = 0;
+    let mut previous: Option<usize> = None;
+    for &wanted in query {
+        let found = (position..candidate.len()).find(|&i| candidate[i] == wanted)?;
+        score += 16;
+        if previous.is_some_and(|p| p + 1 == found) {
+            score += 12;
+        }
+        if is_word_start(candidate, found) {
+            score += 10;
+        }
+        // Skipped characters cost a little, so tight matches win
+        score -= (found - position).min(8) as i64;
+        previous = Some(found);
+        position = found + 1;
+    }
+    if candidate == query {
+        score += 50;
+    } else if candidate.starts_with(query) {
+        score += 25;
+    }
+    // Among otherwise equal matches, the shorter name is the closer one
+    Some(score - (candidate.len() - query.len()).min(20) as i64 / 4)
+}
+
+fn result(entry: &Entry, term: Option<&Term>, score: i64) -> SwitchResult {
+    SwitchResult {
+        target: entry.target,
+        id: entry.id.clone(),
+        title: entry.title.clone(),
+        category_path: entry.category_path.clone(),
+        matched_field: term.map_or(SwitchField::Title, |t| t.field),
+        matched_text: term.map_or_else(|| entry.title.clone(), |t| t.text.clone()),
+        score,
+    }
+}
+
+/// Notes and categories matching `query`, best first, for the Cmd+K switcher. Matches
+/// note titles, aliases and tags, and category names. An empty query lists the newest notes.
+pub async fn quick_switch(query: String, limit: Option<usize>) -> Result<Vec<SwitchResult>, String> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    let query: Vec<char> = query.trim().to_lowercase().chars().collect();
+
+    warm_switcher_index()?;
+    let guard = INDEX.lock().unwrap_or_else(|e| e.into_inner());
+    let Some(index) = guard.as_ref() else { return Ok(Vec::new()) };
+
+    if query.is_empty() {
+        return Ok(index.entries.iter()
+            .filter(|entry| entry.target == SwitchTarget::Note)
+            .take(limit)
+            .map(|entry| result(entry, None, 0))
+            .collect());
+    }
+
+    let mut scored: Vec<(i64, &Entry, &Term)> = index.entries.iter()
+        .filter_map(|entry| entry.terms.iter()
+            .filter_map(|term| fuzzy_score(&query, &term.chars).map(|score| (score - term.field.penalty(), term)))
+            .max_by_key(|(score, _)| *score)
+            .map(|(score, term)| (score, entry, term)))
+        .collect();
+    // Stable sort keeps newer entries ahead on equal scores
+    scored.sort_by_key(|s| std::cmp::Reverse(s.0));
+
+    Ok(scored.into_iter()
+        .take(limit)
+        .map(|(score, entry, term)| result(entry, Some(term), score))
+        .collect())
+}
@@ -0,0 +1,75 @@
+use chrono::Utc;
+use crate::models::{Note, ReadingState};
+use crate::services::note_service::load_notes;
+use crate::services::storage_service::save_notes;
+use crate::services::locale_service::tr;
+
+/// The page a note was clipped from: its capture source, else a feed item's link
+pub fn note_source_url(note: &Note) -> Option<&str> {
+    note.provenance.as_ref().and_then(|p| p.url.as_deref())
+        .or_else(|| note.metadata.get("source_url").and_then(|v| v.as_str()))
+        .filter(|url| !url.trim().is_empty())
+}
+
+fn update_reading(note_id: &str, change: impl FnOnce(&mut Note) -> Result<(), String>) -> Result<Note, String> {
+    let mut database = load_notes()?;
+    let note = database.notes.iter_mut()
+        .find(|n| n.id == note_id)
+        .ok_or_else(|| tr("error.note_not_found", &[&note_id]))?;
+    change(note)?;
+    note.revision += 1;
+    let updated = note.clone();
+    save_notes(&database)?;
+    Ok(updated)
+}
+
+/// Put a clipped article in the read-later queue. Re-queuing a read note starts it over.
+pub async fn enqueue_for_reading(note_id: String) -> Result<Note, String> {
+    update_reading(&note_id, |note| {
+        if note_source_url(note).is_none() {
+            return Err("Only notes with a source URL can be queued for reading".to_string());
+        }
+        if note.reading.as_ref().is_none_or(|r| r.read_at.is_some()) {
+            note.reading = Some(ReadingState { queued_at: Utc::now(), progress: 0, read_at: None });
+        }
+        Ok(())
+    })
+}
+
+pub async fn remove_from_reading_queue(note_id: String) -> Result<Note, String> {
+    update_reading(&note_id, |note| {
+        note.reading = None;
+        Ok(())
+    })
+}
+
+/// Unread notes in the queue, oldest first
+pub async fn get_reading_queue() -> Result<Vec<Note>, String> {
+    let mut queue: Vec<Note> = load_notes()?.notes.into_iter()
+        .filter(|n| n.reading.as_ref().is_some_and(|r| r.read_at.is_none()))
+        .collect();
+    queue.sort_by_key(|n| n.reading.as_ref().map(|r| r.queued_at));
+    Ok(queue)
+}
+
+pub async fn set_reading_progress(note_id: String, pct: u8) -> Result<Note, String> {
+    if pct > 100 {
+        return Err("Reading progress is a percentage from 0 to 100".to_string());
+    }
+    update_reading(&note_id, |note| {
+        let reading = note.reading.as_mut().ok_or("This note is not in the reading queue")?;
+        reading.progress = pct;
+        reading.read_at = (pct == 100).then(Utc::now);
+        Ok(())
+    })
+}
+
+/// Finish a queued note. It leaves the queue but keeps when it was read.
+pub async fn mark_read(note_id: String) -> Result<Note, String> {
+    update_reading(&note_id, |note| {
+        let reading = note.reading.as_mut().ok_or("This note is not in the reading queue")?;
+        reading.progress = 100;
+        reading.read_at = Some(Utc::now());
+        Ok(())
+    })
+}
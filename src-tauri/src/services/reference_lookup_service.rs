@@ -0,0 +1,219 @@
+use regex::Regex;
+use serde_json::Value;
+use std::time::Duration;
+use crate::models::{Citation, Note};
+use crate::services::category_service::ensure_category_path;
+use crate::services::citation_service::{find_existing_reference, reference_note};
+use crate::services::import_service::decode_html_entities;
+use crate::services::note_service::{insert_note, load_notes};
+
+const LOOKUP_TIMEOUT_SECS: u64 = 20;
+
+enum ReferenceId {
+    Doi(String),
+    Arxiv(String),
+    Isbn(String),
+}
+
+/// Recognise a DOI, arXiv id or ISBN, with or without the usual prefixes/URLs
+fn parse_identifier(identifier: &str) -> Result<ReferenceId, String> {
+    let value = identifier.trim();
+    let lower = value.to_lowercase();
+
+    for prefix in ["https://doi.org/", "http://doi.org/", "https://dx.doi.org/", "doi:"] {
+        if lower.starts_with(prefix) {
+            return Ok(ReferenceId::Doi(value[prefix.len()..].trim().to_string()));
+        }
+    }
+    for prefix in ["https://arxiv.org/abs/", "http://arxiv.org/abs/", "https://arxiv.org/pdf/", "arxiv:"] {
+        if lower.starts_with(prefix) {
+            return Ok(ReferenceId::Arxiv(value[prefix.len()..].trim().trim_end_matches(".pdf").to_string()));
+        }
+    }
+    if let Some(rest) = lower.strip_prefix("isbn:").or_else(|| lower.strip_prefix("isbn")) {
+        return Ok(ReferenceId::Isbn(rest.chars().filter(|c| c.is_ascii_alphanumeric()).collect::<String>().to_uppercase()));
+    }
+
+    if Regex::new(r"^10\.\d{4,9}/\S+$").expect("valid DOI pattern").is_match(value) {
+        return Ok(ReferenceId::Doi(value.to_string()));
+    }
+    let arxiv = Regex::new(r"^(\d{4}\.\d{4,5}(v\d+)?|[a-z\-]+(\.[A-Z]{2})?/\d{7}(v\d+)?)$").expect("valid arXiv pattern");
+    if arxiv.is_match(value) {
+        return Ok(ReferenceId::Arxiv(value.to_string()));
+    }
+    let isbn: String = value.chars().filter(|c| !matches!(c, '-' | ' ')).collect::<String>().to_uppercase();
+    if Regex::new(r"^(\d{9}[\dX]|\d{13})$").expect("valid ISBN pattern").is_match(&isbn) {
+        return Ok(ReferenceId::Isbn(isbn));
+    }
+
+    Err(format!("'{}' doesn't look like a DOI, arXiv id or ISBN", value))
+}
+
+async fn fetch(url: &str) -> Result<reqwest::Response, String> {
+    let response = reqwest::Client::new()
+        .get(url)
+        .header(reqwest::header::USER_AGENT, concat!("NowledgeBase/", env!("CARGO_PKG_VERSION")))
+        .timeout(Duration::from_secs(LOOKUP_TIMEOUT_SECS))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach {}: {}", url, e))?;
+    match response.status() {
+        status if status == reqwest::StatusCode::NOT_FOUND => Err("No record found for this identifier".to_string()),
+        status if !status.is_success() => Err(format!("Metadata lookup failed with status {}", status)),
+        _ => Ok(response),
+    }
+}
+
+fn strip_markup(text: &str) -> String {
+    let tags = Regex::new(r"<[^>]+>").expect("valid tag pattern");
+    let text = decode_html_entities(&tags.replace_all(text, " "));
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn json_str(value: &Value, key: &str) -> Option<String> {
+    match value.get(key)? {
+        Value::String(s) if !s.trim().is_empty() => Some(s.trim().to_string()),
+        Value::Array(items) => items.first().and_then(|v| v.as_str()).map(|s| s.trim().to_string()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// Crossref work record -> citation plus abstract
+async fn lookup_doi(doi: &str) -> Result<(Citation, String), String> {
+    let response = fetch(&format!("https://api.crossref.org/works/{}", doi)).await?;
+    let body: Value = response.json().await
+        .map_err(|e| format!("Failed to parse Crossref response: {}", e))?;
+    let work = body.get("message").ok_or("Crossref response had no record")?;
+
+    let authors = work.get("author").and_then(|a| a.as_array()).map(|authors| {
+        authors.iter().filter_map(|author| {
+            match (json_str(author, "family"), json_str(author, "given")) {
+                (Some(family), Some(given)) => Some(format!("{}, {}", family, given)),
+                (Some(family), None) => Some(family),
+                _ => json_str(author, "name"),
+            }
+        }).collect()
+    }).unwrap_or_default();
+    let year = ["issued", "published-print", "published-online"].iter()
+        .find_map(|key| work.get(*key)?.get("date-parts")?.get(0)?.get(0)?.as_i64())
+        .map(|y| y as i32);
+    let entry_type = match work.get("type").and_then(|t| t.as_str()) {
+        Some("journal-article") => "article",
+        Some("book") | Some("monograph") => "book",
+        Some("proceedings-article") => "inproceedings",
+        Some("book-chapter") => "incollection",
+        _ => "misc",
+    };
+
+    let citation = Citation {
+        entry_type: entry_type.to_string(),
+        authors,
+        title: json_str(work, "title").unwrap_or_default(),
+        year,
+        journal: json_str(work, "container-title"),
+        publisher: json_str(work, "publisher"),
+        volume: json_str(work, "volume"),
+        issue: json_str(work, "issue"),
+        pages: json_str(work, "page").map(|p| p.replace('-', "–")),
+        doi: Some(doi.to_string()),
+        url: json_str(work, "URL").or_else(|| Some(format!("https://doi.org/{}", doi))),
+        isbn: json_str(work, "ISBN"),
+        ..Default::default()
+    };
+    let summary = json_str(work, "abstract").map(|a| strip_markup(&a)).unwrap_or_default();
+    Ok((citation, summary))
+}
+
+/// arXiv's API answers in Atom, which the feed parser already understands
+async fn lookup_arxiv(id: &str) -> Result<(Citation, String), String> {
+    let response = fetch(&format!("https://export.arxiv.org/api/query?id_list={}", id)).await?;
+    let bytes = response.bytes().await
+        .map_err(|e| format!("Failed to read arXiv response: {}", e))?;
+    let feed = feed_rs::parser::parse(&bytes[..])
+        .map_err(|e| format!("Failed to parse arXiv response: {}", e))?;
+    let entry = feed.entries.into_iter().next()
+        .filter(|e| e.title.is_some())
+        .ok_or("No record found for this identifier")?;
+
+    let clean = |text: &str| text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let citation = Citation {
+        entry_type: "misc".to_string(),
+        authors: entry.authors.iter().map(|a| a.name.clone()).collect(),
+        title: entry.title.as_ref().map(|t| clean(&t.content)).unwrap_or_default(),
+        year: entry.published.and_then(|d| d.format("%Y").to_string().parse().ok()),
+        journal: Some(format!("arXiv:{}", id)),
+        url: Some(format!("https://arxiv.org/abs/{}", id)),
+        ..Default::default()
+    };
+    let summary = entry.summary.map(|s| clean(&s.content)).unwrap_or_default();
+    Ok((citation, summary))
+}
+
+async fn lookup_isbn(isbn: &str) -> Result<(Citation, String), String> {
+    let key = format!("ISBN:{}", isbn);
+    let response = fetch(&format!("https://openlibrary.org/api/books?bibkeys={}&format=json&jscmd=data", key)).await?;
+    let body: Value = response.json().await
+        .map_err(|e| format!("Failed to parse Open Library response: {}", e))?;
+    let book = body.get(&key).ok_or("No record found for this identifier")?;
+
+    let names = |field: &str| -> Vec<String> {
+        book.get(field).and_then(|v| v.as_array())
+            .map(|items| items.iter().filter_map(|i| json_str(i, "name")).collect())
+            .unwrap_or_default()
+    };
+    let title = match (json_str(book, "title"), json_str(book, "subtitle")) {
+        (Some(title), Some(subtitle)) => format!("{}: {}", title, subtitle),
+        (title, _) => title.unwrap_or_default(),
+    };
+    let year = json_str(book, "publish_date").and_then(|date| {
+        Regex::new(r"\d{4}").expect("valid year pattern").find(&date).and_then(|m| m.as_str().parse().ok())
+    });
+
+    let citation = Citation {
+        entry_type: "book".to_string(),
+        authors: names("authors"),
+        title,
+        year,
+        publisher: names("publishers").into_iter().next(),
+        pages: book.get("number_of_pages").and_then(|p| p.as_u64()).map(|p| p.to_string()),
+        url: json_str(book, "url"),
+        isbn: Some(isbn.to_string()),
+        ..Default::default()
+    };
+    let summary = book.get("excerpts").and_then(|e| e.get(0)).and_then(|e| json_str(e, "text")).unwrap_or_default();
+    Ok((citation, summary))
+}
+
+/// Fetch metadata for a DOI (Crossref), arXiv id (arXiv) or ISBN (Open Library) and create
+/// a reference note from it. If the work is already in the library that note is returned.
+pub async fn lookup_reference(identifier: String) -> Result<Note, String> {
+    let (mut citation, summary) = match parse_identifier(&identifier)? {
+        ReferenceId::Doi(doi) => lookup_doi(&doi).await?,
+        ReferenceId::Arxiv(id) => lookup_arxiv(&id).await?,
+        ReferenceId::Isbn(isbn) => lookup_isbn(&isbn).await?,
+    };
+
+    if citation.citation_key.is_empty() {
+        let author = citation.authors.first()
+            .map(|a| a.split([',', ' ']).find(|p| !p.is_empty()).unwrap_or("anon").to_lowercase())
+            .unwrap_or_else(|| "anon".to_string());
+        citation.citation_key = format!("{}{}", author, citation.year.map(|y| y.to_string()).unwrap_or_default());
+    }
+
+    let database = load_notes()?;
+    if let Some(existing) = find_existing_reference(&database.notes, &citation) {
+        // Generated keys collide between different works - only a DOI or title match is conclusive
+        let existing_citation = existing.citation.as_ref();
+        let same_doi = matches!((existing_citation.and_then(|c| c.doi.as_ref()), &citation.doi), (Some(a), Some(b)) if a.eq_ignore_ascii_case(b));
+        let same_title = existing_citation.is_some_and(|c| c.title.eq_ignore_ascii_case(&citation.title));
+        if same_doi || same_title {
+            return Ok(existing.clone());
+        }
+        citation.citation_key.push_str(&format!("-{}", &uuid::Uuid::new_v4().simple().to_string()[..4]));
+    }
+
+    let note = reference_note(citation, summary, Vec::new());
+    ensure_category_path(&note.category_path)?;
+    insert_note(note, false)
+}
@@ -0,0 +1,563 @@
+use chrono::{DateTime, Datelike, Duration, Local, LocalResult, Months, NaiveDate, NaiveTime, TimeZone, Utc, Weekday};
+use serde::Serialize;
+use crate::models::{Frequency, Note, NoteStatus, Recurrence};
+use crate::services::note_service::{load_notes, set_note_due_date};
+use crate::services::storage_service::save_notes;
+use crate::services::tray_service::{set_tray_badge, TrayBadge};
+use crate::services::locale_service::{format_date, tr};
+
+/// Time used when only a day is given ("tomorrow", "friday")
+const DEFAULT_HOUR: u32 = 9;
+
+/// Occurrences walked before giving up on a rule (27 years of a daily reminder)
+const MAX_OCCURRENCES: usize = 10_000;
+
+/// Look-ahead for `get_upcoming_reminders` when no range is given
+const DEFAULT_UPCOMING_DAYS: i64 = 7;
+
+/// A resolved natural-language date, shown back to the user before it's applied
+#[derive(Serialize, Clone)]
+pub struct ParsedDate {
+    pub timestamp: DateTime<Utc>,
+    pub has_time: bool,      // False when the default time of day was filled in
+    pub description: String, // "Tuesday, 20 October 2026 at 09:00" in local time
+}
+
+#[derive(Serialize, Clone)]
+pub struct UpcomingReminder {
+    pub note_id: String,
+    pub note_title: String,
+    pub due: DateTime<Utc>, // Next occurrence for recurring reminders
+    pub overdue: bool,
+    pub recurrence: Option<String>, // "every 2 weeks on Mon"
+    pub rrule: Option<String>,      // The same rule for calendar apps, "FREQ=WEEKLY;INTERVAL=2;BYDAY=MO"
+}
+
+fn parse_weekday(word: &str) -> Option<Weekday> {
+    match word {
+        "monday" | "mon" => Some(Weekday::Mon),
+        "tuesday" | "tue" | "tues" => Some(Weekday::Tue),
+        "wednesday" | "wed" => Some(Weekday::Wed),
+        "thursday" | "thu" | "thur" | "thurs" => Some(Weekday::Thu),
+        "friday" | "fri" => Some(Weekday::Fri),
+        "saturday" | "sat" => Some(Weekday::Sat),
+        "sunday" | "sun" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+fn parse_month(word: &str) -> Option<u32> {
+    const MONTHS: [&str; 12] = ["jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec"];
+    if word.len() < 3 {
+        return None;
+    }
+    let position = MONTHS.iter().position(|m| word.starts_with(m))?;
+    // "mar" and "march" are fine, "marble" is not
+    let full = ["january", "february", "march", "april", "may", "june", "july", "august", "september", "october", "november", "december"];
+    full[position].starts_with(word).then_some(position as u32 + 1)
+}
+
+/// "20", "20th", "1st"
+fn parse_day(word: &str) -> Option<u32> {
+    let digits = word.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+    let suffix = &word[digits.len()..];
+    if !matches!(suffix, "" | "st" | "nd" | "rd" | "th") {
+        return None;
+    }
+    digits.parse().ok().filter(|d| (1..=31).contains(d))
+}
+
+fn parse_number(word: &str) -> Option<i64> {
+    match word {
+        "a" | "an" | "one" => Some(1),
+        "two" => Some(2),
+        "three" => Some(3),
+        "four" => Some(4),
+        "five" => Some(5),
+        "six" => Some(6),
+        "seven" => Some(7),
+        "eight" => Some(8),
+        "nine" => Some(9),
+        "ten" => Some(10),
+        "couple" => Some(2),
+        "few" => Some(3),
+        _ => word.parse().ok(),
+    }
+}
+
+/// "9am", "9:30pm", "14:00", "9" followed by "am"/"pm", "noon", "midnight"
+fn parse_time(word: &str, next: Option<&str>) -> Option<(NaiveTime, usize)> {
+    match word {
+        "noon" | "midday" => return Some((NaiveTime::from_hms_opt(12, 0, 0)?, 1)),
+        "midnight" => return Some((NaiveTime::from_hms_opt(0, 0, 0)?, 1)),
+        "morning" => return Some((NaiveTime::from_hms_opt(9, 0, 0)?, 1)),
+        "afternoon" => return Some((NaiveTime::from_hms_opt(15, 0, 0)?, 1)),
+        "evening" => return Some((NaiveTime::from_hms_opt(18, 0, 0)?, 1)),
+        "night" => return Some((NaiveTime::from_hms_opt(20, 0, 0)?, 1)),
+        _ => {}
+    }
+
+    let (body, meridiem, used) = if let Some(body) = word.strip_suffix("am").or_else(|| word.strip_suffix("a.m.")) {
+        (body, Some(false), 1)
+    } else if let Some(body) = word.strip_suffix("pm").or_else(|| word.strip_suffix("p.m.")) {
+        (body, Some(true), 1)
+    } else {
+        match next {
+            Some("am" | "a.m.") => (word, Some(false), 2),
+            Some("pm" | "p.m.") => (word, Some(true), 2),
+            _ => (word, None, 1),
+        }
+    };
+
+    let (hour, minute) = match body.split_once(':') {
+        Some((h, m)) => (h.parse::<u32>().ok()?, m.parse::<u32>().ok()?),
+        // A bare number is only a time with am/pm ("9" alone could be a day)
+        None if meridiem.is_some() => (body.parse::<u32>().ok()?, 0),
+        None => return None,
+    };
+    let hour = match meridiem {
+        Some(_) if !(1..=12).contains(&hour) => return None,
+        Some(true) if hour < 12 => hour + 12,
+        Some(false) if hour == 12 => 0,
+        _ => hour,
+    };
+    NaiveTime::from_hms_opt(hour, minute, 0).map(|time| (time, used))
+}
+
+/// The next `weekday` strictly after `from`
+fn upcoming_weekday(from: NaiveDate, weekday: Weekday) -> NaiveDate {
+    let ahead = (weekday.num_days_from_monday() + 7 - from.weekday().num_days_from_monday()) % 7;
+    from + Duration::days(if ahead == 0 { 7 } else { ahead as i64 })
+}
+
+fn add_offset(from: DateTime<Local>, amount: i64, unit: &str) -> Option<DateTime<Local>> {
+    let unit = unit.trim_end_matches('s');
+    let months = |n: i64| -> Option<DateTime<Local>> {
+        if n >= 0 {
+            from.checked_add_months(Months::new(n as u32))
+        } else {
+            from.checked_sub_months(Months::new((-n) as u32))
+        }
+    };
+    match unit {
+        "min" | "minute" => Some(from + Duration::minutes(amount)),
+        "h" | "hr" | "hour" => Some(from + Duration::hours(amount)),
+        "day" => Some(from + Duration::days(amount)),
+        "week" | "wk" => Some(from + Duration::weeks(amount)),
+        "month" => months(amount),
+        "year" | "yr" => months(amount * 12),
+        _ => None,
+    }
+}
+
+/// Resolve `text` relative to `now`. Understands relative offsets ("in 3 days", "2 hours
+/// from now"), named days ("tomorrow", "next tuesday"), calendar dates ("oct 20",
+/// "20 october 2027", "2026-10-20") and times ("9am", "14:30", "noon") in any combination.
+pub fn parse_natural_date_from(text: &str, now: DateTime<Local>) -> Result<ParsedDate, String> {
+    let normalized = text.trim().to_lowercase().replace(',', " ");
+    let words: Vec<&str> = normalized.split_whitespace().collect();
+    if words.is_empty() {
+        return Err("Enter a date like \"tomorrow 9am\" or \"in 3 days\"".to_string());
+    }
+    let not_understood = |word: &str| format!("Couldn't understand '{}' in \"{}\"", word, text.trim());
+
+    let mut date: Option<NaiveDate> = None;
+    let mut time: Option<NaiveTime> = None;
+    // Relative offsets keep the current time of day unless a time is also given
+    let mut exact: Option<DateTime<Local>> = None;
+    let mut year_given = false;
+
+    let mut i = 0;
+    while i < words.len() {
+        let word = words[i];
+        let next = words.get(i + 1).copied();
+
+        match word {
+            "at" | "on" | "by" | "the" | "of" | "due" | "remind" | "me" => i += 1,
+            "now" => {
+                exact = Some(now);
+                i += 1;
+            }
+            "today" => {
+                date = Some(now.date_naive());
+                i += 1;
+            }
+            "tonight" => {
+                date = Some(now.date_naive());
+                time = time.or(NaiveTime::from_hms_opt(20, 0, 0));
+                i += 1;
+            }
+            "tomorrow" | "tmr" | "tmrw" => {
+                date = Some(now.date_naive() + Duration::days(1));
+                i += 1;
+            }
+            "yesterday" => {
+                date = Some(now.date_naive() - Duration::days(1));
+                i += 1;
+            }
+            "day" if next == Some("after") && words.get(i + 2) == Some(&"tomorrow") => {
+                date = Some(now.date_naive() + Duration::days(2));
+                i += 3;
+            }
+            "next" | "this" | "coming" => {
+                let target = next.ok_or_else(|| not_understood(word))?;
+                let today = now.date_naive();
+                if let Some(weekday) = parse_weekday(target) {
+                    date = Some(if word == "this" && today.weekday() == weekday { today } else { upcoming_weekday(today, weekday) });
+                } else {
+                    let offset = if word == "this" { 0 } else { 1 };
+                    let moved = add_offset(now, offset, target).ok_or_else(|| not_understood(target))?;
+                    date = Some(moved.date_naive());
+                }
+                i += 2;
+            }
+            "in" | "after" => {
+                let amount = next.and_then(parse_number).ok_or_else(|| not_understood(word))?;
+                let unit = words.get(i + 2).copied().ok_or_else(|| not_understood(word))?;
+                exact = Some(add_offset(exact.unwrap_or(now), amount, unit).ok_or_else(|| not_understood(unit))?);
+                i += 3;
+            }
+            _ => {
+                if let Some(weekday) = parse_weekday(word) {
+                    date = Some(upcoming_weekday(now.date_naive(), weekday));
+                    i += 1;
+                } else if let Ok(iso) = NaiveDate::parse_from_str(word, "%Y-%m-%d") {
+                    date = Some(iso);
+                    year_given = true;
+                    i += 1;
+                } else if let Some(month) = parse_month(word) {
+                    // "october 20", "oct 20th 2027"
+                    let day = next.and_then(parse_day).ok_or_else(|| not_understood(word))?;
+                    let year = words.get(i + 2).and_then(|y| y.parse::<i32>().ok()).filter(|y| *y >= 1000);
+                    year_given |= year.is_some();
+                    date = Some(NaiveDate::from_ymd_opt(year.unwrap_or(now.year()), month, day).ok_or_else(|| not_understood(word))?);
+                    i += if year.is_some() { 3 } else { 2 };
+                } else if let (Some(day), Some(month)) = (parse_day(word), next.and_then(parse_month)) {
+                    // "20 october", "1st jan 2027"
+                    let year = words.get(i + 2).and_then(|y| y.parse::<i32>().ok()).filter(|y| *y >= 1000);
+                    year_given |= year.is_some();
+                    date = Some(NaiveDate::from_ymd_opt(year.unwrap_or(now.year()), month, day).ok_or_else(|| not_understood(word))?);
+                    i += if year.is_some() { 3 } else { 2 };
+                } else if let Some((parsed, used)) = parse_time(word, next) {
+                    time = Some(parsed);
+                    i += used;
+                } else if let (Some(amount), Some(unit)) = (parse_number(word), next) {
+                    // "3 days from now", "2 hours later", "a week ago"
+                    let direction = match words.get(i + 2).copied() {
+                        Some("ago") => -1,
+                        Some("from") if words.get(i + 3) == Some(&"now") => 1,
+                        Some("later") => 1,
+                        _ => return Err(not_understood(word)),
+                    };
+                    exact = Some(add_offset(exact.unwrap_or(now), amount * direction, unit).ok_or_else(|| not_understood(unit))?);
+                    i += if words.get(i + 2) == Some(&"from") { 4 } else { 3 };
+                } else {
+                    return Err(not_understood(word));
+                }
+            }
+        }
+    }
+
+    // A named date in the past this year means next year ("jan 5" said in December)
+    if let Some(day) = date {
+        if !year_given && day < now.date_naive() && words.iter().any(|w| parse_month(w).is_some()) {
+            date = day.with_year(day.year() + 1);
+        }
+    }
+
+    let has_time = time.is_some() || exact.is_some();
+    let local = match (exact, date, time) {
+        (Some(exact), None, None) => exact,
+        (Some(exact), date, time) => {
+            let day = date.unwrap_or(exact.date_naive());
+            resolve_local(day, time.unwrap_or(exact.time()))?
+        }
+        (None, Some(day), time) => resolve_local(day, time.unwrap_or(NaiveTime::from_hms_opt(DEFAULT_HOUR, 0, 0).unwrap_or_default()))?,
+        (None, None, Some(time)) => {
+            // A bare time that has already passed today means tomorrow
+            let today = resolve_local(now.date_naive(), time)?;
+            if today <= now { resolve_local(now.date_naive() + Duration::days(1), time)? } else { today }
+        }
+        (None, None, None) => return Err(not_understood(words[0])),
+    };
+
+    Ok(ParsedDate {
+        timestamp: local.with_timezone(&Utc),
+        has_time,
+        description: format_date(&local, "date.long"),
+    })
+}
+
+/// Local wall-clock time to an instant. DST gaps move forward an hour; overlaps take the earlier.
+fn resolve_local(day: NaiveDate, time: NaiveTime) -> Result<DateTime<Local>, String> {
+    let naive = day.and_time(time);
+    match Local.from_local_datetime(&naive) {
+        LocalResult::Single(value) | LocalResult::Ambiguous(value, _) => Ok(value),
+        LocalResult::None => Local.from_local_datetime(&(naive + Duration::hours(1)))
+            .earliest()
+            .ok_or_else(|| format!("{} doesn't exist in the local time zone", naive)),
+    }
+}
+
+/// Parse a natural-language date for confirmation before it's used as a reminder
+pub async fn parse_natural_date(text: String) -> Result<ParsedDate, String> {
+    parse_natural_date_from(&text, Local::now())
+}
+
+/// Set a note's reminder (due date) from natural language, e.g. "next tuesday 9am"
+pub async fn set_note_reminder(note_id: String, text: String) -> Result<Note, String> {
+    let parsed = parse_natural_date_from(&text, Local::now())?;
+    set_note_due_date(note_id, Some(parsed.timestamp)).await
+}
+
+// ---------------------------------------------------------------------------
+// Recurrence
+// ---------------------------------------------------------------------------
+
+/// Walk the occurrences of a recurring due date in order, starting with `first` itself,
+/// until `visit` returns false or the rule runs out. Occurrences keep the local time of
+/// day of `first`; monthly/yearly rules on the 29th-31st fall on the month's last day.
+fn for_each_occurrence(first: DateTime<Utc>, rule: &Recurrence, mut visit: impl FnMut(DateTime<Utc>) -> bool) {
+    let start = first.with_timezone(&Local).naive_local();
+    let (start_date, start_time) = (start.date(), start.time());
+    let interval = rule.interval.max(1);
+    let mut emitted: usize = 0;
+
+    let mut weekdays = rule.weekdays.clone();
+    weekdays.sort_by_key(|d| d.num_days_from_monday());
+    let week_start = start_date - Duration::days(start_date.weekday().num_days_from_monday() as i64);
+
+    for period in 0..MAX_OCCURRENCES as u32 {
+        let step = period.saturating_mul(interval);
+        let dates: Vec<NaiveDate> = match rule.frequency {
+            Frequency::Daily => vec![start_date + Duration::days(step as i64)],
+            Frequency::Weekly if weekdays.is_empty() => vec![start_date + Duration::weeks(step as i64)],
+            Frequency::Weekly => weekdays.iter()
+                .map(|day| week_start + Duration::weeks(step as i64) + Duration::days(day.num_days_from_monday() as i64))
+                .filter(|date| *date >= start_date)
+                .collect(),
+            Frequency::Monthly => start_date.checked_add_months(Months::new(step)).into_iter().collect(),
+            Frequency::Yearly => start_date.checked_add_months(Months::new(step.saturating_mul(12))).into_iter().collect(),
+        };
+
+        for date in dates {
+            let Ok(local) = resolve_local(date, start_time) else { continue };
+            let occurrence = local.with_timezone(&Utc);
+            let past_until = rule.until.is_some_and(|until| occurrence > until);
+            let past_count = rule.count.is_some_and(|count| emitted >= count as usize);
+            if past_until || past_count || emitted >= MAX_OCCURRENCES || !visit(occurrence) {
+                return;
+            }
+            emitted += 1;
+        }
+    }
+}
+
+/// First occurrence at or after `after`
+pub fn next_occurrence(first: DateTime<Utc>, rule: &Recurrence, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let mut found = None;
+    for_each_occurrence(first, rule, |occurrence| {
+        if occurrence >= after {
+            found = Some(occurrence);
+        }
+        found.is_none()
+    });
+    found
+}
+
+/// The due date a note's reminder is currently at - for recurring reminders the latest
+/// occurrence that has come round by `now` (or the first one, if none has yet)
+pub fn current_due_date(note: &Note, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let first = note.due_date?;
+    let Some(rule) = &note.recurrence else { return Some(first) };
+    let mut latest = first;
+    for_each_occurrence(first, rule, |occurrence| {
+        if occurrence <= now {
+            latest = occurrence;
+        }
+        occurrence <= now
+    });
+    Some(latest)
+}
+
+/// Whether a note's reminder needs attention at `now`: a one-off that has passed and
+/// isn't done, or a recurring one with an occurrence earlier today
+pub fn is_reminder_due(note: &Note, now: DateTime<Utc>) -> bool {
+    let Some(first) = note.due_date else { return false };
+    match &note.recurrence {
+        None => first <= now && note.status != Some(NoteStatus::Done),
+        Some(_) => current_due_date(note, now).is_some_and(|due| {
+            due <= now && due.with_timezone(&Local).date_naive() == now.with_timezone(&Local).date_naive()
+        }),
+    }
+}
+
+/// Show or clear the tray badge for due reminders
+pub fn refresh_reminder_badge() -> Result<(), String> {
+    let now = Utc::now();
+    let due = load_notes()?.notes.iter().any(|n| is_reminder_due(n, now));
+    set_tray_badge(TrayBadge::RemindersDue, due);
+    Ok(())
+}
+
+/// Add, change or clear (`None`) a note's recurrence, e.g. "monthly", "every 2 weeks",
+/// "every monday" or "FREQ=WEEKLY;BYDAY=MO,TH". A note without a due date gets its first
+/// occurrence scheduled from today.
+pub async fn set_note_recurrence(note_id: String, rule: Option<String>) -> Result<Note, String> {
+    let rule = rule.filter(|r| !r.trim().is_empty()).map(|r| Recurrence::parse(&r)).transpose()?;
+
+    let mut database = load_notes()?;
+    let note = database.notes.iter_mut()
+        .find(|n| n.id == note_id)
+        .ok_or_else(|| tr("error.note_not_found", &[&note_id]))?;
+
+    if let (Some(rule), None) = (&rule, note.due_date) {
+        let now = Local::now();
+        let anchor = resolve_local(now.date_naive(), NaiveTime::from_hms_opt(DEFAULT_HOUR, 0, 0).unwrap_or_default())?;
+        // "Review every month" means the first review is a month away, not today
+        let mut first = None;
+        for_each_occurrence(anchor.with_timezone(&Utc), rule, |occurrence| {
+            if occurrence > now.with_timezone(&Utc) && occurrence != anchor.with_timezone(&Utc) {
+                first = Some(occurrence);
+            }
+            first.is_none()
+        });
+        note.due_date = Some(first.ok_or("This recurrence has no upcoming occurrence")?);
+    }
+    note.recurrence = rule;
+
+    note.revision += 1;
+    let updated = note.clone();
+    save_notes(&database)?;
+    Ok(updated)
+}
+
+/// Mark the current reminder as handled. A recurring reminder moves on to its next
+/// occurrence after now (or after the due date, when completed early); a one-off
+/// reminder (or a finished series) is cleared.
+pub async fn complete_reminder(note_id: String) -> Result<Note, String> {
+    let mut database = load_notes()?;
+    let note = database.notes.iter_mut()
+        .find(|n| n.id == note_id)
+        .ok_or_else(|| tr("error.note_not_found", &[&note_id]))?;
+    let first = note.due_date.ok_or("This note has no reminder")?;
+
+    let handled_until = Utc::now().max(first);
+    let next = match &note.recurrence {
+        Some(rule) => {
+            let mut skipped: u32 = 0;
+            let mut next = None;
+            for_each_occurrence(first, rule, |occurrence| {
+                if occurrence > handled_until {
+                    next = Some(occurrence);
+                } else {
+                    skipped += 1;
+                }
+                next.is_none()
+            });
+            // The series now starts at `next`, so a COUNT shrinks by what has passed
+            if let (Some(rule), Some(_)) = (note.recurrence.as_mut(), next) {
+                rule.count = rule.count.map(|count| count.saturating_sub(skipped));
+            }
+            next
+        }
+        None => None,
+    };
+
+    note.due_date = next;
+    if next.is_none() {
+        note.recurrence = None;
+    }
+    note.revision += 1;
+    let updated = note.clone();
+    save_notes(&database)?;
+    Ok(updated)
+}
+
+/// Reminders due within the next `days` (default a week), soonest first. One-off
+/// reminders that have passed are included as overdue unless the note is done;
+/// recurring ones show their next occurrence.
+pub async fn get_upcoming_reminders(days: Option<i64>) -> Result<Vec<UpcomingReminder>, String> {
+    let now = Utc::now();
+    let horizon = now + Duration::days(days.unwrap_or(DEFAULT_UPCOMING_DAYS).max(0));
+
+    let mut reminders: Vec<UpcomingReminder> = load_notes()?.notes.iter()
+        .filter_map(|note| {
+            let first = note.due_date?;
+            let (due, recurrence, rrule) = match &note.recurrence {
+                Some(rule) => (next_occurrence(first, rule, now)?, Some(rule.describe()), Some(rule.to_rrule())),
+                None if note.status == Some(NoteStatus::Done) => return None,
+                None => (first, None, None),
+            };
+            (due <= horizon).then(|| UpcomingReminder {
+                note_id: note.id.clone(),
+                note_title: note.title.clone(),
+                due,
+                overdue: due < now,
+                recurrence,
+                rrule,
+            })
+        })
+        .collect();
+
+    reminders.sort_by_key(|r| r.due);
+    Ok(reminders)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Thursday 15 October 2026, 14:00 local time
+    fn now() -> DateTime<Local> {
+        Local.with_ymd_and_hms(2026, 10, 15, 14, 0, 0).unwrap()
+    }
+
+    fn local(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        Local.with_ymd_and_hms(year, month, day, hour, minute, 0).unwrap().with_timezone(&Utc)
+    }
+
+    fn parse(text: &str) -> (DateTime<Utc>, bool) {
+        let parsed = parse_natural_date_from(text, now()).unwrap();
+        (parsed.timestamp, parsed.has_time)
+    }
+
+    #[test]
+    fn named_days_default_to_nine_in_the_morning() {
+        assert_eq!(parse("tomorrow"), (local(2026, 10, 16, 9, 0), false));
+        assert_eq!(parse("next tuesday"), (local(2026, 10, 20, 9, 0), false));
+        assert_eq!(parse("this thursday"), (local(2026, 10, 15, 9, 0), false));
+        assert_eq!(parse("friday"), (local(2026, 10, 16, 9, 0), false));
+    }
+
+    #[test]
+    fn dates_and_times_combine() {
+        assert_eq!(parse("tomorrow 9am"), (local(2026, 10, 16, 9, 0), true));
+        assert_eq!(parse("oct 20 at 14:30"), (local(2026, 10, 20, 14, 30), true));
+        assert_eq!(parse("20 october 2027 9:15 pm"), (local(2027, 10, 20, 21, 15), true));
+        assert_eq!(parse("2026-12-01 noon"), (local(2026, 12, 1, 12, 0), true));
+    }
+
+    #[test]
+    fn relative_offsets_count_from_now() {
+        assert_eq!(parse("in 3 days").0, (now() + Duration::days(3)).with_timezone(&Utc));
+        assert_eq!(parse("2 hours from now").0, (now() + Duration::hours(2)).with_timezone(&Utc));
+        assert_eq!(parse("in a week at 8am"), (local(2026, 10, 22, 8, 0), true));
+    }
+
+    #[test]
+    fn past_dates_and_times_roll_forward() {
+        // A month and day already gone this year means next year
+        assert_eq!(parse("jan 5"), (local(2027, 1, 5, 9, 0), false));
+        // A time already gone today means tomorrow
+        assert_eq!(parse("noon"), (local(2026, 10, 16, 12, 0), true));
+        assert_eq!(parse("6pm"), (local(2026, 10, 15, 18, 0), true));
+    }
+
+    #[test]
+    fn rejects_what_it_does_not_understand() {
+        for text in ["", "someday", "in three fortnights", "13pm", "feb 30"] {
+            assert!(parse_natural_date_from(text, now()).is_err(), "{:?} should be rejected", text);
+        }
+    }
+}
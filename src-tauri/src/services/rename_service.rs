@@ -0,0 +1,191 @@
+use serde::Serialize;
+use std::collections::HashSet;
+use crate::models::WebhookEvent;
+use crate::services::note_service::{apply_title_policy, load_notes};
+use crate::services::storage_service::{load_links, save_links, save_notes};
+use crate::services::webhook_service::{emit_webhook_event, note_payload};
+
+/// Metadata key holding a note's other names, as in Obsidian front matter
+const ALIASES_KEY: &str = "aliases";
+
+#[derive(Serialize, Clone, PartialEq)]
+pub enum RenameField {
+    Title,
+    Alias,
+    Wikilink, // A `[[...]]` in the note's content
+    LinkLabel,
+}
+
+#[derive(Serialize)]
+pub struct TermChange {
+    pub field: RenameField,
+    pub note_id: Option<String>, // Set for note fields
+    pub link_id: Option<String>, // Set for link labels
+    pub before: String,
+    pub after: String,
+}
+
+#[derive(Serialize)]
+pub struct TermRename {
+    pub changes: Vec<TermChange>,
+    pub applied: bool, // False for a dry run
+}
+
+fn is_word_char(c: Option<char>) -> bool {
+    c.is_some_and(|c| c.is_alphanumeric() || c == '_')
+}
+
+/// `text` with every whole-word, case-insensitive occurrence of `old` replaced by `new`,
+/// or None if there was none
+fn replace_term(text: &str, old: &str, new: &str) -> Option<String> {
+    let mut result = String::with_capacity(text.len());
+    let mut last = 0;
+    let mut search_from = 0;
+    while let Some((start, _)) = text[search_from..].char_indices()
+        .map(|(i, c)| (search_from + i, c))
+        .find(|(i, _)| text.get(*i..*i + old.len()).is_some_and(|s| s.eq_ignore_ascii_case(old)))
+    {
+        let end = start + old.len();
+        if !is_word_char(text[..start].chars().next_back()) && !is_word_char(text[end..].chars().next()) {
+            result.push_str(&text[last..start]);
+            result.push_str(new);
+            last = end;
+        }
+        search_from = end;
+    }
+    if last == 0 {
+        return None;
+    }
+    result.push_str(&text[last..]);
+    Some(result)
+}
+
+/// Replace the term inside every single-line `[[...]]` of `content` - targets, heading
+/// anchors and aliases alike - leaving the rest of the text alone. Returns the new
+/// content and each rewritten marker as (before, after).
+fn rename_in_wikilinks(content: &str, old: &str, new: &str) -> (String, Vec<(String, String)>) {
+    let mut result = String::with_capacity(content.len());
+    let mut changed = Vec::new();
+    let mut rest = content;
+    while let Some(start) = rest.find("[[") {
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("]]") else { break };
+        let inner = &after[..end];
+        result.push_str(&rest[..start + 2]);
+        match replace_term(inner, old, new).filter(|_| !inner.contains('\n')) {
+            Some(renamed) => {
+                changed.push((format!("[[{}]]", inner), format!("[[{}]]", renamed)));
+                result.push_str(&renamed);
+            }
+            None => result.push_str(inner),
+        }
+        result.push_str("]]");
+        rest = &after[end + 2..];
+    }
+    result.push_str(rest);
+    (result, changed)
+}
+
+/// Rename `old` to `new` across the vault, or within `scope` (a category and its
+/// subcategories): in note titles, aliases, wikilinks and the labels of links touching
+/// those notes. Only whole words are replaced. With `dry_run` nothing is saved and the
+/// changes that would be made are returned for review.
+pub async fn rename_term(old: String, new: String, scope: Option<Vec<String>>, dry_run: bool) -> Result<TermRename, String> {
+    let (old, new) = (old.trim().to_string(), new.trim().to_string());
+    if old.is_empty() || new.is_empty() {
+        return Err("Both the old and the new name are needed".to_string());
+    }
+    if old == new {
+        return Ok(TermRename { changes: Vec::new(), applied: false });
+    }
+    let in_scope = |path: &[String]| scope.as_ref().is_none_or(|scope| path.starts_with(scope));
+
+    let mut database = load_notes()?;
+    let mut changes = Vec::new();
+    let mut changed_notes = Vec::new();
+    for index in 0..database.notes.len() {
+        if !in_scope(&database.notes[index].category_path) {
+            continue;
+        }
+        let note_id = database.notes[index].id.clone();
+        let note_change = |field, before: &str, after: &str| TermChange {
+            field,
+            note_id: Some(note_id.clone()),
+            link_id: None,
+            before: before.to_string(),
+            after: after.to_string(),
+        };
+        let mut note_changes = Vec::new();
+
+        let new_title = match replace_term(&database.notes[index].title, &old, &new) {
+            Some(title) => {
+                let path = database.notes[index].category_path.clone();
+                let title = apply_title_policy(&database.notes, &note_id, &path, title, true).map_err(|c| c.to_string())?;
+                note_changes.push(note_change(RenameField::Title, &database.notes[index].title, &title));
+                Some(title)
+            }
+            None => None,
+        };
+
+        let note = &mut database.notes[index];
+        if let Some(title) = new_title {
+            note.title = title;
+            note.title_is_custom = true; // Otherwise the next edit would regenerate the old name
+        }
+        if let Some(serde_json::Value::Array(aliases)) = note.metadata.get_mut(ALIASES_KEY) {
+            for alias in aliases.iter_mut() {
+                let Some(text) = alias.as_str() else { continue };
+                if let Some(renamed) = replace_term(text, &old, &new) {
+                    note_changes.push(note_change(RenameField::Alias, text, &renamed));
+                    *alias = renamed.into();
+                }
+            }
+        }
+        let (content, wikilinks) = rename_in_wikilinks(&note.content, &old, &new);
+        if !wikilinks.is_empty() {
+            note.content = content;
+            note_changes.extend(wikilinks.iter().map(|(before, after)| note_change(RenameField::Wikilink, before, after)));
+        }
+
+        if !note_changes.is_empty() {
+            note.revision += 1;
+            changed_notes.push(index);
+            changes.extend(note_changes);
+        }
+    }
+
+    let scoped_ids: HashSet<&str> = database.notes.iter()
+        .filter(|n| in_scope(&n.category_path))
+        .map(|n| n.id.as_str())
+        .collect();
+    let mut links_db = load_links()?;
+    let mut links_changed = false;
+    for link in links_db.links.iter_mut().filter(|l| scoped_ids.contains(l.source_id.as_str()) || scoped_ids.contains(l.target_id.as_str())) {
+        let Some(label) = &link.label else { continue };
+        if let Some(renamed) = replace_term(label, &old, &new) {
+            changes.push(TermChange {
+                field: RenameField::LinkLabel,
+                note_id: None,
+                link_id: Some(link.id.clone()),
+                before: label.clone(),
+                after: renamed.clone(),
+            });
+            link.label = Some(renamed);
+            links_changed = true;
+        }
+    }
+
+    if dry_run || changes.is_empty() {
+        return Ok(TermRename { changes, applied: false });
+    }
+    if !changed_notes.is_empty() {
+        save_notes(&database)?;
+    }
+    if links_changed {
+        save_links(&links_db)?;
+    }
+    for index in changed_notes {
+        emit_webhook_event(WebhookEvent::NoteUpdated, note_payload(&database.notes[index]));
+    }
+    Ok(TermRename { changes, applied: true })
+}
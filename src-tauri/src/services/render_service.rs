@@ -0,0 +1,272 @@
+use chrono::Local;
+use base64::Engine;
+use pulldown_cmark::{html, CodeBlockKind, CowStr, Event, Options, Parser, Tag};
+use std::fs;
+use std::sync::OnceLock;
+use syntect::highlighting::ThemeSet;
+use syntect::html::highlighted_html_for_string;
+use syntect::parsing::SyntaxSet;
+use crate::models::{Note, NoteKind};
+use crate::services::attachment_service::attachment_file_path;
+use crate::services::embed_service::expand_embeds;
+use crate::services::export_service::slugify;
+use crate::services::markdown_service::{escape_html, rewrite_markdown_targets};
+use crate::services::note_service::{load_notes, resolve_note_reference};
+use crate::services::outline_service::extract_headings;
+use crate::services::locale_service::{format_date, tr};
+
+const CODE_THEME: &str = "InspiredGitHub";
+
+static SYNTAXES: OnceLock<SyntaxSet> = OnceLock::new();
+static THEMES: OnceLock<ThemeSet> = OnceLock::new();
+
+/// Inline every image attachment as a data URI so the HTML has no external files.
+/// Attachments are looked up on `note` first, then on `others` (for embedded content).
+pub fn inline_attachments(note: &Note, others: &[Note], content: &str) -> String {
+    rewrite_markdown_targets(content, &mut |target| {
+        let attachment_id = target.strip_prefix("attachment:")?;
+        let attachment = std::iter::once(note).chain(others)
+            .flat_map(|n| n.attachments.iter())
+            .find(|a| a.id == attachment_id)?;
+        if !attachment.mime_type.starts_with("image/") {
+            // Non-image files can't travel inside the page - link text stays, target is dropped
+            return Some("#".to_string());
+        }
+        let bytes = fs::read(attachment_file_path(attachment).ok()?).ok()?;
+        Some(format!("data:{};base64,{}", attachment.mime_type, base64::engine::general_purpose::STANDARD.encode(bytes)))
+    })
+}
+
+/// Turn `[[target#heading|alias]]` into regular links to `note:<id>`. Targets that don't
+/// resolve become a `wikilink-missing` span. Fenced code is left untouched.
+pub fn resolve_wikilinks(content: &str, notes: &[Note]) -> String {
+    let mut result = String::with_capacity(content.len());
+    let mut in_fence = false;
+
+    for line in content.split_inclusive('\n') {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+        }
+        if in_fence || !line.contains("[[") {
+            result.push_str(line);
+            continue;
+        }
+
+        let mut rest = line;
+        while let Some(start) = rest.find("[[") {
+            let Some(length) = rest[start + 2..].find("]]") else { break };
+            let inner = &rest[start + 2..start + 2 + length];
+            result.push_str(&rest[..start]);
+            rest = &rest[start + 2 + length + 2..];
+
+            // Embeds nested past the depth limit are shown as written
+            if result.ends_with('!') {
+                result.push_str(&format!("[[{}]]", inner));
+                continue;
+            }
+
+            let (target, alias) = match inner.split_once('|') {
+                Some((target, alias)) => (target.trim(), Some(alias.trim())),
+                None => (inner.trim(), None),
+            };
+            let (note_part, anchor) = match target.find(['#', '^']) {
+                Some(position) => (&target[..position], Some(&target[position..])),
+                None => (target, None),
+            };
+            let text = alias.unwrap_or(target);
+
+            match resolve_note_reference(notes, note_part) {
+                Some(note) => {
+                    let fragment = match anchor {
+                        Some(anchor) if anchor.starts_with('^') => format!("#{}", anchor),
+                        Some(anchor) => format!("#{}", slugify(&anchor[1..])),
+                        None => String::new(),
+                    };
+                    result.push_str(&format!("[{}](note:{}{})", text.replace(']', "\\]"), note.id, fragment));
+                }
+                None => result.push_str(&format!("<span class=\"wikilink-missing\">{}</span>", escape_html(text))),
+            }
+        }
+        result.push_str(rest);
+    }
+
+    result
+}
+
+/// Highlight a code block with inline styles, falling back to plain escaped code
+fn highlight_code(code: &str, language: &str) -> String {
+    let syntaxes = SYNTAXES.get_or_init(SyntaxSet::load_defaults_newlines);
+    let themes = THEMES.get_or_init(ThemeSet::load_defaults);
+
+    let syntax = syntaxes.find_syntax_by_token(language)
+        .unwrap_or_else(|| syntaxes.find_syntax_plain_text());
+    themes.themes.get(CODE_THEME)
+        .and_then(|theme| highlighted_html_for_string(code, syntaxes, syntax, theme).ok())
+        .unwrap_or_else(|| format!("<pre><code>{}</code></pre>", escape_html(code)))
+}
+
+/// Markdown to HTML with highlighted fenced code and heading ids matching `get_note_outline`
+pub fn markdown_to_rich_html(content: &str) -> String {
+    let mut options = Options::empty();
+    options.insert(Options::ENABLE_TABLES);
+    options.insert(Options::ENABLE_STRIKETHROUGH);
+    options.insert(Options::ENABLE_TASKLISTS);
+    options.insert(Options::ENABLE_FOOTNOTES);
+
+    let anchors: Vec<String> = extract_headings(content).into_iter().map(|h| h.anchor).collect();
+    let mut anchors = anchors.iter();
+    let mut code: Option<(String, String)> = None;
+    let mut events = Vec::new();
+
+    for event in Parser::new_ext(content, options) {
+        match event {
+            Event::Start(Tag::CodeBlock(kind)) => {
+                let language = match kind {
+                    CodeBlockKind::Fenced(info) => info.split_whitespace().next().unwrap_or("").to_string(),
+                    CodeBlockKind::Indented => String::new(),
+                };
+                code = Some((language, String::new()));
+            }
+            Event::Text(text) if code.is_some() => {
+                if let Some((_, body)) = code.as_mut() {
+                    body.push_str(&text);
+                }
+            }
+            Event::End(Tag::CodeBlock(_)) => {
+                if let Some((language, body)) = code.take() {
+                    events.push(Event::Html(CowStr::from(highlight_code(&body, &language))));
+                }
+            }
+            Event::Start(Tag::Heading(level, _, classes)) => {
+                let id = anchors.next().map(|a| a.as_str());
+                events.push(Event::Start(Tag::Heading(level, id, classes)));
+            }
+            event => events.push(event),
+        }
+    }
+
+    let mut output = String::new();
+    html::push_html(&mut output, events.into_iter());
+    output
+}
+
+/// Markdown reduced to readable plain text - markup dropped, paragraphs and list items kept
+pub fn markdown_to_plain_text(content: &str) -> String {
+    let mut output = String::new();
+    let mut list_depth: usize = 0;
+
+    for event in Parser::new_ext(content, Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS) {
+        match event {
+            Event::Text(text) | Event::Code(text) => output.push_str(&text),
+            Event::SoftBreak | Event::HardBreak => output.push('\n'),
+            Event::Start(Tag::List(_)) => list_depth += 1,
+            Event::End(Tag::List(_)) => {
+                list_depth = list_depth.saturating_sub(1);
+                if list_depth == 0 {
+                    output.push('\n');
+                }
+            }
+            Event::Start(Tag::Item) => {
+                if !output.is_empty() && !output.ends_with('\n') {
+                    output.push('\n');
+                }
+                output.push_str(&"  ".repeat(list_depth.saturating_sub(1)));
+                output.push_str("- ");
+            }
+            Event::TaskListMarker(done) => output.push_str(if done { "[x] " } else { "[ ] " }),
+            Event::End(Tag::Item) if !output.ends_with('\n') => output.push('\n'),
+            Event::End(Tag::Paragraph) if list_depth > 0 => {}
+            Event::End(Tag::Paragraph | Tag::Heading(..) | Tag::CodeBlock(_) | Tag::TableRow | Tag::TableHead) => {
+                output.push_str("\n\n");
+            }
+            Event::End(Tag::TableCell) => output.push('\t'),
+            _ => {}
+        }
+    }
+
+    output.trim().to_string()
+}
+
+/// Strip scripts, event handlers and unsafe URLs. Data URIs are only kept for images.
+pub fn sanitize_html(html: &str) -> String {
+    ammonia::Builder::default()
+        .add_url_schemes(&["note", "data"])
+        .add_tags(&["input"])
+        .add_tag_attributes("input", &["type", "checked", "disabled"])
+        .add_tag_attributes("span", &["style", "class"])
+        .add_tag_attributes("pre", &["style"])
+        .add_tag_attributes("h1", &["id"])
+        .add_tag_attributes("h2", &["id"])
+        .add_tag_attributes("h3", &["id"])
+        .add_tag_attributes("h4", &["id"])
+        .add_tag_attributes("h5", &["id"])
+        .add_tag_attributes("h6", &["id"])
+        .attribute_filter(|element, attribute, value| {
+            let image_source = element == "img" && attribute == "src" && value.starts_with("data:image/");
+            if value.starts_with("data:") && !image_source {
+                None
+            } else {
+                Some(value.into())
+            }
+        })
+        .clean(html)
+        .to_string()
+}
+
+/// The one renderer every window and export shares: embeds expanded, wikilinks resolved,
+/// attachments inlined, code highlighted, output sanitized
+pub fn render_note_html(note: &Note, notes: &[Note]) -> String {
+    render_note_html_with(note, notes, &mut |content| inline_attachments(note, notes, content))
+}
+
+/// `render_note_html`, with `finish_links` deciding where `note:` and `attachment:`
+/// targets in the prepared Markdown point (folder exports link to files instead)
+pub fn render_note_html_with(note: &Note, notes: &[Note], finish_links: &mut dyn FnMut(&str) -> String) -> String {
+    if note.kind == NoteKind::Snippet {
+        return sanitize_html(&highlight_code(&note.content, note.language.as_deref().unwrap_or("")));
+    }
+    let content = finish_links(&prepare_note_markdown(note, notes));
+    sanitize_html(&markdown_to_rich_html(&content))
+}
+
+/// A note's Markdown as it is rendered: embeds expanded, highlights and source appended,
+/// wikilinks turned into `note:` links. Attachments are still `attachment:` targets.
+pub fn prepare_note_markdown(note: &Note, notes: &[Note]) -> String {
+    let mut content = expand_embeds(notes, note);
+    if !note.highlights.is_empty() {
+        content.push_str("\n\n## Highlights\n");
+        for highlight in &note.highlights {
+            content.push_str(&format!("\n> {}\n", highlight.text.replace('\n', "\n> ")));
+            if let Some(location) = &highlight.location {
+                content.push_str(&format!(">\n> — {}\n", location));
+            }
+            if let Some(comment) = &highlight.comment {
+                content.push_str(&format!("\n{}\n", comment));
+            }
+        }
+    }
+    if let Some(line) = provenance_line(note) {
+        content.push_str(&format!("\n\n*{}*\n", line));
+    }
+    resolve_wikilinks(&content, notes)
+}
+
+/// "Source: Safari, https://..., captured 2026-10-14 09:30" for notes with provenance
+pub fn provenance_line(note: &Note) -> Option<String> {
+    let provenance = note.provenance.as_ref()?;
+    let from: Vec<&str> = provenance.app.iter().chain(provenance.url.iter()).map(String::as_str).collect();
+    if from.is_empty() {
+        return None;
+    }
+    let captured = format_date(&provenance.captured_at.with_timezone(&Local), "date.short");
+    Some(tr("provenance.line", &[&from.join(", "), &captured]))
+}
+
+pub async fn render_markdown(note_id: String) -> Result<String, String> {
+    let database = load_notes()?;
+    let note = database.notes.iter()
+        .find(|n| n.id == note_id)
+        .ok_or_else(|| tr("error.note_not_found", &[&note_id]))?;
+
+    Ok(render_note_html(note, &database.notes))
+}
@@ -0,0 +1,173 @@
+use chrono::{Duration as ChronoDuration, Utc};
+use serde::Serialize;
+use std::time::Duration;
+use tracing::{error, info};
+use uuid::Uuid;
+use crate::models::{Note, RetentionAction, RetentionRule};
+use crate::services::audit_service::{note_deleted, record_deletions};
+use crate::services::category_service::{ensure_category_path, update_category_note_counts};
+use crate::services::note_service::load_notes;
+use crate::services::settings_service::current_settings;
+use crate::services::storage_service::{load_settings, save_notes, save_settings};
+
+/// Where `RetentionAction::Archive` moves notes, keeping their path below it
+const ARCHIVE_CATEGORY: &str = "Archive";
+/// How often the maintenance job checks whether a day has passed since the last run
+const CHECK_INTERVAL_SECS: u64 = 60 * 60;
+const RUN_EVERY_HOURS: i64 = 24;
+
+/// A note a rule applies to, as listed in the report
+#[derive(Serialize, Clone)]
+pub struct RetainedNote {
+    pub note_id: String,
+    pub title: String,
+    pub category_path: Vec<String>,
+    pub age_days: i64,
+    pub rule_id: String,
+}
+
+#[derive(Serialize)]
+pub struct RetentionReport {
+    pub dry_run: bool,
+    pub archived: Vec<RetainedNote>,
+    pub deleted: Vec<RetainedNote>,
+}
+
+pub async fn list_retention_rules() -> Result<Vec<RetentionRule>, String> {
+    Ok(load_settings()?.settings.retention_rules)
+}
+
+/// Create a rule, or update it in place when `id` matches an existing one
+pub async fn save_retention_rule(mut rule: RetentionRule) -> Result<RetentionRule, String> {
+    rule.category_path = rule.category_path.into_iter()
+        .map(|part| part.trim().to_string())
+        .filter(|part| !part.is_empty())
+        .collect();
+    if rule.older_than_days == 0 && rule.action != RetentionAction::Keep {
+        return Err("Notes must be at least a day old before a rule acts on them".to_string());
+    }
+
+    let mut database = load_settings()?;
+    if rule.id.trim().is_empty() {
+        rule.id = Uuid::new_v4().to_string();
+    }
+    let rules = &mut database.settings.retention_rules;
+    if rules.iter().any(|r| r.id != rule.id && r.category_path == rule.category_path) {
+        return Err("This category already has a retention rule - edit that one instead".to_string());
+    }
+    match rules.iter_mut().find(|r| r.id == rule.id) {
+        Some(existing) => *existing = rule.clone(),
+        None => rules.push(rule.clone()),
+    }
+    save_settings(&database)?;
+    Ok(rule)
+}
+
+pub async fn delete_retention_rule(rule_id: String) -> Result<(), String> {
+    let mut database = load_settings()?;
+    let initial_len = database.settings.retention_rules.len();
+    database.settings.retention_rules.retain(|r| r.id != rule_id);
+    if database.settings.retention_rules.len() == initial_len {
+        return Err(format!("Retention rule with id {} not found", rule_id));
+    }
+    save_settings(&database)
+}
+
+/// The enabled rule for the deepest category on the note's path
+fn rule_for<'a>(note: &Note, rules: &'a [RetentionRule]) -> Option<&'a RetentionRule> {
+    rules.iter()
+        .filter(|r| r.enabled && note.category_path.starts_with(&r.category_path))
+        .max_by_key(|r| r.category_path.len())
+}
+
+/// What the rules would do to each note right now
+fn due_actions(notes: &[Note], rules: &[RetentionRule]) -> Vec<(RetentionAction, RetainedNote)> {
+    let now = Utc::now();
+    notes.iter()
+        .filter_map(|note| {
+            let rule = rule_for(note, rules)?;
+            let archived = note.category_path.first().is_some_and(|c| c == ARCHIVE_CATEGORY);
+            let due = now - note.timestamp >= ChronoDuration::days(rule.older_than_days as i64);
+            let acts = match rule.action {
+                RetentionAction::Archive => due && !archived,
+                RetentionAction::Delete => due,
+                RetentionAction::Keep => false,
+            };
+            acts.then(|| (rule.action, RetainedNote {
+                note_id: note.id.clone(),
+                title: note.title.clone(),
+                category_path: note.category_path.clone(),
+                age_days: (now - note.timestamp).num_days(),
+                rule_id: rule.id.clone(),
+            }))
+        })
+        .collect()
+}
+
+/// Report what the retention rules would do. They're only applied with `dry_run` set to
+/// false - a plain call never deletes anything.
+pub async fn run_retention(dry_run: Option<bool>) -> Result<RetentionReport, String> {
+    let dry_run = dry_run.unwrap_or(true);
+    let rules = current_settings().retention_rules;
+    let mut database = load_notes()?;
+    let mut report = RetentionReport { dry_run, archived: Vec::new(), deleted: Vec::new() };
+    for (action, note) in due_actions(&database.notes, &rules) {
+        match action {
+            RetentionAction::Archive => report.archived.push(note),
+            RetentionAction::Delete => report.deleted.push(note),
+            RetentionAction::Keep => {}
+        }
+    }
+    if dry_run || (report.archived.is_empty() && report.deleted.is_empty()) {
+        return Ok(report);
+    }
+
+    for entry in &report.archived {
+        let path: Vec<String> = std::iter::once(ARCHIVE_CATEGORY.to_string())
+            .chain(entry.category_path.iter().cloned())
+            .collect();
+        ensure_category_path(&path)?;
+        if let Some(note) = database.notes.iter_mut().find(|n| n.id == entry.note_id) {
+            note.category_path = path;
+            note.revision += 1;
+        }
+    }
+    let deleted: Vec<Note> = database.notes.iter()
+        .filter(|n| report.deleted.iter().any(|d| d.note_id == n.id))
+        .cloned()
+        .collect();
+    database.notes.retain(|n| !report.deleted.iter().any(|d| d.note_id == n.id));
+
+    save_notes(&database)?;
+    record_deletions(deleted.iter().map(note_deleted).collect());
+    update_category_note_counts()?;
+    Ok(report)
+}
+
+/// Background job started at launch: applies the retention rules once a day. The first
+/// check waits an interval so it doesn't compete with startup.
+pub async fn run_retention_loop() {
+    loop {
+        tokio::time::sleep(Duration::from_secs(CHECK_INTERVAL_SECS)).await;
+        let settings = current_settings();
+        let due = settings.last_retention_run
+            .is_none_or(|at| Utc::now() - at >= ChronoDuration::hours(RUN_EVERY_HOURS));
+        if due && !settings.retention_rules.is_empty() {
+            match run_retention(Some(false)).await {
+                Ok(report) => {
+                    if !report.archived.is_empty() || !report.deleted.is_empty() {
+                        info!("Retention archived {} and deleted {} notes", report.archived.len(), report.deleted.len());
+                    }
+                    let result = load_settings().and_then(|mut database| {
+                        database.settings.last_retention_run = Some(Utc::now());
+                        save_settings(&database)
+                    });
+                    if let Err(e) = result {
+                        error!("Failed to record retention run: {}", e);
+                    }
+                }
+                Err(e) => error!("Retention run failed: {}", e),
+            }
+        }
+    }
+}
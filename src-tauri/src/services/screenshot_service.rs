@@ -0,0 +1,122 @@
+use chrono::Local;
+use serde::Deserialize;
+use std::io::Cursor;
+use xcap::image::{imageops, DynamicImage, ImageFormat, RgbaImage};
+use xcap::{Monitor, Window};
+use crate::models::Note;
+use crate::services::attachment_service::{attachment_file_path, attachment_markdown, store_attachment_bytes};
+use crate::services::category_service::ensure_category_path;
+use crate::services::import_service::ocr_image;
+use crate::services::note_service::insert_note;
+use crate::services::locale_service::{format_date, tr};
+
+/// Where screenshots land unless another category is given
+const SCREENSHOT_CATEGORY: &str = "Inbox";
+
+/// Part of the screen to capture, in physical pixels of the virtual desktop
+#[derive(Deserialize, Clone, Copy)]
+pub struct ScreenRegion {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// The window the user was working in - the focused one, unless that's this app,
+/// then the topmost visible window of another app
+fn target_window() -> Result<Window, String> {
+    let windows = Window::all().map_err(|e| format!("Failed to list windows: {}", e))?;
+    let ours = |w: &Window| w.title().is_ok_and(|t| t == "NowledgeBase" || t == "Quick lookup");
+    let candidates: Vec<Window> = windows.into_iter()
+        .filter(|w| !w.is_minimized().unwrap_or(false) && !ours(w))
+        .collect();
+
+    if let Some(index) = candidates.iter().position(|w| w.is_focused().unwrap_or(false)) {
+        return Ok(candidates.into_iter().nth(index).expect("index from position"));
+    }
+    candidates.into_iter()
+        .max_by_key(|w| w.z().unwrap_or(i32::MIN))
+        .ok_or_else(|| "No window to capture".to_string())
+}
+
+/// Capture an image plus a label for the note title
+fn capture(mode: &str, region: Option<ScreenRegion>) -> Result<(RgbaImage, String), String> {
+    match mode {
+        "screen" => {
+            let monitors = Monitor::all().map_err(|e| format!("Failed to list screens: {}", e))?;
+            let monitor = monitors.iter()
+                .find(|m| m.is_primary().unwrap_or(false))
+                .or(monitors.first())
+                .ok_or("No screen to capture")?;
+            let image = monitor.capture_image().map_err(|e| format!("Failed to capture screen: {}", e))?;
+            Ok((image, "Screen".to_string()))
+        }
+        "window" => {
+            let window = target_window()?;
+            let image = window.capture_image().map_err(|e| format!("Failed to capture window: {}", e))?;
+            let label = window.app_name().ok()
+                .filter(|n| !n.is_empty())
+                .or_else(|| window.title().ok())
+                .unwrap_or_else(|| "Window".to_string());
+            Ok((image, label))
+        }
+        "region" => {
+            let region = region.filter(|r| r.width > 0 && r.height > 0)
+                .ok_or("Region capture needs a region with a width and height")?;
+            let monitor = Monitor::from_point(region.x, region.y)
+                .map_err(|e| format!("No screen at {},{}: {}", region.x, region.y, e))?;
+            let screen = monitor.capture_image().map_err(|e| format!("Failed to capture screen: {}", e))?;
+
+            // Clamp to the screen the region starts on
+            let left = (region.x - monitor.x().unwrap_or(0)).max(0) as u32;
+            let top = (region.y - monitor.y().unwrap_or(0)).max(0) as u32;
+            let width = region.width.min(screen.width().saturating_sub(left));
+            let height = region.height.min(screen.height().saturating_sub(top));
+            if width == 0 || height == 0 {
+                return Err("Region is outside the screen".to_string());
+            }
+            let image = imageops::crop_imm(&screen, left, top, width, height).to_image();
+            Ok((image, "Region".to_string()))
+        }
+        _ => Err(format!("Unknown capture mode '{}'. Expected region, window or screen", mode)),
+    }
+}
+
+/// Take a screenshot of a region, the active window or the whole screen, store it as an
+/// attachment and create a note for it (in Inbox by default). With `ocr` the text
+/// recognised in the image is added below it, when an OCR engine is installed.
+pub async fn capture_screenshot_to_note(mode: String, region: Option<ScreenRegion>, ocr: Option<bool>, category_path: Option<Vec<String>>) -> Result<Note, String> {
+    let mode = mode.trim().to_lowercase();
+    let (image, label) = tokio::task::spawn_blocking(move || capture(&mode, region))
+        .await
+        .map_err(|e| format!("Screenshot task failed: {}", e))??;
+
+    let mut png = Cursor::new(Vec::new());
+    DynamicImage::ImageRgba8(image).write_to(&mut png, ImageFormat::Png)
+        .map_err(|e| format!("Failed to encode screenshot: {}", e))?;
+    let taken = Local::now();
+    let attachment = store_attachment_bytes(&format!("screenshot-{}.png", taken.format("%Y%m%d-%H%M%S")), png.get_ref())?;
+
+    let mut content = attachment_markdown(&attachment);
+    let mut text = String::new();
+    if ocr.unwrap_or(false) {
+        let path = attachment_file_path(&attachment)?;
+        text = tokio::task::spawn_blocking(move || ocr_image(&path))
+            .await
+            .map_err(|e| format!("OCR task failed: {}", e))??
+            .unwrap_or_default();
+        if !text.is_empty() {
+            content.push_str("\n\n");
+            content.push_str(&text);
+        }
+    }
+
+    let category_path = category_path.filter(|p| !p.is_empty()).unwrap_or_else(|| vec![SCREENSHOT_CATEGORY.to_string()]);
+    ensure_category_path(&category_path)?;
+    let title = tr("title.screenshot", &[&label, &format_date(&taken, "date.short")]);
+    let mut note = Note::new(title, content, category_path);
+    note.attachments.push(attachment);
+
+    // Recognised text gives the AI something to title the note after
+    insert_note(note, !text.is_empty())
+}
@@ -0,0 +1,17 @@
+use crate::models::{AppSettings, SettingsDatabase};
+use crate::services::storage_service::{load_settings, save_settings};
+
+pub async fn get_settings() -> Result<AppSettings, String> {
+    Ok(load_settings()?.settings)
+}
+
+/// Replace the stored settings wholesale - the frontend always sends the full object
+pub async fn update_settings(settings: AppSettings) -> Result<AppSettings, String> {
+    save_settings(&SettingsDatabase { settings: settings.clone() })?;
+    Ok(settings)
+}
+
+/// Current settings for internal callers, falling back to defaults if the file is unreadable
+pub fn current_settings() -> AppSettings {
+    load_settings().map(|db| db.settings).unwrap_or_default()
+}
@@ -0,0 +1,60 @@
+use chrono::Utc;
+use uuid::Uuid;
+use crate::models::{Note, NoteKind};
+use crate::services::ai_service::generate_simple_title;
+use crate::services::category_service::ensure_category_path;
+use crate::services::note_service::{insert_note, load_notes};
+use crate::services::locale_service::tr;
+
+/// Save code as a snippet note. Without a title, one is generated from the code in the
+/// background (`note:title-updated` fires when it lands), just like regular notes.
+pub async fn save_snippet(code: String, language: Option<String>, title: Option<String>, category_path: Option<Vec<String>>) -> Result<Note, String> {
+    let category_path = category_path.unwrap_or_else(|| vec!["Snippets".to_string()]);
+    ensure_category_path(&category_path)?;
+
+    let custom_title = title.map(|t| t.trim().to_string()).filter(|t| !t.is_empty());
+    let needs_ai_title = custom_title.is_none();
+    let language = language
+        .map(|l| l.trim().to_lowercase())
+        .filter(|l| !l.is_empty());
+
+    let note = Note {
+        id: Uuid::new_v4().to_string(),
+        title: custom_title.unwrap_or_else(|| generate_simple_title(&code)),
+        content: code,
+        category_path,
+        timestamp: Utc::now(),
+        kind: NoteKind::Snippet,
+        language,
+        ..Default::default()
+    };
+
+    insert_note(note, needs_ai_title)
+}
+
+/// Snippet notes, newest first - optionally only those in `language`
+pub async fn get_snippets(language: Option<String>) -> Result<Vec<Note>, String> {
+    let language = language.map(|l| l.trim().to_lowercase()).filter(|l| !l.is_empty());
+    let mut snippets: Vec<Note> = load_notes()?.notes.into_iter()
+        .filter(|n| n.kind == NoteKind::Snippet)
+        .filter(|n| language.is_none() || n.language == language)
+        .collect();
+    snippets.sort_by_key(|n| std::cmp::Reverse(n.timestamp));
+    Ok(snippets)
+}
+
+/// Put a snippet's code on the clipboard exactly as stored
+pub async fn copy_snippet_to_clipboard(note_id: String) -> Result<(), String> {
+    let database = load_notes()?;
+    let note = database.notes.iter()
+        .find(|n| n.id == note_id)
+        .ok_or_else(|| tr("error.note_not_found", &[&note_id]))?;
+    if note.kind != NoteKind::Snippet {
+        return Err(format!("Note {} is not a snippet", note_id));
+    }
+
+    let mut clipboard = arboard::Clipboard::new()
+        .map_err(|e| tr("error.clipboard_unavailable", &[&e]))?;
+    clipboard.set_text(note.content.clone())
+        .map_err(|e| format!("Failed to copy to clipboard: {}", e))
+}
@@ -0,0 +1,237 @@
+use chrono::{Duration, Local, NaiveDate, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use tracing::warn;
+use crate::models::DailyActivity;
+use crate::services::note_service::load_notes;
+use crate::services::reminder_service::is_reminder_due;
+use crate::services::storage_service::{load_activity, save_activity};
+
+pub fn word_count(content: &str) -> usize {
+    content.split_whitespace().count()
+}
+
+/// Apply `update` to today's activity entry. Failures are only logged - stats must never
+/// block the action being counted.
+fn update_today(update: impl FnOnce(&mut DailyActivity)) {
+    let result = load_activity().and_then(|mut database| {
+        let today = Local::now().date_naive();
+        let index = match database.days.iter().position(|d| d.date == today) {
+            Some(index) => index,
+            None => {
+                database.days.push(DailyActivity { date: today, notes_created: 0, words_written: 0, edits: 0, ai_calls: 0, reviews: 0 });
+                database.days.len() - 1
+            }
+        };
+
+        update(&mut database.days[index]);
+        save_activity(&database)
+    });
+
+    if let Err(e) = result {
+        warn!("Failed to record activity: {}", e);
+    }
+}
+
+/// Record a note creation and/or word delta against today's activity
+pub fn record_activity(notes_created: u32, word_delta: i64) {
+    update_today(|day| {
+        day.notes_created += notes_created;
+        day.words_written += word_delta.max(0) as u64;
+        if notes_created == 0 {
+            day.edits += 1;
+        }
+    });
+}
+
+pub fn record_ai_call() {
+    update_today(|day| day.ai_calls += 1);
+}
+
+pub fn record_review() {
+    update_today(|day| day.reviews += 1);
+}
+
+#[derive(Serialize, Clone)]
+pub struct HeatmapDay {
+    pub date: NaiveDate,
+    pub notes_created: u32,
+    pub words_written: u64,
+    pub edits: u32,
+    pub ai_calls: u32,
+    pub reviews: u32,
+}
+
+impl HeatmapDay {
+    fn empty(date: NaiveDate) -> Self {
+        HeatmapDay { date, notes_created: 0, words_written: 0, edits: 0, ai_calls: 0, reviews: 0 }
+    }
+
+    fn is_active(&self) -> bool {
+        self.notes_created > 0 || self.words_written > 0 || self.edits > 0
+    }
+}
+
+#[derive(Serialize)]
+pub struct WritingStreak {
+    pub current: u32,
+    pub longest: u32,
+    pub last_active: Option<NaiveDate>,
+}
+
+/// Merge recorded activity with note creation dates (which also covers imports and
+/// notes written before tracking existed) into one entry per day
+fn activity_by_day() -> Result<HashMap<NaiveDate, HeatmapDay>, String> {
+    let mut days: HashMap<NaiveDate, HeatmapDay> = HashMap::new();
+
+    for recorded in load_activity()?.days {
+        days.insert(recorded.date, HeatmapDay {
+            date: recorded.date,
+            notes_created: 0,
+            words_written: recorded.words_written,
+            edits: recorded.edits,
+            ai_calls: recorded.ai_calls,
+            reviews: recorded.reviews,
+        });
+    }
+
+    for note in load_notes()?.notes {
+        let date = note.timestamp.with_timezone(&Local).date_naive();
+        days.entry(date)
+            .or_insert_with(|| HeatmapDay::empty(date))
+            .notes_created += 1;
+    }
+
+    Ok(days)
+}
+
+/// One entry per day (oldest first, empty days included) covering the last `days` days,
+/// or everything since the first recorded day when `days` is `None`
+pub fn daily_stats(days: Option<i64>) -> Result<Vec<HeatmapDay>, String> {
+    let activity = activity_by_day()?;
+    let today = Local::now().date_naive();
+    let start = match days {
+        Some(days) => today - Duration::days(days - 1),
+        None => activity.keys().min().copied().unwrap_or(today).min(today),
+    };
+
+    Ok(start.iter_days()
+        .take_while(|date| *date <= today)
+        .map(|date| activity.get(&date).cloned().unwrap_or_else(|| HeatmapDay::empty(date)))
+        .collect())
+}
+
+/// Daily activity for the last `days` days (oldest first), including empty days
+pub async fn get_activity_heatmap(days: u32) -> Result<Vec<HeatmapDay>, String> {
+    daily_stats(Some(days.clamp(1, 3660) as i64))
+}
+
+pub async fn get_writing_streak() -> Result<WritingStreak, String> {
+    let activity = activity_by_day()?;
+    let mut active_days: Vec<NaiveDate> = activity.values()
+        .filter(|day| day.is_active())
+        .map(|day| day.date)
+        .collect();
+    active_days.sort();
+
+    let mut longest = 0;
+    let mut run = 0;
+    let mut previous: Option<NaiveDate> = None;
+    for date in &active_days {
+        run = match previous {
+            Some(prev) if *date - prev == Duration::days(1) => run + 1,
+            _ => 1,
+        };
+        longest = longest.max(run);
+        previous = Some(*date);
+    }
+
+    // The current streak survives until the end of today even if nothing's been written yet
+    let today = Local::now().date_naive();
+    let last_active = active_days.last().copied();
+    let current = match last_active {
+        Some(last) if today - last <= Duration::days(1) => run,
+        _ => 0,
+    };
+
+    Ok(WritingStreak { current, longest, last_active })
+}
+
+/// How often the tray's vault summary is recomputed
+const SUMMARY_REFRESH_SECS: u64 = 60;
+
+/// At-a-glance state of the vault, shown in the tray
+#[derive(Serialize, Clone, PartialEq)]
+pub struct VaultSummary {
+    pub notes: usize,
+    pub notes_today: usize,
+    pub due_reminders: usize,
+    pub reading_queue: usize, // Queued and not read yet
+}
+
+impl VaultSummary {
+    /// One line for the tray, e.g. "120 notes · 3 today · 2 reminders due"
+    pub fn describe(&self) -> String {
+        let mut parts = vec![
+            format!("{} note{}", self.notes, if self.notes == 1 { "" } else { "s" }),
+            format!("{} today", self.notes_today),
+        ];
+        if self.due_reminders > 0 {
+            parts.push(format!("{} reminder{} due", self.due_reminders, if self.due_reminders == 1 { "" } else { "s" }));
+        }
+        if self.reading_queue > 0 {
+            parts.push(format!("{} to read", self.reading_queue));
+        }
+        parts.join(" · ")
+    }
+}
+
+/// Called with a fresh summary whenever it changes. main.rs installs one that updates
+/// the tray tooltip and the summary line of the tray menu.
+type SummaryListener = Box<dyn Fn(&VaultSummary) + Send + Sync>;
+static SUMMARY_LISTENER: OnceLock<SummaryListener> = OnceLock::new();
+static LAST_SUMMARY: Mutex<Option<VaultSummary>> = Mutex::new(None);
+
+pub fn set_summary_listener(listener: impl Fn(&VaultSummary) + Send + Sync + 'static) {
+    let _ = SUMMARY_LISTENER.set(Box::new(listener));
+}
+
+pub fn vault_summary() -> Result<VaultSummary, String> {
+    let notes = load_notes()?.notes;
+    let now = Utc::now();
+    let today = Local::now().date_naive();
+    Ok(VaultSummary {
+        notes: notes.len(),
+        notes_today: notes.iter().filter(|n| n.timestamp.with_timezone(&Local).date_naive() == today).count(),
+        due_reminders: notes.iter().filter(|n| is_reminder_due(n, now)).count(),
+        reading_queue: notes.iter().filter(|n| n.reading.as_ref().is_some_and(|r| r.read_at.is_none())).count(),
+    })
+}
+
+pub async fn get_vault_summary() -> Result<VaultSummary, String> {
+    vault_summary()
+}
+
+/// The summary the background job last computed, without loading the notes. `None` until
+/// its first pass.
+pub fn last_vault_summary() -> Option<VaultSummary> {
+    LAST_SUMMARY.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Background job started at launch: keeps the tray's vault summary current
+pub async fn run_vault_summary_loop() {
+    loop {
+        match vault_summary() {
+            Ok(summary) if last_vault_summary().as_ref() != Some(&summary) => {
+                if let Some(listener) = SUMMARY_LISTENER.get() {
+                    listener(&summary);
+                }
+                *LAST_SUMMARY.lock().unwrap_or_else(|e| e.into_inner()) = Some(summary);
+            }
+            Ok(_) => {}
+            Err(e) => warn!("Failed to compute vault summary: {}", e),
+        }
+        tokio::time::sleep(std::time::Duration::from_secs(SUMMARY_REFRESH_SECS)).await;
+    }
+}
@@ -1,6 +1,14 @@
 use std::fs;
-use std::path::PathBuf;
-use crate::models::{NotesDatabase, CategoriesDatabase, LinksDatabase, UIStateDatabase, UIState, GraphViewport};
+use std::path::{Path, PathBuf};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant, SystemTime};
+use serde::Serialize;
+use tracing::{error, info, instrument, warn};
+use crate::models::{Note, NotesDatabase, CategoriesDatabase, LinksDatabase, BoardsDatabase, FeedsDatabase, ActivityDatabase, SettingsDatabase, UIStateDatabase, UIState, GraphViewport, WebhooksDatabase, AiLogDatabase, AiJobsDatabase, DraftsDatabase, PinnedPastesDatabase, AnswerHistoryDatabase, ContradictionsDatabase, AuditLogDatabase, ProfilesDatabase, SyncStateDatabase, MountedVaultsDatabase};
 
 pub fn get_app_data_dir() -> Result<PathBuf, String> {
     let mut path = dirs::data_dir()
@@ -16,12 +24,153 @@ pub fn get_app_data_dir() -> Result<PathBuf, String> {
     Ok(path)
 }
 
+/// Single-file notes store used before notes were split into segments. Only read to
+/// migrate; it's renamed to `notes.json.bak` once the segments are written.
 pub fn get_notes_file_path() -> Result<PathBuf, String> {
     let mut path = get_app_data_dir()?;
     path.push("notes.json");
     Ok(path)
 }
 
+/// Notes live in `notes/segment-NNNN.json` files of at most this many notes each, so an
+/// edit only rewrites the segment holding the note instead of the whole vault
+const MAX_NOTES_PER_SEGMENT: usize = 250;
+
+pub fn get_notes_segment_dir() -> Result<PathBuf, String> {
+    let mut path = get_app_data_dir()?;
+    path.push("notes");
+    Ok(path)
+}
+
+fn segment_path(dir: &Path, index: usize) -> PathBuf {
+    dir.join(format!("segment-{:04}.json", index))
+}
+
+/// Which segment each note was loaded from or written to, and a hash of each segment
+/// file as it is on disk, so saves can tell which segments actually changed
+#[derive(Default)]
+struct SegmentState {
+    segment_of: HashMap<String, usize>,
+    written: HashMap<usize, u64>,
+}
+
+static SEGMENTS: Mutex<Option<SegmentState>> = Mutex::new(None);
+
+fn content_hash(content: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Size and modification time of each segment file, in segment order. The cache is only
+/// trusted while this matches what's on disk, so segments changed behind the app's back
+/// (another instance, a restored archive) are read again.
+type SegmentFingerprint = Vec<(usize, u64, Option<SystemTime>)>;
+
+/// The notes as last read from or written to the segments. Filled in the background at
+/// launch, so the first window to ask for notes doesn't wait on parsing the whole vault.
+static NOTES_CACHE: Mutex<Option<(SegmentFingerprint, NotesDatabase)>> = Mutex::new(None);
+
+fn segment_fingerprint(dir: &Path, indices: &[usize]) -> SegmentFingerprint {
+    indices.iter()
+        .map(|index| {
+            let meta = fs::metadata(segment_path(dir, *index)).ok();
+            (*index, meta.as_ref().map_or(0, |m| m.len()), meta.and_then(|m| m.modified().ok()))
+        })
+        .collect()
+}
+
+fn cache_notes(fingerprint: SegmentFingerprint, database: NotesDatabase) {
+    *NOTES_CACHE.lock().unwrap_or_else(|e| e.into_inner()) = Some((fingerprint, database));
+    bump_store_generation();
+}
+
+/// Bumped whenever the notes or categories may have changed, so indexes built from them
+/// in memory (the quick switcher's) know to rebuild without reloading anything to check
+static STORE_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+pub fn store_generation() -> u64 {
+    STORE_GENERATION.load(Ordering::Acquire)
+}
+
+fn bump_store_generation() {
+    STORE_GENERATION.fetch_add(1, Ordering::AcqRel);
+}
+
+/// Same JSON shape as `NotesDatabase`, without cloning the notes
+#[derive(Serialize)]
+struct SegmentContent<'a> {
+    notes: Vec<&'a Note>,
+}
+
+/// All notes from the segment files, in segment order. `None` when there are no segments,
+/// i.e. the vault hasn't been split yet or was just restored from an archive.
+#[instrument(target = "timing", name = "storage.load_notes", skip_all)]
+pub fn load_note_segments() -> Result<Option<NotesDatabase>, String> {
+    let dir = get_notes_segment_dir()?;
+    if !dir.exists() {
+        return Ok(None);
+    }
+
+    let mut segments: Vec<(usize, PathBuf)> = fs::read_dir(&dir)
+        .map_err(|e| format!("Failed to read notes directory: {}", e))?
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            let index = path.file_name()?.to_str()?
+                .strip_prefix("segment-")?
+                .strip_suffix(".json")?
+                .parse().ok()?;
+            Some((index, path))
+        })
+        .collect();
+    if segments.is_empty() {
+        return Ok(None);
+    }
+    segments.sort_by_key(|(index, _)| *index);
+
+    let indices: Vec<usize> = segments.iter().map(|(index, _)| *index).collect();
+    let fingerprint = segment_fingerprint(&dir, &indices);
+    if let Some((cached_fingerprint, database)) = NOTES_CACHE.lock().unwrap_or_else(|e| e.into_inner()).as_ref() {
+        if *cached_fingerprint == fingerprint {
+            return Ok(Some(database.clone()));
+        }
+    }
+
+    let mut state = SegmentState::default();
+    let mut notes = Vec::new();
+    for (index, path) in segments {
+        let content = fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read notes segment {}: {}", path.display(), e))?;
+        let segment: NotesDatabase = serde_json::from_str(&content)
+            .map_err(|e| format!("Failed to parse notes segment {}: {}", path.display(), e))?;
+        state.written.insert(index, content_hash(&content));
+        for note in segment.notes {
+            state.segment_of.insert(note.id.clone(), index);
+            notes.push(note);
+        }
+    }
+
+    *SEGMENTS.lock().unwrap_or_else(|e| e.into_inner()) = Some(state);
+    let database = NotesDatabase { notes };
+    cache_notes(fingerprint, database.clone());
+    Ok(Some(database))
+}
+
+/// Read the stores the first screens need, so that by the time the capture window is
+/// summoned the notes are parsed and cached and the rest is in the OS file cache.
+/// Started in the background at launch; failures only cost that head start.
+pub fn preload_stores() {
+    let started = Instant::now();
+    let result = load_note_segments()
+        .and_then(|_| load_links())
+        .and_then(|_| load_settings())
+        .and_then(|_| load_ui_state());
+    match result {
+        Ok(_) => info!("Stores preloaded in {} ms", started.elapsed().as_millis()),
+        Err(e) => warn!("Failed to preload stores: {}", e),
+    }
+}
+
 pub fn get_links_file_path() -> Result<PathBuf, String> {
     let mut file_path = get_app_data_dir()?;
     file_path.push("note_links.json");
@@ -40,6 +189,121 @@ pub fn get_ui_state_file_path() -> Result<PathBuf, String> {
     Ok(path)
 }
 
+pub fn get_boards_file_path() -> Result<PathBuf, String> {
+    let mut path = get_app_data_dir()?;
+    path.push("boards.json");
+    Ok(path)
+}
+
+pub fn get_activity_file_path() -> Result<PathBuf, String> {
+    let mut path = get_app_data_dir()?;
+    path.push("activity.json");
+    Ok(path)
+}
+
+pub fn get_settings_file_path() -> Result<PathBuf, String> {
+    let mut path = get_app_data_dir()?;
+    path.push("settings.json");
+    Ok(path)
+}
+
+pub fn get_feeds_file_path() -> Result<PathBuf, String> {
+    let mut path = get_app_data_dir()?;
+    path.push("feeds.json");
+    Ok(path)
+}
+
+pub fn get_webhooks_file_path() -> Result<PathBuf, String> {
+    let mut path = get_app_data_dir()?;
+    path.push("webhooks.json");
+    Ok(path)
+}
+
+pub fn get_ai_log_file_path() -> Result<PathBuf, String> {
+    let mut path = get_app_data_dir()?;
+    path.push("ai_log.json");
+    Ok(path)
+}
+
+pub fn get_ai_jobs_file_path() -> Result<PathBuf, String> {
+    let mut path = get_app_data_dir()?;
+    path.push("ai_jobs.json");
+    Ok(path)
+}
+
+pub fn get_drafts_file_path() -> Result<PathBuf, String> {
+    let mut path = get_app_data_dir()?;
+    path.push("drafts.json");
+    Ok(path)
+}
+
+pub fn get_pinned_pastes_file_path() -> Result<PathBuf, String> {
+    let mut path = get_app_data_dir()?;
+    path.push("pinned_pastes.json");
+    Ok(path)
+}
+
+pub fn get_answer_history_file_path() -> Result<PathBuf, String> {
+    let mut path = get_app_data_dir()?;
+    path.push("answer_history.json");
+    Ok(path)
+}
+
+pub fn get_contradictions_file_path() -> Result<PathBuf, String> {
+    let mut path = get_app_data_dir()?;
+    path.push("contradictions.json");
+    Ok(path)
+}
+
+pub fn get_audit_log_file_path() -> Result<PathBuf, String> {
+    let mut path = get_app_data_dir()?;
+    path.push("audit_log.json");
+    Ok(path)
+}
+
+pub fn get_profiles_file_path() -> Result<PathBuf, String> {
+    let mut path = get_app_data_dir()?;
+    path.push("profiles.json");
+    Ok(path)
+}
+
+pub fn get_sync_state_file_path() -> Result<PathBuf, String> {
+    let mut path = get_app_data_dir()?;
+    path.push("sync_state.json");
+    Ok(path)
+}
+
+pub fn get_mounted_vaults_file_path() -> Result<PathBuf, String> {
+    let mut path = get_app_data_dir()?;
+    path.push("mounted_vaults.json");
+    Ok(path)
+}
+
+/// One CRDT document per synced note, named after the note id
+pub fn get_sync_docs_dir() -> Result<PathBuf, String> {
+    let mut path = get_app_data_dir()?;
+    path.push("sync");
+    
+    if !path.exists() {
+        fs::create_dir_all(&path)
+            .map_err(|e| format!("Failed to create sync directory: {}", e))?;
+    }
+    
+    Ok(path)
+}
+
+pub fn get_attachments_dir() -> Result<PathBuf, String> {
+    let mut path = get_app_data_dir()?;
+    path.push("attachments");
+    
+    if !path.exists() {
+        fs::create_dir_all(&path)
+            .map_err(|e| format!("Failed to create attachments directory: {}", e))?;
+    }
+    
+    Ok(path)
+}
+
 pub fn load_links() -> Result<LinksDatabase, String> {
     let file_path = get_links_file_path()?;
     
@@ -67,13 +331,521 @@ pub fn save_links(database: &LinksDatabase) -> Result<(), String> {
         .map_err(|e| format!("Failed to write links file: {}", e))
 }
 
-pub fn save_notes(database: &NotesDatabase) -> Result<(), String> {
-    let file_path = get_notes_file_path()?;
+pub fn load_boards() -> Result<BoardsDatabase, String> {
+    let file_path = get_boards_file_path()?;
+    
+    if !file_path.exists() {
+        return Ok(BoardsDatabase { boards: Vec::new() });
+    }
+    
+    let content = fs::read_to_string(&file_path)
+        .map_err(|e| format!("Failed to read boards file: {}", e))?;
+    
+    serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse boards file: {}", e))
+}
+
+pub fn save_boards(database: &BoardsDatabase) -> Result<(), String> {
+    let file_path = get_boards_file_path()?;
     let content = serde_json::to_string_pretty(database)
-        .map_err(|e| format!("Failed to serialize notes: {}", e))?;
+        .map_err(|e| format!("Failed to serialize boards: {}", e))?;
     
     fs::write(&file_path, content)
-        .map_err(|e| format!("Failed to write notes file: {}", e))
+        .map_err(|e| format!("Failed to write boards file: {}", e))
+}
+
+pub fn load_activity() -> Result<ActivityDatabase, String> {
+    let file_path = get_activity_file_path()?;
+    
+    if !file_path.exists() {
+        return Ok(ActivityDatabase::default());
+    }
+    
+    let content = fs::read_to_string(&file_path)
+        .map_err(|e| format!("Failed to read activity file: {}", e))?;
+    
+    serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse activity file: {}", e))
+}
+
+pub fn save_activity(database: &ActivityDatabase) -> Result<(), String> {
+    let file_path = get_activity_file_path()?;
+    let content = serde_json::to_string_pretty(database)
+        .map_err(|e| format!("Failed to serialize activity: {}", e))?;
+    
+    fs::write(&file_path, content)
+        .map_err(|e| format!("Failed to write activity file: {}", e))
+}
+
+pub fn load_settings() -> Result<SettingsDatabase, String> {
+    let file_path = get_settings_file_path()?;
+    
+    if !file_path.exists() {
+        return Ok(SettingsDatabase::default());
+    }
+    
+    let content = fs::read_to_string(&file_path)
+        .map_err(|e| format!("Failed to read settings file: {}", e))?;
+    
+    serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse settings file: {}", e))
+}
+
+pub fn save_settings(database: &SettingsDatabase) -> Result<(), String> {
+    let file_path = get_settings_file_path()?;
+    let content = serde_json::to_string_pretty(database)
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    
+    fs::write(&file_path, content)
+        .map_err(|e| format!("Failed to write settings file: {}", e))
+}
+
+pub fn load_feeds() -> Result<FeedsDatabase, String> {
+    let file_path = get_feeds_file_path()?;
+    
+    if !file_path.exists() {
+        return Ok(FeedsDatabase { feeds: Vec::new() });
+    }
+    
+    let content = fs::read_to_string(&file_path)
+        .map_err(|e| format!("Failed to read feeds file: {}", e))?;
+    
+    serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse feeds file: {}", e))
+}
+
+pub fn save_feeds(database: &FeedsDatabase) -> Result<(), String> {
+    let file_path = get_feeds_file_path()?;
+    let content = serde_json::to_string_pretty(database)
+        .map_err(|e| format!("Failed to serialize feeds: {}", e))?;
+    
+    fs::write(&file_path, content)
+        .map_err(|e| format!("Failed to write feeds file: {}", e))
+}
+
+pub fn load_webhooks() -> Result<WebhooksDatabase, String> {
+    let file_path = get_webhooks_file_path()?;
+    
+    if !file_path.exists() {
+        return Ok(WebhooksDatabase { webhooks: Vec::new(), reminders_sent: HashMap::new() });
+    }
+    
+    let content = fs::read_to_string(&file_path)
+        .map_err(|e| format!("Failed to read webhooks file: {}", e))?;
+    
+    serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse webhooks file: {}", e))
+}
+
+pub fn save_webhooks(database: &WebhooksDatabase) -> Result<(), String> {
+    let file_path = get_webhooks_file_path()?;
+    let content = serde_json::to_string_pretty(database)
+        .map_err(|e| format!("Failed to serialize webhooks: {}", e))?;
+    
+    fs::write(&file_path, content)
+        .map_err(|e| format!("Failed to write webhooks file: {}", e))
+}
+
+pub fn load_ai_log() -> Result<AiLogDatabase, String> {
+    let file_path = get_ai_log_file_path()?;
+    
+    if !file_path.exists() {
+        return Ok(AiLogDatabase::default());
+    }
+    
+    let content = fs::read_to_string(&file_path)
+        .map_err(|e| format!("Failed to read ai_log file: {}", e))?;
+    
+    serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse ai_log file: {}", e))
+}
+
+pub fn save_ai_log(database: &AiLogDatabase) -> Result<(), String> {
+    let file_path = get_ai_log_file_path()?;
+    let content = serde_json::to_string_pretty(database)
+        .map_err(|e| format!("Failed to serialize ai_log: {}", e))?;
+    
+    fs::write(&file_path, content)
+        .map_err(|e| format!("Failed to write ai_log file: {}", e))
+}
+
+pub fn load_ai_jobs() -> Result<AiJobsDatabase, String> {
+    let file_path = get_ai_jobs_file_path()?;
+    
+    if !file_path.exists() {
+        return Ok(AiJobsDatabase::default());
+    }
+    
+    let content = fs::read_to_string(&file_path)
+        .map_err(|e| format!("Failed to read ai_jobs file: {}", e))?;
+    
+    serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse ai_jobs file: {}", e))
+}
+
+pub fn save_ai_jobs(database: &AiJobsDatabase) -> Result<(), String> {
+    let file_path = get_ai_jobs_file_path()?;
+    let content = serde_json::to_string_pretty(database)
+        .map_err(|e| format!("Failed to serialize ai_jobs: {}", e))?;
+    
+    fs::write(&file_path, content)
+        .map_err(|e| format!("Failed to write ai_jobs file: {}", e))
+}
+
+pub fn load_drafts() -> Result<DraftsDatabase, String> {
+    let file_path = get_drafts_file_path()?;
+    
+    if !file_path.exists() {
+        return Ok(DraftsDatabase::default());
+    }
+    
+    let content = fs::read_to_string(&file_path)
+        .map_err(|e| format!("Failed to read drafts file: {}", e))?;
+    
+    serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse drafts file: {}", e))
+}
+
+/// Drafts are written every few seconds, so write to a temp file and rename - a crash
+/// mid-write must never leave a truncated drafts file behind
+pub fn save_drafts(database: &DraftsDatabase) -> Result<(), String> {
+    let file_path = get_drafts_file_path()?;
+    let content = serde_json::to_string_pretty(database)
+        .map_err(|e| format!("Failed to serialize drafts: {}", e))?;
+    
+    let temp_path = file_path.with_extension("json.tmp");
+    fs::write(&temp_path, content)
+        .map_err(|e| format!("Failed to write drafts file: {}", e))?;
+    fs::rename(&temp_path, &file_path)
+        .map_err(|e| format!("Failed to write drafts file: {}", e))
+}
+
+pub fn load_pinned_pastes() -> Result<PinnedPastesDatabase, String> {
+    let file_path = get_pinned_pastes_file_path()?;
+    
+    if !file_path.exists() {
+        return Ok(PinnedPastesDatabase::default());
+    }
+    
+    let content = fs::read_to_string(&file_path)
+        .map_err(|e| format!("Failed to read pinned_pastes file: {}", e))?;
+    
+    serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse pinned_pastes file: {}", e))
+}
+
+pub fn save_pinned_pastes(database: &PinnedPastesDatabase) -> Result<(), String> {
+    let file_path = get_pinned_pastes_file_path()?;
+    let content = serde_json::to_string_pretty(database)
+        .map_err(|e| format!("Failed to serialize pinned_pastes: {}", e))?;
+    
+    fs::write(&file_path, content)
+        .map_err(|e| format!("Failed to write pinned_pastes file: {}", e))
+}
+
+pub fn load_answer_history() -> Result<AnswerHistoryDatabase, String> {
+    let file_path = get_answer_history_file_path()?;
+    
+    if !file_path.exists() {
+        return Ok(AnswerHistoryDatabase::default());
+    }
+    
+    let content = fs::read_to_string(&file_path)
+        .map_err(|e| format!("Failed to read answer history file: {}", e))?;
+    
+    serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse answer history file: {}", e))
+}
+
+pub fn save_answer_history(database: &AnswerHistoryDatabase) -> Result<(), String> {
+    let file_path = get_answer_history_file_path()?;
+    let content = serde_json::to_string_pretty(database)
+        .map_err(|e| format!("Failed to serialize answer history: {}", e))?;
+    
+    fs::write(&file_path, content)
+        .map_err(|e| format!("Failed to write answer history file: {}", e))
+}
+
+pub fn load_contradictions() -> Result<ContradictionsDatabase, String> {
+    let file_path = get_contradictions_file_path()?;
+    
+    if !file_path.exists() {
+        return Ok(ContradictionsDatabase::default());
+    }
+    
+    let content = fs::read_to_string(&file_path)
+        .map_err(|e| format!("Failed to read contradictions file: {}", e))?;
+    
+    serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse contradictions file: {}", e))
+}
+
+pub fn save_contradictions(database: &ContradictionsDatabase) -> Result<(), String> {
+    let file_path = get_contradictions_file_path()?;
+    let content = serde_json::to_string_pretty(database)
+        .map_err(|e| format!("Failed to serialize contradictions: {}", e))?;
+    
+    fs::write(&file_path, content)
+        .map_err(|e| format!("Failed to write contradictions file: {}", e))
+}
+
+pub fn load_audit_log() -> Result<AuditLogDatabase, String> {
+    let file_path = get_audit_log_file_path()?;
+    
+    if !file_path.exists() {
+        return Ok(AuditLogDatabase::default());
+    }
+    
+    let content = fs::read_to_string(&file_path)
+        .map_err(|e| format!("Failed to read audit log file: {}", e))?;
+    
+    serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse audit log file: {}", e))
+}
+
+pub fn save_audit_log(database: &AuditLogDatabase) -> Result<(), String> {
+    let file_path = get_audit_log_file_path()?;
+    let content = serde_json::to_string(database)
+        .map_err(|e| format!("Failed to serialize audit log: {}", e))?;
+    
+    fs::write(&file_path, content)
+        .map_err(|e| format!("Failed to write audit log file: {}", e))
+}
+
+pub fn load_profiles() -> Result<ProfilesDatabase, String> {
+    let file_path = get_profiles_file_path()?;
+    
+    if !file_path.exists() {
+        return Ok(ProfilesDatabase::default());
+    }
+    
+    let content = fs::read_to_string(&file_path)
+        .map_err(|e| format!("Failed to read profiles file: {}", e))?;
+    
+    serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse profiles file: {}", e))
+}
+
+pub fn save_profiles(database: &ProfilesDatabase) -> Result<(), String> {
+    let file_path = get_profiles_file_path()?;
+    let content = serde_json::to_string_pretty(database)
+        .map_err(|e| format!("Failed to serialize profiles: {}", e))?;
+    
+    fs::write(&file_path, content)
+        .map_err(|e| format!("Failed to write profiles file: {}", e))
+}
+
+pub fn load_sync_state() -> Result<SyncStateDatabase, String> {
+    let file_path = get_sync_state_file_path()?;
+    
+    if !file_path.exists() {
+        return Ok(SyncStateDatabase::default());
+    }
+    
+    let content = fs::read_to_string(&file_path)
+        .map_err(|e| format!("Failed to read sync state file: {}", e))?;
+    
+    serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse sync state file: {}", e))
+}
+
+pub fn save_sync_state(database: &SyncStateDatabase) -> Result<(), String> {
+    let file_path = get_sync_state_file_path()?;
+    let content = serde_json::to_string(database)
+        .map_err(|e| format!("Failed to serialize sync state: {}", e))?;
+    
+    fs::write(&file_path, content)
+        .map_err(|e| format!("Failed to write sync state file: {}", e))
+}
+
+pub fn load_mounted_vaults() -> Result<MountedVaultsDatabase, String> {
+    let file_path = get_mounted_vaults_file_path()?;
+    
+    if !file_path.exists() {
+        return Ok(MountedVaultsDatabase::default());
+    }
+    
+    let content = fs::read_to_string(&file_path)
+        .map_err(|e| format!("Failed to read mounted vaults file: {}", e))?;
+    
+    serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse mounted vaults file: {}", e))
+}
+
+pub fn save_mounted_vaults(database: &MountedVaultsDatabase) -> Result<(), String> {
+    let file_path = get_mounted_vaults_file_path()?;
+    let content = serde_json::to_string_pretty(database)
+        .map_err(|e| format!("Failed to serialize mounted vaults: {}", e))?;
+    
+    fs::write(&file_path, content)
+        .map_err(|e| format!("Failed to write mounted vaults file: {}", e))
+}
+
+/// Quiet period after the last deferred save before the notes go to disk
+const WRITE_DELAY_MS: u64 = 1000;
+
+/// Notes saved with `save_notes_deferred` that aren't on disk yet, and when they last changed
+static PENDING_NOTES: Mutex<Option<(NotesDatabase, Instant)>> = Mutex::new(None);
+
+/// The not yet written notes, which are newer than what's on disk. `load_notes` returns
+/// these when there are any.
+pub fn pending_notes() -> Option<NotesDatabase> {
+    PENDING_NOTES.lock().unwrap_or_else(|e| e.into_inner()).as_ref().map(|(database, _)| database.clone())
+}
+
+/// Forget everything held in memory about the notes - deferred saves, segment bookkeeping
+/// and the parsed notes - once the files were replaced underneath them (archive restore)
+pub fn invalidate_store_caches() {
+    PENDING_NOTES.lock().unwrap_or_else(|e| e.into_inner()).take();
+    *SEGMENTS.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    *NOTES_CACHE.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    bump_store_generation();
+}
+
+/// Save the notes once things have been quiet for a moment, coalescing bursts of small
+/// changes (e.g. dragging graph nodes) into one write
+pub fn save_notes_deferred(database: &NotesDatabase) {
+    *PENDING_NOTES.lock().unwrap_or_else(|e| e.into_inner()) = Some((database.clone(), Instant::now()));
+    bump_store_generation();
+}
+
+/// Write deferred notes now. Called by the `flush` command and on exit.
+pub fn flush_pending_writes() -> Result<(), String> {
+    let pending = PENDING_NOTES.lock().unwrap_or_else(|e| e.into_inner()).take();
+    let Some((database, changed_at)) = pending else { return Ok(()) };
+    let result = write_note_segments(&database);
+    if result.is_err() {
+        // Keep the changes for the next attempt unless something newer came in meanwhile
+        PENDING_NOTES.lock().unwrap_or_else(|e| e.into_inner()).get_or_insert((database, changed_at));
+    }
+    result
+}
+
+/// Background job started at launch that writes deferred notes after the quiet period
+pub async fn run_write_flush_loop() {
+    loop {
+        tokio::time::sleep(Duration::from_millis(WRITE_DELAY_MS / 4)).await;
+        let due = PENDING_NOTES.lock().unwrap_or_else(|e| e.into_inner())
+            .as_ref()
+            .is_some_and(|(_, changed_at)| changed_at.elapsed() >= Duration::from_millis(WRITE_DELAY_MS));
+        if due {
+            if let Err(e) = flush_pending_writes() {
+                error!("Failed to write notes: {}", e);
+            }
+        }
+    }
+}
+
+/// Save the notes right away. Any deferred save is superseded, since `database` was
+/// loaded on top of it.
+pub fn save_notes(database: &NotesDatabase) -> Result<(), String> {
+    PENDING_NOTES.lock().unwrap_or_else(|e| e.into_inner()).take();
+    write_note_segments(database)
+}
+
+/// Write the notes back to their segments. Notes stay in the segment they're already in;
+/// new ones fill up the last segment, then start another. Only segments whose content
+/// changed are written, and segments left empty are removed.
+#[instrument(target = "timing", name = "storage.write_notes", skip_all)]
+fn write_note_segments(database: &NotesDatabase) -> Result<(), String> {
+    let dir = get_notes_segment_dir()?;
+    fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create notes directory: {}", e))?;
+
+    let mut guard = SEGMENTS.lock().unwrap_or_else(|e| e.into_inner());
+    let state = guard.get_or_insert_with(SegmentState::default);
+
+    let mut segments: HashMap<usize, Vec<&Note>> = HashMap::new();
+    let mut new_notes = Vec::new();
+    for note in &database.notes {
+        match state.segment_of.get(&note.id) {
+            Some(index) => segments.entry(*index).or_default().push(note),
+            None => new_notes.push(note),
+        }
+    }
+    let mut last = segments.keys().chain(state.written.keys()).max().copied().unwrap_or(0);
+    for note in new_notes {
+        let segment = segments.entry(last).or_default();
+        if segment.len() >= MAX_NOTES_PER_SEGMENT {
+            last += 1;
+        }
+        segments.entry(last).or_default().push(note);
+    }
+
+    let mut written = HashMap::new();
+    let mut segment_of = HashMap::new();
+    for (index, notes) in &segments {
+        let path = segment_path(&dir, *index);
+        let content = serde_json::to_string_pretty(&SegmentContent { notes: notes.clone() })
+            .map_err(|e| format!("Failed to serialize notes: {}", e))?;
+        let hash = content_hash(&content);
+        if state.written.get(index) != Some(&hash) || !path.exists() {
+            fs::write(&path, &content)
+                .map_err(|e| format!("Failed to write notes segment {}: {}", path.display(), e))?;
+        }
+        written.insert(*index, hash);
+        segment_of.extend(notes.iter().map(|n| (n.id.clone(), *index)));
+    }
+    for index in state.written.keys().filter(|index| !segments.contains_key(index)) {
+        let _ = fs::remove_file(segment_path(&dir, *index));
+    }
+    *state = SegmentState { segment_of, written };
+    drop(guard);
+
+    let mut indices: Vec<usize> = segments.keys().copied().collect();
+    indices.sort();
+    let notes = indices.iter().flat_map(|index| segments[index].iter().map(|n| (*n).clone())).collect();
+    cache_notes(segment_fingerprint(&dir, &indices), NotesDatabase { notes });
+    retire_legacy_notes_file()
+}
+
+/// Rewrite the notes packed into as few segments as possible. A normal save never moves
+/// notes between segments, so deletions leave them part-empty over time. Returns how
+/// many segment files were removed.
+pub fn compact_note_segments(database: &NotesDatabase) -> Result<usize, String> {
+    PENDING_NOTES.lock().unwrap_or_else(|e| e.into_inner()).take();
+    let dir = get_notes_segment_dir()?;
+    fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create notes directory: {}", e))?;
+
+    let mut guard = SEGMENTS.lock().unwrap_or_else(|e| e.into_inner());
+    let state = guard.get_or_insert_with(SegmentState::default);
+
+    let mut written = HashMap::new();
+    let mut segment_of = HashMap::new();
+    for (index, notes) in database.notes.chunks(MAX_NOTES_PER_SEGMENT).enumerate() {
+        let path = segment_path(&dir, index);
+        let content = serde_json::to_string_pretty(&SegmentContent { notes: notes.iter().collect() })
+            .map_err(|e| format!("Failed to serialize notes: {}", e))?;
+        let hash = content_hash(&content);
+        if state.written.get(&index) != Some(&hash) || !path.exists() {
+            fs::write(&path, &content)
+                .map_err(|e| format!("Failed to write notes segment {}: {}", path.display(), e))?;
+        }
+        written.insert(index, hash);
+        segment_of.extend(notes.iter().map(|n| (n.id.clone(), index)));
+    }
+    let stale: Vec<usize> = state.written.keys().filter(|index| !written.contains_key(index)).copied().collect();
+    for index in &stale {
+        let _ = fs::remove_file(segment_path(&dir, *index));
+    }
+    let indices: Vec<usize> = (0..written.len()).collect();
+    *state = SegmentState { segment_of, written };
+    drop(guard);
+
+    cache_notes(segment_fingerprint(&dir, &indices), NotesDatabase { notes: database.notes.clone() });
+    retire_legacy_notes_file()?;
+    Ok(stale.len())
+}
+
+fn retire_legacy_notes_file() -> Result<(), String> {
+    // The segments now hold everything the old single file did
+    let legacy = get_notes_file_path()?;
+    if legacy.exists() {
+        fs::rename(&legacy, legacy.with_extension("json.bak"))
+            .map_err(|e| format!("Failed to retire notes.json: {}", e))?;
+    }
+    Ok(())
 }
 
 pub fn save_categories(database: &CategoriesDatabase) -> Result<(), String> {
@@ -82,7 +854,9 @@ pub fn save_categories(database: &CategoriesDatabase) -> Result<(), String> {
         .map_err(|e| format!("Failed to serialize categories: {}", e))?;
     
     fs::write(&file_path, content)
-        .map_err(|e| format!("Failed to write categories file: {}", e))
+        .map_err(|e| format!("Failed to write categories file: {}", e))?;
+    bump_store_generation();
+    Ok(())
 }
 
 pub fn load_ui_state() -> Result<UIStateDatabase, String> {
@@ -97,6 +871,7 @@ pub fn load_ui_state() -> Result<UIStateDatabase, String> {
                     y: 0.0,
                     zoom: 0.8,
                 },
+                window_placements: HashMap::new(),
             },
         };
         save_ui_state(&default_state)?;
@@ -0,0 +1,488 @@
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use std::time::Duration;
+use tracing::error;
+use uuid::Uuid;
+use yrs::updates::decoder::Decode;
+use yrs::{Doc, GetString, OffsetKind, Options, ReadTxn, StateVector, Text, Transact, Update};
+use crate::models::{Note, NoteSyncState, SyncSettings, SyncStatus};
+use crate::services::ai_service::generate_simple_title;
+use crate::services::category_service::{ensure_category_path, update_category_note_counts};
+use crate::services::note_service::load_notes;
+use crate::services::settings_service::current_settings;
+use crate::services::visibility_service::{is_shareable, shareable_notes, SharingPath};
+use crate::services::storage_service::{get_sync_docs_dir, load_settings, load_sync_state, save_notes, save_settings, save_sync_state};
+
+/// Name of the text inside each note's CRDT document
+const CONTENT_TEXT: &str = "content";
+/// Category for notes that arrive from another device without one
+const SYNCED_CATEGORY: &str = "Synced";
+const RELAY_TIMEOUT_SECS: u64 = 60;
+
+/// Called with the overview after every sync. main.rs installs one that emits `sync:updated`.
+type SyncListener = Box<dyn Fn(&SyncOverview) + Send + Sync>;
+static SYNC_LISTENER: OnceLock<SyncListener> = OnceLock::new();
+
+#[derive(Serialize, Clone)]
+pub struct NoteSyncStatus {
+    pub note_id: String,
+    pub status: SyncStatus,
+    pub last_synced_at: Option<DateTime<Utc>>,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct SyncOverview {
+    pub enabled: bool,
+    pub relay_url: Option<String>,
+    pub last_sync_at: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+    pub notes: Vec<NoteSyncStatus>,
+}
+
+/// Body of `POST {relay_url}/sync`. Each note carries its whole CRDT state - merging is
+/// idempotent, so the relay (and the other devices) can apply it however often it arrives.
+#[derive(Serialize)]
+struct SyncRequest {
+    vault_id: String,
+    device_id: String,
+    cursor: Option<String>,
+    notes: Vec<SyncedNote>,
+}
+
+/// The relay's answer: every note changed by other devices since `cursor`
+#[derive(Deserialize)]
+struct SyncResponse {
+    cursor: Option<String>,
+    #[serde(default)]
+    notes: Vec<SyncedNote>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SyncedNote {
+    note_id: String,
+    update: String, // Base64 of the note's CRDT state (yrs update v1)
+    // Plain fields so a device that hasn't seen the note yet can create it
+    title: String,
+    category_path: Vec<String>,
+    timestamp: DateTime<Utc>,
+}
+
+pub fn set_sync_listener(listener: impl Fn(&SyncOverview) + Send + Sync + 'static) {
+    let _ = SYNC_LISTENER.set(Box::new(listener));
+}
+
+fn content_hash(content: &str) -> String {
+    Sha256::digest(content.as_bytes()).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn doc_path(note_id: &str) -> Result<PathBuf, String> {
+    Ok(get_sync_docs_dir()?.join(format!("{}.ydoc", note_id)))
+}
+
+fn new_doc() -> Doc {
+    // Byte offsets, so the diff below can index straight into Rust strings
+    Doc::with_options(Options { offset_kind: OffsetKind::Bytes, ..Options::default() })
+}
+
+fn apply_update(doc: &Doc, bytes: &[u8]) -> Result<(), String> {
+    let update = Update::decode_v1(bytes).map_err(|e| format!("Invalid sync update: {}", e))?;
+    doc.transact_mut().apply_update(update).map_err(|e| format!("Failed to apply sync update: {}", e))
+}
+
+/// The note's document as saved after the last sync, or an empty one
+fn load_doc(note_id: &str) -> Result<Doc, String> {
+    let doc = new_doc();
+    let path = doc_path(note_id)?;
+    if path.exists() {
+        let bytes = fs::read(&path).map_err(|e| format!("Failed to read sync document: {}", e))?;
+        apply_update(&doc, &bytes)?;
+    }
+    Ok(doc)
+}
+
+fn encode_doc(doc: &Doc) -> Vec<u8> {
+    doc.transact().encode_state_as_update_v1(&StateVector::default())
+}
+
+fn save_doc(note_id: &str, doc: &Doc) -> Result<(), String> {
+    fs::write(doc_path(note_id)?, encode_doc(doc)).map_err(|e| format!("Failed to write sync document: {}", e))
+}
+
+/// Delete the documents and sync state of notes that no longer exist. Returns how many
+/// documents were removed.
+pub fn prune_sync_docs(note_ids: &HashSet<&str>) -> Result<usize, String> {
+    let dir = get_sync_docs_dir()?;
+    let mut removed = 0;
+    for entry in fs::read_dir(&dir).map_err(|e| format!("Failed to read sync directory: {}", e))? {
+        let Ok(entry) = entry else { continue };
+        let path = entry.path();
+        let orphaned = path.extension().is_some_and(|ext| ext == "ydoc")
+            && path.file_stem().and_then(|stem| stem.to_str()).is_some_and(|id| !note_ids.contains(id));
+        if orphaned && fs::remove_file(&path).is_ok() {
+            removed += 1;
+        }
+    }
+
+    let mut state = load_sync_state()?;
+    let before = state.notes.len();
+    state.notes.retain(|id, _| note_ids.contains(id.as_str()));
+    if state.notes.len() != before {
+        save_sync_state(&state)?;
+    }
+    Ok(removed)
+}
+
+fn doc_text(doc: &Doc) -> String {
+    let text = doc.get_or_insert_text(CONTENT_TEXT);
+    let txn = doc.transact();
+    text.get_string(&txn)
+}
+
+/// Record a local edit in the document as the smallest single replacement that turns the
+/// document's text into `content`. Keeping the edit small is what lets a concurrent edit
+/// elsewhere in the note survive the merge.
+fn record_local_edit(doc: &Doc, content: &str) {
+    let text = doc.get_or_insert_text(CONTENT_TEXT);
+    let current = {
+        let txn = doc.transact();
+        text.get_string(&txn)
+    };
+    if current == content {
+        return;
+    }
+
+    let prefix = current.char_indices()
+        .zip(content.chars())
+        .take_while(|((_, a), b)| a == b)
+        .last()
+        .map(|((i, a), _)| i + a.len_utf8())
+        .unwrap_or(0);
+    let suffix = current[prefix..].chars().rev()
+        .zip(content[prefix..].chars().rev())
+        .take_while(|(a, b)| a == b)
+        .map(|(a, _)| a.len_utf8())
+        .sum::<usize>();
+
+    let removed = current.len() - suffix - prefix;
+    let inserted = &content[prefix..content.len() - suffix];
+    let mut txn = doc.transact_mut();
+    if removed > 0 {
+        text.remove_range(&mut txn, prefix as u32, removed as u32);
+    }
+    if !inserted.is_empty() {
+        text.insert(&mut txn, prefix as u32, inserted);
+    }
+}
+
+fn note_status(note: &Note, state: Option<&NoteSyncState>, shareable: bool) -> NoteSyncStatus {
+    let status = match state {
+        _ if !shareable => SyncStatus::Private,
+        Some(s) if s.error.is_some() => SyncStatus::Error,
+        Some(s) if s.synced_hash.as_deref() == Some(content_hash(&note.content).as_str()) => SyncStatus::Synced,
+        _ => SyncStatus::Pending,
+    };
+    NoteSyncStatus {
+        note_id: note.id.clone(),
+        status,
+        last_synced_at: state.and_then(|s| s.last_synced_at),
+        error: state.and_then(|s| s.error.clone()),
+    }
+}
+
+fn sync_overview() -> Result<SyncOverview, String> {
+    let settings = current_settings().sync;
+    let state = load_sync_state()?;
+    let notes = load_notes()?.notes;
+    let shareable: HashSet<String> = shareable_notes(notes.clone(), SharingPath::Http)?.into_iter()
+        .map(|n| n.id)
+        .collect();
+    let notes = notes.iter()
+        .map(|note| note_status(note, state.notes.get(&note.id), shareable.contains(&note.id)))
+        .collect();
+    Ok(SyncOverview {
+        enabled: settings.enabled,
+        relay_url: settings.relay_url,
+        last_sync_at: state.last_sync_at,
+        last_error: state.last_error,
+        notes,
+    })
+}
+
+fn notify(overview: &SyncOverview) {
+    if let Some(listener) = SYNC_LISTENER.get() {
+        listener(overview);
+    }
+}
+
+pub async fn get_sync_status() -> Result<SyncOverview, String> {
+    sync_overview()
+}
+
+pub async fn get_note_sync_status(note_id: String) -> Result<NoteSyncStatus, String> {
+    let note = load_notes()?.notes.into_iter()
+        .find(|n| n.id == note_id)
+        .ok_or_else(|| format!("Note {} not found", note_id))?;
+    Ok(note_status(&note, load_sync_state()?.notes.get(&note.id), is_shareable(&note, SharingPath::Http)))
+}
+
+pub async fn set_sync_settings(enabled: bool, relay_url: Option<String>, vault_id: Option<String>, interval_minutes: u32) -> Result<SyncSettings, String> {
+    let relay_url = relay_url.map(|u| u.trim().trim_end_matches('/').to_string()).filter(|u| !u.is_empty());
+    let vault_id = vault_id.map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+    if enabled && (relay_url.is_none() || vault_id.is_none()) {
+        return Err("Sync needs a relay URL and a vault id".to_string());
+    }
+    if relay_url.as_deref().is_some_and(|u| !u.starts_with("http://") && !u.starts_with("https://")) {
+        return Err("The relay URL must start with http:// or https://".to_string());
+    }
+
+    let mut database = load_settings()?;
+    let sync = &mut database.settings.sync;
+    // Another vault on the relay has its own history - start over from the beginning
+    let relay_changed = sync.relay_url != relay_url || sync.vault_id != vault_id;
+    sync.enabled = enabled;
+    sync.relay_url = relay_url;
+    sync.vault_id = vault_id;
+    sync.interval_minutes = interval_minutes.max(1);
+    if sync.device_id.is_none() {
+        sync.device_id = Some(Uuid::new_v4().to_string());
+    }
+    let settings = sync.clone();
+    save_settings(&database)?;
+
+    if relay_changed {
+        let mut state = load_sync_state()?;
+        state.cursor = None;
+        state.notes.clear();
+        save_sync_state(&state)?;
+    }
+    Ok(settings)
+}
+
+/// Exchange changes with the relay: send every note edited since the last sync, then
+/// merge in what other devices changed. Concurrent edits to the same note are merged by
+/// the CRDT instead of one side overwriting the other.
+pub async fn sync_now() -> Result<SyncOverview, String> {
+    let settings = current_settings().sync;
+    let (Some(relay_url), Some(vault_id)) = (settings.relay_url.clone(), settings.vault_id.clone()) else {
+        return Err("Sync needs a relay URL and a vault id".to_string());
+    };
+    let device_id = match settings.device_id.clone() {
+        Some(id) => id,
+        None => set_sync_settings(settings.enabled, settings.relay_url, settings.vault_id, settings.interval_minutes).await?
+            .device_id.unwrap_or_default(),
+    };
+
+    let result = exchange_with_relay(&relay_url, vault_id, device_id).await;
+    let mut state = load_sync_state()?;
+    state.last_error = result.as_ref().err().cloned();
+    if result.is_ok() {
+        state.last_sync_at = Some(Utc::now());
+    }
+    save_sync_state(&state)?;
+
+    let overview = sync_overview()?;
+    notify(&overview);
+    result.map(|_| overview)
+}
+
+async fn exchange_with_relay(relay_url: &str, vault_id: String, device_id: String) -> Result<(), String> {
+    let mut state = load_sync_state()?;
+    // Private notes never go to the relay
+    let notes = shareable_notes(load_notes()?.notes, SharingPath::Http)?;
+
+    // Fold local edits into each changed note's document before sending it
+    let mut docs: HashMap<String, Doc> = HashMap::new();
+    let mut outgoing = Vec::new();
+    for note in &notes {
+        let hash = content_hash(&note.content);
+        let note_state = state.notes.get(&note.id);
+        if note_state.is_some_and(|s| s.synced_hash.as_deref() == Some(hash.as_str()) && s.error.is_none()) {
+            continue;
+        }
+        let doc = load_doc(&note.id)?;
+        record_local_edit(&doc, &note.content);
+        outgoing.push(SyncedNote {
+            note_id: note.id.clone(),
+            update: BASE64.encode(encode_doc(&doc)),
+            title: note.title.clone(),
+            category_path: note.category_path.clone(),
+            timestamp: note.timestamp,
+        });
+        docs.insert(note.id.clone(), doc);
+    }
+
+    let request = SyncRequest { vault_id, device_id, cursor: state.cursor.clone(), notes: outgoing };
+    let response = reqwest::Client::new()
+        .post(format!("{}/sync", relay_url))
+        .timeout(Duration::from_secs(RELAY_TIMEOUT_SECS))
+        .json(&request)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach the sync relay: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("The sync relay answered with status {}", response.status()));
+    }
+    let response: SyncResponse = response.json().await
+        .map_err(|e| format!("Failed to read the sync relay's answer: {}", e))?;
+
+    // Merge what the other devices sent. The notes are loaded again: edits saved while the
+    // relay was answering aren't in the documents yet and are recorded before merging.
+    let mut database = load_notes()?;
+    let mut created = false;
+    let now = Utc::now();
+    for remote in response.notes {
+        let merged = BASE64.decode(&remote.update)
+            .map_err(|e| format!("Invalid sync update: {}", e))
+            .and_then(|bytes| {
+                let doc = match docs.remove(&remote.note_id) {
+                    Some(doc) => doc,
+                    None => load_doc(&remote.note_id)?,
+                };
+                if let Some(note) = database.notes.iter().find(|n| n.id == remote.note_id) {
+                    record_local_edit(&doc, &note.content);
+                }
+                apply_update(&doc, &bytes)?;
+                Ok(doc)
+            });
+        let doc = match merged {
+            Ok(doc) => doc,
+            Err(e) => {
+                error!("Failed to merge synced note {}: {}", remote.note_id, e);
+                state.notes.entry(remote.note_id).or_default().error = Some(e);
+                continue;
+            }
+        };
+
+        let content = doc_text(&doc);
+        match database.notes.iter_mut().find(|n| n.id == remote.note_id) {
+            Some(note) => {
+                if note.content != content {
+                    note.content = content.clone();
+                    note.revision += 1;
+                }
+            }
+            None => {
+                let category_path = if remote.category_path.is_empty() { vec![SYNCED_CATEGORY.to_string()] } else { remote.category_path };
+                ensure_category_path(&category_path)?;
+                let title = if remote.title.trim().is_empty() { generate_simple_title(&content) } else { remote.title };
+                let mut note = Note::new(title, content.clone(), category_path);
+                note.id = remote.note_id.clone();
+                note.timestamp = remote.timestamp;
+                database.notes.push(note);
+                created = true;
+            }
+        }
+        save_doc(&remote.note_id, &doc)?;
+        state.notes.insert(remote.note_id, NoteSyncState {
+            synced_hash: Some(content_hash(&content)),
+            last_synced_at: Some(now),
+            error: None,
+        });
+    }
+    save_notes(&database)?;
+    if created {
+        update_category_note_counts()?;
+    }
+
+    // Whatever was sent and not answered with a merge is now what the relay has
+    for (note_id, doc) in docs {
+        save_doc(&note_id, &doc)?;
+        state.notes.insert(note_id, NoteSyncState {
+            synced_hash: Some(content_hash(&doc_text(&doc))),
+            last_synced_at: Some(now),
+            error: None,
+        });
+    }
+    if response.cursor.is_some() {
+        state.cursor = response.cursor;
+    }
+    save_sync_state(&state)
+}
+
+/// Background job started at launch: syncs on the configured interval while sync is on
+pub async fn run_sync_loop() {
+    loop {
+        let settings = current_settings().sync;
+        if settings.enabled {
+            if let Err(e) = sync_now().await {
+                error!("Sync failed: {}", e);
+            }
+        }
+        let minutes = settings.interval_minutes.max(1) as u64;
+        tokio::time::sleep(Duration::from_secs(minutes * 60)).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn doc_with(content: &str) -> Doc {
+        let doc = new_doc();
+        record_local_edit(&doc, content);
+        doc
+    }
+
+    /// A second device's copy of `doc`
+    fn fork(doc: &Doc) -> Doc {
+        let copy = new_doc();
+        apply_update(&copy, &encode_doc(doc)).unwrap();
+        copy
+    }
+
+    fn exchange(a: &Doc, b: &Doc) {
+        let (from_a, from_b) = (encode_doc(a), encode_doc(b));
+        apply_update(a, &from_b).unwrap();
+        apply_update(b, &from_a).unwrap();
+    }
+
+    #[test]
+    fn records_edits_to_multibyte_text() {
+        let doc = doc_with("héllo wörld 👋");
+        for content in ["héllo wörld 👋🌍", "日本語のテキスト", "日本のテキスト", "ünïcödé", ""] {
+            record_local_edit(&doc, content);
+            assert_eq!(doc_text(&doc), content);
+        }
+    }
+
+    #[test]
+    fn concurrent_edits_in_different_places_both_survive() {
+        let a = doc_with("The quick brown fox");
+        let b = fork(&a);
+        record_local_edit(&a, "The very quick brown fox");
+        record_local_edit(&b, "The quick brown fox jumps");
+        exchange(&a, &b);
+        assert_eq!(doc_text(&a), "The very quick brown fox jumps");
+        assert_eq!(doc_text(&b), doc_text(&a));
+    }
+
+    #[test]
+    fn concurrent_multibyte_edits_both_survive() {
+        let a = doc_with("café ☕ naïve");
+        let b = fork(&a);
+        record_local_edit(&a, "café ☕☕ naïve");
+        record_local_edit(&b, "café ☕ naïve — ünïcödé");
+        exchange(&a, &b);
+        assert_eq!(doc_text(&a), "café ☕☕ naïve — ünïcödé");
+        assert_eq!(doc_text(&b), doc_text(&a));
+    }
+
+    #[test]
+    fn edit_recorded_before_a_remote_update_is_kept() {
+        // What a sync does with an edit saved while the relay was answering
+        let local = doc_with("Shopping: milk");
+        let remote = fork(&local);
+        record_local_edit(&remote, "Shopping: milk, eggs");
+        record_local_edit(&local, "Groceries - Shopping: milk");
+        apply_update(&local, &encode_doc(&remote)).unwrap();
+        assert_eq!(doc_text(&local), "Groceries - Shopping: milk, eggs");
+    }
+}
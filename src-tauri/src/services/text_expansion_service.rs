@@ -0,0 +1,227 @@
+use enigo::{Direction, Enigo, Key, Keyboard, Settings};
+use rdev::{EventType, Key as RawKey};
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tracing::{error, warn};
+use crate::models::{Note, NoteKind};
+use crate::services::note_service::load_notes;
+use crate::services::paste_service::inject_paste;
+use crate::services::settings_service::current_settings;
+use crate::services::storage_service::{load_settings, save_notes, save_settings};
+use crate::services::locale_service::tr;
+use crate::services::app_lock_service::is_locked;
+
+/// Metadata key holding a snippet's trigger text
+const TRIGGER_KEY: &str = "trigger";
+/// Tag that marks a note as usable for expansion
+const SNIPPET_TAG: &str = "snippet";
+
+/// Characters kept of what the user typed - longer than any sensible trigger
+const MAX_TYPED: usize = 64;
+/// How often the trigger list is re-read from the notes while expansion is on
+const REFRESH_INTERVAL_SECS: u64 = 10;
+
+/// Triggers currently listened for, longest first so `;addr2` wins over `;addr`
+static TRIGGERS: Mutex<Vec<(String, String)>> = Mutex::new(Vec::new());
+/// What has been typed since the last break (enter, click, ...)
+static TYPED: Mutex<String> = Mutex::new(String::new());
+static LISTENER_STARTED: AtomicBool = AtomicBool::new(false);
+/// Cached copy of the setting - the hook runs on every keystroke and can't read files
+static ENABLED: AtomicBool = AtomicBool::new(false);
+/// Set while an expansion is being typed, so its own key events are ignored
+static EXPANDING: AtomicBool = AtomicBool::new(false);
+
+#[derive(Serialize, Clone)]
+pub struct Expansion {
+    pub trigger: String,
+    pub note_id: String,
+    pub note_title: String,
+    pub preview: String, // First line of the text that gets inserted
+}
+
+fn is_expansion_note(note: &Note) -> bool {
+    note.kind == NoteKind::Snippet || note.tags.iter().any(|t| t.eq_ignore_ascii_case(SNIPPET_TAG))
+}
+
+fn note_trigger(note: &Note) -> Option<&str> {
+    note.metadata.get(TRIGGER_KEY)?.as_str().map(str::trim).filter(|t| !t.is_empty())
+}
+
+fn expansions_from(notes: &[Note]) -> Vec<Expansion> {
+    let mut expansions: Vec<Expansion> = notes.iter()
+        .filter(|n| is_expansion_note(n))
+        .filter_map(|note| {
+            Some(Expansion {
+                trigger: note_trigger(note)?.to_string(),
+                note_id: note.id.clone(),
+                note_title: note.title.clone(),
+                preview: note.content.lines().next().unwrap_or("").chars().take(80).collect(),
+            })
+        })
+        .collect();
+    expansions.sort_by(|a, b| a.trigger.cmp(&b.trigger));
+    expansions
+}
+
+/// Re-read the triggers from the notes
+fn refresh_triggers() -> Result<(), String> {
+    let mut triggers: Vec<(String, String)> = expansions_from(&load_notes()?.notes).into_iter()
+        .map(|e| (e.trigger, e.note_id))
+        .collect();
+    triggers.sort_by_key(|(trigger, _)| std::cmp::Reverse(trigger.chars().count()));
+    *TRIGGERS.lock().unwrap_or_else(|e| e.into_inner()) = triggers;
+    Ok(())
+}
+
+pub async fn list_expansions() -> Result<Vec<Expansion>, String> {
+    Ok(expansions_from(&load_notes()?.notes))
+}
+
+/// Give a note a trigger (e.g. `;addr`), tagging it `snippet`, or remove it with `None`.
+/// Triggers can't contain whitespace and must be unique.
+pub async fn set_snippet_trigger(note_id: String, trigger: Option<String>) -> Result<Note, String> {
+    let trigger = trigger.map(|t| t.trim().to_string()).filter(|t| !t.is_empty());
+    if let Some(trigger) = &trigger {
+        if trigger.chars().count() < 2 || trigger.chars().count() > MAX_TYPED || trigger.contains(char::is_whitespace) {
+            return Err("Triggers must be 2-64 characters with no spaces, e.g. ;addr".to_string());
+        }
+    }
+
+    let mut database = load_notes()?;
+    if let Some(trigger) = &trigger {
+        let clash = database.notes.iter()
+            .find(|n| n.id != note_id && is_expansion_note(n) && note_trigger(n) == Some(trigger.as_str()));
+        if let Some(other) = clash {
+            return Err(format!("Trigger {} is already used by \"{}\"", trigger, other.title));
+        }
+    }
+
+    let note = database.notes.iter_mut()
+        .find(|n| n.id == note_id)
+        .ok_or_else(|| tr("error.note_not_found", &[&note_id]))?;
+    match trigger {
+        Some(trigger) => {
+            note.metadata.insert(TRIGGER_KEY.to_string(), trigger.into());
+            if !is_expansion_note(note) {
+                note.tags.push(SNIPPET_TAG.to_string());
+            }
+        }
+        None => {
+            note.metadata.remove(TRIGGER_KEY);
+        }
+    }
+
+    note.revision += 1;
+    let updated = note.clone();
+    save_notes(&database)?;
+    refresh_triggers()?;
+    Ok(updated)
+}
+
+/// Turn the expander on or off. The keyboard hook starts the first time it's enabled.
+pub async fn set_text_expansion_enabled(enabled: bool) -> Result<bool, String> {
+    let mut database = load_settings()?;
+    database.settings.text_expansion = enabled;
+    save_settings(&database)?;
+    ENABLED.store(enabled, Ordering::Relaxed);
+    if enabled {
+        refresh_triggers()?;
+        start_listener();
+    }
+    Ok(enabled)
+}
+
+/// Delete the trigger text the user typed and insert the snippet in its place
+fn expand(trigger: String, note_id: String) {
+    // The keyboard hook runs outside the command gate, so it has to respect the lock itself
+    if is_locked() {
+        return;
+    }
+    EXPANDING.store(true, Ordering::SeqCst);
+    let result = (|| -> Result<(), String> {
+        let note = load_notes()?.notes.into_iter()
+            .find(|n| n.id == note_id)
+            .ok_or_else(|| tr("error.note_not_found", &[&note_id]))?;
+
+        let mut enigo = Enigo::new(&Settings::default())
+            .map_err(|e| format!("Keyboard input unavailable: {}", e))?;
+        for _ in 0..trigger.chars().count() {
+            enigo.key(Key::Backspace, Direction::Click)
+                .map_err(|e| format!("Failed to erase trigger: {}", e))?;
+        }
+        inject_paste(note.content)
+    })();
+    if let Err(e) = result {
+        error!("Text expansion failed: {}", e);
+    }
+    TYPED.lock().unwrap_or_else(|e| e.into_inner()).clear();
+    EXPANDING.store(false, Ordering::SeqCst);
+}
+
+/// Keyboard hook callback: track typed text and expand when it ends with a trigger
+fn on_key_event(event: rdev::Event) {
+    if EXPANDING.load(Ordering::SeqCst) || !ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+
+    let mut typed = TYPED.lock().unwrap_or_else(|e| e.into_inner());
+    match event.event_type {
+        EventType::KeyPress(RawKey::Backspace) => {
+            typed.pop();
+            return;
+        }
+        EventType::KeyPress(RawKey::Return | RawKey::Tab | RawKey::Escape | RawKey::UpArrow | RawKey::DownArrow | RawKey::LeftArrow | RawKey::RightArrow)
+        | EventType::ButtonPress(_) => {
+            typed.clear();
+            return;
+        }
+        EventType::KeyPress(_) => {}
+        _ => return,
+    }
+    let Some(text) = event.name.filter(|n| !n.chars().any(char::is_control)) else { return };
+    typed.push_str(&text);
+    let excess = typed.chars().count().saturating_sub(MAX_TYPED);
+    if excess > 0 {
+        let cut = typed.char_indices().nth(excess).map(|(i, _)| i).unwrap_or(0);
+        typed.drain(..cut);
+    }
+
+    let triggers = TRIGGERS.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some((trigger, note_id)) = triggers.iter().find(|(trigger, _)| typed.ends_with(trigger.as_str())) {
+        typed.clear();
+        // Never inject from inside the hook - some platforms deliver events on this thread
+        let (trigger, note_id) = (trigger.clone(), note_id.clone());
+        std::thread::spawn(move || expand(trigger, note_id));
+    }
+}
+
+/// Start the global keyboard hook once. If the platform refuses it (no accessibility
+/// permission, Wayland) the failure is logged and expansion stays inactive.
+fn start_listener() {
+    if LISTENER_STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    std::thread::spawn(|| {
+        if let Err(e) = rdev::listen(on_key_event) {
+            error!("Text expansion keyboard hook failed: {:?}", e);
+        }
+    });
+}
+
+/// Background job started at launch: idles until text expansion is enabled, then keeps
+/// the keyboard hook running and its trigger list fresh
+pub async fn run_text_expansion_loop() {
+    loop {
+        let enabled = current_settings().text_expansion;
+        ENABLED.store(enabled, Ordering::Relaxed);
+        if enabled {
+            match refresh_triggers() {
+                Ok(()) => start_listener(),
+                Err(e) => warn!("Failed to load text expansions: {}", e),
+            }
+        }
+        tokio::time::sleep(Duration::from_secs(REFRESH_INTERVAL_SECS)).await;
+    }
+}
@@ -0,0 +1,95 @@
+use chrono::{Local, Utc};
+use serde::Serialize;
+use tracing::warn;
+use uuid::Uuid;
+use crate::models::{LinkType, Note, NoteLink, TimelineLinks};
+use crate::services::note_service::{daily_note_date, load_notes};
+use crate::services::settings_service::current_settings;
+use crate::services::storage_service::{load_links, load_settings, save_links, save_settings};
+
+#[derive(Serialize)]
+pub struct TimelineEntry {
+    pub note: Note,
+    pub previous_id: Option<String>,
+    pub next_id: Option<String>,
+}
+
+pub async fn set_timeline_links(mode: TimelineLinks) -> Result<TimelineLinks, String> {
+    let mut database = load_settings()?;
+    database.settings.timeline_links = mode;
+    save_settings(&database)?;
+    Ok(mode)
+}
+
+/// The note a newly created one follows on from under `mode`
+fn timeline_predecessor<'a>(notes: &'a [Note], note: &Note, mode: TimelineLinks) -> Option<&'a Note> {
+    if daily_note_date(note).is_some() {
+        return None;
+    }
+    match mode {
+        TimelineLinks::Off => None,
+        TimelineLinks::Category => notes.iter()
+            .filter(|n| n.id != note.id && n.category_path == note.category_path && n.timestamp <= note.timestamp)
+            .max_by_key(|n| n.timestamp),
+        TimelineLinks::DailyNote => {
+            let day = note.timestamp.with_timezone(&Local).date_naive();
+            notes.iter().find(|n| daily_note_date(n) == Some(day))
+        }
+    }
+}
+
+/// Link a freshly created note into its timeline, if that's switched on. Failures are
+/// logged - a save must never fail because the graph couldn't be updated.
+pub fn link_into_timeline(note: &Note) {
+    let mode = current_settings().timeline_links;
+    if mode == TimelineLinks::Off {
+        return;
+    }
+    if let Err(e) = try_link_into_timeline(note, mode) {
+        warn!("Failed to add timeline link for {}: {}", note.id, e);
+    }
+}
+
+fn try_link_into_timeline(note: &Note, mode: TimelineLinks) -> Result<(), String> {
+    let notes = load_notes()?.notes;
+    let Some(previous) = timeline_predecessor(&notes, note, mode) else { return Ok(()) };
+
+    let mut links_db = load_links()?;
+    let already_linked = links_db.links.iter()
+        .any(|l| l.source_id == previous.id && l.target_id == note.id && l.link_type == LinkType::FollowUp);
+    if already_linked {
+        return Ok(());
+    }
+    links_db.links.push(NoteLink {
+        id: Uuid::new_v4().to_string(),
+        source_id: previous.id.clone(),
+        target_id: note.id.clone(),
+        link_type: LinkType::FollowUp,
+        label: None,
+        color: None,
+        directional: Some(true),
+        created_at: Utc::now(),
+        target_block: None,
+        weight: None,
+        weight_is_manual: false,
+    });
+    save_links(&links_db)
+}
+
+/// Notes filed directly in a category, oldest first, each pointing at its neighbours
+pub async fn get_note_timeline(category_path: Vec<String>) -> Result<Vec<TimelineEntry>, String> {
+    let mut notes: Vec<Note> = load_notes()?.notes.into_iter()
+        .filter(|n| n.category_path == category_path)
+        .collect();
+    notes.sort_by_key(|n| n.timestamp);
+
+    let ids: Vec<String> = notes.iter().map(|n| n.id.clone()).collect();
+    Ok(notes.into_iter()
+        .enumerate()
+        .map(|(index, note)| TimelineEntry {
+            note,
+            previous_id: index.checked_sub(1).map(|i| ids[i].clone()),
+            next_id: ids.get(index + 1).cloned(),
+        })
+        .collect())
+}
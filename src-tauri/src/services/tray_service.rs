@@ -0,0 +1,118 @@
+use serde::Serialize;
+use std::sync::{Mutex, OnceLock};
+use crate::models::TrayIconStyle;
+use crate::services::settings_service::current_settings;
+use crate::services::storage_service::{load_settings, save_settings};
+
+/// Badge dot colour (RGB)
+const BADGE_COLOR: [u8; 3] = [0xE5, 0x48, 0x4D];
+
+/// Why the tray icon wants attention. The dot shows while any reason is set.
+#[derive(Serialize, Clone, Copy, PartialEq, Debug)]
+pub enum TrayBadge {
+    RemindersDue, // Cleared by the reminder check once nothing is due
+    JobFinished,  // Cleared when the window is opened
+}
+
+/// What the tray icon should look like right now
+#[derive(Serialize, Clone, PartialEq)]
+pub struct TrayIconState {
+    pub style: TrayIconStyle,
+    pub badges: Vec<TrayBadge>,
+}
+
+impl TrayIconState {
+    pub fn has_badge(&self) -> bool {
+        !self.badges.is_empty()
+    }
+}
+
+static BADGES: Mutex<Vec<TrayBadge>> = Mutex::new(Vec::new());
+
+/// Called with the new state whenever the style or the badge changes. main.rs installs
+/// one that redraws the tray icon.
+type TrayListener = Box<dyn Fn(&TrayIconState) + Send + Sync>;
+static TRAY_LISTENER: OnceLock<TrayListener> = OnceLock::new();
+
+pub fn set_tray_listener(listener: impl Fn(&TrayIconState) + Send + Sync + 'static) {
+    let _ = TRAY_LISTENER.set(Box::new(listener));
+}
+
+pub fn tray_icon_state() -> TrayIconState {
+    TrayIconState {
+        style: current_settings().tray_icon,
+        badges: BADGES.lock().unwrap_or_else(|e| e.into_inner()).clone(),
+    }
+}
+
+fn notify() {
+    if let Some(listener) = TRAY_LISTENER.get() {
+        listener(&tray_icon_state());
+    }
+}
+
+/// Turn one badge reason on or off. Subsystems call this freely - the icon is only
+/// redrawn when the set of reasons actually changes.
+pub fn set_tray_badge(badge: TrayBadge, on: bool) {
+    let changed = {
+        let mut badges = BADGES.lock().unwrap_or_else(|e| e.into_inner());
+        match (on, badges.iter().position(|b| *b == badge)) {
+            (true, None) => {
+                badges.push(badge);
+                true
+            }
+            (false, Some(index)) => {
+                badges.remove(index);
+                true
+            }
+            _ => false,
+        }
+    };
+    if changed {
+        notify();
+    }
+}
+
+pub async fn get_tray_icon_state() -> Result<TrayIconState, String> {
+    Ok(tray_icon_state())
+}
+
+pub async fn set_tray_icon_style(style: TrayIconStyle) -> Result<TrayIconState, String> {
+    let mut database = load_settings()?;
+    database.settings.tray_icon = style;
+    save_settings(&database)?;
+    notify();
+    Ok(tray_icon_state())
+}
+
+/// Redraw the app icon (RGBA, `width` x `height`) for the tray: recoloured for the style,
+/// with a dot in the top-right corner when a badge is set
+pub fn render_tray_icon(rgba: &[u8], width: u32, height: u32, state: &TrayIconState) -> Vec<u8> {
+    let mut pixels = rgba.to_vec();
+    let glyph = match state.style {
+        TrayIconStyle::Color => None,
+        TrayIconStyle::Template | TrayIconStyle::Light => Some([0, 0, 0]),
+        TrayIconStyle::Dark => Some([0xFF, 0xFF, 0xFF]),
+    };
+    if let Some(color) = glyph {
+        for pixel in pixels.chunks_exact_mut(4) {
+            pixel[..3].copy_from_slice(&color);
+        }
+    }
+
+    if state.has_badge() {
+        let radius = width.min(height) as f32 / 5.0;
+        let (centre_x, centre_y) = (width as f32 - radius - 1.0, radius + 1.0);
+        for y in 0..height {
+            for x in 0..width {
+                let (dx, dy) = (x as f32 + 0.5 - centre_x, y as f32 + 0.5 - centre_y);
+                if dx * dx + dy * dy <= radius * radius {
+                    let offset = ((y * width + x) * 4) as usize;
+                    pixels[offset..offset + 3].copy_from_slice(&BADGE_COLOR);
+                    pixels[offset + 3] = 0xFF;
+                }
+            }
+        }
+    }
+    pixels
+}
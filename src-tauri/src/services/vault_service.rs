@@ -0,0 +1,224 @@
+use chrono::Utc;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::warn;
+use uuid::Uuid;
+use crate::models::{CrossVaultLink, MountedVault, Note, NotesDatabase};
+use crate::services::note_service::{load_notes, note_matches_query, NoteQuery};
+use crate::services::storage_service::{get_app_data_dir, load_mounted_vaults, save_mounted_vaults};
+
+/// A note from a mounted vault, with where it came from
+#[derive(Serialize)]
+pub struct SharedNote {
+    pub vault_id: String,
+    pub vault_name: String,
+    pub read_only: bool,
+    pub note: Note,
+}
+
+/// Where a vault keeps its notes: `notes/segment-NNNN.json` files, or one `notes.json`
+/// in vaults that haven't been split yet
+enum NoteFiles {
+    Segments(Vec<PathBuf>),
+    Single(PathBuf),
+}
+
+fn note_files(root: &Path) -> Result<NoteFiles, String> {
+    let dir = root.join("notes");
+    if dir.is_dir() {
+        let mut segments: Vec<PathBuf> = fs::read_dir(&dir)
+            .map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.file_name().and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with("segment-") && n.ends_with(".json")))
+            .collect();
+        if !segments.is_empty() {
+            segments.sort();
+            return Ok(NoteFiles::Segments(segments));
+        }
+    }
+    Ok(NoteFiles::Single(root.join("notes.json")))
+}
+
+fn read_notes_file(path: &Path) -> Result<NotesDatabase, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
+}
+
+fn write_notes_file(path: &Path, database: &NotesDatabase) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(database)
+        .map_err(|e| format!("Failed to serialize notes: {}", e))?;
+    fs::write(path, content)
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Every note in the vault at `root`, read fresh - another app may be writing it
+fn read_vault_notes(root: &Path) -> Result<Vec<Note>, String> {
+    match note_files(root)? {
+        NoteFiles::Segments(paths) => {
+            let mut notes = Vec::new();
+            for path in paths {
+                notes.extend(read_notes_file(&path)?.notes);
+            }
+            Ok(notes)
+        }
+        NoteFiles::Single(path) if path.exists() => Ok(read_notes_file(&path)?.notes),
+        NoteFiles::Single(_) => Ok(Vec::new()),
+    }
+}
+
+fn find_vault(vault_id: &str) -> Result<MountedVault, String> {
+    load_mounted_vaults()?.vaults.into_iter()
+        .find(|v| v.id == vault_id)
+        .ok_or_else(|| format!("Mounted vault {} not found", vault_id))
+}
+
+pub async fn list_mounted_vaults() -> Result<Vec<MountedVault>, String> {
+    Ok(load_mounted_vaults()?.vaults)
+}
+
+/// Show the vault in `path` alongside this one. `path` is the other vault's data folder.
+pub async fn mount_shared_vault(path: String, read_only: bool, name: Option<String>) -> Result<MountedVault, String> {
+    let root = fs::canonicalize(path.trim())
+        .map_err(|e| format!("Vault folder {} not found: {}", path, e))?;
+    if !root.is_dir() {
+        return Err(format!("{} is not a folder", root.display()));
+    }
+    if root == fs::canonicalize(get_app_data_dir()?).unwrap_or_default() {
+        return Err("That's this vault's own folder".to_string());
+    }
+    let has_notes = match note_files(&root)? {
+        NoteFiles::Segments(_) => true,
+        NoteFiles::Single(file) => file.exists(),
+    };
+    if !has_notes {
+        return Err(format!("No NowledgeBase vault found in {}", root.display()));
+    }
+    // Reading it once up front turns a broken vault into an error now rather than later
+    read_vault_notes(&root)?;
+
+    let root_str = root.to_string_lossy().to_string();
+    let mut database = load_mounted_vaults()?;
+    if database.vaults.iter().any(|v| v.path == root_str) {
+        return Err(format!("{} is already mounted", root_str));
+    }
+
+    let vault = MountedVault {
+        id: Uuid::new_v4().to_string(),
+        name: name.map(|n| n.trim().to_string()).filter(|n| !n.is_empty())
+            .or_else(|| root.file_name().map(|n| n.to_string_lossy().to_string()))
+            .unwrap_or_else(|| root_str.clone()),
+        path: root_str,
+        read_only,
+        mounted_at: Utc::now(),
+    };
+    database.vaults.push(vault.clone());
+    save_mounted_vaults(&database)?;
+    Ok(vault)
+}
+
+/// Stop showing a mounted vault. Links into it go too - their targets can't be resolved anymore.
+pub async fn unmount_shared_vault(vault_id: String) -> Result<(), String> {
+    let mut database = load_mounted_vaults()?;
+    let before = database.vaults.len();
+    database.vaults.retain(|v| v.id != vault_id);
+    if database.vaults.len() == before {
+        return Err(format!("Mounted vault {} not found", vault_id));
+    }
+    database.links.retain(|l| l.vault_id != vault_id);
+    save_mounted_vaults(&database)
+}
+
+pub async fn get_shared_vault_notes(vault_id: String) -> Result<Vec<Note>, String> {
+    read_vault_notes(Path::new(&find_vault(&vault_id)?.path))
+}
+
+/// `query_notes` over every mounted vault. A vault that can't be read (drive offline) is
+/// skipped rather than failing the whole search.
+pub async fn search_shared_vaults(query: NoteQuery) -> Result<Vec<SharedNote>, String> {
+    let mut results = Vec::new();
+    for vault in load_mounted_vaults()?.vaults {
+        let notes = match read_vault_notes(Path::new(&vault.path)) {
+            Ok(notes) => notes,
+            Err(e) => {
+                warn!("Skipping mounted vault {}: {}", vault.name, e);
+                continue;
+            }
+        };
+        results.extend(notes.into_iter()
+            .filter(|note| note_matches_query(note, &query))
+            .map(|note| SharedNote { vault_id: vault.id.clone(), vault_name: vault.name.clone(), read_only: vault.read_only, note }));
+    }
+    Ok(results)
+}
+
+/// Edit a note's content in a vault mounted writable
+pub async fn update_shared_note(vault_id: String, note_id: String, content: String) -> Result<Note, String> {
+    let vault = find_vault(&vault_id)?;
+    if vault.read_only {
+        return Err(format!("{} is mounted read-only", vault.name));
+    }
+
+    let paths = match note_files(Path::new(&vault.path))? {
+        NoteFiles::Segments(paths) => paths,
+        NoteFiles::Single(path) => vec![path],
+    };
+    for path in paths.iter().filter(|p| p.exists()) {
+        let mut database = read_notes_file(path)?;
+        if let Some(note) = database.notes.iter_mut().find(|n| n.id == note_id) {
+            note.content = content;
+            note.revision += 1;
+            let updated = note.clone();
+            write_notes_file(path, &database)?;
+            return Ok(updated);
+        }
+    }
+    Err(format!("Note {} not found in {}", note_id, vault.name))
+}
+
+/// Link a note of this vault to a note in a mounted vault
+pub async fn create_cross_vault_link(source_id: String, vault_id: String, target_id: String, label: Option<String>) -> Result<CrossVaultLink, String> {
+    if !load_notes()?.notes.iter().any(|n| n.id == source_id) {
+        return Err(format!("Source note with id {} not found", source_id));
+    }
+    let vault = find_vault(&vault_id)?;
+    if !read_vault_notes(Path::new(&vault.path))?.iter().any(|n| n.id == target_id) {
+        return Err(format!("Note {} not found in {}", target_id, vault.name));
+    }
+
+    let mut database = load_mounted_vaults()?;
+    if database.links.iter().any(|l| l.source_id == source_id && l.vault_id == vault_id && l.target_id == target_id) {
+        return Err("These notes are already linked".to_string());
+    }
+    let link = CrossVaultLink {
+        id: Uuid::new_v4().to_string(),
+        source_id,
+        vault_id,
+        target_id,
+        label: label.map(|l| l.trim().to_string()).filter(|l| !l.is_empty()),
+        created_at: Utc::now(),
+    };
+    database.links.push(link.clone());
+    save_mounted_vaults(&database)?;
+    Ok(link)
+}
+
+pub async fn delete_cross_vault_link(link_id: String) -> Result<(), String> {
+    let mut database = load_mounted_vaults()?;
+    let before = database.links.len();
+    database.links.retain(|l| l.id != link_id);
+    if database.links.len() == before {
+        return Err(format!("Link {} not found", link_id));
+    }
+    save_mounted_vaults(&database)
+}
+
+/// Cross-vault links, optionally only those from one note of this vault
+pub async fn get_cross_vault_links(source_id: Option<String>) -> Result<Vec<CrossVaultLink>, String> {
+    Ok(load_mounted_vaults()?.links.into_iter()
+        .filter(|l| source_id.as_ref().is_none_or(|id| &l.source_id == id))
+        .collect())
+}
@@ -0,0 +1,95 @@
+use crate::models::{Category, Note, Visibility};
+use crate::services::category_service::load_categories;
+use crate::services::note_service::load_notes;
+use crate::services::storage_service::{save_categories, save_notes};
+use crate::services::locale_service::tr;
+
+/// Ways a note can leave the app. Every one of them asks `shareable_notes` or
+/// `ensure_shareable` first, so a private note has no way out.
+#[derive(Clone, Copy, PartialEq)]
+pub enum SharingPath {
+    Export,  // Files written by the export commands
+    Http,    // Webhook payloads and the sync relay
+    Publish, // Standalone HTML made to be handed to anyone
+}
+
+impl SharingPath {
+    fn allows(self, visibility: Visibility) -> bool {
+        match visibility {
+            Visibility::Private => false,
+            Visibility::Shared => self != SharingPath::Publish,
+            Visibility::Public => true,
+        }
+    }
+}
+
+/// The note's own visibility, else that of its deepest category that sets one, else `Shared`
+pub fn effective_visibility(note: &Note, categories: &[Category]) -> Visibility {
+    if let Some(visibility) = note.visibility {
+        return visibility;
+    }
+    (1..=note.category_path.len()).rev()
+        .find_map(|depth| categories.iter()
+            .find(|c| c.path == note.category_path[..depth])
+            .and_then(|c| c.visibility))
+        .unwrap_or_default()
+}
+
+/// The notes allowed out through `path`, in their original order
+pub fn shareable_notes(notes: Vec<Note>, path: SharingPath) -> Result<Vec<Note>, String> {
+    let categories = load_categories()?.categories;
+    Ok(notes.into_iter()
+        .filter(|note| path.allows(effective_visibility(note, &categories)))
+        .collect())
+}
+
+/// Whether one note may go out through `path`. A failure to read the categories counts as
+/// "no" - better a missed webhook than a leaked note.
+pub fn is_shareable(note: &Note, path: SharingPath) -> bool {
+    load_categories()
+        .map(|db| path.allows(effective_visibility(note, &db.categories)))
+        .unwrap_or(false)
+}
+
+pub fn ensure_shareable(note: &Note, path: SharingPath) -> Result<(), String> {
+    if is_shareable(note, path) {
+        return Ok(());
+    }
+    Err(match path {
+        SharingPath::Publish => format!("'{}' isn't public - mark it public to publish it", note.title),
+        _ => format!("'{}' is private and can't be shared", note.title),
+    })
+}
+
+/// Set (or with `None`, clear so the category decides) a note's visibility
+pub async fn set_note_visibility(note_id: String, visibility: Option<Visibility>) -> Result<Note, String> {
+    let mut database = load_notes()?;
+    let note = database.notes.iter_mut()
+        .find(|n| n.id == note_id)
+        .ok_or_else(|| tr("error.note_not_found", &[&note_id]))?;
+    note.visibility = visibility;
+    note.revision += 1;
+    let updated = note.clone();
+    save_notes(&database)?;
+    Ok(updated)
+}
+
+/// Set (or clear) the visibility inherited by a category's notes and subcategories
+pub async fn set_category_visibility(category_path: Vec<String>, visibility: Option<Visibility>) -> Result<Category, String> {
+    let mut database = load_categories()?;
+    let category = database.categories.iter_mut()
+        .find(|c| c.path == category_path)
+        .ok_or_else(|| format!("Category {} not found", category_path.join(" → ")))?;
+    category.visibility = visibility;
+    let updated = category.clone();
+    save_categories(&database)?;
+    Ok(updated)
+}
+
+/// A note's visibility after inheritance, as the sharing paths will see it
+pub async fn get_effective_visibility(note_id: String) -> Result<Visibility, String> {
+    let note = load_notes()?.notes.into_iter()
+        .find(|n| n.id == note_id)
+        .ok_or_else(|| tr("error.note_not_found", &[&note_id]))?;
+    Ok(effective_visibility(&note, &load_categories()?.categories))
+}
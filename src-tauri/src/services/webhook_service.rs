@@ -0,0 +1,211 @@
+use chrono::Utc;
+use std::collections::HashSet;
+use serde_json::json;
+use uuid::Uuid;
+use tracing::error;
+use crate::models::{Note, Webhook, WebhookEvent};
+use crate::services::storage_service::{load_webhooks, save_webhooks};
+use crate::services::note_service::load_notes;
+use crate::services::reminder_service::{current_due_date, refresh_reminder_badge};
+use crate::services::focus_service::is_focusing;
+use crate::services::visibility_service::{is_shareable, SharingPath};
+
+/// Delivery attempts per event before giving up
+const MAX_ATTEMPTS: u32 = 4;
+
+pub async fn register_webhook(url: String, events: Vec<String>, secret: Option<String>) -> Result<Webhook, String> {
+    let url = url.trim().to_string();
+    if !url.starts_with("http://") && !url.starts_with("https://") {
+        return Err("Webhook URL must start with http:// or https://".to_string());
+    }
+
+    let events = events.iter()
+        .map(|e| WebhookEvent::parse(e.trim()))
+        .collect::<Result<Vec<_>, _>>()?;
+    if events.is_empty() {
+        return Err("Select at least one event for the webhook".to_string());
+    }
+
+    let webhook = Webhook {
+        id: Uuid::new_v4().to_string(),
+        url,
+        events,
+        secret: secret.filter(|s| !s.trim().is_empty()),
+        enabled: true,
+        created_at: Utc::now(),
+    };
+
+    let mut database = load_webhooks()?;
+    database.webhooks.push(webhook.clone());
+    save_webhooks(&database)?;
+    Ok(webhook)
+}
+
+pub async fn list_webhooks() -> Result<Vec<Webhook>, String> {
+    Ok(load_webhooks()?.webhooks)
+}
+
+pub async fn delete_webhook(webhook_id: String) -> Result<(), String> {
+    let mut database = load_webhooks()?;
+    let initial_len = database.webhooks.len();
+    database.webhooks.retain(|w| w.id != webhook_id);
+    if database.webhooks.len() == initial_len {
+        return Err(format!("Webhook with id {} not found", webhook_id));
+    }
+    save_webhooks(&database)
+}
+
+pub async fn set_webhook_enabled(webhook_id: String, enabled: bool) -> Result<Webhook, String> {
+    let mut database = load_webhooks()?;
+    let webhook = database.webhooks.iter_mut()
+        .find(|w| w.id == webhook_id)
+        .ok_or_else(|| format!("Webhook with id {} not found", webhook_id))?;
+    webhook.enabled = enabled;
+    let updated = webhook.clone();
+    save_webhooks(&database)?;
+    Ok(updated)
+}
+
+/// POST a payload, retrying with exponential backoff on network errors and 5xx responses
+async fn deliver(webhook: &Webhook, payload: &serde_json::Value) -> Result<(), String> {
+    let client = reqwest::Client::new();
+    let mut last_error = String::new();
+
+    for attempt in 0..MAX_ATTEMPTS {
+        if attempt > 0 {
+            tokio::time::sleep(std::time::Duration::from_secs(2u64.pow(attempt - 1))).await;
+        }
+
+        let mut request = client.post(&webhook.url)
+            .timeout(std::time::Duration::from_secs(15))
+            .json(payload);
+        if let Some(secret) = &webhook.secret {
+            request = request.header("X-Webhook-Secret", secret);
+        }
+
+        match request.send().await {
+            Ok(response) if response.status().is_success() => return Ok(()),
+            Ok(response) if response.status().is_client_error() => {
+                // The receiver rejected the payload - retrying won't help
+                return Err(format!("Webhook {} rejected event with status {}", webhook.url, response.status()));
+            }
+            Ok(response) => last_error = format!("status {}", response.status()),
+            Err(e) => last_error = e.to_string(),
+        }
+    }
+
+    Err(format!("Webhook {} failed after {} attempts: {}", webhook.url, MAX_ATTEMPTS, last_error))
+}
+
+fn event_payload(event: &WebhookEvent, data: serde_json::Value) -> serde_json::Value {
+    json!({
+        "event": event.as_str(),
+        "timestamp": Utc::now().to_rfc3339(),
+        "data": data,
+    })
+}
+
+/// Fire an event to every subscribed webhook. Delivery happens in the background so
+/// callers never wait on (or fail because of) a slow receiver.
+pub fn emit_webhook_event(event: WebhookEvent, data: serde_json::Value) {
+    let webhooks: Vec<Webhook> = match load_webhooks() {
+        Ok(database) => database.webhooks.into_iter()
+            .filter(|w| w.enabled && w.events.contains(&event))
+            .collect(),
+        Err(e) => {
+            error!("Failed to load webhooks: {}", e);
+            return;
+        }
+    };
+    if webhooks.is_empty() {
+        return;
+    }
+
+    let payload = event_payload(&event, data);
+    tokio::spawn(async move {
+        for webhook in &webhooks {
+            if let Err(e) = deliver(webhook, &payload).await {
+                error!("{}", e);
+            }
+        }
+    });
+}
+
+/// The note as sent to webhooks. Private notes are reduced to their id, so receivers
+/// still learn that something happened but not what the note says.
+pub fn note_payload(note: &Note) -> serde_json::Value {
+    if !is_shareable(note, SharingPath::Http) {
+        return json!({ "id": note.id, "private": true });
+    }
+    serde_json::to_value(note).unwrap_or(serde_json::Value::Null)
+}
+
+/// Body of `tag.added`. The tag says something about a private note too, so it's left out
+/// along with the note's contents.
+pub fn tag_added_payload(note: &Note, tag: &str) -> serde_json::Value {
+    if !is_shareable(note, SharingPath::Http) {
+        return json!({ "note": note_payload(note) });
+    }
+    json!({ "tag": tag, "note": note_payload(note) })
+}
+
+/// Send a sample payload straight away and report whether it was accepted
+pub async fn test_webhook(webhook_id: String) -> Result<(), String> {
+    let webhook = load_webhooks()?.webhooks.into_iter()
+        .find(|w| w.id == webhook_id)
+        .ok_or_else(|| format!("Webhook with id {} not found", webhook_id))?;
+    let event = webhook.events.first().cloned().unwrap_or(WebhookEvent::NoteCreated);
+    let payload = event_payload(&event, json!({ "test": true }));
+    deliver(&webhook, &payload).await
+}
+
+/// Fire `reminder.due` for notes whose due date has passed. Each due date (each
+/// occurrence, for recurring reminders) is only announced once - the announced dates are
+/// remembered alongside the webhooks, so the notes themselves aren't touched.
+fn check_due_reminders() -> Result<(), String> {
+    let notes = load_notes()?;
+    let mut webhooks = load_webhooks()?;
+    let now = Utc::now();
+    let mut changed = false;
+
+    for note in &notes.notes {
+        let Some(due) = current_due_date(note, now) else { continue };
+        let due_key = due.to_rfc3339();
+        let already_sent = webhooks.reminders_sent.get(&note.id) == Some(&due_key);
+        if due > now || already_sent {
+            continue;
+        }
+
+        webhooks.reminders_sent.insert(note.id.clone(), due_key);
+        emit_webhook_event(WebhookEvent::ReminderDue, note_payload(note));
+        changed = true;
+    }
+
+    // Forget deleted notes
+    let note_ids: HashSet<&str> = notes.notes.iter().map(|n| n.id.as_str()).collect();
+    let before = webhooks.reminders_sent.len();
+    webhooks.reminders_sent.retain(|id, _| note_ids.contains(id.as_str()));
+    if changed || webhooks.reminders_sent.len() != before {
+        save_webhooks(&webhooks)?;
+    }
+    Ok(())
+}
+
+/// Background job started at launch that watches note due dates
+pub async fn run_reminder_watch_loop() {
+    loop {
+        let has_reminder_hooks = load_webhooks()
+            .map(|db| db.webhooks.iter().any(|w| w.enabled && w.events.contains(&WebhookEvent::ReminderDue)))
+            .unwrap_or(false);
+        // Held back during a focus session and announced once it ends
+        if has_reminder_hooks && !is_focusing() {
+            if let Err(e) = check_due_reminders() {
+                error!("Reminder check failed: {}", e);
+            }
+        }
+        if let Err(e) = refresh_reminder_badge() {
+            error!("Reminder badge check failed: {}", e);
+        }
+        tokio::time::sleep(std::time::Duration::from_secs(60)).await;
+    }
+}
@@ -0,0 +1,61 @@
+use crate::models::{WindowPlacement, WindowSettings};
+use crate::services::storage_service::{load_settings, load_ui_state, save_settings, save_ui_state};
+
+/// Saved placement of a window for the given monitor layout (see `monitor_layout` in main.rs)
+pub fn window_placement(layout: &str, label: &str) -> Option<WindowPlacement> {
+    load_ui_state().ok()?
+        .ui_state.window_placements
+        .get(layout)?
+        .get(label)
+        .cloned()
+}
+
+pub fn save_window_placement(layout: &str, label: &str, placement: WindowPlacement) -> Result<(), String> {
+    let mut database = load_ui_state()?;
+    database.ui_state.window_placements
+        .entry(layout.to_string())
+        .or_default()
+        .insert(label.to_string(), placement);
+    save_ui_state(&database)
+}
+
+/// Drop every window's saved placement for a monitor layout, so they open at the defaults
+pub fn forget_window_placements(layout: &str) -> Result<(), String> {
+    let mut database = load_ui_state()?;
+    if database.ui_state.window_placements.remove(layout).is_some() {
+        save_ui_state(&database)?;
+    }
+    Ok(())
+}
+
+/// Lowest opacity allowed - below this the window is too easy to lose
+const MIN_OPACITY: f64 = 0.3;
+
+pub async fn get_window_appearance() -> Result<WindowSettings, String> {
+    Ok(load_settings()?.settings.window)
+}
+
+fn update_window_settings(change: impl FnOnce(&mut WindowSettings)) -> Result<WindowSettings, String> {
+    let mut database = load_settings()?;
+    change(&mut database.settings.window);
+    save_settings(&database)?;
+    Ok(database.settings.window)
+}
+
+pub async fn set_always_on_top(enabled: bool) -> Result<WindowSettings, String> {
+    update_window_settings(|window| window.always_on_top = enabled)
+}
+
+pub async fn set_window_opacity(value: f64) -> Result<WindowSettings, String> {
+    if !(MIN_OPACITY..=1.0).contains(&value) {
+        return Err(format!("Opacity must be between {} and 1", MIN_OPACITY));
+    }
+    update_window_settings(|window| window.opacity = value)
+}
+
+pub async fn set_window_vibrancy(enabled: bool) -> Result<WindowSettings, String> {
+    if enabled && !cfg!(any(target_os = "macos", target_os = "windows")) {
+        return Err("Window vibrancy is only available on macOS and Windows".to_string());
+    }
+    update_window_settings(|window| window.vibrancy = enabled)
+}
@@ -52,6 +52,7 @@ function App() {
     setCurrentInput,
     setResponseType,
     sendMessage,
+    cancelMessage,
   } = useChat();
 
   // Toast functionality
@@ -278,6 +279,7 @@ function App() {
                   onQuestionChange={setCurrentInput}
                   onResponseTypeChange={setResponseType}
                   onSubmit={handleChatSubmit}
+                  onCancel={cancelMessage}
                 />
               </div>
             ) : (
@@ -7,6 +7,8 @@ interface Category {
   path: string[];
   parent_id?: string;
   note_count: number;
+  direct_note_count?: number;
+  total_note_count?: number;
   color?: string;
 }
 
@@ -377,7 +379,12 @@ export function CategoryTree({ onCategorySelect, selectedCategory, onCategoryDel
             )}
             
             {node.category.note_count > 0 && (
-              <span className="note-count">{node.category.note_count}</span>
+              <span className="note-count">
+                {/* "12 (48)": filed directly here, then including subcategories */}
+                {node.category.direct_note_count !== undefined && node.category.direct_note_count !== node.category.note_count
+                  ? `${node.category.direct_note_count} (${node.category.note_count})`
+                  : node.category.note_count}
+              </span>
             )}
           </div>
           
@@ -8,6 +8,7 @@ interface ChatInputProps {
   onQuestionChange: (value: string) => void;
   onResponseTypeChange: (type: ResponseType) => void;
   onSubmit: (e: FormEvent) => void;
+  onCancel?: () => void;
 }
 
 export function ChatInput({ 
@@ -16,7 +17,8 @@ export function ChatInput({
   loading, 
   onQuestionChange, 
   onResponseTypeChange, 
-  onSubmit 
+  onSubmit,
+  onCancel
 }: ChatInputProps) {
   return (
     <form onSubmit={onSubmit} className="input-form">
@@ -41,9 +43,15 @@ export function ChatInput({
         rows={2}
         disabled={loading}
       />
-      <button type="submit" disabled={loading || !question.trim()}>
-        {loading ? "Thinking..." : "Ask"}
-      </button>
+      {loading && onCancel ? (
+        <button type="button" onClick={onCancel}>
+          Stop
+        </button>
+      ) : (
+        <button type="submit" disabled={loading || !question.trim()}>
+          {loading ? "Thinking..." : "Ask"}
+        </button>
+      )}
     </form>
   );
 }
\ No newline at end of file
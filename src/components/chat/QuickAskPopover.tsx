@@ -0,0 +1,82 @@
+import { FormEvent, useEffect, useRef, useState } from "react";
+import { listen } from "@tauri-apps/api/event";
+import { ApiService } from "../../services/api";
+import { QuickAsk } from "../../types";
+
+/**
+ * Contents of the small popover opened by clicking the tray icon: an ask box and the
+ * last answer. The window hides itself when it loses focus (handled in Rust).
+ */
+export function QuickAskPopover() {
+  const [ask, setAsk] = useState<QuickAsk | null>(null);
+  const [question, setQuestion] = useState("");
+  const [saved, setSaved] = useState(false);
+  const [saveError, setSaveError] = useState<string | null>(null);
+  const inputRef = useRef<HTMLInputElement>(null);
+
+  useEffect(() => {
+    ApiService.getQuickAsk().then(setAsk).catch(() => {});
+    const unlisten = listen<QuickAsk>("quick-ask:updated", (event) => {
+      setAsk(event.payload);
+      setSaved(false);
+      setSaveError(null);
+    });
+    // Ready to type whenever the popover is brought back
+    const focus = () => inputRef.current?.focus();
+    window.addEventListener("focus", focus);
+    return () => {
+      unlisten.then((stop) => stop());
+      window.removeEventListener("focus", focus);
+    };
+  }, []);
+
+  const pending = !!ask && !ask.answer && !ask.error;
+
+  const handleSubmit = (event: FormEvent) => {
+    event.preventDefault();
+    if (!question.trim() || pending) return;
+    ApiService.quickAsk(question).catch(() => {});
+    setQuestion("");
+  };
+
+  const handleSave = async () => {
+    if (!ask?.answer) return;
+    try {
+      await ApiService.saveQaNote(ask.question, ask.answer);
+      setSaved(true);
+    } catch (error) {
+      setSaveError(String(error));
+    }
+  };
+
+  return (
+    <div className="quick-lookup quick-ask">
+      <form onSubmit={handleSubmit}>
+        <input
+          ref={inputRef}
+          className="quick-ask-input"
+          autoFocus
+          value={question}
+          onChange={(e) => setQuestion(e.target.value)}
+          placeholder="Ask anything..."
+        />
+      </form>
+      {ask ? (
+        <>
+          <blockquote className="quick-lookup-selection">{ask.question}</blockquote>
+          <div className={`quick-lookup-answer ${pending ? "loading" : ""}`}>
+            {ask.error ? <span className="quick-lookup-error">{ask.error}</span> : ask.answer ?? "Thinking..."}
+          </div>
+          <div className="quick-lookup-actions">
+            <button className="primary" onClick={handleSave} disabled={!ask.answer || saved}>
+              {saved ? "✓ Saved" : "💾 Save as note"}
+            </button>
+          </div>
+          {saveError && <div className="quick-lookup-error">{saveError}</div>}
+        </>
+      ) : (
+        <div className="quick-lookup-answer loading">Answers appear here.</div>
+      )}
+    </div>
+  );
+}
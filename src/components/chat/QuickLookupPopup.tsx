@@ -0,0 +1,57 @@
+import { useEffect, useState } from "react";
+import { listen } from "@tauri-apps/api/event";
+import { getCurrentWindow } from "@tauri-apps/api/window";
+import { ApiService } from "../../services/api";
+import { QuickLookup } from "../../types";
+
+/**
+ * Contents of the small always-on-top window opened by the quick-lookup shortcut.
+ * Follows the backend's current lookup and saves it as a Q&A note on request.
+ */
+export function QuickLookupPopup() {
+  const [lookup, setLookup] = useState<QuickLookup | null>(null);
+  const [saved, setSaved] = useState(false);
+  const [saveError, setSaveError] = useState<string | null>(null);
+
+  useEffect(() => {
+    ApiService.getQuickLookup().then(setLookup).catch(() => {});
+    const unlisten = listen<QuickLookup>("quick-lookup:updated", (event) => {
+      setLookup(event.payload);
+      setSaved(false);
+      setSaveError(null);
+    });
+    return () => {
+      unlisten.then((stop) => stop());
+    };
+  }, []);
+
+  const handleSave = async () => {
+    if (!lookup?.answer) return;
+    try {
+      await ApiService.saveQaNote(lookup.question, lookup.answer);
+      setSaved(true);
+    } catch (error) {
+      setSaveError(String(error));
+    }
+  };
+
+  if (!lookup) {
+    return <div className="quick-lookup empty">Select text in any app and press Ctrl+Alt+L (⌘⌥L on Mac).</div>;
+  }
+
+  return (
+    <div className="quick-lookup">
+      <blockquote className="quick-lookup-selection">{lookup.selection}</blockquote>
+      <div className={`quick-lookup-answer ${lookup.answer || lookup.error ? "" : "loading"}`}>
+        {lookup.error ? <span className="quick-lookup-error">{lookup.error}</span> : lookup.answer ?? "Thinking..."}
+      </div>
+      <div className="quick-lookup-actions">
+        <button className="primary" onClick={handleSave} disabled={!lookup.answer || saved}>
+          {saved ? "✓ Saved" : "💾 Save as note"}
+        </button>
+        <button onClick={() => getCurrentWindow().hide()}>Close</button>
+      </div>
+      {saveError && <div className="quick-lookup-error">{saveError}</div>}
+    </div>
+  );
+}
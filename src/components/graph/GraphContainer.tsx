@@ -149,7 +149,8 @@ export function GraphContainer({
             animated: false,
             style: {
               stroke: color,
-              strokeWidth: 2,
+              // Stronger links draw thicker: 1px at weight 0, 4px at weight 1
+              strokeWidth: 1 + 3 * (link.weight ?? 0.33),
               cursor: 'pointer',
             },
             markerEnd: isDirectional ? {
@@ -213,6 +214,7 @@ export function GraphContainer({
         case 'FollowUp': return '#10b981';
         case 'Contradicts': return '#ef4444';
         case 'Supports': return '#f59e0b'; // orange/amber
+        case 'Embed': return '#14b8a6'; // teal for composition
         default: return '#8b5cf6';
       }
     }
@@ -246,6 +248,7 @@ export function GraphContainer({
         case 'Reference': // A references B (A → B)
         case 'FollowUp':  // A follows up from B (A → B) 
         case 'Supports':  // A supports B (A → B)
+        case 'Embed':     // A embeds B (A → B)
           console.log('🔄 ✅ DIRECTIONAL link type:', linkType);
           return true;
         case 'Related':   // Bidirectional relationship (A ↔ B)
@@ -441,6 +444,7 @@ export function GraphContainer({
           id: note.id,
           type: 'noteNode',
           position,
+          zIndex: existingPosition?.z_index,
           data: { 
             note,
             onEdit,
@@ -591,13 +595,13 @@ export function GraphContainer({
 
     setNodes(newNodes);
 
-    // Save all new positions
-    for (const node of newNodes) {
-      try {
-        await ApiService.saveNotePosition(node.id, node.position.x, node.position.y);
-      } catch (error) {
-        console.error('Failed to save position for node:', node.id, error);
-      }
+    // Save all new positions in one go
+    try {
+      await ApiService.saveNotePositions(
+        newNodes.map(node => [node.id, node.position.x, node.position.y, null])
+      );
+    } catch (error) {
+      console.error('Failed to save node positions:', error);
     }
   }, [nodes, setNodes]);
 
@@ -0,0 +1,62 @@
+import { FormEvent, ReactNode, useEffect, useState } from "react";
+import { listen } from "@tauri-apps/api/event";
+import { ApiService } from "../../services/api";
+import { AppLockStatus } from "../../types";
+
+/**
+ * Shows a PIN screen instead of the app while it is locked. The backend refuses every
+ * other command meanwhile, so the app is remounted on unlock to load its data again.
+ */
+export function AppLockGate({ children }: { children: ReactNode }) {
+  const [status, setStatus] = useState<AppLockStatus | null>(null);
+  const [unlocks, setUnlocks] = useState(0);
+  const [pin, setPin] = useState("");
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    ApiService.getAppLockStatus().then(setStatus).catch(() => {});
+    const unlisten = listen<AppLockStatus>("app-lock:changed", (event) => {
+      setStatus(event.payload);
+      if (!event.payload.locked) setUnlocks((n) => n + 1);
+    });
+    return () => {
+      unlisten.then((stop) => stop());
+    };
+  }, []);
+
+  const unlock = async (attempt: () => Promise<AppLockStatus>) => {
+    try {
+      setStatus(await attempt());
+      setPin("");
+      setError(null);
+    } catch (e) {
+      setError(String(e));
+    }
+  };
+
+  const handleSubmit = (event: FormEvent) => {
+    event.preventDefault();
+    if (pin) unlock(() => ApiService.unlockApp(pin));
+  };
+
+  if (!status) return null;
+  if (!status.locked) return <div key={unlocks} className="app-lock-content">{children}</div>;
+
+  return (
+    <div className="app-lock">
+      <form className="app-lock-form" onSubmit={handleSubmit}>
+        <h2>NowledgeBase is locked</h2>
+        <input
+          type="password"
+          inputMode="numeric"
+          autoFocus
+          value={pin}
+          onChange={(e) => setPin(e.target.value)}
+          placeholder="PIN"
+        />
+        {error && <div className="app-lock-error">{error}</div>}
+        <button type="submit">Unlock</button>
+      </form>
+    </div>
+  );
+}
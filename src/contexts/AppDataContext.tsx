@@ -246,9 +246,18 @@ export function AppDataProvider({ children }: { children: React.ReactNode }) {
       dispatch({ type: 'SET_LOADING', dataType: 'links', loading: true });
       dispatch({ type: 'SET_LOADING', dataType: 'positions', loading: true });
       
-      const [notes, categories, links, positions, viewport] = await Promise.all([
+      // Notes and categories first so capture and the notes list are usable right away;
+      // links and graph layout are only needed once the graph is opened
+      const [notes, categories] = await Promise.all([
         ApiService.getNotes(),
         ApiService.getCategories(),
+      ]);
+      dispatch({ type: 'SET_NOTES', notes });
+      dispatch({ type: 'SET_CATEGORIES', categories });
+      dispatch({ type: 'SET_LOADING', dataType: 'notes', loading: false });
+      dispatch({ type: 'SET_LOADING', dataType: 'categories', loading: false });
+
+      const [links, positions, viewport] = await Promise.all([
         ApiService.getAllNoteLinks(),
         ApiService.getAllNotePositions(),
         ApiService.getGraphViewport().catch(() => ({ x: 0, y: 0, zoom: 0.8 })), // Use default if load fails
@@ -260,8 +269,6 @@ export function AppDataProvider({ children }: { children: React.ReactNode }) {
         console.log(`🔗 First link from backend:`, links[0]);
       }
       
-      dispatch({ type: 'SET_NOTES', notes });
-      dispatch({ type: 'SET_CATEGORIES', categories });
       dispatch({ type: 'SET_LINKS', links });
       dispatch({ type: 'SET_POSITIONS', positions });
       dispatch({ type: 'SET_GRAPH_VIEWPORT', viewport });
@@ -289,7 +296,9 @@ export function AppDataProvider({ children }: { children: React.ReactNode }) {
     dispatch({ type: 'SET_LOADING', dataType: 'notes', loading: true });
     try {
       const note = await ApiService.saveNote(content, categoryPath || [], customTitle);
-      dispatch({ type: 'ADD_NOTE', note });
+      if (!note.duplicate) {
+        dispatch({ type: 'ADD_NOTE', note });
+      }
       
       // Reload categories from backend to ensure new categories are shown
       const updatedCategories = await ApiService.getCategories();
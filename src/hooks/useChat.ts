@@ -1,4 +1,4 @@
-import { useState, useCallback } from 'react';
+import { useState, useCallback, useRef } from 'react';
 import { ChatMessage, ResponseType } from '../types';
 import { ApiService } from '../services/api';
 
@@ -7,11 +7,15 @@ export function useChat() {
   const [loading, setLoading] = useState(false);
   const [currentInput, setCurrentInput] = useState('');
   const [responseType, setResponseType] = useState<ResponseType>('brief');
+  // Id of the request being answered, so it can be stopped
+  const requestIdRef = useRef<string | null>(null);
 
   const sendMessage = useCallback(async (question: string, type: ResponseType) => {
     if (!question.trim()) return;
 
     const messageId = Date.now();
+    const requestId = crypto.randomUUID();
+    requestIdRef.current = requestId;
     setLoading(true);
 
     // Add loading message immediately
@@ -26,7 +30,7 @@ export function useChat() {
     setMessages(prev => [...prev, loadingMessage]);
 
     try {
-      const response = await ApiService.askAI(question, type);
+      const response = await ApiService.askAI(question, type, requestId);
       
       // Update with actual response
       setMessages(prev => 
@@ -46,10 +50,17 @@ export function useChat() {
         )
       );
     } finally {
+      requestIdRef.current = null;
       setLoading(false);
     }
   }, []);
 
+  const cancelMessage = useCallback(() => {
+    if (requestIdRef.current) {
+      ApiService.cancelAiRequest(requestIdRef.current).catch(() => {});
+    }
+  }, []);
+
   const clearChat = useCallback(() => {
     setMessages([]);
   }, []);
@@ -62,6 +73,7 @@ export function useChat() {
     setCurrentInput,
     setResponseType,
     sendMessage,
+    cancelMessage,
     clearChat,
   };
 }
\ No newline at end of file
@@ -24,7 +24,9 @@ export function useNotes() {
   const saveNote = useCallback(async (content: string, categoryPath: string[]) => {
     try {
       const newNote = await ApiService.saveNote(content, categoryPath);
-      setNotes(prev => [newNote, ...prev]);
+      if (!newNote.duplicate) {
+        setNotes(prev => [newNote, ...prev]);
+      }
       return newNote;
     } catch (err) {
       setError(`Failed to save note: ${err}`);
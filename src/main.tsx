@@ -1,13 +1,55 @@
 import React from "react";
 import ReactDOM from "react-dom/client";
+import { getCurrentWindow } from "@tauri-apps/api/window";
+import { listen } from "@tauri-apps/api/event";
 import App from "./App";
 import { AppDataProvider } from "./contexts/AppDataContext";
+import { QuickLookupPopup } from "./components/chat/QuickLookupPopup";
+import { QuickAskPopover } from "./components/chat/QuickAskPopover";
+import { AppLockGate } from "./components/shared/AppLockGate";
+import { ApiService } from "./services/api";
+import { WindowAppearance } from "./types";
 import "./styles.css";
 
+// Secondary windows load the same bundle and pick their view by label
+const label = getCurrentWindow().label;
+const isQuickLookup = label === "quick-lookup";
+const isQuickAsk = label === "quick-ask";
+
+// Windows have no native opacity, so the page of the (transparent) window fades itself
+const applyAppearance = (appearance: WindowAppearance) => {
+  document.documentElement.style.opacity = String(appearance.opacity);
+  document.documentElement.classList.toggle("vibrancy", appearance.vibrancy);
+};
+if (!isQuickLookup && !isQuickAsk) {
+  ApiService.getWindowAppearance().then(applyAppearance).catch(() => {});
+  listen<WindowAppearance>("window:appearance", (event) => applyAppearance(event.payload));
+
+  // Keep the idle timers from hiding the window while it's in use; one ping per interval is enough
+  let lastActivityPing = 0;
+  const pingActivity = () => {
+    const now = Date.now();
+    if (now - lastActivityPing < 30_000) return;
+    lastActivityPing = now;
+    ApiService.recordUserActivity().catch(() => {});
+  };
+  for (const type of ["mousemove", "mousedown", "keydown", "wheel", "touchstart"]) {
+    window.addEventListener(type, pingActivity, { passive: true });
+  }
+}
+
 ReactDOM.createRoot(document.getElementById("root") as HTMLElement).render(
   <React.StrictMode>
-    <AppDataProvider>
-      <App />
-    </AppDataProvider>
+    {isQuickLookup ? (
+      <QuickLookupPopup />
+    ) : isQuickAsk ? (
+      <QuickAskPopover />
+    ) : (
+      <AppLockGate>
+        <AppDataProvider>
+          <App />
+        </AppDataProvider>
+      </AppLockGate>
+    )}
   </React.StrictMode>,
 );
\ No newline at end of file
@@ -1,5 +1,5 @@
 import { invoke } from "@tauri-apps/api/core";
-import { Note, Category, NoteLink, LinkColor } from "../types";
+import { Note, Visibility, Profile, NoteQuery, MountedVault, SharedNote, CrossVaultLink, TermRename, Category, CategoryTreeImport, NoteLink, LinkColor, QuickLookup, QuickAsk, ModelAnswer, SourcedAnswer, WindowAppearance, AppLockStatus, IdleHideSettings, RetentionRule, RetentionReport, MaintenanceReport, PerformanceMetrics, SwitchResult, NoteSyncStatus, SyncOverview, SyncSettings, GraphPosition, GraphFilter, GraphSubset, GraphSnapshot, NoteNeighborhood, NotePath, RelatedNote, TimelineLinks, TimelineEntry, CategoryLinkMatrix } from "../types";
 
 /**
  * Central API service for all Tauri command calls
@@ -7,8 +7,56 @@ import { Note, Category, NoteLink, LinkColor } from "../types";
  */
 export class ApiService {
   // Chat API
-  static async askAI(question: string, responseType: string): Promise<string> {
-    return await invoke<string>("ask_ai", { question, response_type: responseType });
+  static async askAI(question: string, responseType: string, requestId?: string): Promise<string> {
+    return await invoke<string>("ask_ai", { question, response_type: responseType, requestId });
+  }
+
+  static async askAICompare(question: string, models: string[], responseType?: string, requestId?: string): Promise<ModelAnswer[]> {
+    return await invoke<ModelAnswer[]>("ask_ai_compare", { question, models, responseType, requestId });
+  }
+
+  // Stops an askAI call started with the same requestId
+  static async cancelAiRequest(requestId: string): Promise<boolean> {
+    return await invoke<boolean>("cancel_ai_request", { requestId });
+  }
+
+  static async listResponseTypes(): Promise<string[]> {
+    return await invoke<string[]>("list_response_types");
+  }
+
+  // Answers from the whole vault, citing the notes used
+  static async askVault(question: string): Promise<SourcedAnswer> {
+    return await invoke<SourcedAnswer>("ask_vault", { question });
+  }
+
+  static async getAnswerHistory(limit?: number): Promise<SourcedAnswer[]> {
+    return await invoke<SourcedAnswer[]>("get_answer_history", { limit });
+  }
+
+  static async answerToNote(answerId: string, categoryPath?: string[]): Promise<Note> {
+    return await invoke<Note>("answer_to_note", { answerId, categoryPath });
+  }
+
+  static async saveQaNote(question: string, answer: string, model?: string, categoryPath?: string[]): Promise<Note> {
+    return await invoke<Note>("save_qa_note", { question, answer, model, categoryPath });
+  }
+
+  // Quick lookup popup
+  static async getQuickLookup(): Promise<QuickLookup | null> {
+    return await invoke<QuickLookup | null>("get_quick_lookup");
+  }
+
+  static async quickLookup(selection: string): Promise<QuickLookup> {
+    return await invoke<QuickLookup>("quick_lookup", { selection });
+  }
+
+  // Tray quick-ask popover
+  static async getQuickAsk(): Promise<QuickAsk | null> {
+    return await invoke<QuickAsk | null>("get_quick_ask");
+  }
+
+  static async quickAsk(question: string, responseType?: string): Promise<QuickAsk> {
+    return await invoke<QuickAsk>("quick_ask", { question, responseType });
   }
 
   // Notes API
@@ -28,6 +76,55 @@ export class ApiService {
     return await invoke<Note>("update_note_with_title", { id, content, title });
   }
 
+  static async createNoteWithSource(content: string, sourceApp?: string, sourceUrl?: string, categoryPath?: string[]): Promise<Note> {
+    return await invoke<Note>("create_note_with_source", { content, sourceApp, sourceUrl, categoryPath });
+  }
+
+  static async enqueueForReading(noteId: string): Promise<Note> {
+    return await invoke<Note>("enqueue_for_reading", { noteId });
+  }
+
+  static async removeFromReadingQueue(noteId: string): Promise<Note> {
+    return await invoke<Note>("remove_from_reading_queue", { noteId });
+  }
+
+  static async getReadingQueue(): Promise<Note[]> {
+    return await invoke<Note[]>("get_reading_queue");
+  }
+
+  static async setReadingProgress(noteId: string, pct: number): Promise<Note> {
+    return await invoke<Note>("set_reading_progress", { noteId, pct });
+  }
+
+  static async markRead(noteId: string): Promise<Note> {
+    return await invoke<Note>("mark_read", { noteId });
+  }
+
+  static async appendToNote(noteId: string, text: string, withTimestamp = false): Promise<Note> {
+    return await invoke<Note>("append_to_note", { noteId, text, withTimestamp });
+  }
+
+  static async prependToNote(noteId: string, text: string, withTimestamp = false): Promise<Note> {
+    return await invoke<Note>("prepend_to_note", { noteId, text, withTimestamp });
+  }
+
+  static async regenerateNoteTitle(noteId: string): Promise<Note> {
+    return await invoke<Note>("regenerate_note_title", { noteId });
+  }
+
+  static async summarizeNote(noteId: string): Promise<string> {
+    return await invoke<string>("summarize_note", { noteId });
+  }
+
+  static async askAiAboutNote(noteId: string, question: string): Promise<string> {
+    return await invoke<string>("ask_ai_about_note", { noteId, question });
+  }
+
+  // Renames a term in titles, aliases, wikilinks and link labels; dryRun only lists the changes
+  static async renameTerm(oldName: string, newName: string, scope?: string[], dryRun = false): Promise<TermRename> {
+    return await invoke<TermRename>("rename_term", { old: oldName, new: newName, scope, dryRun });
+  }
+
   static async deleteNote(id: string): Promise<void> {
     return await invoke("delete_note", { id });
   }
@@ -41,6 +138,30 @@ export class ApiService {
     return await invoke<Category>("create_category", { name, parentPath });
   }
 
+  static async exportCategoryTree(): Promise<string> {
+    return await invoke<string>("export_category_tree");
+  }
+
+  static async listTaxonomyTemplates(): Promise<string[]> {
+    return await invoke<string[]>("list_taxonomy_templates");
+  }
+
+  static async applyTaxonomyTemplate(name: string): Promise<CategoryTreeImport> {
+    return await invoke<CategoryTreeImport>("apply_taxonomy_template", { name });
+  }
+
+  static async recountCategories(): Promise<Category[]> {
+    return await invoke<Category[]>("recount_categories");
+  }
+
+  static async findEmptyCategories(): Promise<Category[]> {
+    return await invoke<Category[]>("find_empty_categories");
+  }
+
+  static async importCategoryTree(json: string, merge: boolean): Promise<CategoryTreeImport> {
+    return await invoke<CategoryTreeImport>("import_category_tree", { json, merge });
+  }
+
   static async renameCategory(categoryId: string, newName: string): Promise<void> {
     return await invoke("rename_category", { categoryId, newName });
   }
@@ -59,10 +180,70 @@ export class ApiService {
     return await invoke("save_note_position", { noteId, x, y });
   }
 
+  static async saveNotePositions(positions: Array<[string, number, number, number | null]>): Promise<number> {
+    return await invoke("save_note_positions", { positions });
+  }
+
   static async getAllNotePositions(): Promise<Array<[string, { x: number, y: number, z_index?: number }]>> {
     return await invoke("get_all_note_positions");
   }
 
+  static async bringNoteToFront(noteId: string): Promise<Array<[string, { x: number, y: number, z_index?: number }]>> {
+    return await invoke("bring_note_to_front", { noteId });
+  }
+
+  static async sendNoteToBack(noteId: string): Promise<Array<[string, { x: number, y: number, z_index?: number }]>> {
+    return await invoke("send_note_to_back", { noteId });
+  }
+
+  static async setNoteSize(noteId: string, width?: number, height?: number): Promise<GraphPosition> {
+    return await invoke("set_note_size", { noteId, width, height });
+  }
+
+  static async setNoteCollapsed(noteId: string, collapsed: boolean): Promise<GraphPosition> {
+    return await invoke("set_note_collapsed", { noteId, collapsed });
+  }
+
+  static async getGraphSubset(filter: GraphFilter): Promise<GraphSubset> {
+    return await invoke("get_graph_subset", { filter });
+  }
+
+  static async getGraphSnapshot(at: string): Promise<GraphSnapshot> {
+    return await invoke<GraphSnapshot>("get_graph_snapshot", { at });
+  }
+
+  static async getNoteNeighborhood(noteId: string, depth: number): Promise<NoteNeighborhood> {
+    return await invoke("get_note_neighborhood", { noteId, depth });
+  }
+
+  static async findPathBetweenNotes(from: string, to: string): Promise<NotePath | null> {
+    return await invoke("find_path_between_notes", { from, to });
+  }
+
+  static async getRelatedNotes(noteId: string, limit?: number): Promise<RelatedNote[]> {
+    return await invoke("get_related_notes", { noteId, limit });
+  }
+
+  static async setLinkWeight(linkId: string, weight: number | null): Promise<NoteLink> {
+    return await invoke("set_link_weight", { linkId, weight });
+  }
+
+  static async recomputeLinkWeights(): Promise<number> {
+    return await invoke("recompute_link_weights");
+  }
+
+  static async setTimelineLinks(mode: TimelineLinks): Promise<TimelineLinks> {
+    return await invoke("set_timeline_links", { mode });
+  }
+
+  static async getNoteTimeline(categoryPath: string[]): Promise<TimelineEntry[]> {
+    return await invoke("get_note_timeline", { categoryPath });
+  }
+
+  static async getCategoryLinkMatrix(depth?: number): Promise<CategoryLinkMatrix> {
+    return await invoke("get_category_link_matrix", { depth });
+  }
+
   static async createNoteLink(sourceId: string, targetId: string, linkType: string, label?: string): Promise<NoteLink> {
     return await invoke<NoteLink>("create_note_link", { 
       sourceId: sourceId, 
@@ -103,4 +284,168 @@ export class ApiService {
   static async getGraphViewport(): Promise<{ x: number; y: number; zoom: number }> {
     return await invoke<{ x: number; y: number; zoom: number }>("get_graph_viewport");
   }
-}
\ No newline at end of file
+
+  // Window appearance
+  static async getWindowAppearance(): Promise<WindowAppearance> {
+    return await invoke<WindowAppearance>("get_window_appearance");
+  }
+
+  // App lock - the only calls the backend answers while locked
+  static async getAppLockStatus(): Promise<AppLockStatus> {
+    return await invoke<AppLockStatus>("get_app_lock_status");
+  }
+
+  static async lockApp(): Promise<AppLockStatus> {
+    return await invoke<AppLockStatus>("lock_app");
+  }
+
+  static async unlockApp(pin: string): Promise<AppLockStatus> {
+    return await invoke<AppLockStatus>("unlock_app", { pin });
+  }
+
+  // Sets, changes or (pin null) removes the PIN; currentPin is needed once one exists
+  static async setAppLock(pin: string | null, currentPin: string | null, idleMinutes: number, lockOnShow: boolean): Promise<AppLockStatus> {
+    return await invoke<AppLockStatus>("set_app_lock", { pin, currentPin, idleMinutes, lockOnShow });
+  }
+
+  // Idle timers - the window hides (and optionally locks) after a while without activity
+  static async recordUserActivity(): Promise<void> {
+    await invoke("record_user_activity");
+  }
+
+  static async getIdleHideSettings(): Promise<IdleHideSettings> {
+    return await invoke<IdleHideSettings>("get_idle_hide_settings");
+  }
+
+  static async setIdleHideSettings(settings: IdleHideSettings): Promise<IdleHideSettings> {
+    return await invoke<IdleHideSettings>("set_idle_hide_settings", { settings });
+  }
+
+  // Profiles - notes are attributed to whoever's profile is active when they're created
+  static async listProfiles(): Promise<Profile[]> {
+    return await invoke<Profile[]>("list_profiles");
+  }
+
+  static async getActiveProfile(): Promise<Profile | null> {
+    return await invoke<Profile | null>("get_active_profile");
+  }
+
+  static async createProfile(name: string): Promise<Profile> {
+    return await invoke<Profile>("create_profile", { name });
+  }
+
+  static async switchProfile(profileId: string): Promise<Profile> {
+    return await invoke<Profile>("switch_profile", { profileId });
+  }
+
+  // author null = notes saved before any profile existed
+  static async getNotesByAuthor(author: string | null): Promise<Note[]> {
+    return await invoke<Note[]>("get_notes_by_author", { author });
+  }
+
+  // Sync - note content merges with other devices through the configured relay
+  static async getSyncStatus(): Promise<SyncOverview> {
+    return await invoke<SyncOverview>("get_sync_status");
+  }
+
+  static async getNoteSyncStatus(noteId: string): Promise<NoteSyncStatus> {
+    return await invoke<NoteSyncStatus>("get_note_sync_status", { noteId });
+  }
+
+  static async setSyncSettings(enabled: boolean, relayUrl: string | null, vaultId: string | null, intervalMinutes: number): Promise<SyncSettings> {
+    return await invoke<SyncSettings>("set_sync_settings", { enabled, relayUrl, vaultId, intervalMinutes });
+  }
+
+  static async syncNow(): Promise<SyncOverview> {
+    return await invoke<SyncOverview>("sync_now");
+  }
+
+  // Mounted vaults - other vault folders browsed and searched next to this one
+  static async listMountedVaults(): Promise<MountedVault[]> {
+    return await invoke<MountedVault[]>("list_mounted_vaults");
+  }
+
+  static async mountSharedVault(path: string, readOnly: boolean, name?: string): Promise<MountedVault> {
+    return await invoke<MountedVault>("mount_shared_vault", { path, readOnly, name: name || null });
+  }
+
+  static async unmountSharedVault(vaultId: string): Promise<void> {
+    await invoke("unmount_shared_vault", { vaultId });
+  }
+
+  static async getSharedVaultNotes(vaultId: string): Promise<Note[]> {
+    return await invoke<Note[]>("get_shared_vault_notes", { vaultId });
+  }
+
+  static async searchSharedVaults(query: NoteQuery): Promise<SharedNote[]> {
+    return await invoke<SharedNote[]>("search_shared_vaults", { query });
+  }
+
+  static async updateSharedNote(vaultId: string, noteId: string, content: string): Promise<Note> {
+    return await invoke<Note>("update_shared_note", { vaultId, noteId, content });
+  }
+
+  static async createCrossVaultLink(sourceId: string, vaultId: string, targetId: string, label?: string): Promise<CrossVaultLink> {
+    return await invoke<CrossVaultLink>("create_cross_vault_link", { sourceId, vaultId, targetId, label: label || null });
+  }
+
+  static async deleteCrossVaultLink(linkId: string): Promise<void> {
+    await invoke("delete_cross_vault_link", { linkId });
+  }
+
+  static async getCrossVaultLinks(sourceId?: string): Promise<CrossVaultLink[]> {
+    return await invoke<CrossVaultLink[]>("get_cross_vault_links", { sourceId: sourceId || null });
+  }
+
+  // Visibility - private notes are kept out of exports, webhooks, sync and publishing
+  static async setNoteVisibility(noteId: string, visibility: Visibility | null): Promise<Note> {
+    return await invoke<Note>("set_note_visibility", { noteId, visibility });
+  }
+
+  static async setCategoryVisibility(categoryPath: string[], visibility: Visibility | null): Promise<Category> {
+    return await invoke<Category>("set_category_visibility", { categoryPath, visibility });
+  }
+
+  static async getEffectiveVisibility(noteId: string): Promise<Visibility> {
+    return await invoke<Visibility>("get_effective_visibility", { noteId });
+  }
+
+  // AI privacy - excluded notes and categories are never sent to the AI provider
+  static async setNoteAiExcluded(noteId: string, excluded: boolean): Promise<Note> {
+    return await invoke<Note>("set_note_ai_excluded", { noteId, excluded });
+  }
+
+  static async setCategoryAiExcluded(categoryPath: string[], excluded: boolean): Promise<Category> {
+    return await invoke<Category>("set_category_ai_excluded", { categoryPath, excluded });
+  }
+
+  // Retention - daily housekeeping that archives or deletes old notes per category
+  static async listRetentionRules(): Promise<RetentionRule[]> {
+    return await invoke<RetentionRule[]>("list_retention_rules");
+  }
+
+  static async saveRetentionRule(rule: RetentionRule): Promise<RetentionRule> {
+    return await invoke<RetentionRule>("save_retention_rule", { rule });
+  }
+
+  static async deleteRetentionRule(ruleId: string): Promise<void> {
+    await invoke("delete_retention_rule", { ruleId });
+  }
+
+  // Reports only unless dryRun is explicitly false
+  static async runRetention(dryRun = true): Promise<RetentionReport> {
+    return await invoke<RetentionReport>("run_retention", { dryRun });
+  }
+
+  static async runMaintenance(): Promise<MaintenanceReport> {
+    return await invoke<MaintenanceReport>("run_maintenance");
+  }
+
+  static async getPerformanceMetrics(): Promise<PerformanceMetrics> {
+    return await invoke<PerformanceMetrics>("get_performance_metrics");
+  }
+
+  static async quickSwitch(query: string, limit?: number): Promise<SwitchResult[]> {
+    return await invoke<SwitchResult[]>("quick_switch", { query, limit });
+  }
+}
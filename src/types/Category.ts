@@ -1,10 +1,18 @@
+import { Visibility } from './Note';
+
 export interface Category {
   id: string;
   name: string;
   path: string[];
   parent_id?: string;
   note_count: number;
+  direct_note_count?: number;
+  total_note_count?: number;
   color?: string;
+  auto_created?: boolean;
+  flagged_empty?: boolean;
+  visibility?: Visibility | null; // Inherited by notes and subcategories that don't set one
+  ai_excluded?: boolean; // Also excludes every note and subcategory below it
 }
 
 export interface CategorySuggestion {
@@ -15,4 +23,10 @@ export interface CategorySuggestion {
 
 export interface CategoriesDatabase {
   categories: Category[];
-}
\ No newline at end of file
+}
+export interface CategoryTreeImport {
+  created: number;
+  updated: number;
+  removed: number;
+  kept: number;
+}
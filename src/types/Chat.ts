@@ -14,4 +14,39 @@ export interface ChatState {
   loading: boolean;
   currentInput: string;
   responseType: ResponseType;
-}
\ No newline at end of file
+}
+// Selection looked up with the global quick-lookup shortcut
+export interface QuickLookup {
+  selection: string;
+  question: string;
+  answer?: string | null;
+  error?: string | null;
+}
+
+// Question asked from the tray popover, with its answer once it arrives
+export interface QuickAsk {
+  question: string;
+  response_type: string;
+  answer?: string | null;
+  error?: string | null;
+}
+
+// One model's answer from askAICompare, also sent alone as `ai-compare:answer`
+export interface ModelAnswer {
+  compare_id: string;
+  model: string;
+  answer?: string | null;
+  error?: string | null;
+  elapsed_ms: number;
+}
+
+// A vault answer from askVault; `answer` cites cited_note_ids as [1], [2], ...
+export interface SourcedAnswer {
+  id: string;
+  question: string;
+  answer: string;
+  cited_note_ids: string[];
+  model: string;
+  asked_at: string;
+  note_id?: string | null;
+}
@@ -1,7 +1,13 @@
+import { Note } from './Note';
+import { NoteLink } from './Link';
+
 export interface GraphPosition {
   x: number;
   y: number;
   z_index?: number;
+  width?: number;
+  height?: number;
+  collapsed?: boolean;
 }
 
 export interface GraphViewport {
@@ -20,4 +26,80 @@ export interface GraphNodeData {
   isLinkingMode: boolean;
   isLinkingSource: boolean;
   isHovered: boolean;
-}
\ No newline at end of file
+}
+export interface GraphFilter {
+  category_path?: string[];
+  tags?: string[];
+  from?: string;
+  to?: string;
+  focus_note_id?: string;
+  hops?: number;
+  author?: string;
+}
+
+export interface GraphSubset {
+  notes: Note[];
+  links: NoteLink[];
+  total_notes: number;
+}
+
+// A note in a snapshot; deleted notes only keep these fields
+export interface SnapshotNode {
+  id: string;
+  title: string;
+  category_path: string[];
+  created_at: string;
+  deleted_at?: string | null;
+}
+
+// The graph as it stood at `at`, for playing back how it grew
+export interface GraphSnapshot {
+  at: string;
+  notes: SnapshotNode[];
+  links: NoteLink[];
+  categories: string[][];
+}
+
+export interface NoteNeighborhood {
+  note_id: string;
+  notes: Note[];
+  links: NoteLink[];
+  distances: Record<string, number>;
+}
+
+export interface NotePath {
+  notes: Note[];
+  links: NoteLink[];
+}
+
+export interface RelatedNote {
+  note: Note;
+  weight: number;
+  link_id: string;
+}
+
+export type TimelineLinks = 'Off' | 'Category' | 'DailyNote';
+
+export interface TimelineEntry {
+  note: Note;
+  previous_id?: string | null;
+  next_id?: string | null;
+}
+
+export interface CategoryNode {
+  path: string[];
+  notes: number;
+  internal_links: number;
+}
+
+export interface CategoryEdge {
+  source: string[];
+  target: string[];
+  links: number;
+  weight: number;
+}
+
+export interface CategoryLinkMatrix {
+  categories: CategoryNode[];
+  edges: CategoryEdge[];
+}
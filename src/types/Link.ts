@@ -7,6 +7,9 @@ export interface NoteLink {
   color?: LinkColor;
   directional?: boolean;
   created_at: string;
+  target_block?: string | null;
+  weight?: number | null;
+  weight_is_manual?: boolean;
 }
 
 export type LinkType = 
@@ -15,6 +18,7 @@ export type LinkType =
   | 'FollowUp'
   | 'Contradicts'
   | 'Supports'
+  | 'Embed'
   | { Custom: string };
 
 export type LinkColor = 'purple' | 'yellow';
@@ -1,6 +1,7 @@
 export interface Note {
   id: string;
   title: string;
+  title_is_custom?: boolean;
   content: string;
   category_path: string[];
   timestamp: string;
@@ -8,6 +9,68 @@ export interface Note {
   ai_confidence?: number;
   source?: NoteSource;
   chat_context?: ChatContext;
+  provenance?: Provenance | null;
+  reading?: ReadingState | null;
+  author?: string | null; // Id of the profile the note was created under
+  visibility?: Visibility | null; // null = inherited from the category
+  ai_excluded?: boolean; // Never sent to the AI provider
+  duplicate?: boolean; // Only on save results: the content was already saved as this note
+}
+
+// Private never leaves the app; Shared goes to exports, webhooks and sync; Public can also be published
+export type Visibility = 'Private' | 'Shared' | 'Public';
+
+export interface Profile {
+  id: string;
+  name: string;
+  created_at: string;
+}
+
+export interface NoteQuery {
+  category_path?: string[];
+  tags?: string[];
+  text?: string;
+  metadata?: Record<string, unknown>;
+  status?: string;
+  min_rating?: number;
+}
+
+// Another vault's folder browsed alongside this one
+export interface MountedVault {
+  id: string;
+  name: string;
+  path: string;
+  read_only: boolean;
+  mounted_at: string;
+}
+
+export interface SharedNote {
+  vault_id: string;
+  vault_name: string;
+  read_only: boolean;
+  note: Note;
+}
+
+// Stored in this vault only; the mounted vault is never written
+export interface CrossVaultLink {
+  id: string;
+  source_id: string;
+  vault_id: string;
+  target_id: string;
+  label?: string | null;
+  created_at: string;
+}
+
+export interface ReadingState {
+  queued_at: string;
+  progress: number;
+  read_at?: string | null;
+}
+
+export interface Provenance {
+  app?: string | null;
+  url?: string | null;
+  captured_at: string;
 }
 
 export interface ChatContext {
@@ -20,4 +83,17 @@ export type NoteSource = 'manual' | 'chat' | 'generated';
 
 export interface NotesDatabase {
   notes: Note[];
-}
\ No newline at end of file
+}
+// One change made (or, in a dry run, proposed) by renameTerm
+export interface TermChange {
+  field: 'Title' | 'Alias' | 'Wikilink' | 'LinkLabel';
+  note_id?: string | null;
+  link_id?: string | null;
+  before: string;
+  after: string;
+}
+
+export interface TermRename {
+  changes: TermChange[];
+  applied: boolean;
+}
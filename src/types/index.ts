@@ -12,4 +12,113 @@ export interface ApiResponse<T> {
   error?: string;
 }
 
-export type AppMode = 'chat' | 'notes' | 'graph';
\ No newline at end of file
+export type AppMode = 'chat' | 'notes' | 'graph';
+export interface WindowAppearance {
+  always_on_top: boolean;
+  opacity: number;
+  vibrancy: boolean;
+}
+
+export type SyncStatus = 'Synced' | 'Pending' | 'Error' | 'Private';
+
+export interface NoteSyncStatus {
+  note_id: string;
+  status: SyncStatus;
+  last_synced_at?: string | null;
+  error?: string | null;
+}
+
+export interface SyncOverview {
+  enabled: boolean;
+  relay_url?: string | null;
+  last_sync_at?: string | null;
+  last_error?: string | null;
+  notes: NoteSyncStatus[];
+}
+
+export interface SyncSettings {
+  enabled: boolean;
+  relay_url?: string | null;
+  vault_id?: string | null;
+  device_id?: string | null;
+  interval_minutes: number;
+}
+
+export type RetentionAction = 'Archive' | 'Delete' | 'Keep';
+
+// Deepest category's rule wins; notes are aged from creation
+export interface RetentionRule {
+  id: string; // Empty to create a new rule
+  category_path: string[];
+  older_than_days: number;
+  action: RetentionAction;
+  enabled: boolean;
+}
+
+export interface RetainedNote {
+  note_id: string;
+  title: string;
+  category_path: string[];
+  age_days: number;
+  rule_id: string;
+}
+
+export interface RetentionReport {
+  dry_run: boolean;
+  archived: RetainedNote[];
+  deleted: RetainedNote[];
+}
+
+export interface MaintenanceReport {
+  ran_at: string;
+  bytes_before: number;
+  bytes_after: number;
+  bytes_reclaimed: number;
+  segments_removed: number;
+  attachments_removed: number;
+  sync_docs_removed: number;
+  links_removed: number;
+  drafts_removed: number;
+  pins_removed: number;
+}
+
+export interface OperationLatency {
+  name: string; // Command name, or e.g. "storage.load_notes" / "ai.send_prompt"
+  calls: number;
+  p50_ms: number;
+  p95_ms: number;
+  max_ms: number;
+}
+
+export interface SlowOperation {
+  name: string;
+  duration_ms: number;
+  at: string;
+}
+
+export interface PerformanceMetrics {
+  operations: OperationLatency[]; // Slowest p95 first
+  slowest: SlowOperation[];
+}
+
+export interface SwitchResult {
+  target: 'Note' | 'Category';
+  id: string; // Note id, or category id
+  title: string;
+  category_path: string[];
+  matched_field: 'Title' | 'Alias' | 'Tag' | 'Category';
+  matched_text: string;
+  score: number;
+}
+
+export interface IdleHideSettings {
+  minutes: number; // 0 = never hide
+  lock: boolean;
+}
+
+export interface AppLockStatus {
+  enabled: boolean;
+  locked: boolean;
+  idle_minutes: number;
+  lock_on_show: boolean;
+}